@@ -1,12 +1,13 @@
 use cosmwasm_schema::write_api;
 
-use oraiswap_staking::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use oraiswap_staking::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
         query: QueryMsg,
-        migrate: MigrateMsg
+        migrate: MigrateMsg,
+        sudo: SudoMsg
     }
 }
@@ -2,28 +2,97 @@
 use cosmwasm_std::entry_point;
 
 use crate::rewards::{
-    deposit_reward, process_reward_assets, query_all_reward_infos, query_reward_info,
-    withdraw_reward, withdraw_reward_others,
+    accrue_pool_rewards, asset_obligations, change_rewards_per_sec, claim_pending_withdraw,
+    claim_vested, deposit_prefunded_reward, deposit_reward, deposit_reward_cw20,
+    format_pool_amounts, hook_failed_reply, query_all_reward_infos, query_all_staker_info,
+    query_asset_balance, query_outstanding_rewards, query_pending_rewards, query_pending_withdraw,
+    query_reward_info, query_reward_infos_batch, query_reward_pool_count, query_reward_vestings,
+    reclaim_pending_reward, reward_transfer_failed_reply, split_weighted_reward, update_pool_index,
+    withdraw_all_reward, withdraw_and_swap, withdraw_and_swap_reply, withdraw_reward,
+    withdraw_reward_for, withdraw_reward_others, CLAIM_HOOK_REPLY_ID, MAX_REWARD_TRANSFERS,
+    REWARD_TRANSFER_REPLY_ID, STAKE_HOOK_REPLY_ID, WITHDRAW_AND_SWAP_REPLY_ID,
+};
+use crate::staking::{
+    bond, bond_from, bond_from_reply, bond_native, bond_split, boost, cancel_unbond, compound,
+    emergency_unbond, expire_boost, force_unbond, import_stakers, instant_unbond, migrate_stake,
+    restake, unbond, unbond_many, zap_in, zap_in_reply, BOND_CALLBACK_REPLY_ID, BOND_FROM_REPLY_ID,
+    ZAP_IN_REPLY_ID,
 };
-use crate::staking::{bond, unbond};
 use crate::state::{
-    read_all_pool_infos, read_config, read_pool_info, read_rewards_per_sec, read_unbonding_period,
-    read_user_lock_info, stakers_read, store_config, store_pool_info, store_rewards_per_sec,
-    store_unbonding_period, Config, PoolInfo, STAKED_BALANCES, STAKED_TOTAL,
+    self, add_token_pool, backfill_lock_index, backfill_staked_snapshots, backfill_staker_count,
+    backfill_total_unbonding, insert_pending_change, is_allowed_staking_token, is_blacklisted,
+    is_claim_hook, is_hook, is_native_staking_token, is_open_deposit, is_operator, is_rewarder,
+    migrate_legacy_config, migrate_legacy_entries, page_bounds, pool_key, pool_staking_token,
+    read_accrual_mode, read_all_pool_infos, read_all_rewards_per_sec, read_allowed_staking_tokens,
+    read_blacklist, read_block_at_time, read_boost, read_boost_curve, read_claim_delegate,
+    read_claim_hooks, read_config, read_cw20_stake_pool, read_hook_failures, read_hooks,
+    read_hooks_of, read_import_open, read_instant_unbond_penalty, read_last_distributed,
+    read_lock_period, read_membership, read_min_bond_amount, read_min_claim_amount, read_operators,
+    read_ownership_proposal, read_paused, read_pending_change, read_pending_changes,
+    read_pending_rewarder, read_pool_admin, read_pool_count, read_pool_info, read_pool_infos,
+    read_pool_locks, read_pool_status, read_reward_dust, read_reward_schedules,
+    read_reward_schedules_synced, read_reward_split, read_rewarders, read_rewards_per_sec,
+    read_rewards_per_sec_limits, read_rewards_per_sec_updated, read_router,
+    read_staked_balance_changelog, read_staked_total_changelog, read_staker_count,
+    read_staking_token_pool, read_token_pools, read_total_unbonding, read_unbond_fee,
+    read_unbonding_period, read_unbonding_periods, read_user_lock_amount, read_user_lock_count,
+    read_user_lock_info, read_user_matured_locks, record_block_time, remove_blacklisted,
+    remove_boost_curve, remove_claim_delegate, remove_cw20_stake_pool, remove_hook_failures,
+    remove_import_open, remove_instant_unbond_penalty, remove_last_distributed, remove_membership,
+    remove_ownership_proposal, remove_paused, remove_pending_change, remove_pending_rewarder,
+    remove_pool_admin, remove_reward_split, remove_reward_vesting_period, remove_router,
+    remove_stake_migration, remove_staking_token_pool, remove_token_pool, remove_unbonding_period,
+    store_accrual_mode, store_allowed_staking_token, store_blacklisted, store_boost_curve,
+    store_claim_delegate, store_claim_hook, store_config, store_cw20_stake_pool, store_hook,
+    store_import_open, store_instant_unbond_penalty, store_last_distributed, store_membership,
+    store_min_bond_amount, store_min_claim_amount, store_native_staking_token, store_open_deposit,
+    store_operator, store_ownership_proposal, store_paused, store_pending_rewarder,
+    store_pool_admin, store_pool_count, store_pool_info, store_pool_status, store_reward_schedules,
+    store_reward_schedules_synced, store_reward_split, store_reward_vesting_period, store_rewarder,
+    store_rewards_per_sec_limits, store_rewards_per_sec_updated, store_router,
+    store_stake_migration, store_staking_token_pool, store_unbond_fee, store_unbonding_period,
+    Config, Membership, OwnershipProposal, PendingChange, PoolInfo, RewardSchedule, RewardSplit,
+    RewarderProposal, DEFAULT_LIMIT, LOCK_INFOS, MAX_EXPORT_LIMIT, MAX_HOOKS, MAX_HOOK_FAILURES,
+    MAX_LIMIT, MAX_REWARD_SCHEDULES, MAX_REWARD_SPLIT_BPS, ORACLE_QUOTE_DENOM, POOL_INFOS, REWARDS,
+    REWARDS_PER_SEC, REWARD_INDEX, SECONDS_PER_YEAR, STAKED_BALANCES, STAKED_TOTAL, STAKERS,
 };
 
 use crate::msg::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, LockInfoResponse, LockInfosResponse,
-    MigrateMsg, PoolInfoResponse, QueryMsg, QueryPoolInfoResponse, RewardsPerSecResponse,
-    StakedBalanceAtHeightResponse, TotalStakedAtHeightResponse,
+    AccrualMode, AccrualModeResponse, AllRewardsPerSecResponseItem, BoostCurve, BoostResponse,
+    ClaimableResponse, ConfigResponse, Cw20HookMsg, Cw20StakeConfigResponse, ExecuteMsg,
+    ExportKind, ExportRecord, ExportStateResponse, HookFailuresResponseItem, HookKind,
+    HooksResponse, InfoResponse, InstantiateMsg, IsStakerResponse, LegacyKind, ListStakersResponse,
+    LockCountResponse, LockInfoResponse, LockInfosResponse, LockPeriodResponse, Member,
+    MemberListResponse, MemberResponse, MembershipResponse, MigrateMsg, OwnershipProposalResponse,
+    PausedResponse, PendingChangeResponse, PoolAprResponse, PoolInfoResponse,
+    PoolLocksResponseItem, PoolStakersResponseItem, PoolStatus, PoolValueResponse,
+    PoolsResponseItem, QueryMsg, QueryPoolInfoResponse, RewardAprResponse, RewardDustResponse,
+    RewardScheduleResponse, RewardScheduleStatus, RewardSplitResponse, RewardStateAtHeightResponse,
+    RewarderProposalResponse, RewardsPerSecLimits, RewardsPerSecResponse,
+    SnapshotChangeResponseItem, SnapshotConsistencyResponse, StakedBalanceAtHeightResponse,
+    StakedBalanceAtTimeResponse, StakedValueResponse, StakerBalanceResponse, StakerCountResponse,
+    SudoMsg, SummaryPoolItem, SummaryResponse, TotalPowerAtHeightResponse,
+    TotalStakedAtHeightResponse, TotalUnbondingResponse, TotalValueResponse, TotalWeightResponse,
+    UnbondingPeriodResponse, UnbondingPeriodsResponseItem, UnbondingSummaryResponse,
+    VotingPowerAtHeightResponse,
 };
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Api, Binary, CanonicalAddr, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128,
+    attr, from_binary, to_binary, Addr, Api, Binary, CanonicalAddr, Decimal, Deps, DepsMut, Env,
+    Event, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, Timestamp, Uint128,
 };
-use oraiswap::asset::{Asset, AssetRaw};
+use cw_controllers::{Claim, ClaimsResponse};
+use cw_storage_plus::{Bound, PrimaryKey};
+use cw_utils::{Duration, Expiration};
+use oraiswap::asset::{Asset, AssetInfo, AssetInfoRaw, AssetRaw};
+use oraiswap::oracle::{ExchangeQueryMsg, ExchangeRateResponse, QueryMsg as OracleQueryMsg};
+
+use cw2::{get_contract_version, set_contract_version, CONTRACT};
+use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, Cw20ReceiveMsg};
+use semver::Version;
 
-use cw20::Cw20ReceiveMsg;
+// version info for migration info
+pub const CONTRACT_NAME: &str = "crates.io:oraiswap-staking";
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -32,37 +101,363 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     store_config(
         deps.storage,
         &Config {
-            owner: deps
-                .api
-                .addr_canonicalize(msg.owner.unwrap_or(info.sender.clone()).as_str())?,
-            rewarder: deps.api.addr_canonicalize(msg.rewarder.as_str())?,
+            owner: Some(
+                deps.api
+                    .addr_canonicalize(msg.owner.unwrap_or(info.sender.clone()).as_str())?,
+            ),
+            fee_collector: None,
+            config_timelock: 0,
+            max_unbonding_period: None,
+            max_lock_entries: None,
+            governance_token: msg.governance_token,
+            oracle: None,
         },
     )?;
+    store_rewarder(
+        deps.storage,
+        &deps.api.addr_canonicalize(msg.rewarder.as_str())?,
+    )?;
+    if msg.import_open.unwrap_or(false) {
+        store_import_open(deps.storage)?;
+    }
     Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    // only the owner config update stays available while the contract is paused
+    if !matches!(msg, ExecuteMsg::UpdateConfig { .. }) && read_paused(deps.storage)?.is_some() {
+        return Err(StdError::generic_err("contract is paused"));
+    }
+    // maps the snapshot heights to wall-clock time for StakedBalanceAtTime
+    record_block_time(deps.storage, env.block.height, env.block.time.seconds())?;
+
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
-        ExecuteMsg::UpdateConfig { rewarder, owner } => update_config(deps, info, owner, rewarder),
+        ExecuteMsg::UpdateConfig {
+            fee_collector,
+            config_timelock,
+            max_unbonding_period,
+            max_lock_entries,
+            governance_token,
+            oracle,
+        } => update_config(
+            deps,
+            env,
+            info,
+            fee_collector,
+            config_timelock,
+            max_unbonding_period,
+            max_lock_entries,
+            governance_token,
+            oracle,
+        ),
+        ExecuteMsg::ExecutePending { id } => execute_pending(deps, env, id),
+        ExecuteMsg::CancelPending { id } => cancel_pending(deps, info, id),
+        ExecuteMsg::AddRewarder { address } => add_rewarder(deps, env, info, address),
+        ExecuteMsg::RemoveRewarder { address } => remove_rewarder(deps, env, info, address),
+        ExecuteMsg::AddOperator { address } => add_operator(deps, info, address),
+        ExecuteMsg::RemoveOperator { address } => remove_operator(deps, info, address),
+        ExecuteMsg::AddHook { addr } => add_hook(deps, info, addr),
+        ExecuteMsg::RemoveHook { addr } => remove_hook(deps, info, addr),
+        ExecuteMsg::AddClaimHook { addr } => add_claim_hook(deps, info, addr),
+        ExecuteMsg::RemoveClaimHook { addr } => remove_claim_hook(deps, info, addr),
+        ExecuteMsg::ResetHookFailures { kind, addr } => reset_hook_failures(deps, info, kind, addr),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            propose_new_owner(deps, env, info, owner, expires_in)
+        }
+        ExecuteMsg::DropOwnershipProposal {} => drop_ownership_proposal(deps, info),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, env, info),
+        ExecuteMsg::RenounceOwnership {} => renounce_ownership(deps, info),
+        ExecuteMsg::ProposeNewRewarder { rewarder, replace } => {
+            propose_new_rewarder(deps, env, info, rewarder, replace)
+        }
+        ExecuteMsg::AcceptRewarder {} => accept_rewarder(deps, info),
         ExecuteMsg::UpdateRewardsPerSec {
             staking_token,
             assets,
-        } => update_rewards_per_sec(deps, info, staking_token, assets),
-        ExecuteMsg::DepositReward { rewards } => deposit_reward(deps, info, rewards),
+            force,
+        } => update_rewards_per_sec(deps, env, info, staking_token, assets, force),
+        ExecuteMsg::SetRewardsPerSecLimits {
+            staking_token,
+            max_rewards_per_sec,
+            cooldown,
+        } => set_rewards_per_sec_limits(
+            deps,
+            info,
+            staking_token,
+            RewardsPerSecLimits {
+                max_rewards_per_sec,
+                cooldown,
+            },
+        ),
+        ExecuteMsg::CreateRewardSchedule {
+            staking_token,
+            asset_info,
+            amount_per_sec,
+            start_time,
+            end_time,
+        } => create_reward_schedule(
+            deps,
+            env,
+            info,
+            staking_token,
+            asset_info,
+            amount_per_sec,
+            start_time,
+            end_time,
+        ),
+        ExecuteMsg::CancelRewardSchedule { staking_token, id } => {
+            cancel_reward_schedule(deps, env, info, staking_token, id)
+        }
+        ExecuteMsg::SetOpenDeposit {
+            staking_token,
+            open_deposit,
+        } => set_open_deposit(deps, info, staking_token, open_deposit),
+        ExecuteMsg::ReclaimPendingReward {
+            staking_token,
+            recipient,
+        } => reclaim_pending_reward(deps, info, staking_token, recipient),
+        ExecuteMsg::SetAccrualMode {
+            staking_token,
+            mode,
+        } => set_accrual_mode(deps, env, info, staking_token, mode),
+        ExecuteMsg::UpdatePoolIndex { staking_token } => {
+            update_pool_index(deps, env, staking_token)
+        }
+        ExecuteMsg::SetBoostCurve {
+            staking_token,
+            curve,
+        } => set_boost_curve(deps, info, staking_token, curve),
+        ExecuteMsg::DepositReward { rewards } => deposit_reward(deps, env, info, rewards),
+        ExecuteMsg::DepositPrefundedReward { rewards } => {
+            deposit_prefunded_reward(deps, env, info, rewards)
+        }
+        ExecuteMsg::DepositWeightedReward {
+            total_amount,
+            weights,
+        } => {
+            let rewards = split_weighted_reward(total_amount, &weights)?;
+            let pool_amounts = format_pool_amounts(&rewards);
+            Ok(deposit_reward(deps, env, info, rewards)?
+                .add_attribute("pool_amounts", pool_amounts))
+        }
+        ExecuteMsg::AddAllowedStakingToken { staking_token } => {
+            add_allowed_staking_token(deps, info, staking_token)
+        }
+        ExecuteMsg::RemoveAllowedStakingToken { staking_token } => {
+            remove_allowed_staking_token(deps, info, staking_token)
+        }
+        ExecuteMsg::SetBlacklist {
+            address,
+            blacklisted,
+        } => set_blacklist(deps, info, address, blacklisted),
         ExecuteMsg::RegisterAsset {
             staking_token,
             unbonding_period,
-        } => register_asset(deps, info, staking_token, unbonding_period),
+            native_token,
+        } => register_asset(
+            deps,
+            env,
+            info,
+            staking_token,
+            unbonding_period,
+            native_token.unwrap_or_default(),
+        ),
+        ExecuteMsg::UpdateUnbondingPeriod {
+            staking_token,
+            new_period,
+        } => update_unbonding_period(deps, env, info, staking_token, new_period),
+        ExecuteMsg::UpdateUnbondFee {
+            staking_token,
+            unbond_fee,
+        } => update_unbond_fee(deps, env, info, staking_token, unbond_fee),
+        ExecuteMsg::UpdateInstantUnbondPenalty {
+            staking_token,
+            penalty,
+        } => update_instant_unbond_penalty(deps, env, info, staking_token, penalty),
+        ExecuteMsg::UpdateMinBondAmount {
+            staking_token,
+            min_bond_amount,
+        } => update_min_bond_amount(deps, info, staking_token, min_bond_amount),
+        ExecuteMsg::UpdateMinClaimAmount {
+            staking_token,
+            min_claim_amount,
+        } => update_min_claim_amount(deps, info, staking_token, min_claim_amount),
+        ExecuteMsg::UpdateRewardVestingPeriod {
+            staking_token,
+            reward_vesting_period,
+        } => update_reward_vesting_period(deps, info, staking_token, reward_vesting_period),
+        ExecuteMsg::MigrateStakingToken {
+            staking_token,
+            new_staking_token,
+        } => migrate_staking_token(deps, env, info, staking_token, new_staking_token),
+        ExecuteMsg::BackfillSnapshots {
+            staking_token,
+            start_after,
+            limit,
+        } => backfill_snapshots(deps, env, info, staking_token, start_after, limit),
+        ExecuteMsg::MigrateLegacy { kind, limit } => migrate_legacy(deps, info, kind, limit),
+        ExecuteMsg::ImportStakers {
+            staking_token,
+            entries,
+        } => import_stakers(deps, env, info, staking_token, entries),
+        ExecuteMsg::FinalizeImport {} => finalize_import(deps, info),
+        ExecuteMsg::ForceUnbond {
+            staking_token,
+            staker_addr,
+        } => force_unbond(deps, env, info, staking_token, staker_addr),
+        ExecuteMsg::Sweep {
+            asset_info,
+            recipient,
+        } => sweep(deps, env, info, asset_info, recipient),
+        ExecuteMsg::SetStakeMigration {
+            from_staking_token,
+            to_staking_token,
+            enabled,
+        } => set_stake_migration(deps, info, from_staking_token, to_staking_token, enabled),
+        ExecuteMsg::DeprecatePool { staking_token } => deprecate_pool(deps, info, staking_token),
+        ExecuteMsg::SetPoolAdmin {
+            staking_token,
+            pool_admin,
+        } => set_pool_admin(deps, info, staking_token, pool_admin),
+        ExecuteMsg::SetPoolStatus {
+            staking_token,
+            bond_paused,
+            unbond_paused,
+            withdraw_paused,
+        } => set_pool_status(
+            deps,
+            info,
+            staking_token,
+            PoolStatus {
+                bond_paused,
+                unbond_paused,
+                withdraw_paused,
+            },
+        ),
+        ExecuteMsg::Bond { asset_info, staker } => bond_native(deps, env, info, asset_info, staker),
+        ExecuteMsg::BondFrom { asset_info, amount } => {
+            bond_from(deps, env, info, asset_info, amount)
+        }
+        ExecuteMsg::ZapIn {
+            asset_info,
+            min_bond_amount,
+        } => {
+            let offer_asset = match info.funds.as_slice() {
+                [fund] => Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: fund.denom.clone(),
+                    },
+                    amount: fund.amount,
+                },
+                _ => {
+                    return Err(StdError::generic_err(
+                        "must send exactly one coin to zap in",
+                    ))
+                }
+            };
+            zap_in(
+                deps,
+                env,
+                info.sender,
+                offer_asset,
+                asset_info,
+                min_bond_amount,
+            )
+        }
         ExecuteMsg::Unbond {
             staking_token,
             amount,
-        } => unbond(deps, env, info.sender, staking_token, amount),
-        ExecuteMsg::Withdraw { staking_token } => withdraw_reward(deps, env, info, staking_token),
+            withdraw_rewards,
+            recipient,
+            limit,
+        } => unbond(
+            deps,
+            env,
+            info.sender,
+            staking_token,
+            amount,
+            withdraw_rewards.unwrap_or_default(),
+            recipient,
+            limit,
+        ),
+        ExecuteMsg::UnbondMany { unbonds } => unbond_many(deps, env, info.sender, unbonds),
+        ExecuteMsg::CancelUnbond {
+            staking_token,
+            amount,
+        } => cancel_unbond(deps, env, info.sender, staking_token, amount),
+        ExecuteMsg::MigrateStake {
+            from_staking_token,
+            to_staking_token,
+            amount,
+        } => migrate_stake(
+            deps,
+            env,
+            info.sender,
+            from_staking_token,
+            to_staking_token,
+            amount,
+        ),
+        ExecuteMsg::Compound { staking_token } => compound(deps, env, info.sender, staking_token),
+        ExecuteMsg::EmergencyUnbond { staking_token } => {
+            emergency_unbond(deps, env, info.sender, staking_token)
+        }
+        ExecuteMsg::Boost {
+            staking_token,
+            lock_weeks,
+        } => boost(deps, env, info, staking_token, lock_weeks),
+        ExecuteMsg::ExpireBoost {
+            staking_token,
+            staker_addr,
+        } => expire_boost(deps, env, staking_token, staker_addr),
+        ExecuteMsg::Restake { staking_token } => restake(deps, env, info.sender, staking_token),
+        ExecuteMsg::InstantUnbond {
+            staking_token,
+            amount,
+        } => instant_unbond(deps, env, info.sender, staking_token, amount),
+        ExecuteMsg::Withdraw {
+            staking_token,
+            skip_failing,
+        } => withdraw_reward(
+            deps,
+            env,
+            info,
+            staking_token,
+            skip_failing.unwrap_or_default(),
+        ),
+        ExecuteMsg::WithdrawAndSwap {
+            staking_token,
+            to_asset,
+            min_receive,
+        } => withdraw_and_swap(deps, env, info, staking_token, to_asset, min_receive),
+        ExecuteMsg::UpdateCw20StakePool { staking_token } => {
+            update_cw20_stake_pool(deps, info, staking_token)
+        }
+        ExecuteMsg::UpdateRouter { router } => update_router(deps, info, router),
+        ExecuteMsg::UpdateMembership {
+            staking_token,
+            weight_divisor,
+        } => update_membership(deps, info, staking_token, weight_divisor),
+        ExecuteMsg::WithdrawAll { start_after, limit } => {
+            withdraw_all_reward(deps, env, info, start_after, limit)
+        }
+        ExecuteMsg::ClaimPendingWithdraw { staking_token } => {
+            claim_pending_withdraw(deps, info, staking_token)
+        }
+        ExecuteMsg::ClaimVested { staking_token } => claim_vested(deps, env, info, staking_token),
+        ExecuteMsg::SetClaimDelegate { delegate } => set_claim_delegate(deps, info, delegate),
+        ExecuteMsg::SetRewardSplit { beneficiary, bps } => {
+            set_reward_split(deps, info, beneficiary, bps)
+        }
+        ExecuteMsg::WithdrawFor {
+            staker,
+            staking_token,
+        } => withdraw_reward_for(deps, env, info, staker, staking_token),
         ExecuteMsg::WithdrawOthers {
             staking_token,
             staker_addrs,
@@ -70,6 +465,69 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
     }
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> StdResult<Response> {
+    match msg {
+        SudoMsg::Pause {} => {
+            store_paused(deps.storage, env.block.height)?;
+            Ok(Response::new().add_attributes([
+                ("action", "pause"),
+                ("height", &env.block.height.to_string()),
+            ]))
+        }
+        SudoMsg::Unpause {} => {
+            remove_paused(deps.storage);
+            Ok(Response::new().add_attribute("action", "unpause"))
+        }
+        SudoMsg::UpdateOwner { new_owner } => sudo_update_owner(deps, new_owner),
+    }
+}
+
+fn sudo_update_owner(deps: DepsMut, new_owner: String) -> StdResult<Response> {
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    let mut config = read_config(deps.storage)?;
+    let old_owner = config
+        .owner
+        .map(|owner| deps.api.addr_humanize(&owner))
+        .transpose()?;
+    config.owner = Some(deps.api.addr_canonicalize(new_owner.as_str())?);
+    store_config(deps.storage, &config)?;
+    // a proposal made with a lost or compromised key must not take the ownership back
+    remove_ownership_proposal(deps.storage);
+
+    // a distinct event so indexers can tell it apart from an owner update by the owner
+    Ok(Response::new().add_event(
+        Event::new("sudo_update_owner")
+            .add_attribute(
+                "old_owner",
+                old_owner.map(|owner| owner.to_string()).unwrap_or_default(),
+            )
+            .add_attribute("new_owner", new_owner.as_str()),
+    ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        BOND_FROM_REPLY_ID => bond_from_reply(deps, env),
+        WITHDRAW_AND_SWAP_REPLY_ID => withdraw_and_swap_reply(deps, env),
+        ZAP_IN_REPLY_ID => zap_in_reply(deps, env),
+        BOND_CALLBACK_REPLY_ID => bond_callback_failed_reply(msg.result),
+        id if (STAKE_HOOK_REPLY_ID..STAKE_HOOK_REPLY_ID + MAX_HOOKS as u64).contains(&id) => {
+            hook_failed_reply(deps, HookKind::Stake, id - STAKE_HOOK_REPLY_ID, msg.result)
+        }
+        id if (CLAIM_HOOK_REPLY_ID..CLAIM_HOOK_REPLY_ID + MAX_HOOKS as u64).contains(&id) => {
+            hook_failed_reply(deps, HookKind::Claim, id - CLAIM_HOOK_REPLY_ID, msg.result)
+        }
+        id if (REWARD_TRANSFER_REPLY_ID..REWARD_TRANSFER_REPLY_ID + MAX_REWARD_TRANSFERS)
+            .contains(&id) =>
+        {
+            reward_transfer_failed_reply(deps, id - REWARD_TRANSFER_REPLY_ID, msg.result)
+        }
+        _ => Err(StdError::generic_err("unknown reply id")),
+    }
+}
+
 pub fn receive_cw20(
     deps: DepsMut,
     env: Env,
@@ -77,165 +535,2149 @@ pub fn receive_cw20(
     cw20_msg: Cw20ReceiveMsg,
 ) -> StdResult<Response> {
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::Bond {}) => bond(
+        Ok(Cw20HookMsg::Bond { staker, callback }) => bond(
+            deps,
+            env,
+            Addr::unchecked(cw20_msg.sender),
+            staker,
+            info.sender,
+            cw20_msg.amount,
+            callback,
+        ),
+        Ok(Cw20HookMsg::BondSplit { allocations }) => bond_split(
+            deps,
+            env,
+            Addr::unchecked(cw20_msg.sender),
+            info.sender,
+            cw20_msg.amount,
+            allocations,
+        ),
+        Ok(Cw20HookMsg::ZapIn {
+            asset_info,
+            min_bond_amount,
+        }) => zap_in(
+            deps,
+            env,
+            Addr::unchecked(cw20_msg.sender),
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+                amount: cw20_msg.amount,
+            },
+            asset_info,
+            min_bond_amount,
+        ),
+        Ok(Cw20HookMsg::DepositReward { rewards }) => deposit_reward_cw20(
             deps,
             env,
             Addr::unchecked(cw20_msg.sender),
             info.sender,
             cw20_msg.amount,
+            rewards,
         ),
+        Ok(Cw20HookMsg::DepositWeightedReward { weights }) => {
+            let rewards = split_weighted_reward(cw20_msg.amount, &weights)?;
+            let pool_amounts = format_pool_amounts(&rewards);
+            Ok(deposit_reward_cw20(
+                deps,
+                env,
+                Addr::unchecked(cw20_msg.sender),
+                info.sender,
+                cw20_msg.amount,
+                rewards,
+            )?
+            .add_attribute("pool_amounts", pool_amounts))
+        }
         Err(_) => Err(StdError::generic_err("invalid cw20 hook message")),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    owner: Option<Addr>,
-    rewarder: Option<Addr>,
+    fee_collector: Option<Addr>,
+    config_timelock: Option<u64>,
+    max_unbonding_period: Option<u64>,
+    max_lock_entries: Option<u64>,
+    governance_token: Option<Addr>,
+    oracle: Option<Addr>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    timelock_or_apply(
+        deps,
+        env,
+        &config,
+        ExecuteMsg::UpdateConfig {
+            fee_collector,
+            config_timelock,
+            max_unbonding_period,
+            max_lock_entries,
+            governance_token,
+            oracle,
+        },
+    )
+}
+
+fn _update_config(
+    deps: DepsMut,
+    fee_collector: Option<Addr>,
+    config_timelock: Option<u64>,
+    max_unbonding_period: Option<u64>,
+    max_lock_entries: Option<u64>,
+    governance_token: Option<Addr>,
+    oracle: Option<Addr>,
 ) -> StdResult<Response> {
     let mut config: Config = read_config(deps.storage)?;
 
-    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
-        return Err(StdError::generic_err("unauthorized"));
+    if let Some(fee_collector) = fee_collector {
+        config.fee_collector = Some(deps.api.addr_canonicalize(fee_collector.as_str())?);
+    }
+
+    if let Some(config_timelock) = config_timelock {
+        config.config_timelock = config_timelock;
+    }
+
+    if let Some(max_unbonding_period) = max_unbonding_period {
+        config.max_unbonding_period = Some(max_unbonding_period);
+    }
+
+    if let Some(max_lock_entries) = max_lock_entries {
+        config.max_lock_entries = Some(max_lock_entries);
     }
 
-    if let Some(owner) = owner {
-        config.owner = deps.api.addr_canonicalize(owner.as_str())?;
+    if let Some(governance_token) = governance_token {
+        config.governance_token = Some(governance_token);
     }
 
-    if let Some(rewarder) = rewarder {
-        config.rewarder = deps.api.addr_canonicalize(rewarder.as_str())?;
+    if let Some(oracle) = oracle {
+        config.oracle = Some(deps.api.addr_canonicalize(oracle.as_str())?);
     }
 
     store_config(deps.storage, &config)?;
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
-// need to withdraw all rewards of the stakers belong to the pool
-// may need to call withdraw from backend side by querying all stakers with pagination in case out of gas
-fn update_rewards_per_sec(
+// queue the change while a timelock is configured, otherwise apply it right away
+fn timelock_or_apply(
     deps: DepsMut,
-    info: MessageInfo,
-    staking_token: Addr,
-    assets: Vec<Asset>,
+    env: Env,
+    config: &Config,
+    msg: ExecuteMsg,
 ) -> StdResult<Response> {
-    let config: Config = read_config(deps.storage)?;
+    if config.config_timelock == 0 {
+        return apply_change(deps, env, msg);
+    }
 
-    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
-        return Err(StdError::generic_err("unauthorized"));
+    let execute_after = env.block.time.plus_seconds(config.config_timelock);
+    let id = insert_pending_change(deps.storage, &PendingChange { msg, execute_after })?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "queue_change"),
+        attr("id", id.to_string()),
+        attr("execute_after", execute_after.seconds().to_string()),
+    ]))
+}
+
+// the sender was authorized when the change was submitted
+fn apply_change(deps: DepsMut, env: Env, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            fee_collector,
+            config_timelock,
+            max_unbonding_period,
+            max_lock_entries,
+            governance_token,
+            oracle,
+        } => _update_config(
+            deps,
+            fee_collector,
+            config_timelock,
+            max_unbonding_period,
+            max_lock_entries,
+            governance_token,
+            oracle,
+        ),
+        ExecuteMsg::UpdateRewardsPerSec {
+            staking_token,
+            assets,
+            ..
+        } => _update_rewards_per_sec(deps, env, staking_token, assets),
+        ExecuteMsg::UpdateUnbondingPeriod {
+            staking_token,
+            new_period,
+        } => _update_unbonding_period(deps, staking_token, new_period),
+        ExecuteMsg::UpdateUnbondFee {
+            staking_token,
+            unbond_fee,
+        } => _update_unbond_fee(deps, staking_token, unbond_fee),
+        ExecuteMsg::UpdateInstantUnbondPenalty {
+            staking_token,
+            penalty,
+        } => _update_instant_unbond_penalty(deps, staking_token, penalty),
+        ExecuteMsg::AddRewarder { address } => _add_rewarder(deps, address),
+        ExecuteMsg::RemoveRewarder { address } => _remove_rewarder(deps, address),
+        ExecuteMsg::ProposeNewRewarder { rewarder, replace } => {
+            _propose_new_rewarder(deps, rewarder, replace)
+        }
+        _ => Err(StdError::generic_err("change cannot be timelocked")),
     }
+}
 
-    let asset_key = deps.api.addr_canonicalize(staking_token.as_str())?.to_vec();
+pub fn execute_pending(deps: DepsMut, env: Env, id: u64) -> StdResult<Response> {
+    let pending_change = read_pending_change(deps.storage, id)?
+        .ok_or_else(|| StdError::generic_err("no pending change found"))?;
 
-    // withdraw all rewards for all stakers from this pool
-    let staker_addrs = stakers_read(deps.storage, &asset_key)
-        .range(None, None, Order::Ascending)
-        .map(|item| {
-            let (k, _) = item?;
-            Ok(CanonicalAddr::from(k))
-        })
-        .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+    if env.block.time < pending_change.execute_after {
+        return Err(StdError::generic_err("pending change is still timelocked"));
+    }
 
-    // let mut messages: Vec<CosmosMsg> = vec![];
+    remove_pending_change(deps.storage, id);
+    let response = apply_change(deps, env, pending_change.msg)?;
 
-    // withdraw reward for each staker
-    for staker_addr_raw in staker_addrs {
-        process_reward_assets(
-            deps.storage,
-            &staker_addr_raw,
-            &Some(asset_key.clone()),
-            false,
-        )?;
+    Ok(Response::new()
+        .add_attributes([
+            attr("action", "execute_pending"),
+            attr("id", id.to_string()),
+        ])
+        .add_submessages(response.messages)
+        .add_attributes(response.attributes))
+}
+
+pub fn cancel_pending(deps: DepsMut, info: MessageInfo, id: u64) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    if read_pending_change(deps.storage, id)?.is_none() {
+        return Err(StdError::generic_err("no pending change found"));
     }
+    remove_pending_change(deps.storage, id);
 
-    // convert assets to raw_assets
-    let raw_assets = assets
-        .into_iter()
-        .map(|w| w.to_raw(deps.api))
-        .collect::<StdResult<Vec<AssetRaw>>>()?;
+    Ok(Response::new()
+        .add_attributes([attr("action", "cancel_pending"), attr("id", id.to_string())]))
+}
 
-    store_rewards_per_sec(deps.storage, &asset_key, raw_assets)?;
+pub fn add_rewarder(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: Addr,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
 
-    Ok(Response::new().add_attribute("action", "update_rewards_per_sec"))
+    timelock_or_apply(deps, env, &config, ExecuteMsg::AddRewarder { address })
 }
 
-fn register_asset(
+fn _add_rewarder(deps: DepsMut, address: Addr) -> StdResult<Response> {
+    store_rewarder(deps.storage, &deps.api.addr_canonicalize(address.as_str())?)?;
+
+    Ok(
+        Response::new()
+            .add_attributes([("action", "add_rewarder"), ("rewarder", address.as_str())]),
+    )
+}
+
+pub fn remove_rewarder(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    staking_token: Addr,
-    unbonding_period: Option<u64>,
+    address: Addr,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
 
-    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
-        return Err(StdError::generic_err("unauthorized"));
+    if !is_rewarder(deps.storage, &deps.api.addr_canonicalize(address.as_str())?)? {
+        return Err(StdError::generic_err("address is not a rewarder"));
     }
 
-    // query asset_key from AssetInfo
-    let asset_key = deps.api.addr_canonicalize(staking_token.as_str())?;
-    if read_pool_info(deps.storage, &asset_key).is_ok() {
-        return Err(StdError::generic_err("Asset was already registered"));
+    timelock_or_apply(deps, env, &config, ExecuteMsg::RemoveRewarder { address })
+}
+
+// checked again, the rewarders may have changed while the removal was timelocked
+fn _remove_rewarder(deps: DepsMut, address: Addr) -> StdResult<Response> {
+    let rewarder = deps.api.addr_canonicalize(address.as_str())?;
+    if !is_rewarder(deps.storage, &rewarder)? {
+        return Err(StdError::generic_err("address is not a rewarder"));
     }
+    state::remove_rewarder(deps.storage, &rewarder);
 
-    store_pool_info(
-        deps.storage,
-        &asset_key.clone(),
-        &PoolInfo {
-            staking_token: asset_key.clone(),
-            total_bond_amount: Uint128::zero(),
-            reward_index: Decimal::zero(),
-            pending_reward: Uint128::zero(),
-        },
-    )?;
+    // keep at least one rewarder, otherwise rewards can no longer be distributed
+    if read_rewarders(deps.storage, None, Some(1))?.is_empty() {
+        return Err(StdError::generic_err("cannot remove the last rewarder"));
+    }
 
-    if let Some(unbonding_period) = unbonding_period {
-        if unbonding_period > 0 {
-            store_unbonding_period(deps.storage, &asset_key, unbonding_period)?;
-        }
+    Ok(Response::new().add_attributes([
+        ("action", "remove_rewarder"),
+        ("rewarder", address.as_str()),
+    ]))
+}
+
+pub fn add_operator(deps: DepsMut, info: MessageInfo, address: Addr) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    store_operator(deps.storage, &deps.api.addr_canonicalize(address.as_str())?)?;
+
+    Ok(
+        Response::new()
+            .add_attributes([("action", "add_operator"), ("operator", address.as_str())]),
+    )
+}
+
+pub fn remove_operator(deps: DepsMut, info: MessageInfo, address: Addr) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let operator = deps.api.addr_canonicalize(address.as_str())?;
+    if !is_operator(deps.storage, &operator)? {
+        return Err(StdError::generic_err("address is not an operator"));
     }
+    state::remove_operator(deps.storage, &operator);
 
     Ok(Response::new().add_attributes([
-        ("action", "register_asset"),
-        ("staking_token", staking_token.as_str()),
-        (
-            "unbonding_period",
-            &unbonding_period.unwrap_or(0).to_string(),
-        ),
+        ("action", "remove_operator"),
+        ("operator", address.as_str()),
     ]))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::PoolInfo { staking_token } => to_binary(&query_pool_info(deps, staking_token)?),
-        QueryMsg::RewardsPerSec { staking_token } => {
-            to_binary(&query_rewards_per_sec(deps, staking_token)?)
-        }
-        QueryMsg::RewardInfo {
-            staker_addr,
-            staking_token,
-        } => to_binary(&query_reward_info(deps, staker_addr, staking_token)?),
-        QueryMsg::RewardInfos {
-            staking_token,
-            start_after,
-            limit,
-            order,
-        } => to_binary(&query_all_reward_infos(
-            deps,
-            staking_token,
-            start_after,
-            limit,
-            order,
+pub fn add_hook(deps: DepsMut, info: MessageInfo, addr: String) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let hook = deps
+        .api
+        .addr_canonicalize(deps.api.addr_validate(&addr)?.as_str())?;
+    if is_hook(deps.storage, &hook)? {
+        return Err(StdError::generic_err("hook is already registered"));
+    }
+    if read_hooks(deps.storage)?.len() >= MAX_HOOKS as usize {
+        return Err(StdError::generic_err(format!(
+            "too many hooks, at most {}",
+            MAX_HOOKS
+        )));
+    }
+    store_hook(deps.storage, &hook)?;
+
+    Ok(Response::new().add_attributes([("action", "add_hook"), ("hook", addr.as_str())]))
+}
+
+pub fn remove_hook(deps: DepsMut, info: MessageInfo, addr: String) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let hook = deps.api.addr_canonicalize(&addr)?;
+    if !is_hook(deps.storage, &hook)? {
+        return Err(StdError::generic_err("hook is not registered"));
+    }
+    state::remove_hook(deps.storage, &hook);
+    remove_hook_failures(deps.storage, &HookKind::Stake, &hook);
+
+    Ok(Response::new().add_attributes([("action", "remove_hook"), ("hook", addr.as_str())]))
+}
+
+pub fn add_claim_hook(deps: DepsMut, info: MessageInfo, addr: String) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let hook = deps
+        .api
+        .addr_canonicalize(deps.api.addr_validate(&addr)?.as_str())?;
+    if is_claim_hook(deps.storage, &hook)? {
+        return Err(StdError::generic_err("hook is already registered"));
+    }
+    if read_claim_hooks(deps.storage)?.len() >= MAX_HOOKS as usize {
+        return Err(StdError::generic_err(format!(
+            "too many hooks, at most {}",
+            MAX_HOOKS
+        )));
+    }
+    store_claim_hook(deps.storage, &hook)?;
+
+    Ok(Response::new().add_attributes([("action", "add_claim_hook"), ("hook", addr.as_str())]))
+}
+
+pub fn remove_claim_hook(deps: DepsMut, info: MessageInfo, addr: String) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let hook = deps.api.addr_canonicalize(&addr)?;
+    if !is_claim_hook(deps.storage, &hook)? {
+        return Err(StdError::generic_err("hook is not registered"));
+    }
+    state::remove_claim_hook(deps.storage, &hook);
+    remove_hook_failures(deps.storage, &HookKind::Claim, &hook);
+
+    Ok(Response::new().add_attributes([("action", "remove_claim_hook"), ("hook", addr.as_str())]))
+}
+
+pub fn reset_hook_failures(
+    deps: DepsMut,
+    info: MessageInfo,
+    kind: HookKind,
+    addr: String,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let hook = deps.api.addr_canonicalize(&addr)?;
+    if !read_hooks_of(deps.storage, &kind)?.contains(&hook) {
+        return Err(StdError::generic_err("hook is not registered"));
+    }
+    remove_hook_failures(deps.storage, &kind, &hook);
+
+    Ok(
+        Response::new()
+            .add_attributes([("action", "reset_hook_failures"), ("hook", addr.as_str())]),
+    )
+}
+
+pub fn set_blacklist(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: Addr,
+    blacklisted: bool,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let address_raw = deps.api.addr_canonicalize(address.as_str())?;
+    if blacklisted {
+        store_blacklisted(deps.storage, &address_raw)?;
+    } else {
+        remove_blacklisted(deps.storage, &address_raw);
+    }
+
+    Ok(Response::new().add_attributes([
+        ("action", "set_blacklist"),
+        ("address", address.as_str()),
+        ("blacklisted", &blacklisted.to_string()),
+    ]))
+}
+
+pub fn propose_new_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: Addr,
+    expires_in: u64,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let new_owner = deps.api.addr_canonicalize(owner.as_str())?;
+    if config.owner.as_ref() == Some(&new_owner) {
+        return Err(StdError::generic_err(
+            "new owner cannot be the current owner",
+        ));
+    }
+
+    // a new proposal overrides the pending one
+    let expires_at = env.block.time.plus_seconds(expires_in);
+    store_ownership_proposal(
+        deps.storage,
+        &OwnershipProposal {
+            owner: new_owner,
+            expires_at,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "propose_new_owner"),
+        attr("new_owner", owner.as_str()),
+        attr("expires_at", expires_at.seconds().to_string()),
+    ]))
+}
+
+pub fn drop_ownership_proposal(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    remove_ownership_proposal(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "drop_ownership_proposal"))
+}
+
+pub fn accept_ownership(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+    let proposal = read_ownership_proposal(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("no ownership proposal found"))?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != proposal.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if env.block.time >= proposal.expires_at {
+        return Err(StdError::generic_err("ownership proposal expired"));
+    }
+
+    let mut config: Config = read_config(deps.storage)?;
+    config.owner = Some(proposal.owner);
+    store_config(deps.storage, &config)?;
+    remove_ownership_proposal(deps.storage);
+
+    Ok(Response::new().add_attributes([
+        attr("action", "accept_ownership"),
+        attr("new_owner", info.sender.as_str()),
+    ]))
+}
+
+// once renounced, every owner gated message fails forever
+pub fn renounce_ownership(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    config.owner = None;
+    store_config(deps.storage, &config)?;
+    remove_ownership_proposal(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "renounce_ownership"))
+}
+
+pub fn propose_new_rewarder(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rewarder: Addr,
+    replace: Option<Addr>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    if let Some(replace) = &replace {
+        if !is_rewarder(deps.storage, &deps.api.addr_canonicalize(replace.as_str())?)? {
+            return Err(StdError::generic_err("address is not a rewarder"));
+        }
+    }
+
+    timelock_or_apply(
+        deps,
+        env,
+        &config,
+        ExecuteMsg::ProposeNewRewarder { rewarder, replace },
+    )
+}
+
+fn _propose_new_rewarder(
+    deps: DepsMut,
+    rewarder: Addr,
+    replace: Option<Addr>,
+) -> StdResult<Response> {
+    let replace = replace
+        .map(|replace| deps.api.addr_canonicalize(replace.as_str()))
+        .transpose()?;
+    if let Some(replace) = &replace {
+        if !is_rewarder(deps.storage, replace)? {
+            return Err(StdError::generic_err("address is not a rewarder"));
+        }
+    }
+
+    store_pending_rewarder(
+        deps.storage,
+        &RewarderProposal {
+            rewarder: deps.api.addr_canonicalize(rewarder.as_str())?,
+            replace: replace.clone(),
+        },
+    )?;
+
+    let mut response = Response::new().add_attributes([
+        attr("action", "propose_new_rewarder"),
+        attr("new_rewarder", rewarder.as_str()),
+    ]);
+    if let Some(replace) = replace {
+        response = response.add_attribute("old_rewarder", deps.api.addr_humanize(&replace)?);
+    }
+    Ok(response)
+}
+
+pub fn accept_rewarder(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let proposal = read_pending_rewarder(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("no pending rewarder found"))?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != proposal.rewarder {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let mut response = Response::new().add_attributes([
+        attr("action", "accept_rewarder"),
+        attr("new_rewarder", info.sender.as_str()),
+    ]);
+    if let Some(replace) = proposal.replace {
+        state::remove_rewarder(deps.storage, &replace);
+        response = response.add_attribute("old_rewarder", deps.api.addr_humanize(&replace)?);
+    }
+    store_rewarder(deps.storage, &proposal.rewarder)?;
+    remove_pending_rewarder(deps.storage);
+
+    Ok(response)
+}
+
+// need to withdraw all rewards of the stakers belong to the pool
+// may need to call withdraw from backend side by querying all stakers with pagination in case out of gas
+fn update_rewards_per_sec(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    assets: Vec<Asset>,
+    force: Option<bool>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    config.assert_pool_manager(deps.storage, deps.api, &info.sender, &asset_key)?;
+    if force.unwrap_or_default() {
+        config.assert_owner(deps.api, &info.sender)?;
+    }
+
+    for asset in assets.iter() {
+        validate_reward_asset(&asset.info)?;
+    }
+    let limits = read_rewards_per_sec_limits(deps.storage, &asset_key)?;
+    if let Some(max_rewards_per_sec) = limits.max_rewards_per_sec {
+        if assets
+            .iter()
+            .any(|asset| asset.amount > max_rewards_per_sec)
+        {
+            return Err(StdError::generic_err("rewards per sec exceeds the maximum"));
+        }
+    }
+    if !force.unwrap_or_default() {
+        if let Some(updated_at) = read_rewards_per_sec_updated(deps.storage, &asset_key)? {
+            if env.block.time < updated_at.plus_seconds(limits.cooldown) {
+                return Err(StdError::generic_err(
+                    "rewards per sec was updated too recently",
+                ));
+            }
+        }
+    }
+
+    timelock_or_apply(
+        deps,
+        env,
+        &config,
+        ExecuteMsg::UpdateRewardsPerSec {
+            staking_token,
+            assets,
+            force,
+        },
+    )
+}
+
+fn _update_rewards_per_sec(
+    deps: DepsMut,
+    env: Env,
+    staking_token: Addr,
+    assets: Vec<Asset>,
+) -> StdResult<Response> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    if read_pool_info(deps.storage, &asset_key)
+        .map(|pool_info| pool_info.deprecated)
+        .unwrap_or_default()
+    {
+        return Err(StdError::generic_err("pool is deprecated"));
+    }
+
+    // convert assets to raw_assets
+    let raw_assets = assets
+        .into_iter()
+        .map(|w| w.to_raw(deps.api))
+        .collect::<StdResult<Vec<AssetRaw>>>()?;
+
+    // the flat rate is the open-ended part of the schedules, the former one stops now
+    let now = env.block.time.seconds();
+    let mut schedules = read_reward_schedules(deps.storage, &asset_key)?;
+    for schedule in schedules.iter_mut() {
+        if schedule.end_time.is_none() {
+            schedule.end_time = Some(now.max(schedule.start_time));
+        }
+    }
+    schedules.extend(flat_rate_schedules(&schedules, &raw_assets, now));
+    store_reward_schedules(deps.storage, &asset_key, schedules)?;
+    if read_reward_schedules_synced(deps.storage, &asset_key)?.is_none() {
+        store_reward_schedules_synced(deps.storage, &asset_key, now)?;
+    }
+
+    change_rewards_per_sec(deps.storage, &asset_key, raw_assets)?;
+    store_rewards_per_sec_updated(deps.storage, &asset_key, env.block.time)?;
+
+    Ok(Response::new().add_attribute("action", "update_rewards_per_sec"))
+}
+
+/// native reward denoms follow the sdk rules, which allow the 68 characters of ibc/<hash> denoms and more
+fn validate_reward_asset(asset_info: &AssetInfo) -> StdResult<()> {
+    if let AssetInfo::NativeToken { denom } = asset_info {
+        let mut chars = denom.chars();
+        let valid = (3..=128).contains(&denom.len())
+            && chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+        if !valid {
+            return Err(StdError::generic_err(format!("invalid denom: {}", denom)));
+        }
+    }
+    Ok(())
+}
+
+fn next_reward_schedule_id(schedules: &[RewardSchedule]) -> u64 {
+    schedules
+        .iter()
+        .map(|schedule| schedule.id + 1)
+        .max()
+        .unwrap_or(1)
+}
+
+// open-ended schedules distributing the flat rate from now on
+fn flat_rate_schedules(
+    schedules: &[RewardSchedule],
+    raw_assets: &[AssetRaw],
+    now: u64,
+) -> Vec<RewardSchedule> {
+    let next_id = next_reward_schedule_id(schedules);
+    raw_assets
+        .iter()
+        .filter(|rw| !rw.amount.is_zero())
+        .enumerate()
+        .map(|(i, rw)| RewardSchedule {
+            id: next_id + i as u64,
+            asset_info: rw.info.clone(),
+            amount_per_sec: rw.amount,
+            start_time: now,
+            end_time: None,
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_reward_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    asset_info: AssetInfo,
+    amount_per_sec: Uint128,
+    start_time: u64,
+    end_time: Option<u64>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    config.assert_pool_manager(deps.storage, deps.api, &info.sender, &asset_key)?;
+
+    if read_pool_info(deps.storage, &asset_key)?.deprecated {
+        return Err(StdError::generic_err("pool is deprecated"));
+    }
+    if amount_per_sec.is_zero() {
+        return Err(StdError::generic_err("amount per sec cannot be zero"));
+    }
+    validate_reward_asset(&asset_info)?;
+    if let Some(max_rewards_per_sec) =
+        read_rewards_per_sec_limits(deps.storage, &asset_key)?.max_rewards_per_sec
+    {
+        if amount_per_sec > max_rewards_per_sec {
+            return Err(StdError::generic_err("rewards per sec exceeds the maximum"));
+        }
+    }
+
+    // schedules cannot distribute retroactively
+    let now = env.block.time.seconds();
+    let start_time = start_time.max(now);
+    if let Some(end_time) = end_time {
+        if end_time <= start_time {
+            return Err(StdError::generic_err("schedule must end after it starts"));
+        }
+    }
+
+    let mut schedules = read_reward_schedules(deps.storage, &asset_key)?;
+    if schedules
+        .iter()
+        .filter(|schedule| !schedule.is_ended(now))
+        .count()
+        >= MAX_REWARD_SCHEDULES
+    {
+        return Err(StdError::generic_err(
+            "too many reward schedules for this pool",
+        ));
+    }
+
+    // what was deposited so far keeps the current weights, the next sync leaves them at the reward index
+    let id = next_reward_schedule_id(&schedules);
+    schedules.push(RewardSchedule {
+        id,
+        asset_info: asset_info.to_raw(deps.api)?,
+        amount_per_sec,
+        start_time,
+        end_time,
+    });
+    store_reward_schedules(deps.storage, &asset_key, schedules)?;
+    if read_reward_schedules_synced(deps.storage, &asset_key)?.is_none() {
+        store_reward_schedules_synced(deps.storage, &asset_key, now)?;
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "create_reward_schedule"),
+        attr("staking_token", staking_token.as_str()),
+        attr("id", id.to_string()),
+        attr("amount_per_sec", amount_per_sec.to_string()),
+        attr("start_time", start_time.to_string()),
+        attr(
+            "end_time",
+            end_time
+                .map(|end_time| end_time.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+    ]))
+}
+
+fn cancel_reward_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    id: u64,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    config.assert_pool_manager(deps.storage, deps.api, &info.sender, &asset_key)?;
+
+    let now = env.block.time.seconds();
+    let mut schedules = read_reward_schedules(deps.storage, &asset_key)?;
+    let index = schedules
+        .iter()
+        .position(|schedule| schedule.id == id && !schedule.is_ended(now))
+        .ok_or_else(|| StdError::generic_err("reward schedule not found"))?;
+
+    // a schedule that has not started yet is dropped, otherwise it ends now
+    if schedules[index].start_time >= now {
+        schedules.remove(index);
+    } else {
+        schedules[index].end_time = Some(now);
+    }
+    store_reward_schedules(deps.storage, &asset_key, schedules)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "cancel_reward_schedule"),
+        attr("staking_token", staking_token.as_str()),
+        attr("id", id.to_string()),
+    ]))
+}
+
+fn set_rewards_per_sec_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+    limits: RewardsPerSecLimits,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    store_rewards_per_sec_limits(deps.storage, &asset_key, &limits)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_rewards_per_sec_limits"),
+        attr("staking_token", staking_token.as_str()),
+        attr(
+            "max_rewards_per_sec",
+            limits
+                .max_rewards_per_sec
+                .map(|max| max.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+        attr("cooldown", limits.cooldown.to_string()),
+    ]))
+}
+
+fn add_allowed_staking_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    store_allowed_staking_token(
+        deps.storage,
+        &deps.api.addr_canonicalize(staking_token.as_str())?,
+    )?;
+
+    Ok(Response::new().add_attributes([
+        ("action", "add_allowed_staking_token"),
+        ("staking_token", staking_token.as_str()),
+    ]))
+}
+
+fn remove_allowed_staking_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    // registered pools are not affected
+    state::remove_allowed_staking_token(
+        deps.storage,
+        &deps.api.addr_canonicalize(staking_token.as_str())?,
+    );
+
+    Ok(Response::new().add_attributes([
+        ("action", "remove_allowed_staking_token"),
+        ("staking_token", staking_token.as_str()),
+    ]))
+}
+
+// points the pool to the new staking token once the contract holds enough of it to cover the bonds,
+// stakers, rewards and locks are kept as they are
+fn migrate_staking_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    new_staking_token: Addr,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    let mut pool_info = read_pool_info(deps.storage, &asset_key)?;
+    if is_native_staking_token(deps.storage, &pool_info.staking_token)? {
+        return Err(StdError::generic_err(
+            "native staking tokens cannot be migrated",
+        ));
+    }
+    let new_token_raw = deps.api.addr_canonicalize(new_staking_token.as_str())?;
+    if pool_info.staking_token == new_token_raw {
+        return Err(StdError::generic_err(
+            "new staking token cannot be the current one",
+        ));
+    }
+    // pools already staking the new token share its balance, stakes move between them with MigrateStake
+    let sharing_pools = read_token_pools(deps.storage, &new_token_raw)?;
+    let mut total_bond_amount = pool_info.total_bond_amount;
+    for sharing_key in sharing_pools.iter() {
+        total_bond_amount += read_pool_info(deps.storage, sharing_key)?.total_bond_amount;
+    }
+
+    // the migrated balance must cover every bonded token
+    let balance: Cw20BalanceResponse = deps.querier.query_wasm_smart(
+        new_staking_token.as_str(),
+        &Cw20QueryMsg::Balance {
+            address: env.contract.address.to_string(),
+        },
+    )?;
+    if balance.balance < total_bond_amount {
+        return Err(StdError::generic_err(
+            "new staking token balance is lower than the total bond amount",
+        ));
+    }
+
+    let old_token_raw = pool_info.staking_token;
+    if old_token_raw.as_slice() != asset_key.as_slice() {
+        remove_staking_token_pool(deps.storage, &old_token_raw);
+    }
+    // the pool keeps its key, so stakers, rewards and locks stay where they are
+    // bonds of a shared token keep going to the pool already staking it
+    if sharing_pools.is_empty() {
+        store_staking_token_pool(deps.storage, &new_token_raw, &asset_key)?;
+    }
+    remove_token_pool(deps.storage, &old_token_raw, &asset_key);
+    add_token_pool(deps.storage, &new_token_raw, &asset_key)?;
+    pool_info.staking_token = new_token_raw;
+    store_pool_info(deps.storage, &asset_key, &pool_info)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "migrate_staking_token"),
+        attr("staking_token", staking_token.as_str()),
+        attr(
+            "old_staking_token",
+            deps.api.addr_humanize(&old_token_raw)?.as_str(),
+        ),
+        attr("new_staking_token", new_staking_token.as_str()),
+    ]))
+}
+
+fn sweep(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    recipient: Addr,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset = match &asset_info {
+        AssetInfo::NativeToken { denom } => denom.to_string(),
+        AssetInfo::Token { contract_addr } => contract_addr.to_string(),
+    };
+    let balance = query_asset_balance(&deps.querier, &env.contract.address, &asset_info)?;
+
+    let protected_amount =
+        asset_obligations(deps.storage, deps.api, &asset_info.to_raw(deps.api)?)?;
+    let swept_amount = balance.saturating_sub(protected_amount);
+    if swept_amount.is_zero() {
+        return Err(StdError::generic_err("nothing to sweep"));
+    }
+
+    let msg = Asset {
+        info: asset_info,
+        amount: swept_amount,
+    }
+    .into_msg(None, &deps.querier, recipient.clone())?;
+
+    Ok(Response::new().add_message(msg).add_attributes([
+        attr("action", "sweep"),
+        attr("asset", asset),
+        attr("recipient", recipient.as_str()),
+        attr("swept_amount", swept_amount.to_string()),
+        attr("protected_amount", protected_amount.to_string()),
+    ]))
+}
+
+fn update_min_bond_amount(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+    min_bond_amount: Uint128,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+    // existing positions below the new minimum are kept until they change
+    store_min_bond_amount(deps.storage, &asset_key, min_bond_amount)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_min_bond_amount"),
+        attr("staking_token", staking_token.as_str()),
+        attr("min_bond_amount", min_bond_amount.to_string()),
+    ]))
+}
+
+fn update_min_claim_amount(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+    min_claim_amount: Uint128,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+    store_min_claim_amount(deps.storage, &asset_key, min_claim_amount)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_min_claim_amount"),
+        attr("staking_token", staking_token.as_str()),
+        attr("min_claim_amount", min_claim_amount.to_string()),
+    ]))
+}
+
+fn update_reward_vesting_period(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+    reward_vesting_period: Option<u64>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+    // tranches vesting already keep their unlock times
+    match reward_vesting_period {
+        Some(0) => {
+            return Err(StdError::generic_err(
+                "reward vesting period must be positive",
+            ))
+        }
+        Some(period) => store_reward_vesting_period(deps.storage, &asset_key, period)?,
+        None => remove_reward_vesting_period(deps.storage, &asset_key),
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_reward_vesting_period"),
+        attr("staking_token", staking_token.as_str()),
+        attr(
+            "reward_vesting_period",
+            reward_vesting_period
+                .map(|period| period.to_string())
+                .unwrap_or_default(),
+        ),
+    ]))
+}
+
+fn backfill_snapshots(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    let start_after = start_after
+        .map(|staker| {
+            deps.api
+                .addr_canonicalize(staker.as_str())
+                .map(|raw| raw.to_vec())
+        })
+        .transpose()?;
+    let (updated, last) = backfill_staked_snapshots(
+        deps.storage,
+        deps.api,
+        &asset_key,
+        start_after,
+        limit,
+        env.block.height,
+    )?;
+
+    let mut response = Response::new().add_attributes([
+        attr("action", "backfill_snapshots"),
+        attr("staking_token", staking_token.as_str()),
+        attr("updated", updated.to_string()),
+    ]);
+    // the next page starts after the last staker, no attribute once every staker is written
+    if let Some(last) = last {
+        response = response.add_attribute(
+            "last_staker",
+            deps.api.addr_humanize(&CanonicalAddr::from(last))?,
+        );
+    }
+    Ok(response)
+}
+
+fn migrate_legacy(
+    deps: DepsMut,
+    info: MessageInfo,
+    kind: LegacyKind,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let (migrated, done) = migrate_legacy_entries(deps.storage, &kind, limit);
+    let kind = match kind {
+        LegacyKind::PoolInfos => "pool_infos",
+        LegacyKind::Rewards => "rewards",
+        LegacyKind::Stakers => "stakers",
+        LegacyKind::RewardsPerSec => "rewards_per_sec",
+    };
+    Ok(Response::new().add_attributes([
+        attr("action", "migrate_legacy"),
+        attr("kind", kind),
+        attr("migrated", migrated.to_string()),
+        attr("done", done.to_string()),
+    ]))
+}
+
+fn finalize_import(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+    if !read_import_open(deps.storage)? {
+        return Err(StdError::generic_err("import is closed"));
+    }
+
+    remove_import_open(deps.storage);
+    Ok(Response::new().add_attribute("action", "finalize_import"))
+}
+
+fn deprecate_pool(deps: DepsMut, info: MessageInfo, staking_token: Addr) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    let mut pool_info = read_pool_info(deps.storage, &asset_key)?;
+    if pool_info.deprecated {
+        return Err(StdError::generic_err("pool is deprecated"));
+    }
+
+    // stakers keep what they earned so far, nothing is distributed afterwards
+    change_rewards_per_sec(deps.storage, &asset_key, vec![])?;
+    store_reward_schedules(deps.storage, &asset_key, vec![])?;
+
+    pool_info.deprecated = true;
+    store_pool_info(deps.storage, &asset_key, &pool_info)?;
+
+    Ok(Response::new().add_attributes([
+        ("action", "deprecate_pool"),
+        ("staking_token", staking_token.as_str()),
+    ]))
+}
+
+fn register_asset(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    unbonding_period: Option<u64>,
+    native_token: bool,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    // query asset_key from AssetInfo, a native staking token is a denom
+    let asset_key = if native_token {
+        CanonicalAddr::from(staking_token.as_bytes())
+    } else {
+        deps.api.addr_canonicalize(staking_token.as_str())?
+    };
+    if read_pool_info(deps.storage, &asset_key).is_ok()
+        || read_staking_token_pool(deps.storage, &asset_key)?.is_some()
+    {
+        return Err(StdError::generic_err("Asset was already registered"));
+    }
+    if !is_allowed_staking_token(deps.storage, &asset_key)? {
+        return Err(StdError::generic_err("staking token is not allowed"));
+    }
+
+    store_pool_info(
+        deps.storage,
+        &asset_key.clone(),
+        &PoolInfo {
+            staking_token: asset_key.clone(),
+            total_bond_amount: Uint128::zero(),
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            deprecated: false,
+            total_weighted_amount: None,
+        },
+    )?;
+    add_token_pool(deps.storage, &asset_key, &asset_key)?;
+    REWARD_INDEX.save(
+        deps.storage,
+        asset_key.as_slice(),
+        &Decimal::zero(),
+        env.block.height,
+    )?;
+    store_pool_count(deps.storage, read_pool_count(deps.storage)? + 1)?;
+    if native_token {
+        store_native_staking_token(deps.storage, staking_token.as_str())?;
+    }
+
+    if let Some(unbonding_period) = unbonding_period {
+        if unbonding_period > 0 {
+            store_unbonding_period(deps.storage, &asset_key, unbonding_period)?;
+        }
+    }
+
+    Ok(Response::new().add_attributes([
+        ("action", "register_asset"),
+        ("staking_token", staking_token.as_str()),
+        (
+            "unbonding_period",
+            &unbonding_period.unwrap_or(0).to_string(),
+        ),
+    ]))
+}
+
+fn update_unbonding_period(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    new_period: u64,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    config.assert_pool_manager(deps.storage, deps.api, &info.sender, &asset_key)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+
+    // protect stakers from unbounded increases, shortening is always possible
+    let old_period = read_unbonding_period(deps.storage, &asset_key).unwrap_or_default();
+    if new_period > old_period && new_period > config.max_unbonding_period() {
+        return Err(StdError::generic_err(
+            "unbonding period exceeds the maximum",
+        ));
+    }
+
+    timelock_or_apply(
+        deps,
+        env,
+        &config,
+        ExecuteMsg::UpdateUnbondingPeriod {
+            staking_token,
+            new_period,
+        },
+    )
+}
+
+fn _update_unbonding_period(
+    deps: DepsMut,
+    staking_token: Addr,
+    new_period: u64,
+) -> StdResult<Response> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    let old_period = read_unbonding_period(deps.storage, &asset_key).unwrap_or_default();
+
+    // existing locks keep their unlock time, only future unbonds use the new period
+    if new_period > 0 {
+        store_unbonding_period(deps.storage, &asset_key, new_period)?;
+    } else {
+        remove_unbonding_period(deps.storage, &asset_key);
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_unbonding_period"),
+        attr("staking_token", staking_token.as_str()),
+        attr("old_unbonding_period", old_period.to_string()),
+        attr("new_unbonding_period", new_period.to_string()),
+    ]))
+}
+
+fn update_unbond_fee(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    unbond_fee: Decimal,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    if unbond_fee >= Decimal::one() {
+        return Err(StdError::generic_err("unbond fee must be less than 1"));
+    }
+
+    // make sure the pool exists
+    read_pool_info(
+        deps.storage,
+        &pool_key(deps.storage, deps.api, &staking_token)?,
+    )?;
+
+    timelock_or_apply(
+        deps,
+        env,
+        &config,
+        ExecuteMsg::UpdateUnbondFee {
+            staking_token,
+            unbond_fee,
+        },
+    )
+}
+
+fn _update_unbond_fee(
+    deps: DepsMut,
+    staking_token: Addr,
+    unbond_fee: Decimal,
+) -> StdResult<Response> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+
+    // locks created before keep their amount, only future unbonds are charged
+    store_unbond_fee(deps.storage, &asset_key, unbond_fee)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_unbond_fee"),
+        attr("staking_token", staking_token.as_str()),
+        attr("unbond_fee", unbond_fee.to_string()),
+    ]))
+}
+
+fn update_instant_unbond_penalty(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    penalty: Decimal,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    if penalty >= Decimal::one() {
+        return Err(StdError::generic_err(
+            "instant unbond penalty must be less than 1",
+        ));
+    }
+
+    // make sure the pool exists
+    read_pool_info(
+        deps.storage,
+        &pool_key(deps.storage, deps.api, &staking_token)?,
+    )?;
+
+    timelock_or_apply(
+        deps,
+        env,
+        &config,
+        ExecuteMsg::UpdateInstantUnbondPenalty {
+            staking_token,
+            penalty,
+        },
+    )
+}
+
+fn _update_instant_unbond_penalty(
+    deps: DepsMut,
+    staking_token: Addr,
+    penalty: Decimal,
+) -> StdResult<Response> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+
+    if penalty.is_zero() {
+        remove_instant_unbond_penalty(deps.storage, &asset_key);
+    } else {
+        store_instant_unbond_penalty(deps.storage, &asset_key, penalty)?;
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_instant_unbond_penalty"),
+        attr("staking_token", staking_token.as_str()),
+        attr("penalty", penalty.to_string()),
+    ]))
+}
+
+fn set_stake_migration(
+    deps: DepsMut,
+    info: MessageInfo,
+    from_staking_token: Addr,
+    to_staking_token: Addr,
+    enabled: bool,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let from_asset_key = pool_key(deps.storage, deps.api, &from_staking_token)?;
+    let to_asset_key = pool_key(deps.storage, deps.api, &to_staking_token)?;
+    if enabled {
+        if from_asset_key == to_asset_key {
+            return Err(StdError::generic_err(
+                "cannot migrate stake to the same pool",
+            ));
+        }
+        if read_pool_info(deps.storage, &from_asset_key)?.staking_token
+            != read_pool_info(deps.storage, &to_asset_key)?.staking_token
+        {
+            return Err(StdError::generic_err(
+                "pools do not share the same staking token",
+            ));
+        }
+        store_stake_migration(deps.storage, &from_asset_key, &to_asset_key)?;
+    } else {
+        remove_stake_migration(deps.storage, &from_asset_key, &to_asset_key);
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_stake_migration"),
+        attr("from_staking_token", from_staking_token.as_str()),
+        attr("to_staking_token", to_staking_token.as_str()),
+        attr("enabled", enabled.to_string()),
+    ]))
+}
+
+fn set_claim_delegate(
+    deps: DepsMut,
+    info: MessageInfo,
+    delegate: Option<String>,
+) -> StdResult<Response> {
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let delegate = delegate
+        .map(|delegate| deps.api.addr_validate(&delegate))
+        .transpose()?;
+    match &delegate {
+        Some(delegate) => store_claim_delegate(
+            deps.storage,
+            &staker_raw,
+            &deps.api.addr_canonicalize(delegate.as_str())?,
+        )?,
+        None => remove_claim_delegate(deps.storage, &staker_raw),
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_claim_delegate"),
+        attr("staker_addr", info.sender.as_str()),
+        attr(
+            "delegate",
+            delegate
+                .map(|delegate| delegate.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+    ]))
+}
+
+fn set_reward_split(
+    deps: DepsMut,
+    info: MessageInfo,
+    beneficiary: Option<String>,
+    bps: u16,
+) -> StdResult<Response> {
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let beneficiary = beneficiary
+        .map(|beneficiary| deps.api.addr_validate(&beneficiary))
+        .transpose()?;
+    match &beneficiary {
+        Some(beneficiary) => {
+            if bps == 0 || bps > MAX_REWARD_SPLIT_BPS {
+                return Err(StdError::generic_err(format!(
+                    "reward split bps must be between 1 and {}",
+                    MAX_REWARD_SPLIT_BPS
+                )));
+            }
+            store_reward_split(
+                deps.storage,
+                &staker_raw,
+                &RewardSplit {
+                    beneficiary: deps.api.addr_canonicalize(beneficiary.as_str())?,
+                    bps,
+                },
+            )?
+        }
+        None => remove_reward_split(deps.storage, &staker_raw),
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_reward_split"),
+        attr("staker_addr", info.sender.as_str()),
+        attr(
+            "beneficiary",
+            beneficiary
+                .map(|beneficiary| beneficiary.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+        attr("bps", bps.to_string()),
+    ]))
+}
+
+fn query_reward_split(deps: Deps, staker_addr: Addr) -> StdResult<Option<RewardSplitResponse>> {
+    read_reward_split(
+        deps.storage,
+        &deps.api.addr_canonicalize(staker_addr.as_str())?,
+    )?
+    .map(|reward_split| {
+        Ok(RewardSplitResponse {
+            beneficiary: deps.api.addr_humanize(&reward_split.beneficiary)?,
+            bps: reward_split.bps,
+        })
+    })
+    .transpose()
+}
+
+fn set_pool_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+    pool_admin: Option<Addr>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+
+    match &pool_admin {
+        Some(pool_admin) => store_pool_admin(
+            deps.storage,
+            &asset_key,
+            &deps.api.addr_canonicalize(pool_admin.as_str())?,
+        )?,
+        None => remove_pool_admin(deps.storage, &asset_key),
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_pool_admin"),
+        attr("staking_token", staking_token.as_str()),
+        attr(
+            "pool_admin",
+            pool_admin
+                .map(|pool_admin| pool_admin.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+    ]))
+}
+
+fn set_open_deposit(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+    open_deposit: bool,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+    store_open_deposit(deps.storage, &asset_key, open_deposit)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_open_deposit"),
+        attr("staking_token", staking_token.as_str()),
+        attr("open_deposit", open_deposit.to_string()),
+    ]))
+}
+
+fn update_router(deps: DepsMut, info: MessageInfo, router: Option<Addr>) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    match &router {
+        Some(router) => store_router(deps.storage, &deps.api.addr_canonicalize(router.as_str())?)?,
+        None => remove_router(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_router"),
+        attr(
+            "router",
+            router.map(|router| router.to_string()).unwrap_or_default(),
+        ),
+    ]))
+}
+
+fn update_membership(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Option<Addr>,
+    weight_divisor: Option<Uint128>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let staking_token = match staking_token {
+        Some(staking_token) => staking_token,
+        None => {
+            remove_membership(deps.storage);
+            return Ok(Response::new().add_attribute("action", "update_membership"));
+        }
+    };
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    read_pool_info(deps.storage, &asset_key)?;
+    let weight_divisor = weight_divisor.unwrap_or(Uint128::one());
+    if weight_divisor.is_zero() {
+        return Err(StdError::generic_err("weight divisor cannot be zero"));
+    }
+    store_membership(
+        deps.storage,
+        &Membership {
+            asset_key,
+            weight_divisor,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_membership"),
+        attr("staking_token", staking_token.as_str()),
+        attr("weight_divisor", weight_divisor.to_string()),
+    ]))
+}
+
+fn membership(deps: Deps) -> StdResult<Membership> {
+    read_membership(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("no membership pool configured"))
+}
+
+fn member_weight(amount: Uint128, weight_divisor: Uint128) -> StdResult<u64> {
+    u64::try_from((amount / weight_divisor).u128()).map_err(|_| {
+        StdError::generic_err("weight does not fit in a u64, increase the weight divisor")
+    })
+}
+
+pub fn query_member(deps: Deps, addr: String, at_height: Option<u64>) -> StdResult<MemberResponse> {
+    let membership = membership(deps)?;
+    let addr = deps.api.addr_validate(&addr)?;
+    let key = (membership.asset_key.as_slice(), &addr);
+    let amount = match at_height {
+        Some(height) => STAKED_BALANCES.may_load_at_height(deps.storage, key, height)?,
+        None => STAKED_BALANCES.may_load(deps.storage, key)?,
+    }
+    .unwrap_or_default();
+    // balances below the weight divisor do not make a member
+    let weight = Some(member_weight(amount, membership.weight_divisor)?).filter(|w| *w > 0);
+    Ok(MemberResponse { weight })
+}
+
+pub fn query_list_members(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<MemberListResponse> {
+    let membership = membership(deps)?;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_canonicalize(&addr).map(|addr| addr.to_vec()))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let members = STAKERS
+        .prefix(&membership.asset_key)
+        .keys(
+            deps.storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .map(|item| {
+            let addr = deps.api.addr_humanize(&CanonicalAddr::from(item?))?;
+            let amount = STAKED_BALANCES
+                .may_load(deps.storage, (&membership.asset_key, &addr))?
+                .unwrap_or_default();
+            Ok(Member {
+                addr: addr.to_string(),
+                weight: member_weight(amount, membership.weight_divisor)?,
+            })
+        })
+        // unbonded stakers and the ones below the weight divisor are no members
+        .filter(|item| !matches!(item, Ok(member) if member.weight == 0))
+        .take(limit)
+        .collect::<StdResult<Vec<Member>>>()?;
+    Ok(MemberListResponse { members })
+}
+
+pub fn query_total_weight(deps: Deps, at_height: Option<u64>) -> StdResult<TotalWeightResponse> {
+    let membership = membership(deps)?;
+    let total = match at_height {
+        Some(height) => {
+            STAKED_TOTAL.may_load_at_height(deps.storage, &membership.asset_key, height)?
+        }
+        None => STAKED_TOTAL.may_load(deps.storage, &membership.asset_key)?,
+    }
+    .unwrap_or_default();
+    Ok(TotalWeightResponse {
+        weight: member_weight(total, membership.weight_divisor)?,
+    })
+}
+
+fn query_membership(deps: Deps) -> StdResult<Option<MembershipResponse>> {
+    read_membership(deps.storage)?
+        .map(|membership| {
+            Ok(MembershipResponse {
+                staking_token: pool_staking_token(
+                    deps.storage,
+                    deps.api,
+                    &CanonicalAddr::from(membership.asset_key),
+                )?,
+                weight_divisor: membership.weight_divisor,
+            })
+        })
+        .transpose()
+}
+
+fn update_cw20_stake_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Option<Addr>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let staking_token = match staking_token {
+        Some(staking_token) => staking_token,
+        None => {
+            remove_cw20_stake_pool(deps.storage);
+            return Ok(Response::new().add_attribute("action", "update_cw20_stake_pool"));
+        }
+    };
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    read_pool_info(deps.storage, &asset_key)?;
+    store_cw20_stake_pool(deps.storage, &asset_key)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_cw20_stake_pool"),
+        attr("staking_token", staking_token.as_str()),
+    ]))
+}
+
+fn cw20_stake_pool(deps: Deps) -> StdResult<CanonicalAddr> {
+    read_cw20_stake_pool(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("no cw20-stake pool configured"))
+}
+
+pub fn query_staked_value(deps: Deps, address: String) -> StdResult<StakedValueResponse> {
+    let asset_key = cw20_stake_pool(deps)?;
+    let address = deps.api.addr_validate(&address)?;
+    let value = STAKED_BALANCES
+        .may_load(deps.storage, (&asset_key, &address))?
+        .unwrap_or_default();
+    Ok(StakedValueResponse { value })
+}
+
+pub fn query_total_value(deps: Deps) -> StdResult<TotalValueResponse> {
+    let asset_key = cw20_stake_pool(deps)?;
+    let pool_info = read_pool_info(deps.storage, &asset_key)?;
+    Ok(TotalValueResponse {
+        total: pool_info.total_bond_amount,
+    })
+}
+
+pub fn query_cw20_stake_config(deps: Deps) -> StdResult<Cw20StakeConfigResponse> {
+    let asset_key = cw20_stake_pool(deps)?;
+    let pool_info = read_pool_info(deps.storage, &asset_key)?;
+    Ok(Cw20StakeConfigResponse {
+        token_address: pool_staking_token(deps.storage, deps.api, &pool_info.staking_token)?,
+        // a pool without an unbonding period pays out on unbond
+        unstaking_duration: read_unbonding_period(deps.storage, &asset_key)
+            .ok()
+            .filter(|period| *period > 0)
+            .map(Duration::Time),
+    })
+}
+
+pub fn query_list_stakers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListStakersResponse> {
+    let asset_key = cw20_stake_pool(deps)?;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_canonicalize(&addr).map(|addr| addr.to_vec()))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let stakers = STAKERS
+        .prefix(&asset_key)
+        .keys(
+            deps.storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let address = deps.api.addr_humanize(&CanonicalAddr::from(item?))?;
+            let balance = STAKED_BALANCES
+                .may_load(deps.storage, (&asset_key, &address))?
+                .unwrap_or_default();
+            Ok(StakerBalanceResponse {
+                address: address.to_string(),
+                balance,
+            })
+        })
+        .collect::<StdResult<Vec<StakerBalanceResponse>>>()?;
+    Ok(ListStakersResponse { stakers })
+}
+
+pub fn query_claims(deps: Deps, address: String) -> StdResult<ClaimsResponse> {
+    let asset_key = cw20_stake_pool(deps)?;
+    let address = deps.api.addr_validate(&address)?;
+    // locks are stored under the token the pool was registered with
+    let staking_token = pool_staking_token(deps.storage, deps.api, &asset_key)?;
+
+    // cw20-stake returns every claim at once, so the lock pages are walked to the end
+    let mut claims = vec![];
+    let mut start_after = None;
+    loop {
+        let locks = read_user_lock_info(
+            deps.storage,
+            staking_token.as_bytes(),
+            address.as_bytes(),
+            start_after,
+            Some(MAX_LIMIT),
+            None,
+            None,
+            None,
+        )?;
+        start_after = match locks.last() {
+            Some(lock) => Some(lock.unlock_time.seconds()),
+            None => break,
+        };
+        claims.extend(locks.into_iter().map(|lock| Claim {
+            amount: lock.amount,
+            release_at: Expiration::AtTime(lock.unlock_time),
+        }));
+    }
+    Ok(ClaimsResponse { claims })
+}
+
+fn query_router(deps: Deps) -> StdResult<Option<Addr>> {
+    read_router(deps.storage)?
+        .map(|router| deps.api.addr_humanize(&router))
+        .transpose()
+}
+
+fn set_accrual_mode(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    mode: AccrualMode,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+    // what the pull mode owes until now is accrued before leaving it
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key)?;
+    match mode {
+        AccrualMode::Push => remove_last_distributed(deps.storage, &asset_key),
+        AccrualMode::Pull => {
+            if read_accrual_mode(deps.storage, &asset_key)? != AccrualMode::Pull {
+                store_last_distributed(deps.storage, &asset_key, env.block.time.seconds())?;
+            }
+        }
+    }
+    store_accrual_mode(deps.storage, &asset_key, &mode)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_accrual_mode"),
+        attr("staking_token", staking_token.as_str()),
+        attr(
+            "mode",
+            match mode {
+                AccrualMode::Push => "push",
+                AccrualMode::Pull => "pull",
+            },
+        ),
+    ]))
+}
+
+fn set_boost_curve(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+    curve: Option<BoostCurve>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+    // running boosts keep their multiplier until they expire
+    match &curve {
+        Some(curve) => {
+            if curve.max_multiplier < Decimal::one() {
+                return Err(StdError::generic_err(
+                    "max multiplier cannot be lower than 1",
+                ));
+            }
+            if curve.max_lock_weeks == 0 {
+                return Err(StdError::generic_err("max lock weeks cannot be zero"));
+            }
+            store_boost_curve(deps.storage, &asset_key, curve)?;
+        }
+        None => remove_boost_curve(deps.storage, &asset_key),
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_boost_curve"),
+        attr("staking_token", staking_token.as_str()),
+        attr(
+            "max_multiplier",
+            curve
+                .as_ref()
+                .map_or("none".to_string(), |curve| curve.max_multiplier.to_string()),
+        ),
+        attr(
+            "max_lock_weeks",
+            curve
+                .as_ref()
+                .map_or("none".to_string(), |curve| curve.max_lock_weeks.to_string()),
+        ),
+    ]))
+}
+
+fn set_pool_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+    status: PoolStatus,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    config.assert_owner_or_pool_admin(deps.storage, deps.api, &info.sender, &asset_key)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+
+    store_pool_status(deps.storage, &asset_key, &status)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_pool_status"),
+        attr("staking_token", staking_token.as_str()),
+        attr("bond_paused", status.bond_paused.to_string()),
+        attr("unbond_paused", status.unbond_paused.to_string()),
+        attr("withdraw_paused", status.withdraw_paused.to_string()),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::VotingPowerAtHeight { address, height } => {
+            to_binary(&query_voting_power_at_height(deps, env, address, height)?)
+        }
+        QueryMsg::TotalPowerAtHeight { height } => {
+            to_binary(&query_total_power_at_height(deps, env, height)?)
+        }
+        QueryMsg::Info {} => to_binary(&InfoResponse {
+            info: get_contract_version(deps.storage)?,
+        }),
+        QueryMsg::OwnershipProposal {} => to_binary(&query_ownership_proposal(deps)?),
+        QueryMsg::PendingRewarder {} => to_binary(&query_pending_rewarder(deps)?),
+        QueryMsg::Paused {} => to_binary(&query_paused(deps)?),
+        QueryMsg::PendingChanges { start_after, limit } => {
+            to_binary(&query_pending_changes(deps, start_after, limit)?)
+        }
+        QueryMsg::Rewarders { start_after, limit } => {
+            to_binary(&query_rewarders(deps, start_after, limit)?)
+        }
+        QueryMsg::Hooks {} => to_binary(&query_hooks(deps)?),
+        QueryMsg::ClaimHooks {} => to_binary(&query_claim_hooks(deps)?),
+        QueryMsg::HookFailures {} => to_binary(&query_hook_failures(deps)?),
+        QueryMsg::Operators { start_after, limit } => {
+            to_binary(&query_operators(deps, start_after, limit)?)
+        }
+        QueryMsg::AllowedStakingTokens { start_after, limit } => {
+            to_binary(&query_allowed_staking_tokens(deps, start_after, limit)?)
+        }
+        QueryMsg::Blacklist { start_after, limit } => {
+            to_binary(&query_blacklist(deps, start_after, limit)?)
+        }
+        QueryMsg::IsBlacklisted { address } => to_binary(&is_blacklisted(
+            deps.storage,
+            &deps.api.addr_canonicalize(address.as_str())?,
+        )?),
+        QueryMsg::ClaimDelegate { staker_addr } => to_binary(
+            &read_claim_delegate(
+                deps.storage,
+                &deps.api.addr_canonicalize(staker_addr.as_str())?,
+            )?
+            .map(|delegate| deps.api.addr_humanize(&delegate))
+            .transpose()?,
+        ),
+        QueryMsg::PoolInfo { staking_token } => to_binary(&query_pool_info(deps, staking_token)?),
+        QueryMsg::PoolValue { asset_info } => to_binary(&query_pool_value(deps, asset_info)?),
+        QueryMsg::RewardsPerSec { staking_token } => {
+            to_binary(&query_rewards_per_sec(deps, staking_token)?)
+        }
+        QueryMsg::PoolApr { staking_token } => to_binary(&query_pool_apr(deps, staking_token)?),
+        QueryMsg::Router {} => to_binary(&query_router(deps)?),
+        QueryMsg::Member { addr, at_height } => to_binary(&query_member(deps, addr, at_height)?),
+        QueryMsg::ListMembers { start_after, limit } => {
+            to_binary(&query_list_members(deps, start_after, limit)?)
+        }
+        QueryMsg::TotalWeight { at_height } => to_binary(&query_total_weight(deps, at_height)?),
+        QueryMsg::Membership {} => to_binary(&query_membership(deps)?),
+        QueryMsg::StakedValue { address } => to_binary(&query_staked_value(deps, address)?),
+        QueryMsg::TotalValue {} => to_binary(&query_total_value(deps)?),
+        QueryMsg::GetConfig {} => to_binary(&query_cw20_stake_config(deps)?),
+        QueryMsg::ListStakers { start_after, limit } => {
+            to_binary(&query_list_stakers(deps, start_after, limit)?)
+        }
+        QueryMsg::Claims { address } => to_binary(&query_claims(deps, address)?),
+        QueryMsg::OutstandingRewards {} => to_binary(&query_outstanding_rewards(deps)?),
+        QueryMsg::RewardSplit { staker_addr } => to_binary(&query_reward_split(deps, staker_addr)?),
+        QueryMsg::AccrualMode { staking_token } => {
+            to_binary(&query_accrual_mode(deps, staking_token)?)
+        }
+        QueryMsg::RewardDust { staking_token } => {
+            to_binary(&query_reward_dust(deps, staking_token)?)
+        }
+        QueryMsg::RewardSchedules {
+            staking_token,
+            start_after,
+            limit,
+        } => to_binary(&query_reward_schedules(
+            deps,
+            env,
+            staking_token,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::RewardInfo {
+            staker_addr,
+            staking_token,
+        } => to_binary(&query_reward_info(deps, staker_addr, staking_token)?),
+        QueryMsg::Boost {
+            staker_addr,
+            staking_token,
+        } => to_binary(&query_boost(deps, staker_addr, staking_token)?),
+        QueryMsg::PendingRewards {
+            staker_addr,
+            staking_token,
+        } => to_binary(&query_pending_rewards(deps, staker_addr, staking_token)?),
+        QueryMsg::RewardPoolCount { staker_addr } => {
+            to_binary(&query_reward_pool_count(deps, staker_addr)?)
+        }
+        QueryMsg::PendingWithdraw {
+            staker_addr,
+            staking_token,
+        } => to_binary(&query_pending_withdraw(deps, staker_addr, staking_token)?),
+        QueryMsg::RewardVestings {
+            staker_addr,
+            staking_token,
+        } => to_binary(&query_reward_vestings(
+            deps,
+            env,
+            staker_addr,
+            staking_token,
+        )?),
+        QueryMsg::RewardInfos {
+            staking_token,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_all_reward_infos(
+            deps,
+            staking_token,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::PoolStakers {
+            staking_token,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_pool_stakers(
+            deps,
+            staking_token,
+            start_after,
+            limit,
+            order,
         )?),
         QueryMsg::GetPoolsInformation {} => to_binary(&query_get_pools_infomation(deps)?),
+        QueryMsg::Pools {
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_pools(deps, start_after, limit, order)?),
+        QueryMsg::Summary {
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_summary(deps, start_after, limit, order)?),
+        QueryMsg::UnbondingPeriods {
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_unbonding_periods(deps, start_after, limit, order)?),
+        QueryMsg::AllRewardsPerSec {
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_all_rewards_per_sec(deps, start_after, limit, order)?),
+        QueryMsg::UnbondingPeriod {
+            staking_token,
+            staker_addr,
+            start_after,
+            limit,
+        } => to_binary(&query_unbonding_period(
+            deps,
+            staking_token,
+            staker_addr,
+            start_after,
+            limit,
+        )?),
         QueryMsg::LockInfos {
             staker_addr,
             staking_token,
             start_after,
             limit,
             order,
+            start_time,
+            end_time,
         } => to_binary(&query_lock_infos(
             deps,
             env,
@@ -244,7 +2686,70 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             start_after,
             limit,
             order,
+            start_time,
+            end_time,
+        )?),
+        QueryMsg::PoolLocks {
+            staking_token,
+            start_after,
+            limit,
+        } => to_binary(&query_pool_locks(deps, staking_token, start_after, limit)?),
+        QueryMsg::IsStaker {
+            staking_token,
+            staker_addr,
+        } => to_binary(&query_is_staker(deps, staking_token, staker_addr)?),
+        QueryMsg::PoolByStakingToken { staking_token } => {
+            to_binary(&query_pool_by_staking_token(deps, staking_token)?)
+        }
+        QueryMsg::PoolsByStakingToken { staking_token } => {
+            to_binary(&query_pools_by_staking_token(deps, staking_token)?)
+        }
+        QueryMsg::StakerCount { staking_token } => {
+            to_binary(&query_staker_count(deps, staking_token)?)
+        }
+        QueryMsg::TotalUnbonding { staking_token } => {
+            to_binary(&query_total_unbonding(deps, staking_token)?)
+        }
+        QueryMsg::Claimable {
+            staker_addr,
+            staking_token,
+            at_time,
+        } => to_binary(&query_claimable(
+            deps,
+            env,
+            staker_addr,
+            staking_token,
+            at_time,
+        )?),
+        QueryMsg::UnbondingSummary {
+            staker_addr,
+            staking_token,
+        } => to_binary(&query_unbonding_summary(
+            deps,
+            env,
+            staker_addr,
+            staking_token,
+        )?),
+        QueryMsg::RewardInfosBatch {
+            staking_token,
+            stakers,
+        } => to_binary(&query_reward_infos_batch(deps, staking_token, stakers)?),
+        QueryMsg::AllStakerInfo {
+            staker_addr,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_all_staker_info(
+            deps,
+            staker_addr,
+            start_after,
+            limit,
+            order,
         )?),
+        QueryMsg::LockCount {
+            staker_addr,
+            staking_token,
+        } => to_binary(&query_lock_count(deps, staker_addr, staking_token)?),
         QueryMsg::StakedBalanceAtHeight {
             asset_key,
             address,
@@ -255,64 +2760,718 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::TotalStakedAtHeight { asset_key, height } => {
             to_binary(&query_total_staked_at_height(deps, env, asset_key, height)?)
         }
+        QueryMsg::ExportState {
+            kind,
+            start_after,
+            limit,
+        } => to_binary(&query_export_state(deps, kind, start_after, limit)?),
+        QueryMsg::SnapshotConsistency { staking_token } => {
+            to_binary(&query_snapshot_consistency(deps, staking_token)?)
+        }
+        QueryMsg::StakedBalanceAtTime {
+            asset_key,
+            address,
+            time,
+        } => to_binary(&query_staked_balance_at_time(
+            deps, asset_key, address, time,
+        )?),
+        QueryMsg::StakedBalanceChangelog {
+            asset_key,
+            address,
+            start_after_height,
+            limit,
+        } => to_binary(&query_staked_balance_changelog(
+            deps,
+            asset_key,
+            address,
+            start_after_height,
+            limit,
+        )?),
+        QueryMsg::StakedTotalChangelog {
+            asset_key,
+            start_after_height,
+            limit,
+        } => to_binary(&query_staked_total_changelog(
+            deps,
+            asset_key,
+            start_after_height,
+            limit,
+        )?),
+        QueryMsg::RewardStateAtHeight {
+            staker_addr,
+            staking_token,
+            height,
+        } => to_binary(&query_reward_state_at_height(
+            deps,
+            env,
+            staker_addr,
+            staking_token,
+            height,
+        )?),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn query_lock_infos(
+    deps: Deps,
+    _env: Env,
+    staker_addr: Addr,
+    staking_token: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<i32>,
+    start_time: Option<u64>,
+    end_time: Option<u64>,
+) -> StdResult<LockInfosResponse> {
+    let lock_infos = read_user_lock_info(
+        deps.storage,
+        staking_token.as_bytes(),
+        staker_addr.as_bytes(),
+        start_after,
+        limit,
+        order,
+        start_time,
+        end_time,
+    )?;
+    Ok(LockInfosResponse {
+        staker_addr,
+        staking_token,
+        lock_infos: lock_infos
+            .into_iter()
+            .map(|lock| LockInfoResponse {
+                amount: lock.amount,
+                unlock_time: lock.unlock_time.seconds(),
+            })
+            .collect(),
+    })
+}
+
+pub fn query_pool_locks(
+    deps: Deps,
+    staking_token: Addr,
+    start_after: Option<(Addr, u64)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PoolLocksResponseItem>> {
+    let locks = read_pool_locks(
+        deps.storage,
+        staking_token.as_bytes(),
+        start_after
+            .map(|(staker_addr, unlock_time)| (staker_addr.as_bytes().to_vec(), unlock_time)),
+        limit,
+    )?;
+    locks
+        .into_iter()
+        .map(|(user, lock)| {
+            Ok(PoolLocksResponseItem {
+                staker_addr: Addr::unchecked(
+                    String::from_utf8(user).map_err(StdError::invalid_utf8)?,
+                ),
+                unlock_time: lock.unlock_time.seconds(),
+                amount: lock.amount,
+            })
+        })
+        .collect()
+}
+
+pub fn query_is_staker(
+    deps: Deps,
+    staking_token: Addr,
+    staker_addr: Addr,
+) -> StdResult<IsStakerResponse> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let bond_amount = REWARDS
+        .may_load(deps.storage, (&staker_addr_raw, &asset_key))?
+        .map(|reward_info| reward_info.bond_amount)
+        .unwrap_or_default();
+    Ok(IsStakerResponse {
+        is_staker: !bond_amount.is_zero(),
+        bond_amount,
+    })
+}
+
+pub fn query_staker_count(deps: Deps, staking_token: Addr) -> StdResult<StakerCountResponse> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+    Ok(StakerCountResponse {
+        staking_token,
+        staker_count: read_staker_count(deps.storage, &asset_key)?,
+    })
+}
+
+pub fn query_total_unbonding(deps: Deps, staking_token: Addr) -> StdResult<TotalUnbondingResponse> {
+    // make sure the pool exists
+    read_pool_info(
+        deps.storage,
+        &pool_key(deps.storage, deps.api, &staking_token)?,
+    )?;
+    Ok(TotalUnbondingResponse {
+        total_unbonding: read_total_unbonding(deps.storage, staking_token.as_bytes())?,
+        staking_token,
+    })
+}
+
+pub fn query_claimable(
+    deps: Deps,
+    env: Env,
+    staker_addr: Addr,
+    staking_token: Addr,
+    at_time: Option<u64>,
+) -> StdResult<ClaimableResponse> {
+    let at_time = at_time.map_or(env.block.time, Timestamp::from_seconds);
+    let (amount, lock_count) = read_user_matured_locks(
+        deps.storage,
+        staking_token.as_bytes(),
+        staker_addr.as_bytes(),
+        at_time,
+    )?;
+    Ok(ClaimableResponse {
+        amount,
+        lock_count,
+        at_time: at_time.seconds(),
+    })
+}
+
+pub fn query_unbonding_summary(
+    deps: Deps,
+    env: Env,
+    staker_addr: Addr,
+    staking_token: Addr,
+) -> StdResult<UnbondingSummaryResponse> {
+    let asset_key = staking_token.as_bytes();
+    let user = staker_addr.as_bytes();
+    // locks are ascending by unlock time, so the first one unlocks next
+    let next_unlock_time = read_user_lock_info(
+        deps.storage,
+        asset_key,
+        user,
+        None,
+        Some(1),
+        None,
+        None,
+        None,
+    )?
+    .first()
+    .map(|lock| lock.unlock_time.seconds());
+    Ok(UnbondingSummaryResponse {
+        total_locked: read_user_lock_amount(deps.storage, asset_key, user)?,
+        lock_count: read_user_lock_count(deps.storage, asset_key, user)?,
+        next_unlock_time,
+        matured_amount: read_user_matured_locks(deps.storage, asset_key, user, env.block.time)?.0,
+        staker_addr,
+        staking_token,
+    })
+}
+
+pub fn query_lock_count(
+    deps: Deps,
+    staker_addr: Addr,
+    staking_token: Addr,
+) -> StdResult<LockCountResponse> {
+    Ok(LockCountResponse {
+        count: read_user_lock_count(
+            deps.storage,
+            staking_token.as_bytes(),
+            staker_addr.as_bytes(),
+        )?,
+        max_lock_entries: read_config(deps.storage)?.max_lock_entries(),
+    })
+}
+
+pub fn query_unbonding_period(
+    deps: Deps,
+    staking_token: Addr,
+    staker_addr: Option<Addr>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<UnbondingPeriodResponse> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    let locks = match staker_addr {
+        Some(staker_addr) => read_user_lock_info(
+            deps.storage,
+            staking_token.as_bytes(),
+            staker_addr.as_bytes(),
+            start_after,
+            limit,
+            None,
+            None,
+            None,
+        )?
+        .into_iter()
+        .map(|lock| {
+            Ok(LockPeriodResponse {
+                amount: lock.amount,
+                unlock_time: lock.unlock_time.seconds(),
+                unbonding_period: read_lock_period(
+                    deps.storage,
+                    staking_token.as_bytes(),
+                    staker_addr.as_bytes(),
+                    lock.unlock_time,
+                )?,
+            })
+        })
+        .collect::<StdResult<Vec<LockPeriodResponse>>>()?,
+        None => vec![],
+    };
+
+    Ok(UnbondingPeriodResponse {
+        unbonding_period: read_unbonding_period(deps.storage, &asset_key).ok(),
+        locks,
+    })
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let state = read_config(deps.storage)?;
+    let contract_version = get_contract_version(deps.storage)?;
+    let resp = ConfigResponse {
+        owner: state
+            .owner
+            .map(|owner| deps.api.addr_humanize(&owner))
+            .transpose()?,
+        fee_collector: state
+            .fee_collector
+            .map(|fee_collector| deps.api.addr_humanize(&fee_collector))
+            .transpose()?,
+        config_timelock: state.config_timelock,
+        max_unbonding_period: state.max_unbonding_period(),
+        max_lock_entries: state.max_lock_entries(),
+        governance_token: state.governance_token,
+        oracle: state
+            .oracle
+            .map(|oracle| deps.api.addr_humanize(&oracle))
+            .transpose()?,
+        contract_name: contract_version.contract,
+        contract_version: contract_version.version,
+        pool_count: read_pool_count(deps.storage)?,
+        import_open: read_import_open(deps.storage)?,
+    };
+
+    Ok(resp)
+}
+
+pub fn query_ownership_proposal(deps: Deps) -> StdResult<Option<OwnershipProposalResponse>> {
+    read_ownership_proposal(deps.storage)?
+        .map(|proposal| {
+            Ok(OwnershipProposalResponse {
+                owner: deps.api.addr_humanize(&proposal.owner)?,
+                expires_at: proposal.expires_at.seconds(),
+            })
+        })
+        .transpose()
+}
+
+pub fn query_pending_rewarder(deps: Deps) -> StdResult<Option<RewarderProposalResponse>> {
+    read_pending_rewarder(deps.storage)?
+        .map(|proposal| {
+            Ok(RewarderProposalResponse {
+                rewarder: deps.api.addr_humanize(&proposal.rewarder)?,
+                replace: proposal
+                    .replace
+                    .map(|replace| deps.api.addr_humanize(&replace))
+                    .transpose()?,
+            })
+        })
+        .transpose()
+}
+
+pub fn query_pending_changes(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PendingChangeResponse>> {
+    Ok(read_pending_changes(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(id, pending_change)| PendingChangeResponse {
+            id,
+            msg: pending_change.msg,
+            execute_after: pending_change.execute_after.seconds(),
+        })
+        .collect())
+}
+
+pub fn query_paused(deps: Deps) -> StdResult<PausedResponse> {
+    let height = read_paused(deps.storage)?;
+    Ok(PausedResponse {
+        paused: height.is_some(),
+        height,
+    })
+}
+
+pub fn query_rewarders(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let start_after = start_after
+        .map(|a| deps.api.addr_canonicalize(a.as_str()))
+        .transpose()?;
+    read_rewarders(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|rewarder| deps.api.addr_humanize(&rewarder))
+        .collect()
+}
+
+pub fn query_hooks(deps: Deps) -> StdResult<HooksResponse> {
+    Ok(HooksResponse {
+        hooks: read_hooks(deps.storage)?
+            .into_iter()
+            .map(|hook| Ok(deps.api.addr_humanize(&hook)?.to_string()))
+            .collect::<StdResult<Vec<String>>>()?,
+    })
+}
+
+pub fn query_claim_hooks(deps: Deps) -> StdResult<HooksResponse> {
+    Ok(HooksResponse {
+        hooks: read_claim_hooks(deps.storage)?
+            .into_iter()
+            .map(|hook| Ok(deps.api.addr_humanize(&hook)?.to_string()))
+            .collect::<StdResult<Vec<String>>>()?,
+    })
+}
+
+pub fn query_hook_failures(deps: Deps) -> StdResult<Vec<HookFailuresResponseItem>> {
+    let mut items = vec![];
+    for kind in [HookKind::Stake, HookKind::Claim] {
+        for hook in read_hooks_of(deps.storage, &kind)? {
+            let failures = read_hook_failures(deps.storage, &kind, &hook)?;
+            items.push(HookFailuresResponseItem {
+                kind: kind.clone(),
+                hook: deps.api.addr_humanize(&hook)?.to_string(),
+                failures,
+                disabled: failures >= MAX_HOOK_FAILURES,
+            });
+        }
     }
+    Ok(items)
 }
 
-pub fn query_lock_infos(
+pub fn query_operators(
     deps: Deps,
-    _env: Env,
-    staker_addr: Addr,
-    staking_token: Addr,
-    start_after: Option<u64>,
+    start_after: Option<Addr>,
     limit: Option<u32>,
-    order: Option<i32>,
-) -> StdResult<LockInfosResponse> {
-    let lock_infos = read_user_lock_info(
-        deps.storage,
-        staking_token.as_bytes(),
-        staker_addr.as_bytes(),
-        start_after,
-        limit,
-        order,
-    )?;
-    Ok(LockInfosResponse {
-        staker_addr,
-        staking_token,
-        lock_infos: lock_infos
-            .into_iter()
-            .map(|lock| LockInfoResponse {
-                amount: lock.amount,
-                unlock_time: lock.unlock_time.seconds(),
-            })
-            .collect(),
-    })
+) -> StdResult<Vec<Addr>> {
+    let start_after = start_after
+        .map(|a| deps.api.addr_canonicalize(a.as_str()))
+        .transpose()?;
+    read_operators(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|operator| deps.api.addr_humanize(&operator))
+        .collect()
 }
 
-pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let state = read_config(deps.storage)?;
-    let resp = ConfigResponse {
-        owner: deps.api.addr_humanize(&state.owner)?,
-        rewarder: deps.api.addr_humanize(&state.rewarder)?,
-    };
+pub fn query_blacklist(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let start_after = start_after
+        .map(|a| deps.api.addr_canonicalize(a.as_str()))
+        .transpose()?;
+    read_blacklist(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|address| deps.api.addr_humanize(&address))
+        .collect()
+}
 
-    Ok(resp)
+pub fn query_allowed_staking_tokens(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let start_after = start_after
+        .map(|a| deps.api.addr_canonicalize(a.as_str()))
+        .transpose()?;
+    read_allowed_staking_tokens(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|staking_token| deps.api.addr_humanize(&staking_token))
+        .collect()
 }
 
 pub fn query_pool_info(deps: Deps, staking_token: Addr) -> StdResult<PoolInfoResponse> {
-    let asset_key = deps.api.addr_canonicalize(staking_token.as_str())?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    _pool_info_response(deps, &asset_key)
+}
+
+pub fn query_pool_value(deps: Deps, asset_info: AssetInfo) -> StdResult<PoolValueResponse> {
+    let staking_token = match &asset_info {
+        AssetInfo::Token { contract_addr } => contract_addr.clone(),
+        AssetInfo::NativeToken { denom } => Addr::unchecked(denom),
+    };
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
     let pool_info = read_pool_info(deps.storage, &asset_key)?;
-    let unbonding_period = read_unbonding_period(deps.storage, &asset_key).ok();
+
+    let oracle = match read_config(deps.storage)?.oracle {
+        Some(oracle) => deps.api.addr_humanize(&oracle)?,
+        None => {
+            return Ok(PoolValueResponse {
+                asset_info,
+                total_bond_amount: pool_info.total_bond_amount,
+                price: None,
+                value: None,
+            })
+        }
+    };
+    let res: ExchangeRateResponse = deps
+        .querier
+        .query_wasm_smart(
+            oracle,
+            &OracleQueryMsg::Exchange(ExchangeQueryMsg::ExchangeRate {
+                base_denom: Some(staking_token.to_string()),
+                quote_denom: ORACLE_QUOTE_DENOM.to_string(),
+            }),
+        )
+        .map_err(|err| {
+            StdError::generic_err(format!(
+                "cannot query the oracle price of {}: {}",
+                staking_token, err
+            ))
+        })?;
+    let price = res.item.exchange_rate;
+
+    Ok(PoolValueResponse {
+        asset_info,
+        total_bond_amount: pool_info.total_bond_amount,
+        price: Some(price),
+        value: Some(
+            pool_info
+                .total_bond_amount
+                .checked_multiply_ratio(price.atomics(), Decimal::one().atomics())
+                .map_err(|_| StdError::generic_err("pool value overflow"))?,
+        ),
+    })
+}
+
+/// the pool bonds of the token go to, PoolsByStakingToken lists every pool sharing it
+pub fn query_pool_by_staking_token(
+    deps: Deps,
+    staking_token: String,
+) -> StdResult<Vec<PoolInfoResponse>> {
+    let token_raw = pool_key(deps.storage, deps.api, &Addr::unchecked(staking_token))?;
+    // a migrated staking token points to the pool registered for the original one
+    let asset_key = read_staking_token_pool(deps.storage, &token_raw)?
+        .map(CanonicalAddr::from)
+        .unwrap_or_else(|| token_raw.clone());
+    match read_pool_info(deps.storage, &asset_key) {
+        Ok(pool_info) if pool_info.staking_token == token_raw => {
+            Ok(vec![_pool_info_response(deps, &asset_key)?])
+        }
+        _ => Err(StdError::not_found("pool")),
+    }
+}
+
+pub fn query_pools_by_staking_token(deps: Deps, staking_token: String) -> StdResult<Vec<Addr>> {
+    let token_raw = pool_key(deps.storage, deps.api, &Addr::unchecked(staking_token))?;
+    read_token_pools(deps.storage, &token_raw)?
+        .into_iter()
+        .map(|asset_key| {
+            pool_staking_token(deps.storage, deps.api, &CanonicalAddr::from(asset_key))
+        })
+        .collect()
+}
+
+fn _pool_info_response(deps: Deps, asset_key: &CanonicalAddr) -> StdResult<PoolInfoResponse> {
+    let pool_info = read_pool_info(deps.storage, asset_key)?;
+    let unbonding_period = read_unbonding_period(deps.storage, asset_key).ok();
+    // the locks are keyed by the token the pool was registered with
+    let total_unbonding = read_total_unbonding(
+        deps.storage,
+        pool_staking_token(deps.storage, deps.api, asset_key)?.as_bytes(),
+    )?;
+    let staking_token = pool_staking_token(deps.storage, deps.api, &pool_info.staking_token)?;
     Ok(PoolInfoResponse {
-        staking_token: deps.api.addr_humanize(&pool_info.staking_token)?,
+        staking_token,
         total_bond_amount: pool_info.total_bond_amount,
+        total_weighted_amount: pool_info.total_weight(),
         reward_index: pool_info.reward_index,
         pending_reward: pool_info.pending_reward,
         unbonding_period,
+        unbond_fee: read_unbond_fee(deps.storage, asset_key)?,
+        instant_unbond_penalty: read_instant_unbond_penalty(deps.storage, asset_key)?,
+        min_bond_amount: read_min_bond_amount(deps.storage, asset_key)?,
+        min_claim_amount: read_min_claim_amount(deps.storage, asset_key)?,
+        total_unbonding,
+        staker_count: read_staker_count(deps.storage, asset_key)?,
+        open_deposit: is_open_deposit(deps.storage, asset_key)?,
+        deprecated: pool_info.deprecated,
+        pool_admin: read_pool_admin(deps.storage, asset_key)?
+            .map(|pool_admin| deps.api.addr_humanize(&pool_admin))
+            .transpose()?,
+        status: read_pool_status(deps.storage, asset_key)?,
+    })
+}
+
+pub fn query_pool_stakers(
+    deps: Deps,
+    staking_token: Addr,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    order: Option<i32>,
+) -> StdResult<Vec<PoolStakersResponseItem>> {
+    let order_by = Order::try_from(order.unwrap_or(1))?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+
+    let start_after = start_after
+        .map(|staker_addr| deps.api.addr_canonicalize(staker_addr.as_str()))
+        .transpose()?
+        .map(|staker_addr| staker_addr.to_vec());
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let (start, end) = page_bounds(start_after.as_deref(), order_by);
+
+    // stakers left with only rewards to withdraw are skipped
+    STAKERS
+        .prefix(&asset_key)
+        .keys(deps.storage, start, end, order_by)
+        .map(|item| {
+            let staker_addr = item?;
+            let bond_amount = REWARDS
+                .may_load(deps.storage, (&staker_addr, &asset_key))?
+                .map(|reward_info| reward_info.bond_amount)
+                .unwrap_or_default();
+            Ok((CanonicalAddr::from(staker_addr), bond_amount))
+        })
+        .filter(|item| !matches!(item, Ok((_, bond_amount)) if bond_amount.is_zero()))
+        .take(limit)
+        .map(|item: StdResult<(CanonicalAddr, Uint128)>| {
+            let (staker_addr, bond_amount) = item?;
+            Ok(PoolStakersResponseItem {
+                staker_addr: deps.api.addr_humanize(&staker_addr)?,
+                bond_amount,
+            })
+        })
+        .collect()
+}
+
+pub fn query_pools(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    order: Option<i32>,
+) -> StdResult<Vec<PoolsResponseItem>> {
+    let start_after = start_after
+        .map(|staking_token| pool_key(deps.storage, deps.api, &staking_token))
+        .transpose()?
+        .map(|asset_key| asset_key.to_vec());
+
+    read_pool_infos(deps.storage, start_after, limit, order)?
+        .into_iter()
+        .map(|(asset_key, pool_info)| {
+            Ok(PoolsResponseItem {
+                staking_token: pool_staking_token(
+                    deps.storage,
+                    deps.api,
+                    &CanonicalAddr::from(asset_key.clone()),
+                )?,
+                total_bond_amount: pool_info.total_bond_amount,
+                reward_index: pool_info.reward_index,
+                pending_reward: pool_info.pending_reward,
+                unbonding_period: read_unbonding_period(deps.storage, &asset_key).ok(),
+                rewards_per_sec: read_rewards_per_sec(deps.storage, &asset_key)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|rw| rw.to_normal(deps.api))
+                    .collect::<StdResult<Vec<Asset>>>()?,
+            })
+        })
+        .collect()
+}
+
+pub fn query_summary(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    order: Option<i32>,
+) -> StdResult<SummaryResponse> {
+    let start_after = start_after
+        .map(|staking_token| pool_key(deps.storage, deps.api, &staking_token))
+        .transpose()?
+        .map(|asset_key| asset_key.to_vec());
+
+    // only the maintained counters are read, a page never walks the stakers of a pool
+    let pools = read_pool_infos(deps.storage, start_after, limit, order)?
+        .into_iter()
+        .map(|(asset_key, pool_info)| {
+            let staking_token = pool_staking_token(
+                deps.storage,
+                deps.api,
+                &CanonicalAddr::from(asset_key.clone()),
+            )?;
+            Ok(SummaryPoolItem {
+                total_bond_amount: pool_info.total_bond_amount,
+                total_unbonding: read_total_unbonding(deps.storage, staking_token.as_bytes())?,
+                staker_count: read_staker_count(deps.storage, &asset_key)?,
+                staking_token,
+            })
+        })
+        .collect::<StdResult<Vec<SummaryPoolItem>>>()?;
+
+    Ok(SummaryResponse {
+        version: get_contract_version(deps.storage)?,
+        pool_count: read_pool_count(deps.storage)?,
+        pools,
     })
 }
 
+pub fn query_unbonding_periods(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    order: Option<i32>,
+) -> StdResult<Vec<UnbondingPeriodsResponseItem>> {
+    let start_after = start_after
+        .map(|staking_token| pool_key(deps.storage, deps.api, &staking_token))
+        .transpose()?
+        .map(|asset_key| asset_key.to_vec());
+
+    read_unbonding_periods(deps.storage, start_after, limit, order)?
+        .into_iter()
+        .map(|(asset_key, unbonding_period)| {
+            Ok(UnbondingPeriodsResponseItem {
+                staking_token: pool_staking_token(
+                    deps.storage,
+                    deps.api,
+                    &CanonicalAddr::from(asset_key),
+                )?,
+                unbonding_period,
+            })
+        })
+        .collect()
+}
+
+pub fn query_all_rewards_per_sec(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    order: Option<i32>,
+) -> StdResult<Vec<AllRewardsPerSecResponseItem>> {
+    let start_after = start_after
+        .map(|staking_token| pool_key(deps.storage, deps.api, &staking_token))
+        .transpose()?
+        .map(|asset_key| asset_key.to_vec());
+
+    read_all_rewards_per_sec(deps.storage, start_after, limit, order)?
+        .into_iter()
+        .map(|(asset_key, raw_assets)| {
+            Ok(AllRewardsPerSecResponseItem {
+                staking_token: pool_staking_token(
+                    deps.storage,
+                    deps.api,
+                    &CanonicalAddr::from(asset_key),
+                )?,
+                assets: raw_assets
+                    .into_iter()
+                    .map(|rw| rw.to_normal(deps.api))
+                    .collect::<StdResult<Vec<Asset>>>()?,
+            })
+        })
+        .collect()
+}
+
 pub fn query_rewards_per_sec(deps: Deps, staking_token: Addr) -> StdResult<RewardsPerSecResponse> {
-    let asset_key = deps.api.addr_canonicalize(staking_token.as_str())?.to_vec();
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
 
     let raw_assets = read_rewards_per_sec(deps.storage, &asset_key)?;
 
@@ -321,7 +3480,147 @@ pub fn query_rewards_per_sec(deps: Deps, staking_token: Addr) -> StdResult<Rewar
         .map(|w| w.to_normal(deps.api))
         .collect::<StdResult<Vec<Asset>>>()?;
 
-    Ok(RewardsPerSecResponse { assets })
+    Ok(RewardsPerSecResponse {
+        assets,
+        limits: read_rewards_per_sec_limits(deps.storage, &asset_key)?,
+        last_updated: read_rewards_per_sec_updated(deps.storage, &asset_key)?
+            .map(|updated_at| updated_at.seconds()),
+    })
+}
+
+pub fn query_reward_schedules(
+    deps: Deps,
+    env: Env,
+    staking_token: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<RewardScheduleResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    let now = env.block.time.seconds();
+    let synced_at = read_reward_schedules_synced(deps.storage, &asset_key)?.unwrap_or(now);
+
+    read_reward_schedules(deps.storage, &asset_key)?
+        .into_iter()
+        .filter(|schedule| start_after.map_or(true, |start_after| schedule.id > start_after))
+        .take(limit)
+        .map(|schedule| {
+            let status = if schedule.is_ended(now) {
+                RewardScheduleStatus::Ended
+            } else if schedule.start_time > now {
+                RewardScheduleStatus::Pending
+            } else {
+                RewardScheduleStatus::Active
+            };
+            let remaining_duration = schedule
+                .end_time
+                .map(|end_time| end_time.saturating_sub(now.max(schedule.start_time)));
+            let undistributed_budget = schedule
+                .end_time
+                .map(|end_time| {
+                    schedule.amount_per_sec.checked_mul(Uint128::from(
+                        end_time.saturating_sub(synced_at.max(schedule.start_time)),
+                    ))
+                })
+                .transpose()?;
+            Ok(RewardScheduleResponse {
+                id: schedule.id,
+                asset_info: schedule.asset_info.to_normal(deps.api)?,
+                amount_per_sec: schedule.amount_per_sec,
+                start_time: schedule.start_time,
+                end_time: schedule.end_time,
+                status,
+                remaining_duration,
+                undistributed_budget,
+            })
+        })
+        .collect()
+}
+
+pub fn query_boost(deps: Deps, staker_addr: Addr, staking_token: Addr) -> StdResult<BoostResponse> {
+    let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    let curve = read_boost_curve(deps.storage, &asset_key)?;
+    let (bond_amount, weighted_amount) = REWARDS
+        .may_load(deps.storage, (&staker_addr_raw, &asset_key))?
+        .map(|reward_info| (reward_info.bond_amount, reward_info.weight()))
+        .unwrap_or_default();
+    // an expired boost keeps its weight until ExpireBoost or the next bond change
+    let boost = read_boost(deps.storage, &asset_key, &staker_addr_raw)?;
+
+    Ok(BoostResponse {
+        multiplier: boost
+            .as_ref()
+            .map_or(Decimal::one(), |boost| boost.multiplier),
+        expires_at: boost.map(|boost| boost.expires_at),
+        bond_amount,
+        weighted_amount,
+        curve,
+    })
+}
+
+pub fn query_accrual_mode(deps: Deps, staking_token: Addr) -> StdResult<AccrualModeResponse> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+    Ok(AccrualModeResponse {
+        mode: read_accrual_mode(deps.storage, &asset_key)?,
+        last_distributed: read_last_distributed(deps.storage, &asset_key)?,
+        staking_token,
+    })
+}
+
+pub fn query_reward_dust(deps: Deps, staking_token: Addr) -> StdResult<RewardDustResponse> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    // make sure the pool exists
+    read_pool_info(deps.storage, &asset_key)?;
+    Ok(RewardDustResponse {
+        dust: read_reward_dust(deps.storage, &asset_key)?,
+        staking_token,
+    })
+}
+
+pub fn query_pool_apr(deps: Deps, staking_token: Addr) -> StdResult<PoolAprResponse> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    let pool_info = read_pool_info(deps.storage, &asset_key)?;
+
+    let mut requires_prices = false;
+    let rewards = read_rewards_per_sec(deps.storage, &asset_key)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|rw| !rw.amount.is_zero())
+        .map(|rw| {
+            let annual_emission = rw.amount.checked_mul(Uint128::from(SECONDS_PER_YEAR))?;
+            // native staking tokens are stored as their denom
+            let asset_bytes = match &rw.info {
+                AssetInfoRaw::Token { contract_addr } => contract_addr.as_slice(),
+                AssetInfoRaw::NativeToken { denom } => denom.as_bytes(),
+            };
+            let apr = if asset_bytes != pool_info.staking_token.as_slice() {
+                requires_prices = true;
+                None
+            } else if pool_info.total_bond_amount.is_zero() {
+                None
+            } else {
+                Some(Decimal::from_ratio(
+                    annual_emission,
+                    pool_info.total_bond_amount,
+                ))
+            };
+            Ok(RewardAprResponse {
+                asset_info: rw.info.to_normal(deps.api)?,
+                annual_emission,
+                apr,
+            })
+        })
+        .collect::<StdResult<Vec<RewardAprResponse>>>()?;
+
+    Ok(PoolAprResponse {
+        staking_token,
+        total_bond_amount: pool_info.total_bond_amount,
+        rewards,
+        requires_prices,
+    })
 }
 
 pub fn parse_read_all_pool_infos(
@@ -333,16 +3632,30 @@ pub fn parse_read_all_pool_infos(
         .into_iter()
         .map(|(key, pool_info)| {
             let asset_key = CanonicalAddr::from(key);
-            let staking_token = api.addr_humanize(&asset_key)?;
+            let staking_token = pool_staking_token(storage, api, &asset_key)?;
             let unbonding_period = read_unbonding_period(storage, &asset_key).ok();
+            let total_unbonding = read_total_unbonding(storage, staking_token.as_bytes())?;
             Ok(QueryPoolInfoResponse {
                 asset_key: staking_token.to_string(),
                 pool_info: PoolInfoResponse {
                     staking_token,
                     total_bond_amount: pool_info.total_bond_amount,
+                    total_weighted_amount: pool_info.total_weight(),
                     reward_index: pool_info.reward_index,
                     pending_reward: pool_info.pending_reward,
                     unbonding_period,
+                    unbond_fee: read_unbond_fee(storage, &asset_key)?,
+                    instant_unbond_penalty: read_instant_unbond_penalty(storage, &asset_key)?,
+                    min_bond_amount: read_min_bond_amount(storage, &asset_key)?,
+                    min_claim_amount: read_min_claim_amount(storage, &asset_key)?,
+                    total_unbonding,
+                    staker_count: read_staker_count(storage, &asset_key)?,
+                    open_deposit: is_open_deposit(storage, &asset_key)?,
+                    deprecated: pool_info.deprecated,
+                    pool_admin: read_pool_admin(storage, &asset_key)?
+                        .map(|pool_admin| api.addr_humanize(&pool_admin))
+                        .transpose()?,
+                    status: read_pool_status(storage, &asset_key)?,
                 },
             })
         })
@@ -361,7 +3674,7 @@ pub fn query_staked_balance_at_height(
     address: String,
     height: Option<u64>,
 ) -> StdResult<StakedBalanceAtHeightResponse> {
-    let asset_key = deps.api.addr_canonicalize(asset_key.as_str())?.to_vec();
+    let asset_key = pool_key(deps.storage, deps.api, &asset_key)?.to_vec();
     let address = deps.api.addr_validate(&address)?;
     let height = height.unwrap_or(env.block.height);
     let balance = STAKED_BALANCES
@@ -370,21 +3683,359 @@ pub fn query_staked_balance_at_height(
     Ok(StakedBalanceAtHeightResponse { balance, height })
 }
 
+pub fn query_staked_balance_at_time(
+    deps: Deps,
+    asset_key: Addr,
+    address: String,
+    time: u64,
+) -> StdResult<StakedBalanceAtTimeResponse> {
+    let asset_key = pool_key(deps.storage, deps.api, &asset_key)?.to_vec();
+    let address = deps.api.addr_validate(&address)?;
+    let block = read_block_at_time(deps.storage, time)?
+        .ok_or_else(|| StdError::generic_err("time predates the oldest recorded block"))?;
+    // the snapshot at a height is taken before its block, so read the next one
+    let balance = STAKED_BALANCES
+        .may_load_at_height(deps.storage, (&asset_key, &address), block.height + 1)?
+        .unwrap_or_default();
+    Ok(StakedBalanceAtTimeResponse {
+        balance,
+        height: block.height,
+        time,
+    })
+}
+
 pub fn query_total_staked_at_height(
     deps: Deps,
-    _env: Env,
+    env: Env,
     asset_key: Addr,
     height: Option<u64>,
 ) -> StdResult<TotalStakedAtHeightResponse> {
-    let asset_key = deps.api.addr_canonicalize(asset_key.as_str())?.to_vec();
-    let height = height.unwrap_or(_env.block.height);
+    let asset_key = pool_key(deps.storage, deps.api, &asset_key)?.to_vec();
+    let height = height.unwrap_or(env.block.height);
     let total = STAKED_TOTAL
         .may_load_at_height(deps.storage, &asset_key, height)?
         .unwrap_or_default();
     Ok(TotalStakedAtHeightResponse { total, height })
 }
+
+/// the records are in raw key order from the key after start_after, which is the raw key of the
+/// last record of the previous page, so the pages at a height are the same for every caller
+pub fn query_export_state(
+    deps: Deps,
+    kind: ExportKind,
+    start_after: Option<Binary>,
+    limit: Option<u32>,
+) -> StdResult<ExportStateResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_EXPORT_LIMIT) as usize;
+    let start = start_after.map(|key| key.to_vec());
+    let humanize_pool = |asset_key: &[u8]| {
+        pool_staking_token(deps.storage, deps.api, &CanonicalAddr::from(asset_key))
+    };
+
+    let records = match kind {
+        ExportKind::Pools => POOL_INFOS
+            .range(
+                deps.storage,
+                start.map(Bound::ExclusiveRaw),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| {
+                let (asset_key, pool_info) = item?;
+                let record = ExportRecord::Pool {
+                    staking_token: humanize_pool(&asset_key)?,
+                    current_staking_token: humanize_pool(&pool_info.staking_token)?,
+                    total_bond_amount: pool_info.total_bond_amount,
+                    total_weighted_amount: pool_info.total_weighted_amount,
+                    reward_index: pool_info.reward_index,
+                    pending_reward: pool_info.pending_reward,
+                    deprecated: pool_info.deprecated,
+                };
+                Ok((asset_key, record))
+            })
+            .collect::<StdResult<Vec<(Vec<u8>, ExportRecord)>>>()?,
+        ExportKind::Stakers => REWARDS
+            .range(
+                deps.storage,
+                start.map(Bound::ExclusiveRaw),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| {
+                let ((staker, asset_key), reward_info) = item?;
+                let record = ExportRecord::Staker {
+                    staker_addr: deps
+                        .api
+                        .addr_humanize(&CanonicalAddr::from(staker.as_slice()))?,
+                    staking_token: humanize_pool(&asset_key)?,
+                    native_token: reward_info.native_token,
+                    index: reward_info.index,
+                    bond_amount: reward_info.bond_amount,
+                    weighted_amount: reward_info.weighted_amount,
+                    pending_reward: reward_info.pending_reward,
+                    pending_withdraw: reward_info
+                        .pending_withdraw
+                        .into_iter()
+                        .map(|asset| asset.to_normal(deps.api))
+                        .collect::<StdResult<Vec<Asset>>>()?,
+                };
+                Ok((
+                    (staker.as_slice(), asset_key.as_slice()).joined_key(),
+                    record,
+                ))
+            })
+            .collect::<StdResult<Vec<(Vec<u8>, ExportRecord)>>>()?,
+        // the locks are keyed by the human readable addresses
+        ExportKind::Locks => LOCK_INFOS
+            .range(
+                deps.storage,
+                start.map(Bound::ExclusiveRaw),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| {
+                let ((staking_token, user, unlock_time), amount) = item?;
+                let key = (staking_token.as_slice(), user.as_slice(), unlock_time).joined_key();
+                let record = ExportRecord::Lock {
+                    staking_token: Addr::unchecked(
+                        String::from_utf8(staking_token).map_err(StdError::invalid_utf8)?,
+                    ),
+                    staker_addr: Addr::unchecked(
+                        String::from_utf8(user).map_err(StdError::invalid_utf8)?,
+                    ),
+                    unlock_time,
+                    amount,
+                };
+                Ok((key, record))
+            })
+            .collect::<StdResult<Vec<(Vec<u8>, ExportRecord)>>>()?,
+        ExportKind::RewardsPerSec => REWARDS_PER_SEC
+            .range(
+                deps.storage,
+                start.map(Bound::ExclusiveRaw),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| {
+                let (asset_key, raw_assets) = item?;
+                let record = ExportRecord::RewardsPerSec {
+                    staking_token: humanize_pool(&asset_key)?,
+                    assets: raw_assets
+                        .into_iter()
+                        .map(|asset| asset.to_normal(deps.api))
+                        .collect::<StdResult<Vec<Asset>>>()?,
+                };
+                Ok((asset_key, record))
+            })
+            .collect::<StdResult<Vec<(Vec<u8>, ExportRecord)>>>()?,
+    };
+
+    let next = if records.len() < limit {
+        None
+    } else {
+        records.last().map(|(key, _)| Binary::from(key.as_slice()))
+    };
+    Ok(ExportStateResponse {
+        records: records.into_iter().map(|(_, record)| record).collect(),
+        next,
+    })
+}
+
+pub fn query_snapshot_consistency(
+    deps: Deps,
+    staking_token: Addr,
+) -> StdResult<SnapshotConsistencyResponse> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    let total_bond_amount = read_pool_info(deps.storage, &asset_key)?.total_bond_amount;
+    let snapshot_total = STAKED_TOTAL
+        .may_load(deps.storage, &asset_key)?
+        .unwrap_or_default();
+    Ok(SnapshotConsistencyResponse {
+        staking_token,
+        snapshot_total,
+        total_bond_amount,
+        consistent: snapshot_total == total_bond_amount,
+    })
+}
+
+pub fn query_staked_balance_changelog(
+    deps: Deps,
+    asset_key: Addr,
+    address: String,
+    start_after_height: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<SnapshotChangeResponseItem>> {
+    let asset_key = pool_key(deps.storage, deps.api, &asset_key)?.to_vec();
+    let address = deps.api.addr_validate(&address)?;
+    Ok(read_staked_balance_changelog(
+        deps.storage,
+        &asset_key,
+        &address,
+        start_after_height,
+        limit,
+    )?
+    .into_iter()
+    .map(|(height, old_value)| SnapshotChangeResponseItem { height, old_value })
+    .collect())
+}
+
+pub fn query_staked_total_changelog(
+    deps: Deps,
+    asset_key: Addr,
+    start_after_height: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<SnapshotChangeResponseItem>> {
+    let asset_key = pool_key(deps.storage, deps.api, &asset_key)?.to_vec();
+    Ok(
+        read_staked_total_changelog(deps.storage, &asset_key, start_after_height, limit)?
+            .into_iter()
+            .map(|(height, old_value)| SnapshotChangeResponseItem { height, old_value })
+            .collect(),
+    )
+}
+
+fn governance_token(deps: Deps) -> StdResult<Addr> {
+    read_config(deps.storage)?
+        .governance_token
+        .ok_or_else(|| StdError::generic_err("no governance token configured"))
+}
+
+pub fn query_voting_power_at_height(
+    deps: Deps,
+    env: Env,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<VotingPowerAtHeightResponse> {
+    let res = query_staked_balance_at_height(deps, env, governance_token(deps)?, address, height)?;
+    Ok(VotingPowerAtHeightResponse {
+        power: res.balance,
+        height: res.height,
+    })
+}
+
+pub fn query_total_power_at_height(
+    deps: Deps,
+    env: Env,
+    height: Option<u64>,
+) -> StdResult<TotalPowerAtHeightResponse> {
+    let res = query_total_staked_at_height(deps, env, governance_token(deps)?, height)?;
+    Ok(TotalPowerAtHeightResponse {
+        power: res.total,
+        height: res.height,
+    })
+}
+
+pub fn query_reward_state_at_height(
+    deps: Deps,
+    env: Env,
+    staker_addr: Addr,
+    staking_token: Addr,
+    height: Option<u64>,
+) -> StdResult<RewardStateAtHeightResponse> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    let height = height.unwrap_or(env.block.height);
+    // None is a height before the pool was registered or, for older pools, before their first deposit
+    let reward_index = REWARD_INDEX
+        .may_load_at_height(deps.storage, &asset_key, height)?
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "no reward index checkpoint of the pool at height {}",
+                height
+            ))
+        })?;
+    let bond_amount = STAKED_BALANCES
+        .may_load_at_height(deps.storage, (&asset_key, &staker_addr), height)?
+        .unwrap_or_default();
+    Ok(RewardStateAtHeightResponse {
+        staker_addr,
+        staking_token,
+        height,
+        bond_amount,
+        reward_index,
+    })
+}
 // migrate contract
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let target_version = parse_version(CONTRACT_VERSION)?;
+    // contracts deployed before the version was stored have none
+    let previous_version = match CONTRACT.may_load(deps.storage)? {
+        Some(stored) => {
+            if stored.contract != CONTRACT_NAME {
+                return Err(StdError::generic_err(format!(
+                    "cannot migrate from contract {}",
+                    stored.contract
+                )));
+            }
+            let version = parse_version(&stored.version)?;
+            if version >= target_version {
+                return Err(StdError::generic_err(format!(
+                    "cannot migrate from version {} to {}",
+                    version, target_version
+                )));
+            }
+            Some(version)
+        }
+        None => None,
+    };
+
+    // the transforms of every version newer than the stored one, oldest first
+    match previous_version {
+        Some(version) if version >= Version::new(0, 2, 0) => {}
+        _ => migrate_to_v0_2(deps, env)?,
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new().add_attributes([
+        attr("action", "migrate"),
+        attr(
+            "from_version",
+            previous_version.map_or_else(|| "none".to_string(), |version| version.to_string()),
+        ),
+        attr("to_version", CONTRACT_VERSION),
+    ]))
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    Version::parse(version)
+        .map_err(|_| StdError::generic_err(format!("invalid contract version {}", version)))
+}
+
+// the state written before 0.2.0, every step is idempotent
+fn migrate_to_v0_2(deps: DepsMut, env: Env) -> StdResult<()> {
+    migrate_legacy_config(deps.storage)?;
+    // there is one entry per pool, the rewards and stakers are left to MigrateLegacy
+    POOL_INFOS.migrate_legacy(deps.storage, usize::MAX);
+    REWARDS_PER_SEC.migrate_legacy(deps.storage, usize::MAX);
+    let pool_infos = read_all_pool_infos(deps.storage)?;
+    // backfill the pool counter for contracts registered before it existed
+    store_pool_count(deps.storage, pool_infos.len() as u64)?;
+    // the flat rate of pools created before the schedules keeps running as an open-ended schedule
+    let now = env.block.time.seconds();
+    for (asset_key, pool_info) in pool_infos {
+        // index the locks created before the pool wide lock index existed, locks stay keyed by
+        // the token the pool was registered with
+        let staking_token = pool_staking_token(
+            deps.storage,
+            deps.api,
+            &CanonicalAddr::from(asset_key.clone()),
+        )?;
+        backfill_lock_index(deps.storage, staking_token.as_bytes())?;
+        backfill_total_unbonding(deps.storage, staking_token.as_bytes())?;
+        backfill_staker_count(deps.storage, &asset_key)?;
+        add_token_pool(deps.storage, &pool_info.staking_token, &asset_key)?;
+        if pool_info.deprecated || !read_reward_schedules(deps.storage, &asset_key)?.is_empty() {
+            continue;
+        }
+        let raw_assets = read_rewards_per_sec(deps.storage, &asset_key).unwrap_or_default();
+        let schedules = flat_rate_schedules(&[], &raw_assets, now);
+        if !schedules.is_empty() {
+            store_reward_schedules(deps.storage, &asset_key, schedules)?;
+            store_reward_schedules_synced(deps.storage, &asset_key, now)?;
+        }
+    }
+    Ok(())
 }
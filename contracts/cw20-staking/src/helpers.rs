@@ -0,0 +1,423 @@
+//! cw-multi-test scaffolding for contracts integrating with the staking contract, enabled by the
+//! `testing` feature.
+//!
+//! `TestSuite` deploys the staking contract owned by `OWNER` with `REWARDER` as the rewarder, cw20
+//! tokens are cw20-base contracts. Rewards are deposited as prefunded like on chain, so the
+//! contract must be funded with `fund_contract` or `fund_contract_token` first.
+
+use cosmwasm_schema::serde::de::DeserializeOwned;
+use cosmwasm_std::{to_binary, Addr, BlockInfo, Coin, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
+use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
+use cw_multi_test::{App, AppBuilder, AppResponse, BankSudo, Contract, ContractWrapper, Executor};
+use oraiswap::asset::Asset;
+
+use crate::msg::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg,
+    RewardInfoResponse, StakedBalanceAtHeightResponse, SudoMsg, TotalStakedAtHeightResponse,
+};
+
+pub const OWNER: &str = "owner";
+pub const REWARDER: &str = "rewarder";
+
+pub fn staking_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            crate::contract::execute,
+            crate::contract::instantiate,
+            crate::contract::query,
+        )
+        .with_reply(crate::contract::reply)
+        .with_sudo(crate::contract::sudo)
+        .with_migrate(crate::contract::migrate),
+    )
+}
+
+pub fn cw20_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+pub struct TestSuite {
+    pub app: App,
+    pub staking_addr: Addr,
+    cw20_code_id: u64,
+}
+
+impl Default for TestSuite {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+impl TestSuite {
+    /// deploys the staking contract, balances are the native coins of each account
+    pub fn new(balances: &[(&str, Vec<Coin>)]) -> Self {
+        let mut app = AppBuilder::new().build(|router, _, storage| {
+            for (addr, coins) in balances {
+                router
+                    .bank
+                    .init_balance(storage, &Addr::unchecked(*addr), coins.clone())
+                    .unwrap();
+            }
+        });
+        let code_id = app.store_code(staking_contract());
+        let cw20_code_id = app.store_code(cw20_contract());
+        let staking_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    owner: Some(Addr::unchecked(OWNER)),
+                    rewarder: Addr::unchecked(REWARDER),
+                    governance_token: None,
+                    import_open: None,
+                },
+                &[],
+                "staking",
+                Some(OWNER.to_string()),
+            )
+            .unwrap();
+
+        Self {
+            app,
+            staking_addr,
+            cw20_code_id,
+        }
+    }
+
+    /// a cw20-base token holding the balances, the owner can mint more
+    pub fn create_token(&mut self, symbol: &str, balances: &[(&str, u128)]) -> Addr {
+        self.app
+            .instantiate_contract(
+                self.cw20_code_id,
+                Addr::unchecked(OWNER),
+                &Cw20InstantiateMsg {
+                    name: symbol.to_string(),
+                    symbol: symbol.to_string(),
+                    decimals: 6,
+                    initial_balances: balances
+                        .iter()
+                        .map(|(address, amount)| Cw20Coin {
+                            address: address.to_string(),
+                            amount: Uint128::from(*amount),
+                        })
+                        .collect(),
+                    mint: Some(MinterResponse {
+                        minter: OWNER.to_string(),
+                        cap: None,
+                    }),
+                    marketing: None,
+                },
+                &[],
+                symbol,
+                None,
+            )
+            .unwrap()
+    }
+
+    /// executes msg on the staking contract, errors are the message of the root cause
+    pub fn execute(
+        &mut self,
+        sender: &str,
+        msg: &ExecuteMsg,
+        funds: &[Coin],
+    ) -> Result<AppResponse, String> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(sender),
+                self.staking_addr.clone(),
+                msg,
+                funds,
+            )
+            .map_err(|err| err.root_cause().to_string())
+    }
+
+    /// runs msg through the sudo entry point, as the chain governance does
+    pub fn sudo(&mut self, msg: &SudoMsg) -> Result<AppResponse, String> {
+        self.app
+            .wasm_sudo(self.staking_addr.clone(), msg)
+            .map_err(|err| err.root_cause().to_string())
+    }
+
+    pub fn register_pool(
+        &mut self,
+        staking_token: &Addr,
+        unbonding_period: Option<u64>,
+    ) -> Result<AppResponse, String> {
+        self.execute(
+            OWNER,
+            &ExecuteMsg::RegisterAsset {
+                staking_token: staking_token.clone(),
+                unbonding_period,
+                native_token: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn update_rewards_per_sec(
+        &mut self,
+        staking_token: &Addr,
+        assets: Vec<Asset>,
+    ) -> Result<AppResponse, String> {
+        self.execute(
+            OWNER,
+            &ExecuteMsg::UpdateRewardsPerSec {
+                staking_token: staking_token.clone(),
+                assets,
+                force: None,
+            },
+            &[],
+        )
+    }
+
+    /// sends amount of the cw20 staking token to the contract with a Bond hook
+    pub fn bond(
+        &mut self,
+        staker: &str,
+        staking_token: &Addr,
+        amount: u128,
+    ) -> Result<AppResponse, String> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(staker),
+                staking_token.clone(),
+                &Cw20ExecuteMsg::Send {
+                    contract: self.staking_addr.to_string(),
+                    amount: Uint128::from(amount),
+                    msg: to_binary(&Cw20HookMsg::Bond {
+                        staker: None,
+                        callback: None,
+                    })
+                    .unwrap(),
+                },
+                &[],
+            )
+            .map_err(|err| err.root_cause().to_string())
+    }
+
+    /// lets the staking contract pull amount of the token from owner, for BondFrom
+    pub fn increase_allowance(
+        &mut self,
+        owner: &str,
+        token: &Addr,
+        amount: u128,
+    ) -> Result<AppResponse, String> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(owner),
+                token.clone(),
+                &Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: self.staking_addr.to_string(),
+                    amount: Uint128::from(amount),
+                    expires: None,
+                },
+                &[],
+            )
+            .map_err(|err| err.root_cause().to_string())
+    }
+
+    /// unbonds the whole bond when amount is None
+    pub fn unbond(
+        &mut self,
+        staker: &str,
+        staking_token: &Addr,
+        amount: Option<u128>,
+    ) -> Result<AppResponse, String> {
+        self.execute(
+            staker,
+            &ExecuteMsg::Unbond {
+                staking_token: staking_token.clone(),
+                amount: amount.map(Uint128::from),
+                withdraw_rewards: None,
+                recipient: None,
+                limit: None,
+            },
+            &[],
+        )
+    }
+
+    /// deposits amount the contract was funded with into the pool by the rewarder, split by the
+    /// rewards per sec of the pool
+    pub fn deposit_rewards(
+        &mut self,
+        staking_token: &Addr,
+        amount: u128,
+    ) -> Result<AppResponse, String> {
+        self.execute(
+            REWARDER,
+            &ExecuteMsg::DepositPrefundedReward {
+                rewards: vec![crate::msg::RewardMsg {
+                    staking_token: staking_token.clone(),
+                    total_accumulation_amount: Uint128::from(amount),
+                }],
+            },
+            &[],
+        )
+    }
+
+    /// withdraws the rewards of every pool when staking_token is None
+    pub fn withdraw(
+        &mut self,
+        staker: &str,
+        staking_token: Option<&Addr>,
+    ) -> Result<AppResponse, String> {
+        self.execute(
+            staker,
+            &ExecuteMsg::Withdraw {
+                staking_token: staking_token.cloned(),
+                skip_failing: None,
+            },
+            &[],
+        )
+    }
+
+    /// mints native coins to the staking contract, for the rewards it pays out
+    pub fn fund_contract(&mut self, amount: Vec<Coin>) {
+        self.app
+            .sudo(
+                BankSudo::Mint {
+                    to_address: self.staking_addr.to_string(),
+                    amount,
+                }
+                .into(),
+            )
+            .unwrap();
+    }
+
+    /// mints the cw20 token to the staking contract
+    pub fn fund_contract_token(&mut self, token: &Addr, amount: u128) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                token.clone(),
+                &Cw20ExecuteMsg::Mint {
+                    recipient: self.staking_addr.to_string(),
+                    amount: Uint128::from(amount),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    /// moves the block time forward, the height by one block every five seconds
+    pub fn advance_seconds(&mut self, seconds: u64) {
+        self.app.update_block(|block: &mut BlockInfo| {
+            block.time = block.time.plus_seconds(seconds);
+            block.height += (seconds / 5).max(1);
+        });
+    }
+
+    pub fn query<T: DeserializeOwned>(&self, msg: &QueryMsg) -> T {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.staking_addr.clone(), msg)
+            .unwrap()
+    }
+
+    pub fn config(&self) -> ConfigResponse {
+        self.query(&QueryMsg::Config {})
+    }
+
+    pub fn pool_info(&self, staking_token: &Addr) -> PoolInfoResponse {
+        self.query(&QueryMsg::PoolInfo {
+            staking_token: staking_token.clone(),
+        })
+    }
+
+    /// the reward infos of the staker, of every pool when staking_token is None
+    pub fn pending_rewards(
+        &self,
+        staker: &str,
+        staking_token: Option<&Addr>,
+    ) -> RewardInfoResponse {
+        self.query(&QueryMsg::RewardInfo {
+            staker_addr: Addr::unchecked(staker),
+            staking_token: staking_token.cloned(),
+        })
+    }
+
+    /// the bond of the staker at height, the latest one when height is None
+    pub fn staked_balance_at_height(
+        &self,
+        staker: &str,
+        staking_token: &Addr,
+        height: Option<u64>,
+    ) -> Uint128 {
+        let res: StakedBalanceAtHeightResponse = self.query(&QueryMsg::StakedBalanceAtHeight {
+            asset_key: staking_token.clone(),
+            address: staker.to_string(),
+            height,
+        });
+        res.balance
+    }
+
+    pub fn total_staked_at_height(&self, staking_token: &Addr, height: Option<u64>) -> Uint128 {
+        let res: TotalStakedAtHeightResponse = self.query(&QueryMsg::TotalStakedAtHeight {
+            asset_key: staking_token.clone(),
+            height,
+        });
+        res.total
+    }
+
+    pub fn native_balance(&self, address: &str, denom: &str) -> Uint128 {
+        self.app
+            .wrap()
+            .query_balance(address, denom)
+            .unwrap()
+            .amount
+    }
+
+    pub fn token_balance(&self, token: &Addr, address: &str) -> Uint128 {
+        let res: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                token,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance
+    }
+
+    pub fn assert_bond_amount(&self, staker: &str, staking_token: &Addr, expected: u128) {
+        let bond_amount = self
+            .pending_rewards(staker, Some(staking_token))
+            .reward_infos
+            .first()
+            .map(|reward_info| reward_info.bond_amount)
+            .unwrap_or_default();
+        assert_eq!(
+            bond_amount,
+            Uint128::from(expected),
+            "bond amount of {}",
+            staker
+        );
+    }
+
+    pub fn assert_native_balance(&self, address: &str, denom: &str, expected: u128) {
+        assert_eq!(
+            self.native_balance(address, denom),
+            Uint128::from(expected),
+            "{} balance of {}",
+            denom,
+            address
+        );
+    }
+
+    pub fn assert_token_balance(&self, token: &Addr, address: &str, expected: u128) {
+        assert_eq!(
+            self.token_balance(token, address),
+            Uint128::from(expected),
+            "{} balance of {}",
+            token,
+            address
+        );
+    }
+}
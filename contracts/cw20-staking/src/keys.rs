@@ -0,0 +1,67 @@
+//! Raw storage keys written by the contract, for reading its state with raw queries.
+//!
+//! Pools are keyed by the pool key, the canonical address of the cw20 token the pool was
+//! registered with or the denom bytes of a native staking token. Stakers are keyed by their
+//! canonical address, except for the locks which use the human readable addresses.
+
+use cosmwasm_std::Addr;
+use cosmwasm_storage::to_length_prefixed;
+
+use crate::state::{
+    KEY_CONFIG, LOCK_INFOS, POOL_INFOS, REWARDS, REWARDS_PER_SEC, STAKED_BALANCES, STAKED_TOTAL,
+    STAKERS, TOTAL_UNBONDING, UNBONDING_PERIODS,
+};
+
+fn bucket_key(namespace: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut raw_key = to_length_prefixed(namespace);
+    raw_key.extend_from_slice(key);
+    raw_key
+}
+
+pub fn config_key() -> Vec<u8> {
+    to_length_prefixed(KEY_CONFIG)
+}
+
+pub fn pool_info_key(asset_key: &[u8]) -> Vec<u8> {
+    POOL_INFOS.key(asset_key).to_vec()
+}
+
+pub fn rewards_per_sec_key(asset_key: &[u8]) -> Vec<u8> {
+    REWARDS_PER_SEC.key(asset_key).to_vec()
+}
+
+pub fn unbonding_period_key(asset_key: &[u8]) -> Vec<u8> {
+    UNBONDING_PERIODS.key(asset_key).to_vec()
+}
+
+/// staker is the canonical address
+pub fn reward_info_key(staker: &[u8], asset_key: &[u8]) -> Vec<u8> {
+    REWARDS.key((staker, asset_key)).to_vec()
+}
+
+/// staker is the canonical address
+pub fn staker_key(asset_key: &[u8], staker: &[u8]) -> Vec<u8> {
+    STAKERS.key((asset_key, staker)).to_vec()
+}
+
+/// staking_token and user are the human readable addresses, staking_token is the token the
+/// pool was registered with
+pub fn lock_info_key(staking_token: &str, user: &str, unlock_time: u64) -> Vec<u8> {
+    LOCK_INFOS
+        .key((staking_token.as_bytes(), user.as_bytes(), unlock_time))
+        .to_vec()
+}
+
+/// staking_token is the human readable token the pool was registered with
+pub fn total_unbonding_key(staking_token: &str) -> Vec<u8> {
+    bucket_key(TOTAL_UNBONDING, staking_token.as_bytes())
+}
+
+/// the current staked balance, the history is kept in the snapshot checkpoints
+pub fn staked_balance_key(asset_key: &[u8], address: &Addr) -> Vec<u8> {
+    STAKED_BALANCES.key((asset_key, address)).to_vec()
+}
+
+pub fn staked_total_key(asset_key: &[u8]) -> Vec<u8> {
+    STAKED_TOTAL.key(asset_key).to_vec()
+}
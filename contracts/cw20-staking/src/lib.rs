@@ -1,5 +1,9 @@
 pub mod contract;
-pub mod msg;
+#[cfg(any(test, feature = "testing"))]
+pub mod helpers;
+pub mod keys;
+// the message types live in the package so callers share a single definition
+pub use cw20_staking_pkg::msg;
 mod rewards;
 mod staking;
 mod state;
@@ -1,91 +1,1489 @@
 use std::convert::TryFrom;
 
-use crate::msg::{RewardInfoResponse, RewardInfoResponseItem, RewardMsg};
+use crate::msg::{
+    AccrualMode, ClaimHookExecuteMsg, ClaimHookMsg, HookKind, PendingWithdrawResponse, PoolWeight,
+    RewardInfoResponse, RewardInfoResponseItem, RewardInfosBatchResponseItem, RewardMsg,
+    RewardPoolCountResponse, RewardVestingResponse, RewardVestingsResponse, StakerInfoResponseItem,
+    DEPOSIT_REWARD_EVENT, PAYOUT_AMOUNT_KEY_PREFIX, REWARD_AMOUNT_KEY_PREFIX,
+    WITHDRAW_REWARD_EVENT,
+};
 use crate::state::{
-    read_config, read_pool_info, read_rewards_per_sec, rewards_read, rewards_store, stakers_read,
-    store_pool_info, PoolInfo, RewardInfo, DEFAULT_LIMIT, MAX_LIMIT,
+    add_hook_failure, insert_reward_vesting, is_open_deposit, is_rewarder, page_bounds,
+    pool_asset_info, pool_key, pool_staking_token, read_accrual_mode, read_all_pool_infos,
+    read_claim_delegate, read_claim_hooks, read_config, read_hook_failures, read_hooks_of,
+    read_last_distributed, read_min_claim_amount, read_outstanding_reward, read_pending_payout,
+    read_pending_swap, read_pool_info, read_pool_status, read_rate_checkpoints, read_reward_dust,
+    read_reward_schedules, read_reward_schedules_synced, read_reward_split,
+    read_reward_vesting_period, read_reward_vestings, read_rewards_per_sec, read_router,
+    read_total_unbonding, read_user_lock_amount, remove_matured_reward_vestings,
+    remove_pending_swap, store_last_distributed, store_outstanding_reward, store_pending_payout,
+    store_pending_swap, store_pool_info, store_rate_checkpoint, store_reward_dust,
+    store_reward_schedules, store_reward_schedules_synced, store_rewards_per_sec, PendingPayout,
+    PendingSwap, PoolInfo, RewardInfo, DEFAULT_LIMIT, MAX_HOOK_FAILURES, MAX_LIMIT,
+    MAX_REWARD_INFOS_BATCH, MAX_REWARD_SPLIT_BPS, REWARDS, REWARD_INDEX, STAKERS, VESTING_STEP,
 };
 use cosmwasm_std::{
-    Addr, Api, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdError, StdResult, Storage, Uint128,
+    attr, coin, to_binary, Addr, Api, Binary, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut,
+    Env, Event, MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Storage, SubMsg,
+    SubMsgResult, Timestamp, Uint128, Uint256, WasmMsg,
+};
+use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_storage_plus::Bound;
+use oraiswap::asset::{Asset, AssetInfo, AssetInfoRaw, AssetRaw};
+use oraiswap::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, QueryMsg as RouterQueryMsg,
+    SimulateSwapOperationsResponse, SwapOperation,
 };
-use oraiswap::asset::{Asset, AssetRaw};
-use oraiswap::querier::calc_range_start;
 
 // deposit_reward must be from reward token contract
 pub fn deposit_reward(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     rewards: Vec<RewardMsg>,
 ) -> StdResult<Response> {
-    let config = read_config(deps.storage)?;
+    // funds sent along must match the declared rewards exactly, whoever sends them
+    let open = !is_rewarder(
+        deps.storage,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+    )?;
+    let sent = info
+        .funds
+        .iter()
+        .map(|coin| AssetRaw {
+            info: AssetInfoRaw::NativeToken {
+                denom: coin.denom.clone(),
+            },
+            amount: coin.amount,
+        })
+        .collect();
 
-    // only rewarder can execute this message, rewarder may be a contract
-    if config.rewarder != deps.api.addr_canonicalize(info.sender.as_str())? {
+    _deposit_reward(deps, env, info.sender, open, Some(sent), true, rewards)
+}
+
+// rewarders may be contracts funding the contract beforehand, nothing is sent along
+pub fn deposit_prefunded_reward(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rewards: Vec<RewardMsg>,
+) -> StdResult<Response> {
+    if !is_rewarder(
+        deps.storage,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+    )? {
         return Err(StdError::generic_err("unauthorized"));
     }
+    if !info.funds.is_empty() {
+        return Err(StdError::generic_err(
+            "prefunded rewards cannot be sent along, use DepositReward",
+        ));
+    }
+
+    _deposit_reward(deps, env, info.sender, false, None, false, rewards)
+}
 
+// the token contract must be the reward asset of the pools, rewarders must transfer at least the declared
+// amount, open deposits exactly that
+pub fn deposit_reward_cw20(
+    deps: DepsMut,
+    env: Env,
+    depositor: Addr,
+    token_addr: Addr,
+    amount: Uint128,
+    rewards: Vec<RewardMsg>,
+) -> StdResult<Response> {
+    let sent = vec![AssetRaw {
+        info: AssetInfoRaw::Token {
+            contract_addr: deps.api.addr_canonicalize(token_addr.as_str())?,
+        },
+        amount,
+    }];
+
+    let open = !is_rewarder(
+        deps.storage,
+        &deps.api.addr_canonicalize(depositor.as_str())?,
+    )?;
+
+    _deposit_reward(deps, env, depositor, open, Some(sent), open, rewards)
+}
+
+// the share of each pool is rounded down, the remainder goes to the heaviest pool, the first one on ties
+pub fn split_weighted_reward(
+    total_amount: Uint128,
+    weights: &[PoolWeight],
+) -> StdResult<Vec<RewardMsg>> {
+    let total_weight: u64 = weights.iter().map(|pw| pw.weight).sum();
+    if weights.iter().any(|pw| pw.weight == 0) {
+        return Err(StdError::generic_err("pool weights must be positive"));
+    }
+    let heaviest = weights
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|(_, pw)| pw.weight)
+        .map(|(i, _)| i)
+        .ok_or_else(|| StdError::generic_err("no pools to deposit the reward to"))?;
+    for (i, pw) in weights.iter().enumerate() {
+        if weights[..i]
+            .iter()
+            .any(|other| other.staking_token == pw.staking_token)
+        {
+            return Err(StdError::generic_err(format!(
+                "duplicate pool {}",
+                pw.staking_token
+            )));
+        }
+    }
+
+    let mut rewards = weights
+        .iter()
+        .map(|pw| RewardMsg {
+            staking_token: pw.staking_token.clone(),
+            total_accumulation_amount: total_amount.multiply_ratio(pw.weight, total_weight),
+        })
+        .collect::<Vec<RewardMsg>>();
+    let split_amount: Uint128 = rewards.iter().map(|rw| rw.total_accumulation_amount).sum();
+    rewards[heaviest].total_accumulation_amount += total_amount - split_amount;
+    Ok(rewards)
+}
+
+// the deposited amounts per pool, like staking1:60,staking2:40
+pub fn format_pool_amounts(rewards: &[RewardMsg]) -> String {
+    rewards
+        .iter()
+        .map(|rw| format!("{}:{}", rw.staking_token, rw.total_accumulation_amount))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+// sent is what backs the deposit, exact rejects sending more than declared
+// without it the contract balance must cover what it owes in every credited asset
+fn _deposit_reward(
+    deps: DepsMut,
+    env: Env,
+    depositor: Addr,
+    open: bool,
+    sent: Option<Vec<AssetRaw>>,
+    exact: bool,
+    rewards: Vec<RewardMsg>,
+) -> StdResult<Response> {
     let mut rewards_amount = Uint128::zero();
+    // what the deposit must have sent
+    let mut declared: Vec<AssetRaw> = vec![];
+    // the assets a prefunded deposit credits
+    let mut prefunded: Vec<AssetInfoRaw> = vec![];
+    let mut events: Vec<Event> = vec![];
 
     for reward_msg in rewards.iter() {
-        let asset_key = deps
-            .api
-            .addr_canonicalize(reward_msg.staking_token.as_str())?;
+        let asset_key = pool_key(deps.storage, deps.api, &reward_msg.staking_token)?;
+        if open && !is_open_deposit(deps.storage, &asset_key)? {
+            return Err(StdError::generic_err("unauthorized"));
+        }
         let mut pool_info: PoolInfo = read_pool_info(deps.storage, &asset_key)?;
+        if read_accrual_mode(deps.storage, &asset_key)? == AccrualMode::Pull {
+            return Err(StdError::generic_err(
+                "pool accrues its rewards from rewards per sec",
+            ));
+        }
+        // deprecated pools no longer receive rewards
+        if pool_info.deprecated {
+            if open {
+                return Err(StdError::generic_err("pool is deprecated"));
+            }
+            continue;
+        }
+        // nothing accrues while none of the schedules of the pool is running
+        if !sync_reward_schedules(deps.storage, &asset_key, env.block.time.seconds())? {
+            if open {
+                return Err(StdError::generic_err("pool has no running reward schedule"));
+            }
+            continue;
+        }
 
-        let mut normal_reward = reward_msg.total_accumulation_amount;
-
-        // normal rewards are array of Assets
-        if pool_info.total_bond_amount.is_zero() {
-            pool_info.pending_reward += normal_reward;
-        } else {
-            normal_reward += pool_info.pending_reward;
-            let normal_reward_per_bond =
-                Decimal::from_ratio(normal_reward, pool_info.total_bond_amount);
-            pool_info.reward_index = pool_info.reward_index + normal_reward_per_bond;
-            pool_info.pending_reward = Uint128::zero();
+        // the deposit is only backed by the funds when all of it is paid in a single asset
+        if sent.is_some() {
+            let mut reward_assets = read_rewards_per_sec(deps.storage, &asset_key)?
+                .into_iter()
+                .filter(|rw| !rw.amount.is_zero());
+            match (reward_assets.next(), reward_assets.next()) {
+                (Some(rw), None) => update_reward_assets_amount(
+                    &mut declared,
+                    rw,
+                    reward_msg.total_accumulation_amount,
+                ),
+                _ => {
+                    return Err(StdError::generic_err(
+                        "funded deposits need a pool rewarded in a single asset",
+                    ))
+                }
+            }
         }
 
+        distribute_reward(
+            deps.storage,
+            &asset_key,
+            &mut pool_info,
+            reward_msg.total_accumulation_amount,
+            env.block.height,
+        )?;
         store_pool_info(deps.storage, &asset_key, &pool_info)?;
 
+        let mut credited: Vec<AssetRaw> = vec![];
+        split_pending_reward(
+            reward_msg.total_accumulation_amount,
+            read_rewards_per_sec(deps.storage, &asset_key)?,
+            &mut credited,
+        );
+        let mut event = Event::new(DEPOSIT_REWARD_EVENT).add_attributes([
+            attr("action", "deposit_reward"),
+            attr("asset_key", reward_msg.staking_token.as_str()),
+        ]);
+        for ra in credited {
+            if sent.is_none() && !prefunded.contains(&ra.info) {
+                prefunded.push(ra.info.clone());
+            }
+            let asset = ra.to_normal(deps.api)?;
+            event = event.add_attribute(
+                format!("{}{}", REWARD_AMOUNT_KEY_PREFIX, asset_name(&asset.info)),
+                asset.amount.to_string(),
+            );
+        }
+        events.push(event.add_attribute("reward_index", pool_info.reward_index.to_string()));
+
         rewards_amount += reward_msg.total_accumulation_amount;
     }
 
-    Ok(Response::new().add_attributes([
+    for asset_info in prefunded.iter() {
+        let balance = query_asset_balance(
+            &deps.querier,
+            &env.contract.address,
+            &asset_info.to_normal(deps.api)?,
+        )?;
+        if balance < asset_obligations(deps.storage, deps.api, asset_info)? {
+            return Err(StdError::generic_err(
+                "contract balance does not cover the prefunded rewards",
+            ));
+        }
+    }
+    if let Some(sent) = sent {
+        let sent = sent
+            .into_iter()
+            .filter(|asset| !asset.amount.is_zero())
+            .collect::<Vec<AssetRaw>>();
+        let is_token = |asset: &AssetRaw| matches!(asset.info, AssetInfoRaw::Token { .. });
+        if declared.iter().any(is_token) && !sent.iter().any(is_token) {
+            return Err(StdError::generic_err(
+                "cw20 rewards are deposited through the Receive hook",
+            ));
+        }
+        if (exact && sent.len() != declared.len())
+            || declared.iter().any(|asset| {
+                !sent.iter().any(|s| {
+                    s.info == asset.info
+                        && (s.amount == asset.amount || (!exact && s.amount > asset.amount))
+                })
+            })
+        {
+            return Err(StdError::generic_err(
+                "sent funds do not match the declared rewards",
+            ));
+        }
+    }
+
+    Ok(Response::new().add_events(events).add_attributes([
         ("action", "deposit_reward"),
         ("rewards_amount", &rewards_amount.to_string()),
+        ("depositor", depositor.as_str()),
+    ]))
+}
+
+// the weights splitting a deposit are the schedules integrated over the seconds elapsed since the previous
+// one, a change leaves the former weights at the current reward index for the stakers to be split with lazily
+// returns false when no schedule overlapped the elapsed interval
+pub fn sync_reward_schedules(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    now: u64,
+) -> StdResult<bool> {
+    // pools that never had a schedule keep their flat rewards per second
+    let synced_at = match read_reward_schedules_synced(storage, asset_key)? {
+        Some(synced_at) => synced_at,
+        None => return Ok(true),
+    };
+    let schedules = read_reward_schedules(storage, asset_key)?;
+    let current = read_rewards_per_sec(storage, asset_key).unwrap_or_default();
+    if now <= synced_at {
+        return Ok(current.iter().any(|rw| !rw.amount.is_zero()));
+    }
+
+    let elapsed = Uint128::from(now - synced_at);
+    let mut integrated: Vec<AssetRaw> = vec![];
+    for schedule in schedules.iter() {
+        let amount = schedule
+            .amount_per_sec
+            .checked_mul(Uint128::from(schedule.overlap(synced_at, now)))?;
+        if amount.is_zero() {
+            continue;
+        }
+        let rw = AssetRaw {
+            info: schedule.asset_info.clone(),
+            amount: Uint128::zero(),
+        };
+        update_reward_assets_amount(&mut integrated, rw, amount);
+    }
+    if integrated.is_empty() {
+        return Ok(false);
+    }
+
+    // stored as the average rate over the interval, an asset paid below one per second keeps its share
+    let rewards_per_sec = integrated
+        .into_iter()
+        .map(|rw| AssetRaw {
+            info: rw.info,
+            amount: rw.amount.multiply_ratio(1u128, elapsed).max(Uint128::one()),
+        })
+        .collect::<Vec<AssetRaw>>();
+    if rewards_per_sec != current {
+        change_rewards_per_sec(storage, asset_key, rewards_per_sec)?;
+    }
+
+    // ended schedules are fully accounted
+    store_reward_schedules(
+        storage,
+        asset_key,
+        schedules
+            .into_iter()
+            .filter(|schedule| !schedule.is_ended(now))
+            .collect(),
+    )?;
+    store_reward_schedules_synced(storage, asset_key, now)?;
+    Ok(true)
+}
+
+// add amount to the reward index of the pool, it waits in pending_reward while nothing is bonded
+fn distribute_reward(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    pool_info: &mut PoolInfo,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()> {
+    add_outstanding_units(storage, asset_key, amount)?;
+    if pool_info.total_weight().is_zero() {
+        pool_info.pending_reward += amount;
+        return Ok(());
+    }
+
+    let (reward_per_bond, dust) = spread_reward(
+        amount + pool_info.pending_reward,
+        read_reward_dust(storage, asset_key)?,
+        pool_info.total_weight(),
+    )?;
+    pool_info.reward_index = pool_info.reward_index.checked_add(reward_per_bond)?;
+    pool_info.pending_reward = Uint128::zero();
+    store_reward_dust(storage, asset_key, dust)?;
+    REWARD_INDEX.save(storage, asset_key, &pool_info.reward_index, height)
+}
+
+// in pull mode the pool pays its rewards per sec since last_distributed itself,
+// only as far as the contract holds them on top of what it already owes
+// the time nothing could be paid for is not made up later
+pub fn accrue_pool_rewards(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    querier: &QuerierWrapper,
+    env: &Env,
+    asset_key: &[u8],
+) -> StdResult<Uint128> {
+    if read_accrual_mode(storage, asset_key)? != AccrualMode::Pull {
+        return Ok(Uint128::zero());
+    }
+    let now = env.block.time.seconds();
+    let last_distributed = read_last_distributed(storage, asset_key)?.unwrap_or(now);
+    if now <= last_distributed {
+        return Ok(Uint128::zero());
+    }
+    // schedules leave their average rate over the elapsed time in rewards per sec
+    let running = sync_reward_schedules(storage, asset_key, now)?;
+    store_last_distributed(storage, asset_key, now)?;
+    let mut pool_info = read_pool_info(storage, asset_key)?;
+    let rewards_per_sec = read_rewards_per_sec(storage, asset_key)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|rw| !rw.amount.is_zero())
+        .collect::<Vec<AssetRaw>>();
+    if pool_info.deprecated
+        || !running
+        || rewards_per_sec.is_empty()
+        || pool_info.total_weight().is_zero()
+    {
+        return Ok(Uint128::zero());
+    }
+
+    let mut elapsed = now - last_distributed;
+    for rw in rewards_per_sec.iter() {
+        let balance =
+            query_asset_balance(querier, &env.contract.address, &rw.info.to_normal(api)?)?;
+        let available = balance.saturating_sub(asset_obligations(storage, api, &rw.info)?);
+        let funded = u64::try_from((available / rw.amount).u128()).unwrap_or(u64::MAX);
+        elapsed = elapsed.min(funded);
+    }
+    let amount = rewards_per_sec
+        .iter()
+        .map(|rw| rw.amount)
+        .sum::<Uint128>()
+        .checked_mul(Uint128::from(elapsed))?;
+    if amount.is_zero() {
+        return Ok(amount);
+    }
+
+    distribute_reward(storage, asset_key, &mut pool_info, amount, env.block.height)?;
+    store_pool_info(storage, asset_key, &pool_info)?;
+    Ok(amount)
+}
+
+// permissionless crank of the pull mode
+pub fn update_pool_index(deps: DepsMut, env: Env, staking_token: Addr) -> StdResult<Response> {
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    if read_accrual_mode(deps.storage, &asset_key)? != AccrualMode::Pull {
+        return Err(StdError::generic_err("pool is not in pull mode"));
+    }
+    let accrued = accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_pool_index"),
+        attr("staking_token", staking_token.as_str()),
+        attr("accrued_amount", accrued.to_string()),
+        attr("last_distributed", env.block.time.seconds().to_string()),
     ]))
 }
 
+/// amount of an asset owed to stakers, as bonded and locked staking tokens or not paid out rewards,
+/// read from the running totals of the pools so it does not depend on the number of stakers
+pub fn asset_obligations(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    asset_info: &AssetInfoRaw,
+) -> StdResult<Uint128> {
+    // native staking tokens are stored as their denom
+    let asset_bytes = match asset_info {
+        AssetInfoRaw::Token { contract_addr } => contract_addr.as_slice(),
+        AssetInfoRaw::NativeToken { denom } => denom.as_bytes(),
+    };
+    let mut obligations = Uint128::zero();
+    for (asset_key, pool_info) in read_all_pool_infos(storage)? {
+        if pool_info.staking_token.as_slice() == asset_bytes {
+            // locks are keyed by the humanized pool key
+            let staking_token =
+                pool_staking_token(storage, api, &CanonicalAddr::from(asset_key.clone()))?;
+            obligations += pool_info.total_bond_amount
+                + read_total_unbonding(storage, staking_token.as_bytes())?;
+        }
+
+        // units not split into pending_withdraw yet are shared by the assets of rewards_per_sec
+        let outstanding_reward = read_outstanding_reward(storage, &asset_key)?;
+        let mut owed = outstanding_reward.assets;
+        split_pending_reward(
+            outstanding_reward.units,
+            read_rewards_per_sec(storage, &asset_key).unwrap_or_default(),
+            &mut owed,
+        );
+        obligations += owed
+            .iter()
+            .filter(|ra| ra.info.eq(asset_info))
+            .map(|ra| ra.amount)
+            .sum::<Uint128>();
+    }
+    Ok(obligations)
+}
+
+pub fn query_asset_balance(
+    querier: &QuerierWrapper,
+    contract_addr: &Addr,
+    asset_info: &AssetInfo,
+) -> StdResult<Uint128> {
+    match asset_info {
+        AssetInfo::NativeToken { denom } => Ok(querier.query_balance(contract_addr, denom)?.amount),
+        AssetInfo::Token {
+            contract_addr: token,
+        } => {
+            let balance: Cw20BalanceResponse = querier.query_wasm_smart(
+                token.as_str(),
+                &Cw20QueryMsg::Balance {
+                    address: contract_addr.to_string(),
+                },
+            )?;
+            Ok(balance.balance)
+        }
+    }
+}
+
+// what was earned so far stays split by the former rate: the distributed units are owed in its assets
+// and it is left at the current reward index for the stakers to be split with lazily
+// only the proportions split the rewards, a new magnitude of the same split needs no checkpoint
+pub fn change_rewards_per_sec(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    rewards_per_sec: Vec<AssetRaw>,
+) -> StdResult<()> {
+    let current = read_rewards_per_sec(storage, asset_key).unwrap_or_default();
+    // nothing was earned before the first rate
+    if current.iter().any(|rw| !rw.amount.is_zero()) && !same_split(&current, &rewards_per_sec) {
+        let pool_info = read_pool_info(storage, asset_key)?;
+        // the units waiting for a bond are not owed yet
+        let earned = read_outstanding_reward(storage, asset_key)?
+            .units
+            .saturating_sub(pool_info.pending_reward);
+        let mut split: Vec<AssetRaw> = vec![];
+        split_pending_reward(earned, current.clone(), &mut split);
+        sub_outstanding_units(storage, asset_key, earned)?;
+        add_outstanding_assets(storage, asset_key, &split)?;
+        store_rate_checkpoint(storage, asset_key, pool_info.reward_index, current)?;
+    }
+    store_rewards_per_sec(storage, asset_key, rewards_per_sec)
+}
+
+// compared in cross products, the share of every asset must be exactly the same
+fn same_split(a: &[AssetRaw], b: &[AssetRaw]) -> bool {
+    let total =
+        |rates: &[AssetRaw]| Uint256::from(rates.iter().map(|rw| rw.amount).sum::<Uint128>());
+    let share = |rates: &[AssetRaw], info: &AssetInfoRaw| {
+        Uint256::from(
+            rates
+                .iter()
+                .filter(|rw| rw.info == *info)
+                .map(|rw| rw.amount)
+                .sum::<Uint128>(),
+        )
+    };
+    let (total_a, total_b) = (total(a), total(b));
+    if total_a.is_zero() || total_b.is_zero() {
+        return total_a == total_b;
+    }
+    a.iter()
+        .chain(b.iter())
+        .all(|rw| share(a, &rw.info) * total_b == share(b, &rw.info) * total_a)
+}
+
 // withdraw all rewards or single reward depending on asset_token
 pub fn withdraw_reward(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Option<Addr>,
+    skip_failing: bool,
+) -> StdResult<Response> {
+    let response = _withdraw_reward_payout(deps, &env, &info.sender, staking_token, skip_failing)?;
+
+    Ok(response.add_attribute("action", "withdraw_reward"))
+}
+
+// same as withdrawing each pool on its own, paused pools are skipped
+pub fn withdraw_all_reward(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    let staker_addr_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let start_after = start_after
+        .map(|staking_token| pool_key(deps.storage, deps.api, &staking_token))
+        .transpose()?
+        .map(|asset_key| asset_key.to_vec());
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let mut asset_keys = REWARDS
+        .prefix(&staker_addr_raw)
+        .keys(
+            deps.storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit + 1)
+        .collect::<StdResult<Vec<Vec<u8>>>>()?;
+    let has_more = asset_keys.len() > limit;
+    asset_keys.truncate(limit);
+
+    let mut reward_assets: Vec<AssetRaw> = vec![];
+    for asset_key in asset_keys.iter() {
+        for ra in _withdraw_pool_rewards(deps.branch(), &env, &staker_addr_raw, asset_key, false)? {
+            update_reward_assets_amount(&mut reward_assets, ra.clone(), ra.amount);
+        }
+    }
+
+    let last_staking_token = asset_keys
+        .last()
+        .map(|asset_key| {
+            pool_staking_token(
+                deps.storage,
+                deps.api,
+                &CanonicalAddr::from(asset_key.clone()),
+            )
+        })
+        .transpose()?;
+    let reward_assets = reward_assets
+        .into_iter()
+        .map(|ra| ra.to_normal(deps.api))
+        .collect::<StdResult<Vec<Asset>>>()?;
+    let mut transfers = RewardTransfers::default();
+    let response = payout_reward_assets(
+        deps.as_ref(),
+        &mut transfers,
+        &info.sender,
+        None,
+        reward_assets,
+    )?;
+    transfers.save(deps.storage, &staker_addr_raw, None)?;
+
+    Ok(response.add_attributes([
+        attr("action", "withdraw_all"),
+        attr("staker_addr", info.sender.as_str()),
+        attr("processed_pools", asset_keys.len().to_string()),
+        attr(
+            "last_staking_token",
+            last_staking_token
+                .map(|staking_token| staking_token.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+        attr("has_more_pools", has_more.to_string()),
+    ]))
+}
+
+// the claim delegate only triggers the withdrawal, rewards are always sent to the staker
+pub fn withdraw_reward_for(
+    deps: DepsMut,
+    env: Env,
     info: MessageInfo,
+    staker_addr: Addr,
     staking_token: Option<Addr>,
 ) -> StdResult<Response> {
-    let staker_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let delegate = read_claim_delegate(
+        deps.storage,
+        &deps.api.addr_canonicalize(staker_addr.as_str())?,
+    )?;
+    if delegate != Some(deps.api.addr_canonicalize(info.sender.as_str())?) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let response = _withdraw_reward_payout(deps, &env, &staker_addr, staking_token, false)?;
+
+    Ok(response.add_attributes([
+        ("action", "withdraw_reward_for"),
+        ("staker_addr", staker_addr.as_str()),
+        ("delegate", info.sender.as_str()),
+    ]))
+}
+
+fn _withdraw_reward_payout(
+    mut deps: DepsMut,
+    env: &Env,
+    staker_addr: &Addr,
+    staking_token: Option<Addr>,
+    skip_failing: bool,
+) -> StdResult<Response> {
+    let asset_key = staking_token
+        .as_ref()
+        .map(|staking_token| pool_key(deps.storage, deps.api, staking_token))
+        .transpose()?;
+    // the first pool of the staker keeps the failing transfers of a withdraw from all the pools
+    let skip_into = match (&asset_key, skip_failing) {
+        (_, false) => None,
+        (Some(asset_key), true) => Some(asset_key.to_vec()),
+        (None, true) => REWARDS
+            .prefix(&deps.api.addr_canonicalize(staker_addr.as_str())?)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .transpose()?,
+    };
+    let reward_assets = _withdraw_reward_assets(deps.branch(), env, staker_addr, staking_token)?;
+
+    let mut transfers = RewardTransfers::default();
+    let response = payout_reward_assets(
+        deps.as_ref(),
+        &mut transfers,
+        staker_addr,
+        asset_key.as_deref(),
+        reward_assets,
+    )?;
+    transfers.save(
+        deps.storage,
+        &deps.api.addr_canonicalize(staker_addr.as_str())?,
+        skip_into,
+    )?;
+    Ok(response)
+}
+
+/// notifies every claim hook of the rewards paid out, asset_key is None for all the pools
+fn claim_hooks(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    staker: &Addr,
+    asset_key: Option<&[u8]>,
+    reward_assets: &[Asset],
+) -> StdResult<Vec<SubMsg>> {
+    let hooks = read_claim_hooks(storage)?;
+    let rewards: Vec<Asset> = reward_assets
+        .iter()
+        .filter(|asset| !asset.amount.is_zero())
+        .cloned()
+        .collect();
+    if hooks.is_empty() || rewards.is_empty() {
+        return Ok(vec![]);
+    }
+    let msg = to_binary(&ClaimHookExecuteMsg::ClaimHook(ClaimHookMsg {
+        staker: staker.clone(),
+        asset_info: asset_key
+            .map(|asset_key| {
+                pool_asset_info(storage, api, &CanonicalAddr::from(asset_key.to_vec()))
+            })
+            .transpose()?,
+        rewards,
+    }))?;
+    hook_msgs(storage, api, HookKind::Claim, hooks, &msg)
+}
+
+// the reply id of a hook is the base of its kind plus its position in the hook list
+pub const STAKE_HOOK_REPLY_ID: u64 = 100;
+pub const CLAIM_HOOK_REPLY_ID: u64 = 200;
+
+/// the same message to every hook, as submessages so they run after the state is saved
+/// a failing hook only replies, so it cannot revert the message that triggered it
+pub fn hook_msgs(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    kind: HookKind,
+    hooks: Vec<CanonicalAddr>,
+    msg: &Binary,
+) -> StdResult<Vec<SubMsg>> {
+    let reply_id = match kind {
+        HookKind::Stake => STAKE_HOOK_REPLY_ID,
+        HookKind::Claim => CLAIM_HOOK_REPLY_ID,
+    };
+    let mut messages = vec![];
+    for (index, hook) in hooks.into_iter().enumerate() {
+        // skipped after too many failures, the position of the others is kept
+        if read_hook_failures(storage, &kind, &hook)? >= MAX_HOOK_FAILURES {
+            continue;
+        }
+        messages.push(SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: api.addr_humanize(&hook)?.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            },
+            reply_id + index as u64,
+        ));
+    }
+    Ok(messages)
+}
+
+// the hook list cannot change until the replies of the hooks are handled, so the
+// position still points to the hook that was called
+pub fn hook_failed_reply(
+    deps: DepsMut,
+    kind: HookKind,
+    index: u64,
+    result: SubMsgResult,
+) -> StdResult<Response> {
+    let error = match result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Err(StdError::generic_err("hook did not fail")),
+    };
+    let hook = read_hooks_of(deps.storage, &kind)?
+        .into_iter()
+        .nth(index as usize)
+        .ok_or_else(|| StdError::generic_err("unknown hook"))?;
+    let failures = add_hook_failure(deps.storage, &kind, &hook)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "hook_failed"),
+        attr("hook", deps.api.addr_humanize(&hook)?.as_str()),
+        attr("failures", failures.to_string()),
+        attr("disabled", (failures >= MAX_HOOK_FAILURES).to_string()),
+        attr("error", error),
+    ]))
+}
+
+// the reply id of a cw20 reward transfer is the base plus its position in the pending payout
+pub const REWARD_TRANSFER_REPLY_ID: u64 = 300;
+// transfers of a payout with a reply, the ones after it are sent without
+pub const MAX_REWARD_TRANSFERS: u64 = 100;
+
+/// the reward transfers of a payout, a failing cw20 transfer replies with its position so the
+/// error can name the token
+#[derive(Default)]
+pub struct RewardTransfers {
+    transfers: Vec<(Addr, Asset)>,
+}
+
+impl RewardTransfers {
+    pub fn transfer_msg(
+        &mut self,
+        querier: &QuerierWrapper,
+        recipient: &Addr,
+        asset: Asset,
+    ) -> StdResult<SubMsg> {
+        let msg = asset.clone().into_msg(None, querier, recipient.clone())?;
+        let index = self.transfers.len() as u64;
+        if matches!(asset.info, AssetInfo::NativeToken { .. }) || index >= MAX_REWARD_TRANSFERS {
+            return Ok(SubMsg::new(msg));
+        }
+        self.transfers.push((recipient.clone(), asset));
+        Ok(SubMsg::reply_on_error(
+            msg,
+            REWARD_TRANSFER_REPLY_ID + index,
+        ))
+    }
+
+    /// keeps the transfers for their replies, see PendingPayout
+    pub fn save(
+        self,
+        storage: &mut dyn Storage,
+        staker_addr: &CanonicalAddr,
+        skip_into: Option<Vec<u8>>,
+    ) -> StdResult<()> {
+        if self.transfers.is_empty() {
+            return Ok(());
+        }
+        store_pending_payout(
+            storage,
+            &PendingPayout {
+                staker_addr: staker_addr.clone(),
+                skip_into,
+                transfers: self.transfers,
+            },
+        )
+    }
+}
+
+/// names the failing transfer, or keeps its asset in pending_withdraw when the payout skips failures
+pub fn reward_transfer_failed_reply(
+    deps: DepsMut,
+    index: u64,
+    result: SubMsgResult,
+) -> StdResult<Response> {
+    let error = match result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Err(StdError::generic_err("transfer did not fail")),
+    };
+    let pending_payout = read_pending_payout(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("no pending payout"))?;
+    let (recipient, asset) = pending_payout
+        .transfers
+        .into_iter()
+        .nth(index as usize)
+        .ok_or_else(|| StdError::generic_err("unknown transfer"))?;
+    let asset_key = match pending_payout.skip_into {
+        Some(asset_key) => asset_key,
+        None => {
+            return Err(StdError::generic_err(format!(
+                "cannot transfer {}{} to {}: {}",
+                asset.amount,
+                asset_name(&asset.info),
+                recipient,
+                error
+            )))
+        }
+    };
+
+    // the transfer was reverted, so the reward is owed to the staker again
+    let asset_raw = asset.to_raw(deps.api)?;
+    let mut reward_info = REWARDS
+        .may_load(deps.storage, (&pending_payout.staker_addr, &asset_key))?
+        .unwrap_or_else(|| RewardInfo {
+            native_token: false,
+            index: Decimal::zero(),
+            bond_amount: Uint128::zero(),
+            pending_reward: Uint128::zero(),
+            pending_withdraw: vec![],
+            weighted_amount: None,
+        });
+    update_reward_assets_amount(
+        &mut reward_info.pending_withdraw,
+        asset_raw.clone(),
+        asset_raw.amount,
+    );
+    REWARDS.save(
+        deps.storage,
+        (&pending_payout.staker_addr, &asset_key),
+        &reward_info,
+    )?;
+    add_outstanding_assets(deps.storage, &asset_key, &[asset_raw])?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "skip_failing_transfer"),
+        attr("recipient", recipient.as_str()),
+        attr("asset", format_assets(&[asset])),
+        attr("error", error),
+    ]))
+}
+
+// sends the rewards to the staker, less the share of their reward split that goes to the beneficiary,
+// every reward leaving the contract is paid through here. The caller saves the transfers,
+// asset_key is the pool the rewards come from for the claim hooks, None for several pools
+pub fn payout_reward_assets(
+    deps: Deps,
+    transfers: &mut RewardTransfers,
+    staker_addr: &Addr,
+    asset_key: Option<&[u8]>,
+    reward_assets: Vec<Asset>,
+) -> StdResult<Response> {
+    let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    // the hooks run after the payouts, which they leave untouched
+    let claim_hook_msgs = claim_hooks(
+        deps.storage,
+        deps.api,
+        staker_addr,
+        asset_key,
+        &reward_assets,
+    )?;
+    let mut response = Response::new();
+    if reward_assets.iter().any(|asset| !asset.amount.is_zero()) {
+        let mut event = Event::new(WITHDRAW_REWARD_EVENT).add_attributes([
+            attr("action", "withdraw_reward"),
+            attr("staker_addr", staker_addr.as_str()),
+        ]);
+        for asset in reward_assets.iter().filter(|asset| !asset.amount.is_zero()) {
+            event = event.add_attribute(
+                format!("{}{}", PAYOUT_AMOUNT_KEY_PREFIX, asset_name(&asset.info)),
+                asset.amount.to_string(),
+            );
+        }
+        response = response.add_event(event);
+    }
+
+    let (staker_assets, beneficiary) =
+        split_reward_assets(deps.storage, deps.api, &staker_addr_raw, reward_assets)?;
+    let (beneficiary, beneficiary_assets) = match beneficiary {
+        Some(beneficiary) => beneficiary,
+        None => {
+            let messages = staker_assets
+                .into_iter()
+                .map(|asset| transfers.transfer_msg(&deps.querier, staker_addr, asset))
+                .collect::<StdResult<Vec<SubMsg>>>()?;
+            return Ok(response
+                .add_submessages(messages)
+                .add_submessages(claim_hook_msgs));
+        }
+    };
+
+    let attributes = [
+        attr("beneficiary", beneficiary.as_str()),
+        attr("staker_amount", format_assets(&staker_assets)),
+        attr("beneficiary_amount", format_assets(&beneficiary_assets)),
+    ];
+    // a skipped beneficiary share goes back to the staker, whose split applies again later
+    let mut messages = vec![];
+    for asset in staker_assets {
+        messages.push(transfers.transfer_msg(&deps.querier, staker_addr, asset)?);
+    }
+    for asset in beneficiary_assets {
+        messages.push(transfers.transfer_msg(&deps.querier, &beneficiary, asset)?);
+    }
+
+    Ok(response
+        .add_submessages(messages)
+        .add_submessages(claim_hook_msgs)
+        .add_attributes(attributes))
+}
+
+/// the shares of the staker and of the beneficiary of their reward split, the staker keeps
+/// everything without one
+#[allow(clippy::type_complexity)]
+fn split_reward_assets(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    staker_addr: &CanonicalAddr,
+    reward_assets: Vec<Asset>,
+) -> StdResult<(Vec<Asset>, Option<(Addr, Vec<Asset>)>)> {
+    let reward_split = match read_reward_split(storage, staker_addr)? {
+        Some(reward_split) => reward_split,
+        None => return Ok((reward_assets, None)),
+    };
+
+    let beneficiary = api.addr_humanize(&reward_split.beneficiary)?;
+    let mut staker_assets: Vec<Asset> = vec![];
+    let mut beneficiary_assets: Vec<Asset> = vec![];
+    for asset in reward_assets {
+        // the beneficiary share is rounded down, the staker gets the rest
+        let beneficiary_amount = asset
+            .amount
+            .multiply_ratio(reward_split.bps, MAX_REWARD_SPLIT_BPS);
+        beneficiary_assets.push(Asset {
+            info: asset.info.clone(),
+            amount: beneficiary_amount,
+        });
+        staker_assets.push(Asset {
+            info: asset.info,
+            amount: asset.amount - beneficiary_amount,
+        });
+    }
+    staker_assets.retain(|asset| !asset.amount.is_zero());
+    beneficiary_assets.retain(|asset| !asset.amount.is_zero());
+    Ok((staker_assets, Some((beneficiary, beneficiary_assets))))
+}
+
+// amounts of the assets as a comma separated list, like 100orai,200atom
+fn format_assets(assets: &[Asset]) -> String {
+    assets
+        .iter()
+        .map(|asset| format!("{}{}", asset.amount, asset_name(&asset.info)))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+// the denom or the token contract address
+fn asset_name(asset_info: &AssetInfo) -> String {
+    match asset_info {
+        AssetInfo::Token { contract_addr } => contract_addr.to_string(),
+        AssetInfo::NativeToken { denom } => denom.clone(),
+    }
+}
+
+fn _withdraw_reward_assets(
+    mut deps: DepsMut,
+    env: &Env,
+    staker_addr: &Addr,
+    staking_token: Option<Addr>,
+) -> StdResult<Vec<Asset>> {
+    let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
     let asset_key = staking_token.map_or(None, |a| {
-        deps.api
-            .addr_canonicalize(a.as_str())
+        pool_key(deps.storage, deps.api, &a)
             .map(|a| a.to_vec())
             .ok()
     });
 
-    let reward_assets = process_reward_assets(deps.storage, &staker_addr, &asset_key, true)?;
+    // pull pools catch up before paying out
+    let pool_keys = match &asset_key {
+        Some(asset_key) => vec![asset_key.clone()],
+        None => REWARDS
+            .prefix(&staker_addr_raw)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<Vec<u8>>>>()?,
+    };
+    let mut reward_assets: Vec<AssetRaw> = vec![];
+    for pool_key in pool_keys {
+        for ra in _withdraw_pool_rewards(
+            deps.branch(),
+            env,
+            &staker_addr_raw,
+            &pool_key,
+            asset_key.is_some(),
+        )? {
+            update_reward_assets_amount(&mut reward_assets, ra.clone(), ra.amount);
+        }
+    }
 
-    let messages = reward_assets
+    reward_assets
         .into_iter()
-        .map(|ra| {
-            Ok(ra
-                .to_normal(deps.api)?
-                .into_msg(None, &deps.querier, info.sender.clone())?)
-        })
+        .map(|ra| ra.to_normal(deps.api))
+        .collect()
+}
+
+// the rewards of the staker in a single pool, once the pool caught up, for every withdrawal
+// whether it is from one pool or from several
+fn _withdraw_pool_rewards(
+    deps: DepsMut,
+    env: &Env,
+    staker_addr_raw: &CanonicalAddr,
+    asset_key: &[u8],
+    single_pool: bool,
+) -> StdResult<Vec<AssetRaw>> {
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, env, asset_key)?;
+    if !can_withdraw_rewards(deps.storage, staker_addr_raw, asset_key, single_pool)? {
+        return Ok(vec![]);
+    }
+    process_reward_assets(
+        deps.storage,
+        staker_addr_raw,
+        &Some(asset_key.to_vec()),
+        Some(env.block.time),
+    )
+}
+
+// every payout of the rewards of a pool is checked here, only a staker leaving the pool is exempt
+// from the minimum claim. A single pool errors where withdrawing from several pools skips it
+pub fn can_withdraw_rewards(
+    storage: &dyn Storage,
+    staker_addr: &CanonicalAddr,
+    asset_key: &[u8],
+    single_pool: bool,
+) -> StdResult<bool> {
+    let error = if read_pool_status(storage, asset_key)?.withdraw_paused {
+        "withdrawing is paused for this pool".to_string()
+    } else if let Some((pending, min_claim_amount)) =
+        claim_below_minimum(storage, staker_addr, asset_key)?
+    {
+        format!(
+            "claim too small: pending rewards {} are below the minimum claim amount {}",
+            pending, min_claim_amount
+        )
+    } else {
+        return Ok(true);
+    };
+    if single_pool {
+        return Err(StdError::generic_err(error));
+    }
+    Ok(false)
+}
+
+// the pending rewards summed over the reward assets and the minimum claim of the pool, if they are below it
+// stakers without a bond left can always take the rest
+fn claim_below_minimum(
+    storage: &dyn Storage,
+    staker_addr: &CanonicalAddr,
+    asset_key: &[u8],
+) -> StdResult<Option<(Uint128, Uint128)>> {
+    let min_claim_amount = read_min_claim_amount(storage, asset_key)?;
+    if min_claim_amount.is_zero() {
+        return Ok(None);
+    }
+    let mut reward_info = match REWARDS.may_load(storage, (staker_addr, asset_key))? {
+        Some(reward_info) if !reward_info.bond_amount.is_zero() => reward_info,
+        _ => return Ok(None),
+    };
+    split_at_rate_checkpoints(storage, asset_key, &mut reward_info)?;
+    before_share_change(
+        read_pool_info(storage, asset_key)?.reward_index,
+        &mut reward_info,
+    )?;
+
+    let pending = reward_info
+        .pending_withdraw
+        .iter()
+        .map(|pw| pw.amount)
+        .sum::<Uint128>()
+        .checked_add(reward_info.pending_reward)?;
+    Ok((pending < min_claim_amount).then_some((pending, min_claim_amount)))
+}
+
+pub const WITHDRAW_AND_SWAP_REPLY_ID: u64 = 2;
+
+// withdraw like Withdraw, the rewards the router has a route for are swapped into to_asset
+// the swaps pay the contract so min_receive is checked on their total in the reply
+pub fn withdraw_and_swap(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Option<Addr>,
+    to_asset: AssetInfo,
+    min_receive: Option<Uint128>,
+) -> StdResult<Response> {
+    let router = read_router(deps.storage)?
+        .map(|router| deps.api.addr_humanize(&router))
+        .transpose()?
+        .ok_or_else(|| StdError::generic_err("no router to swap the rewards with"))?;
+    let asset_key = staking_token
+        .as_ref()
+        .map(|staking_token| pool_key(deps.storage, deps.api, staking_token))
+        .transpose()?;
+    let reward_assets = _withdraw_reward_assets(deps.branch(), &env, &info.sender, staking_token)?;
+    let staker_addr_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    // the hooks see the rewards as they were withdrawn, before the swaps
+    let claim_hook_msgs = claim_hooks(
+        deps.storage,
+        deps.api,
+        &info.sender,
+        asset_key.as_deref(),
+        &reward_assets,
+    )?;
+
+    // the beneficiary of the reward split is paid its share as it is, only the rest is swapped
+    let mut transfers = RewardTransfers::default();
+    let mut response = Response::new();
+    let (reward_assets, beneficiary) =
+        split_reward_assets(deps.storage, deps.api, &staker_addr_raw, reward_assets)?;
+    if let Some((beneficiary, beneficiary_assets)) = beneficiary {
+        response = response.add_attributes([
+            attr("beneficiary", beneficiary.as_str()),
+            attr("beneficiary_amount", format_assets(&beneficiary_assets)),
+        ]);
+        for asset in beneficiary_assets {
+            response = response.add_submessage(transfers.transfer_msg(
+                &deps.querier,
+                &beneficiary,
+                asset,
+            )?);
+        }
+    }
+
+    let mut direct_amount = Uint128::zero();
+    let mut swaps: Vec<CosmosMsg> = vec![];
+    let mut unswapped: Vec<SubMsg> = vec![];
+    for asset in reward_assets {
+        if asset.info == to_asset {
+            direct_amount += asset.amount;
+            continue;
+        }
+        let operations = vec![SwapOperation::OraiSwap {
+            offer_asset_info: asset.info.clone(),
+            ask_asset_info: to_asset.clone(),
+        }];
+        // a reward without a route is paid as it is
+        let simulation: StdResult<SimulateSwapOperationsResponse> = deps.querier.query_wasm_smart(
+            router.as_str(),
+            &RouterQueryMsg::SimulateSwapOperations {
+                offer_amount: asset.amount,
+                operations: operations.clone(),
+            },
+        );
+        if simulation.is_err() {
+            unswapped.push(transfers.transfer_msg(&deps.querier, &info.sender, asset)?);
+            continue;
+        }
+        swaps.push(match &asset.info {
+            AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+                contract_addr: router.to_string(),
+                msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to: None,
+                })?,
+                funds: vec![coin(asset.amount.u128(), denom)],
+            }
+            .into(),
+            AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: router.to_string(),
+                    amount: asset.amount,
+                    msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations,
+                        minimum_receive: None,
+                        to: None,
+                    })?,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+        });
+    }
+
+    let min_receive = min_receive.unwrap_or_default();
+    response = response.add_submessages(unswapped);
+    let swap_count = swaps.len();
+    if let Some(last_swap) = swaps.pop() {
+        store_pending_swap(
+            deps.storage,
+            &PendingSwap {
+                staker_addr: info.sender.clone(),
+                to_asset: to_asset.clone(),
+                prev_balance: query_asset_balance(&deps.querier, &env.contract.address, &to_asset)?,
+                direct_amount,
+                min_receive,
+            },
+        )?;
+        // the reply of the last swap runs once all of them are done
+        response = response
+            .add_messages(swaps)
+            .add_submessage(SubMsg::reply_on_success(
+                last_swap,
+                WITHDRAW_AND_SWAP_REPLY_ID,
+            ));
+    } else {
+        if direct_amount < min_receive {
+            return Err(StdError::generic_err(
+                "received amount is below the minimum receive",
+            ));
+        }
+        if !direct_amount.is_zero() {
+            response = response.add_submessage(transfers.transfer_msg(
+                &deps.querier,
+                &info.sender,
+                Asset {
+                    info: to_asset,
+                    amount: direct_amount,
+                },
+            )?);
+        }
+    }
+    transfers.save(deps.storage, &staker_addr_raw, None)?;
+
+    Ok(response.add_submessages(claim_hook_msgs).add_attributes([
+        attr("action", "withdraw_and_swap"),
+        attr("staker_addr", info.sender.as_str()),
+        attr("swaps", swap_count.to_string()),
+    ]))
+}
+
+/// sends the swapped rewards to the staker once they reach min_receive
+pub fn withdraw_and_swap_reply(deps: DepsMut, env: Env) -> StdResult<Response> {
+    let pending_swap =
+        read_pending_swap(deps.storage)?.ok_or_else(|| StdError::generic_err("no pending swap"))?;
+    remove_pending_swap(deps.storage);
+
+    let balance =
+        query_asset_balance(&deps.querier, &env.contract.address, &pending_swap.to_asset)?;
+    let received = balance
+        .checked_sub(pending_swap.prev_balance)?
+        .checked_add(pending_swap.direct_amount)?;
+    if received < pending_swap.min_receive {
+        return Err(StdError::generic_err(
+            "received amount is below the minimum receive",
+        ));
+    }
+
+    let mut response = Response::new();
+    if !received.is_zero() {
+        // the transfers of the withdrawal have all run by now, so the pending payout is free again
+        let mut transfers = RewardTransfers::default();
+        response = response.add_submessage(transfers.transfer_msg(
+            &deps.querier,
+            &pending_swap.staker_addr,
+            Asset {
+                info: pending_swap.to_asset,
+                amount: received,
+            },
+        )?);
+        transfers.save(
+            deps.storage,
+            &deps
+                .api
+                .addr_canonicalize(pending_swap.staker_addr.as_str())?,
+            None,
+        )?;
+    }
+    Ok(response.add_attributes([
+        attr("action", "withdraw_and_swap_reply"),
+        attr("staker_addr", pending_swap.staker_addr.as_str()),
+        attr("received_amount", received.to_string()),
+    ]))
+}
+
+// pending_reward keeps accruing untouched
+pub fn claim_pending_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Option<Addr>,
+) -> StdResult<Response> {
+    let staker_addr_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let asset_key = staking_token
+        .map(|staking_token| pool_key(deps.storage, deps.api, &staking_token))
+        .transpose()?;
+
+    let reward_pairs = if let Some(asset_key) = &asset_key {
+        REWARDS
+            .may_load(deps.storage, (&staker_addr_raw, asset_key))?
+            .map(|reward_info| vec![(asset_key.to_vec(), reward_info)])
+            .unwrap_or_default()
+    } else {
+        REWARDS
+            .prefix(&staker_addr_raw)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(Vec<u8>, RewardInfo)>>>()?
+    };
+
+    let mut reward_assets: Vec<AssetRaw> = vec![];
+    for (pool_key, mut reward_info) in reward_pairs {
+        if !can_withdraw_rewards(
+            deps.storage,
+            &staker_addr_raw,
+            &pool_key,
+            asset_key.is_some(),
+        )? {
+            continue;
+        }
+        if reward_info.pending_withdraw.is_empty() {
+            continue;
+        }
+
+        sub_outstanding_assets(deps.storage, &pool_key, &reward_info.pending_withdraw)?;
+        for rw in reward_info.pending_withdraw {
+            if !rw.amount.is_zero() {
+                update_reward_assets_amount(&mut reward_assets, rw.clone(), rw.amount);
+            }
+        }
+        reward_info.pending_withdraw = vec![];
+
+        if reward_info.bond_amount.is_zero() && reward_info.pending_reward.is_zero() {
+            REWARDS.remove(deps.storage, (&staker_addr_raw, &pool_key));
+        } else {
+            REWARDS.save(deps.storage, (&staker_addr_raw, &pool_key), &reward_info)?;
+        }
+    }
+
+    if reward_assets.is_empty() {
+        return Err(StdError::generic_err("no pending withdraw to claim"));
+    }
+
+    let reward_assets = reward_assets
+        .into_iter()
+        .map(|ra| ra.to_normal(deps.api))
+        .collect::<StdResult<Vec<Asset>>>()?;
+    let mut transfers = RewardTransfers::default();
+    let response = payout_reward_assets(
+        deps.as_ref(),
+        &mut transfers,
+        &info.sender,
+        asset_key.as_deref(),
+        reward_assets,
+    )?;
+    transfers.save(deps.storage, &staker_addr_raw, None)?;
+
+    Ok(response.add_attributes([
+        ("action", "claim_pending_withdraw"),
+        ("staker_addr", info.sender.as_str()),
+    ]))
+}
+
+// the rewards deposited while nobody was bonded are only claimable once the pool is empty,
+// as the next deposit would hand them to the new stakers
+pub fn reclaim_pending_reward(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_token: Addr,
+    recipient: String,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    let mut pool_info = read_pool_info(deps.storage, &asset_key)?;
+    if !pool_info.total_bond_amount.is_zero() {
+        return Err(StdError::generic_err(
+            "pool has bonded stakers, the pending reward belongs to them",
+        ));
+    }
+    if pool_info.pending_reward.is_zero() {
+        return Err(StdError::generic_err("no pending reward to reclaim"));
+    }
+
+    let mut reclaimed: Vec<AssetRaw> = vec![];
+    split_pending_reward(
+        pool_info.pending_reward,
+        read_rewards_per_sec(deps.storage, &asset_key)?,
+        &mut reclaimed,
+    );
+    if reclaimed.is_empty() {
+        return Err(StdError::generic_err(
+            "pool has no reward assets to split the pending reward",
+        ));
+    }
+    sub_outstanding_units(deps.storage, &asset_key, pool_info.pending_reward)?;
+    pool_info.pending_reward = Uint128::zero();
+    store_pool_info(deps.storage, &asset_key, &pool_info)?;
+
+    let reclaimed = reclaimed
+        .into_iter()
+        .filter(|ra| !ra.amount.is_zero())
+        .map(|ra| ra.to_normal(deps.api))
+        .collect::<StdResult<Vec<Asset>>>()?;
+    let amounts = format_assets(&reclaimed);
+    let messages = reclaimed
+        .into_iter()
+        .map(|asset| asset.into_msg(None, &deps.querier, recipient.clone()))
         .collect::<StdResult<Vec<CosmosMsg>>>()?;
 
-    Ok(Response::new()
-        .add_messages(messages)
-        .add_attribute("action", "withdraw_reward"))
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "reclaim_pending_reward"),
+        attr("staking_token", staking_token.as_str()),
+        attr("recipient", recipient.as_str()),
+        attr("reclaimed", amounts),
+    ]))
 }
 
 pub fn withdraw_reward_others(
@@ -98,13 +1496,10 @@ pub fn withdraw_reward_others(
     let config = read_config(deps.storage)?;
 
     // only admin can execute this message
-    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
-        return Err(StdError::generic_err("unauthorized"));
-    }
+    config.assert_owner(deps.api, &info.sender)?;
 
     let asset_key = staker_addr.map_or(None, |a| {
-        deps.api
-            .addr_canonicalize(a.as_str())
+        pool_key(deps.storage, deps.api, &a)
             .map(|a| a.to_vec())
             .ok()
     });
@@ -113,12 +1508,88 @@ pub fn withdraw_reward_others(
     // withdraw reward for each staker
     for staker_addr in staker_addrs {
         let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
-        process_reward_assets(deps.storage, &staker_addr_raw, &asset_key, false)?;
+        process_reward_assets(deps.storage, &staker_addr_raw, &asset_key, None)?;
     }
 
     Ok(Response::new().add_attribute("action", "withdraw_reward_others"))
 }
 
+pub fn add_outstanding_units(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    units: Uint128,
+) -> StdResult<()> {
+    let mut outstanding_reward = read_outstanding_reward(storage, asset_key)?;
+    outstanding_reward.units = outstanding_reward.units.checked_add(units)?;
+    store_outstanding_reward(storage, asset_key, &outstanding_reward)
+}
+
+// saturating, as pools may hold rewards from before they were tracked
+pub fn sub_outstanding_units(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    units: Uint128,
+) -> StdResult<()> {
+    let mut outstanding_reward = read_outstanding_reward(storage, asset_key)?;
+    outstanding_reward.units = outstanding_reward.units.saturating_sub(units);
+    store_outstanding_reward(storage, asset_key, &outstanding_reward)
+}
+
+fn add_outstanding_assets(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    assets: &[AssetRaw],
+) -> StdResult<()> {
+    let mut outstanding_reward = read_outstanding_reward(storage, asset_key)?;
+    for asset in assets {
+        update_reward_assets_amount(&mut outstanding_reward.assets, asset.clone(), asset.amount);
+    }
+    store_outstanding_reward(storage, asset_key, &outstanding_reward)
+}
+
+// saturating like sub_outstanding_units
+pub fn sub_outstanding_assets(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    assets: &[AssetRaw],
+) -> StdResult<()> {
+    let mut outstanding_reward = read_outstanding_reward(storage, asset_key)?;
+    for asset in assets {
+        if let Some(outstanding) = outstanding_reward
+            .assets
+            .iter_mut()
+            .find(|ra| ra.info.eq(&asset.info))
+        {
+            outstanding.amount = outstanding.amount.saturating_sub(asset.amount);
+        }
+    }
+    outstanding_reward.assets.retain(|ra| !ra.amount.is_zero());
+    store_outstanding_reward(storage, asset_key, &outstanding_reward)
+}
+
+// what the contract owes the stakers of all pools per reward asset, the units not split yet
+// are shared by the current rewards per sec of their pool
+pub fn query_outstanding_rewards(deps: Deps) -> StdResult<Vec<Asset>> {
+    let mut outstanding: Vec<AssetRaw> = vec![];
+    for (asset_key, _) in read_all_pool_infos(deps.storage)? {
+        let outstanding_reward = read_outstanding_reward(deps.storage, &asset_key)?;
+        split_pending_reward(
+            outstanding_reward.units,
+            read_rewards_per_sec(deps.storage, &asset_key).unwrap_or_default(),
+            &mut outstanding,
+        );
+        for ra in outstanding_reward.assets {
+            update_reward_assets_amount(&mut outstanding, ra.clone(), ra.amount);
+        }
+    }
+
+    outstanding
+        .into_iter()
+        .filter(|ra| !ra.amount.is_zero())
+        .map(|ra| ra.to_normal(deps.api))
+        .collect()
+}
+
 fn update_reward_assets_amount(reward_assets: &mut Vec<AssetRaw>, rw: AssetRaw, amount: Uint128) {
     match reward_assets.iter_mut().find(|ra| ra.info.eq(&rw.info)) {
         None => {
@@ -133,58 +1604,122 @@ fn update_reward_assets_amount(reward_assets: &mut Vec<AssetRaw>, rw: AssetRaw,
     }
 }
 
+// index increase for amount and the dust carried from before, with the remainder the index truncated
+// amount == index increase * total_weight + new dust - former dust holds exactly
+fn spread_reward(
+    amount: Uint128,
+    dust: Decimal,
+    total_weight: Uint128,
+) -> StdResult<(Decimal, Decimal)> {
+    // amounts of 18 decimals tokens do not fit 128 bits once scaled
+    let total_weight = Uint256::from(total_weight);
+    let atomics = Uint256::from(amount)
+        .checked_mul(decimal_fractional())?
+        .checked_add(dust.atomics().into())?;
+    let per_weight = atomics.checked_div(total_weight)?;
+    let dust = atomics.checked_sub(per_weight.checked_mul(total_weight)?)?;
+    Ok((
+        Decimal::new(to_uint128(per_weight)?),
+        Decimal::new(to_uint128(dust)?),
+    ))
+}
+
+fn decimal_fractional() -> Uint256 {
+    Uint256::from(10u128.pow(Decimal::DECIMAL_PLACES))
+}
+
+fn to_uint128(value: Uint256) -> StdResult<Uint128> {
+    Uint128::try_from(value).map_err(|_| StdError::generic_err("reward amount overflows 128 bits"))
+}
+
+/// weight * index in 256 bits, floored to a reward amount
+pub fn index_amount(weight: Uint128, index: Decimal) -> StdResult<Uint256> {
+    Ok(Uint256::from(weight)
+        .checked_mul(index.atomics().into())?
+        .checked_div(decimal_fractional())?)
+}
+
+// split the pending reward by the weights of the reward assets into pending_withdraw
+fn split_pending_reward(
+    pending_reward: Uint128,
+    rewards_per_sec: Vec<AssetRaw>,
+    pending_withdraw: &mut Vec<AssetRaw>,
+) {
+    // now calculate weight
+    let total_amount: Uint128 = rewards_per_sec.iter().map(|rw| rw.amount).sum();
+
+    for rw in rewards_per_sec {
+        // ignore empty weight
+        if rw.amount.is_zero() {
+            continue;
+        }
+        let amount = pending_reward * Decimal::from_ratio(rw.amount, total_amount);
+
+        // update pending_withdraw, first time push it, later update the amount
+        update_reward_assets_amount(pending_withdraw, rw, amount);
+    }
+}
+
 // this function will return total asset to reward, then later can be updated as pending_withdraw, or send to client
+// withdrawing at withdraw_time vests the rewards of pools with a vesting period instead of returning them
 pub fn process_reward_assets(
     storage: &mut dyn Storage,
     staker_addr: &CanonicalAddr,
     asset_key: &Option<Vec<u8>>,
-    do_withdraw: bool,
+    withdraw_time: Option<Timestamp>,
 ) -> StdResult<Vec<AssetRaw>> {
-    let rewards_bucket = rewards_read(storage, staker_addr);
-
     // single reward withdraw, using Vec to store reference variable in local function
     let reward_pairs = if let Some(asset_key) = asset_key {
-        let reward_info = rewards_bucket.may_load(&asset_key)?;
+        let reward_info = REWARDS.may_load(storage, (staker_addr, asset_key))?;
         if let Some(reward_info) = reward_info {
             vec![(asset_key.to_vec(), reward_info)]
         } else {
             vec![]
         }
     } else {
-        rewards_bucket
-            .range(None, None, Order::Ascending)
+        REWARDS
+            .prefix(staker_addr)
+            .range(storage, None, None, Order::Ascending)
             .collect::<StdResult<Vec<(Vec<u8>, RewardInfo)>>>()?
     };
 
-    // only has value when do_withdraw
+    // only has value when withdrawing
     let mut reward_assets: Vec<AssetRaw> = vec![];
 
     for reward_pair in reward_pairs {
-        let (asset_key, mut reward_info) = reward_pair;
+        let (pool_key, mut reward_info) = reward_pair;
+
+        // paused pools keep their rewards until unpaused, withdrawing from all pools just skips them
+        if withdraw_time.is_some() && read_pool_status(storage, &pool_key)?.withdraw_paused {
+            if asset_key.is_some() {
+                return Err(StdError::generic_err("withdrawing is paused for this pool"));
+            }
+            continue;
+        }
+
+        let asset_key = pool_key;
         let pool_info: PoolInfo = read_pool_info(storage, &asset_key)?;
 
         // Withdraw reward to pending reward
         // if the lp token was migrated, and the user did not close their position yet, cap the reward at the snapshot
         let pool_index = pool_info.reward_index;
 
+        split_at_rate_checkpoints(storage, &asset_key, &mut reward_info)?;
         before_share_change(pool_index, &mut reward_info)?;
 
         if !reward_info.pending_reward.is_zero() {
             // calculate and accumulate the reward amount
             let rewards_per_sec = read_rewards_per_sec(storage, &asset_key)?;
-            // now calculate weight
-            let total_amount: Uint128 = rewards_per_sec.iter().map(|rw| rw.amount).sum();
-
-            for rw in rewards_per_sec {
-                // ignore empty weight
-                if rw.amount.is_zero() {
-                    continue;
-                }
-                let amount =
-                    reward_info.pending_reward * Decimal::from_ratio(rw.amount, total_amount);
-
-                // update pending_withdraw, first time push it, later update the amount
-                update_reward_assets_amount(&mut reward_info.pending_withdraw, rw, amount);
+            let mut split: Vec<AssetRaw> = vec![];
+            split_pending_reward(reward_info.pending_reward, rewards_per_sec, &mut split);
+            sub_outstanding_units(storage, &asset_key, reward_info.pending_reward)?;
+            add_outstanding_assets(storage, &asset_key, &split)?;
+            for rw in split {
+                update_reward_assets_amount(
+                    &mut reward_info.pending_withdraw,
+                    rw.clone(),
+                    rw.amount,
+                );
             }
 
             // reset pending_reward
@@ -192,31 +1727,170 @@ pub fn process_reward_assets(
         }
 
         // if withdraw, then update reward_assets to create MsgSend
-        if do_withdraw {
-            for rw in reward_info.pending_withdraw {
-                update_reward_assets_amount(&mut reward_assets, rw.clone(), rw.amount);
+        if let Some(withdraw_time) = withdraw_time {
+            let pending_withdraw = std::mem::take(&mut reward_info.pending_withdraw);
+            match read_reward_vesting_period(storage, &asset_key)? {
+                // the vesting rewards are still owed, so they stay outstanding until claimed
+                Some(period) => vest_reward_assets(
+                    storage,
+                    &asset_key,
+                    staker_addr,
+                    pending_withdraw,
+                    withdraw_time,
+                    period,
+                )?,
+                None => {
+                    sub_outstanding_assets(storage, &asset_key, &pending_withdraw)?;
+                    for rw in pending_withdraw {
+                        update_reward_assets_amount(&mut reward_assets, rw.clone(), rw.amount);
+                    }
+                }
             }
-            reward_info.pending_withdraw = vec![];
         }
 
         // Update rewards info, if empty bond_amount and withdraw then remove
-        if reward_info.bond_amount.is_zero() && do_withdraw {
-            rewards_store(storage, staker_addr).remove(&asset_key);
+        if reward_info.bond_amount.is_zero() && withdraw_time.is_some() {
+            REWARDS.remove(storage, (staker_addr, &asset_key));
         } else {
-            rewards_store(storage, staker_addr).save(&asset_key, &reward_info)?;
+            REWARDS.save(storage, (staker_addr, &asset_key), &reward_info)?;
         }
     }
 
     Ok(reward_assets)
 }
 
+// the rewards unlock in equal tranches over the period, the unlock times are rounded up to the
+// tranche length so the tranches of later withdraws merge into the existing ones
+fn vest_reward_assets(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    staker_addr: &CanonicalAddr,
+    rewards: Vec<AssetRaw>,
+    time: Timestamp,
+    period: u64,
+) -> StdResult<()> {
+    let tranches = (period / VESTING_STEP).max(1);
+    let step = period / tranches;
+    let start = (time.seconds() + step - 1) / step * step;
+    for i in 1..=tranches {
+        let tranche = rewards
+            .iter()
+            .map(|rw| AssetRaw {
+                info: rw.info.clone(),
+                amount: rw.amount.multiply_ratio(i, tranches)
+                    - rw.amount.multiply_ratio(i - 1, tranches),
+            })
+            .filter(|rw| !rw.amount.is_zero())
+            .collect::<Vec<AssetRaw>>();
+        if !tranche.is_empty() {
+            insert_reward_vesting(storage, asset_key, staker_addr, start + i * step, tranche)?;
+        }
+    }
+    Ok(())
+}
+
+// releases the matured vesting tranches of the sender
+pub fn claim_vested(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+) -> StdResult<Response> {
+    let staker_addr_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+
+    let rewards =
+        remove_matured_reward_vestings(deps.storage, &asset_key, &staker_addr_raw, env.block.time)?;
+    if rewards.is_empty() {
+        return Err(StdError::generic_err("no vested rewards to claim"));
+    }
+    sub_outstanding_assets(deps.storage, &asset_key, &rewards)?;
+
+    let rewards = rewards
+        .into_iter()
+        .map(|ra| ra.to_normal(deps.api))
+        .collect::<StdResult<Vec<Asset>>>()?;
+    let mut transfers = RewardTransfers::default();
+    let response = payout_reward_assets(
+        deps.as_ref(),
+        &mut transfers,
+        &info.sender,
+        Some(asset_key.as_slice()),
+        rewards,
+    )?;
+    transfers.save(deps.storage, &staker_addr_raw, None)?;
+
+    Ok(response.add_attributes([
+        attr("action", "claim_vested"),
+        attr("staker_addr", info.sender.as_str()),
+        attr("staking_token", staking_token.as_str()),
+    ]))
+}
+
+pub fn query_reward_vestings(
+    deps: Deps,
+    env: Env,
+    staker_addr: Addr,
+    staking_token: Addr,
+) -> StdResult<RewardVestingsResponse> {
+    let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+
+    Ok(RewardVestingsResponse {
+        vesting_period: read_reward_vesting_period(deps.storage, &asset_key)?,
+        vestings: read_reward_vestings(deps.storage, &asset_key, &staker_addr_raw)?
+            .into_iter()
+            .map(|(unlock_time, rewards)| {
+                Ok(RewardVestingResponse {
+                    rewards: rewards
+                        .into_iter()
+                        .map(|ra| ra.to_normal(deps.api))
+                        .collect::<StdResult<Vec<Asset>>>()?,
+                    unlock_time,
+                    matured: unlock_time <= env.block.time.seconds(),
+                })
+            })
+            .collect::<StdResult<Vec<RewardVestingResponse>>>()?,
+        staker_addr,
+        staking_token,
+    })
+}
+
+// the rewards earned before each rate change since the staker was last settled are split with the former rate,
+// to be called before every before_share_change
+// the outstanding rewards moved to the assets when the rate changed
+pub fn split_at_rate_checkpoints(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    reward_info: &mut RewardInfo,
+) -> StdResult<()> {
+    if reward_info.weight().is_zero() && reward_info.pending_reward.is_zero() {
+        return Ok(());
+    }
+    for (reward_index, rewards_per_sec) in
+        read_rate_checkpoints(storage, asset_key, reward_info.index)?
+    {
+        before_share_change(reward_index, reward_info)?;
+        split_pending_reward(
+            reward_info.pending_reward,
+            rewards_per_sec,
+            &mut reward_info.pending_withdraw,
+        );
+        reward_info.pending_reward = Uint128::zero();
+    }
+    Ok(())
+}
+
 // withdraw reward to pending reward
 pub fn before_share_change(pool_index: Decimal, reward_info: &mut RewardInfo) -> StdResult<()> {
-    let pending_reward = (reward_info.bond_amount * pool_index)
-        .checked_sub(reward_info.bond_amount * reward_info.index)?;
+    let weight = reward_info.weight();
+    let pending_reward =
+        index_amount(weight, pool_index)?.checked_sub(index_amount(weight, reward_info.index)?)?;
 
     reward_info.index = pool_index;
-    reward_info.pending_reward += pending_reward;
+    reward_info.pending_reward = reward_info
+        .pending_reward
+        .checked_add(to_uint128(pending_reward)?)?;
     Ok(())
 }
 
@@ -245,7 +1919,7 @@ pub fn query_all_reward_infos(
 ) -> StdResult<Vec<RewardInfoResponse>> {
     // default is Ascending
     let order_by = Order::try_from(order.unwrap_or(1))?;
-    let asset_key = deps.api.addr_canonicalize(staking_token.as_str())?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
 
     let start_after = start_after
         .map_or(None, |a| deps.api.addr_canonicalize(a.as_str()).ok())
@@ -253,17 +1927,14 @@ pub fn query_all_reward_infos(
 
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
-    let (start, end) = match order_by {
-        Order::Ascending => (calc_range_start(start_after), None),
-        Order::Descending => (None, start_after),
-    };
+    let (start, end) = page_bounds(start_after.as_deref(), order_by);
 
-    let info_responses = stakers_read(deps.storage, &asset_key)
-        .range(start.as_deref(), end.as_deref(), order_by)
+    let info_responses = STAKERS
+        .prefix(&asset_key)
+        .keys(deps.storage, start, end, order_by)
         .take(limit)
         .map(|item| {
-            let (k, _) = item?;
-            let staker_addr_raw = CanonicalAddr::from(k);
+            let staker_addr_raw = CanonicalAddr::from(item?);
             let reward_infos: Vec<RewardInfoResponseItem> = _read_reward_infos_response(
                 deps.api,
                 deps.storage,
@@ -281,6 +1952,109 @@ pub fn query_all_reward_infos(
     Ok(info_responses)
 }
 
+pub fn query_reward_infos_batch(
+    deps: Deps,
+    staking_token: Addr,
+    stakers: Vec<String>,
+) -> StdResult<Vec<RewardInfosBatchResponseItem>> {
+    if stakers.len() > MAX_REWARD_INFOS_BATCH {
+        return Err(StdError::generic_err(format!(
+            "too many stakers, at most {} per batch",
+            MAX_REWARD_INFOS_BATCH
+        )));
+    }
+    // make sure the pool exists
+    read_pool_info(
+        deps.storage,
+        &pool_key(deps.storage, deps.api, &staking_token)?,
+    )?;
+
+    stakers
+        .into_iter()
+        .map(|staker| {
+            let staker_addr = deps.api.addr_validate(&staker)?;
+            let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
+            let reward_info = _read_reward_infos_response(
+                deps.api,
+                deps.storage,
+                &staker_addr_raw,
+                &Some(staking_token.clone()),
+            )?
+            .pop();
+            Ok(match reward_info {
+                Some(reward_info) => RewardInfosBatchResponseItem {
+                    staker_addr,
+                    bond_amount: reward_info.bond_amount,
+                    pending_reward: reward_info.pending_reward,
+                    pending_withdraw: reward_info.pending_withdraw,
+                },
+                None => RewardInfosBatchResponseItem {
+                    staker_addr,
+                    bond_amount: Uint128::zero(),
+                    pending_reward: Uint128::zero(),
+                    pending_withdraw: vec![],
+                },
+            })
+        })
+        .collect()
+}
+
+pub fn query_all_staker_info(
+    deps: Deps,
+    staker_addr: Addr,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    order: Option<i32>,
+) -> StdResult<Vec<StakerInfoResponseItem>> {
+    let order_by = Order::try_from(order.unwrap_or(1))?;
+    let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
+
+    let start_after = start_after
+        .map(|staking_token| pool_key(deps.storage, deps.api, &staking_token))
+        .transpose()?
+        .map(|asset_key| asset_key.to_vec());
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let (start, end) = page_bounds(start_after.as_deref(), order_by);
+
+    REWARDS
+        .prefix(&staker_addr_raw)
+        .range(deps.storage, start, end, order_by)
+        .map(|item| -> StdResult<StakerInfoResponseItem> {
+            let (asset_key, mut reward_info) = item?;
+            let pool_info = read_pool_info(deps.storage, &asset_key)?;
+            split_at_rate_checkpoints(deps.storage, &asset_key, &mut reward_info)?;
+            before_share_change(pool_info.reward_index, &mut reward_info)?;
+            let staking_token =
+                pool_staking_token(deps.storage, deps.api, &CanonicalAddr::from(asset_key))?;
+            Ok(StakerInfoResponseItem {
+                total_unbonding: read_user_lock_amount(
+                    deps.storage,
+                    staking_token.as_bytes(),
+                    staker_addr.as_bytes(),
+                )?,
+                staking_token,
+                bond_amount: reward_info.bond_amount,
+                pending_reward: reward_info.pending_reward,
+                pending_withdraw: reward_info
+                    .pending_withdraw
+                    .into_iter()
+                    .filter(|pw| !pw.amount.is_zero())
+                    .map(|pw| pw.to_normal(deps.api))
+                    .collect::<StdResult<Vec<Asset>>>()?,
+            })
+        })
+        // skip the pools the staker has nothing left in
+        .filter(|item| {
+            !matches!(item, Ok(info) if info.bond_amount.is_zero()
+                && info.pending_reward.is_zero()
+                && info.pending_withdraw.is_empty()
+                && info.total_unbonding.is_zero())
+        })
+        .take(limit)
+        .collect()
+}
+
 fn _read_reward_infos_response(
     api: &dyn Api,
     storage: &dyn Storage,
@@ -291,11 +2065,12 @@ fn _read_reward_infos_response(
     let reward_infos: Vec<RewardInfoResponseItem> = results
         .into_iter()
         .map(|(staking_token, mut reward_info)| {
-            let asset_key = api.addr_canonicalize(staking_token.as_str())?.to_vec();
+            let asset_key = pool_key(storage, api, &staking_token)?.to_vec();
             let pool_info = read_pool_info(storage, &asset_key)?;
 
             let pool_index = pool_info.reward_index;
 
+            split_at_rate_checkpoints(storage, &asset_key, &mut reward_info)?;
             before_share_change(pool_index, &mut reward_info)?;
 
             let pending_withdraw = reward_info
@@ -316,29 +2091,115 @@ fn _read_reward_infos_response(
     Ok(reward_infos)
 }
 
+pub fn query_pending_withdraw(
+    deps: Deps,
+    staker_addr: Addr,
+    staking_token: Option<Addr>,
+) -> StdResult<PendingWithdrawResponse> {
+    let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
+
+    let mut pending_withdraw: Vec<AssetRaw> = vec![];
+    for (_, reward_info) in
+        _read_reward_infos(deps.api, deps.storage, &staker_addr_raw, &staking_token)?
+    {
+        for rw in reward_info.pending_withdraw {
+            if !rw.amount.is_zero() {
+                update_reward_assets_amount(&mut pending_withdraw, rw.clone(), rw.amount);
+            }
+        }
+    }
+
+    Ok(PendingWithdrawResponse {
+        staker_addr,
+        pending_withdraw: pending_withdraw
+            .into_iter()
+            .map(|rw| rw.to_normal(deps.api))
+            .collect::<StdResult<Vec<Asset>>>()?,
+    })
+}
+
+pub fn query_reward_pool_count(
+    deps: Deps,
+    staker_addr: Addr,
+) -> StdResult<RewardPoolCountResponse> {
+    let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
+
+    let count = _read_reward_infos_response(deps.api, deps.storage, &staker_addr_raw, &None)?
+        .into_iter()
+        .filter(|reward_info| {
+            !reward_info.pending_reward.is_zero()
+                || reward_info
+                    .pending_withdraw
+                    .iter()
+                    .any(|asset| !asset.amount.is_zero())
+        })
+        .count() as u64;
+
+    Ok(RewardPoolCountResponse { count })
+}
+
+// what a withdraw would pay out now, pending_withdraw included
+pub fn query_pending_rewards(
+    deps: Deps,
+    staker_addr: Addr,
+    staking_token: Option<Addr>,
+) -> StdResult<Vec<Asset>> {
+    let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
+
+    let mut reward_assets: Vec<AssetRaw> = vec![];
+    for (staking_token, mut reward_info) in
+        _read_reward_infos(deps.api, deps.storage, &staker_addr_raw, &staking_token)?
+    {
+        let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+        let pool_info = read_pool_info(deps.storage, &asset_key)?;
+        split_at_rate_checkpoints(deps.storage, &asset_key, &mut reward_info)?;
+        before_share_change(pool_info.reward_index, &mut reward_info)?;
+
+        if !reward_info.pending_reward.is_zero() {
+            let rewards_per_sec = read_rewards_per_sec(deps.storage, &asset_key)?;
+            split_pending_reward(
+                reward_info.pending_reward,
+                rewards_per_sec,
+                &mut reward_info.pending_withdraw,
+            );
+        }
+        for rw in reward_info.pending_withdraw {
+            if !rw.amount.is_zero() {
+                update_reward_assets_amount(&mut reward_assets, rw.clone(), rw.amount);
+            }
+        }
+    }
+
+    reward_assets
+        .into_iter()
+        .map(|rw| rw.to_normal(deps.api))
+        .collect()
+}
+
 fn _read_reward_infos(
     api: &dyn Api,
     storage: &dyn Storage,
     staker_addr: &CanonicalAddr,
     staking_token: &Option<Addr>,
 ) -> StdResult<Vec<(Addr, RewardInfo)>> {
-    let rewards_bucket = rewards_read(storage, staker_addr);
     let results = if let Some(staking_token) = staking_token {
-        let asset_key = api.addr_canonicalize(staking_token.as_str())?.to_vec();
+        let asset_key = pool_key(storage, api, staking_token)?;
 
-        if let Some(reward_info) = rewards_bucket.may_load(&asset_key)? {
+        if let Some(reward_info) = REWARDS.may_load(storage, (staker_addr, &asset_key))? {
             vec![(staking_token.clone(), reward_info)]
         } else {
             vec![]
         }
     } else {
-        rewards_bucket
-            .range(None, None, Order::Ascending)
+        REWARDS
+            .prefix(staker_addr)
+            .range(storage, None, None, Order::Ascending)
             .map(|item| {
                 let (asset_key, reward_info) = item?;
 
                 // try convert to AssetInfo based on reward info
-                let staking_token = api.addr_humanize(&CanonicalAddr::from(asset_key))?;
+                let staking_token =
+                    pool_staking_token(storage, api, &CanonicalAddr::from(asset_key))?;
                 Ok((staking_token, reward_info))
             })
             .collect::<StdResult<Vec<(Addr, RewardInfo)>>>()?
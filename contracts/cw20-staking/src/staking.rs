@@ -1,170 +1,1513 @@
-use crate::msg::LockInfo;
-use crate::rewards::before_share_change;
+use crate::msg::{
+    BondCallbackExecuteMsg, BondCallbackMsg, BoostCurve, HookKind, LockInfo,
+    StakeChangedExecuteMsg, StakeChangedHookMsg, UnbondMsg,
+};
+use crate::rewards::{
+    accrue_pool_rewards, before_share_change, can_withdraw_rewards, hook_msgs, index_amount,
+    payout_reward_assets, process_reward_assets, query_asset_balance, split_at_rate_checkpoints,
+    sub_outstanding_assets, sub_outstanding_units, RewardTransfers,
+};
 use crate::state::{
-    insert_lock_info, read_pool_info, read_unbonding_period, remove_and_accumulate_lock_info,
-    rewards_read, rewards_store, stakers_store, store_pool_info, PoolInfo, RewardInfo,
-    STAKED_BALANCES, STAKED_TOTAL,
+    add_staker, insert_lock_info, is_blacklisted, is_native_staking_token,
+    is_stake_migration_enabled, pool_asset_info, pool_key, pool_staking_token, read_boost,
+    read_boost_curve, read_config, read_hooks, read_import_open, read_instant_unbond_penalty,
+    read_min_bond_amount, read_pending_bond, read_pending_zap, read_pool_info, read_pool_status,
+    read_router, read_staking_token_pool, read_unbond_fee, read_unbonding_period,
+    read_user_lock_amount, remove_and_accumulate_lock_info,
+    remove_and_accumulate_lock_info_limited, remove_boost, remove_lock_amount, remove_pending_bond,
+    remove_pending_zap, remove_staker, store_boost, store_lock_period, store_pending_bond,
+    store_pending_zap, store_pool_info, Boost, Config, PendingBond, PendingZap, PoolInfo,
+    RewardInfo, DEFAULT_LOCK_LIMIT, REWARDS, STAKED_BALANCES, STAKED_TOTAL, WEEK,
 };
 use cosmwasm_std::{
-    attr, to_binary, Addr, Api, CanonicalAddr, CosmosMsg, Decimal, DepsMut, Env, Response,
-    StdError, StdResult, Storage, Uint128, WasmMsg,
+    attr, coin, to_binary, Addr, Api, BankMsg, Binary, BlockInfo, CanonicalAddr, CosmosMsg,
+    Decimal, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage, SubMsg,
+    SubMsgResult, Timestamp, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
-use oraiswap::asset::{self, Asset};
+use oraiswap::asset::{self, Asset, AssetInfo, AssetInfoRaw};
+use oraiswap::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
+};
 
 pub fn bond(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
-    staker_addr: Addr,
+    funder: Addr,
+    staker: Option<String>,
     staking_token: Addr,
     amount: Uint128,
+    callback: Option<Binary>,
 ) -> StdResult<Response> {
+    // the funder can credit the position to another staker
+    let staker_addr = match staker {
+        Some(staker) => deps.api.addr_validate(&staker)?,
+        None => funder.clone(),
+    };
     let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    if is_blacklisted(deps.storage, &staker_addr_raw)?
+        || is_blacklisted(deps.storage, &deps.api.addr_canonicalize(funder.as_str())?)?
+    {
+        return Err(StdError::generic_err("staker is blacklisted"));
+    }
+    let token_raw = pool_key(deps.storage, deps.api, &staking_token)?;
+    // a migrated staking token still bonds into the pool registered for the original one
+    let asset_key =
+        read_staking_token_pool(deps.storage, &token_raw)?.unwrap_or_else(|| token_raw.to_vec());
+    let pool_info = read_pool_info(deps.storage, &asset_key)?;
+    if pool_info.staking_token != token_raw {
+        return Err(StdError::generic_err("staking token was migrated"));
+    }
+
+    let response = _bond_pool(
+        deps.branch(),
+        &env,
+        &funder,
+        &staker_addr,
+        &staker_addr_raw,
+        asset_key.clone(),
+        &pool_info,
+        amount,
+    )?;
+    match callback {
+        Some(callback) => Ok(response.add_submessage(bond_callback_msg(
+            deps.storage,
+            deps.api,
+            &funder,
+            &staker_addr,
+            &staker_addr_raw,
+            &asset_key,
+            amount,
+            callback,
+        )?)),
+        None => Ok(response),
+    }
+}
+
+pub const BOND_CALLBACK_REPLY_ID: u64 = 4;
+
+/// calls the contract which bonded back with the resulting position, the callback is replied on
+/// error so its failure does not revert the bond
+#[allow(clippy::too_many_arguments)]
+fn bond_callback_msg(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    contract: &Addr,
+    staker_addr: &Addr,
+    staker_addr_raw: &CanonicalAddr,
+    asset_key: &[u8],
+    amount: Uint128,
+    callback: Binary,
+) -> StdResult<SubMsg> {
+    let msg = BondCallbackExecuteMsg::BondCallback(BondCallbackMsg {
+        staker: staker_addr.clone(),
+        asset_info: pool_asset_info(storage, api, &CanonicalAddr::from(asset_key.to_vec()))?,
+        bonded_amount: amount,
+        total_bond: _bond_amount(storage, staker_addr_raw, asset_key)?,
+        msg: callback,
+    });
+
+    Ok(SubMsg::reply_on_error(
+        WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        },
+        BOND_CALLBACK_REPLY_ID,
+    ))
+}
+
+/// the bond is kept when its callback failed
+pub fn bond_callback_failed_reply(result: SubMsgResult) -> StdResult<Response> {
+    match result {
+        SubMsgResult::Err(error) => Ok(Response::new()
+            .add_attributes([attr("action", "bond_callback_failed"), attr("error", error)])),
+        SubMsgResult::Ok(_) => Err(StdError::generic_err("bond callback did not fail")),
+    }
+}
+
+pub fn bond_split(
+    mut deps: DepsMut,
+    env: Env,
+    funder: Addr,
+    staking_token: Addr,
+    amount: Uint128,
+    allocations: Vec<(AssetInfo, Uint128)>,
+) -> StdResult<Response> {
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(funder.as_str())?;
+    if is_blacklisted(deps.storage, &staker_addr_raw)? {
+        return Err(StdError::generic_err("staker is blacklisted"));
+    }
+    let total = allocations
+        .iter()
+        .try_fold(Uint128::zero(), |total, (_, amount)| {
+            total.checked_add(*amount)
+        })?;
+    if total != amount {
+        return Err(StdError::generic_err(
+            "allocations must sum to the sent amount",
+        ));
+    }
+
+    let token_raw = pool_key(deps.storage, deps.api, &staking_token)?;
+    let mut response = Response::new();
+    for (asset_info, allocation) in allocations {
+        if allocation.is_zero() {
+            return Err(StdError::generic_err("allocation amount cannot be zero"));
+        }
+        let pool_addr = match asset_info {
+            AssetInfo::Token { contract_addr } => contract_addr,
+            AssetInfo::NativeToken { denom } => Addr::unchecked(denom),
+        };
+        let asset_key = pool_key(deps.storage, deps.api, &pool_addr)?.to_vec();
+        let pool_info = read_pool_info(deps.storage, &asset_key)?;
+        // every pool must accept the token that was actually sent
+        if pool_info.staking_token != token_raw {
+            return Err(StdError::generic_err(
+                "pool staking token does not match the sent token",
+            ));
+        }
+
+        let pool_response = _bond_pool(
+            deps.branch(),
+            &env,
+            &funder,
+            &funder,
+            &staker_addr_raw,
+            asset_key,
+            &pool_info,
+            allocation,
+        )?;
+        response = response
+            .add_submessages(pool_response.messages)
+            .add_attributes(pool_response.attributes);
+    }
+    Ok(response)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn _bond_pool(
+    deps: DepsMut,
+    env: &Env,
+    funder: &Addr,
+    staker_addr: &Addr,
+    staker_addr_raw: &CanonicalAddr,
+    asset_key: Vec<u8>,
+    pool_info: &PoolInfo,
+    amount: Uint128,
+) -> StdResult<Response> {
+    if read_pool_status(deps.storage, &asset_key)?.bond_paused {
+        return Err(StdError::generic_err("bonding is paused for this pool"));
+    }
+    if pool_info.deprecated {
+        return Err(StdError::generic_err("pool is deprecated"));
+    }
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, env, &asset_key)?;
+    let staking_token = pool_staking_token(
+        deps.storage,
+        deps.api,
+        &CanonicalAddr::from(asset_key.clone()),
+    )?;
+
+    let old_amount = _bond_amount(deps.storage, staker_addr_raw, &asset_key)?;
     _increase_bond_amount(
         deps.storage,
         deps.api,
-        env.block.height,
-        &staker_addr_raw,
+        &env.block,
+        staker_addr_raw,
         staking_token.clone(),
         amount,
     )?;
 
-    Ok(Response::new().add_attributes([
-        ("action", "bond"),
-        ("staker_addr", staker_addr.as_str()),
-        ("staking_token", staking_token.as_str()),
-        ("amount", &amount.to_string()),
-    ]))
+    Ok(Response::new()
+        .add_submessages(stake_changed_hooks(
+            deps.storage,
+            deps.api,
+            staker_addr,
+            &asset_key,
+            old_amount,
+            old_amount + amount,
+        )?)
+        .add_attributes([
+            attr("action", "bond"),
+            attr("funder", funder.as_str()),
+            attr("staker_addr", staker_addr.as_str()),
+            attr("staking_token", staking_token.as_str()),
+            attr("amount", amount.to_string()),
+        ]))
 }
 
-pub fn unbond(
+fn _bond_amount(
+    storage: &dyn Storage,
+    staker_addr_raw: &CanonicalAddr,
+    asset_key: &[u8],
+) -> StdResult<Uint128> {
+    Ok(REWARDS
+        .may_load(storage, (staker_addr_raw, asset_key))?
+        .map(|reward_info| reward_info.bond_amount)
+        .unwrap_or_default())
+}
+
+/// notifies every hook of the new bond of the staker, the hooks run after the state is saved
+pub fn stake_changed_hooks(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    staker: &Addr,
+    asset_key: &[u8],
+    old_amount: Uint128,
+    new_amount: Uint128,
+) -> StdResult<Vec<SubMsg>> {
+    let hooks = read_hooks(storage)?;
+    if hooks.is_empty() || old_amount == new_amount {
+        return Ok(vec![]);
+    }
+    let msg = to_binary(&StakeChangedExecuteMsg::StakeChangeHook(
+        StakeChangedHookMsg {
+            staker: staker.clone(),
+            asset_info: pool_asset_info(storage, api, &CanonicalAddr::from(asset_key.to_vec()))?,
+            old_amount,
+            new_amount,
+        },
+    ))?;
+    hook_msgs(storage, api, HookKind::Stake, hooks, &msg)
+}
+
+pub const BOND_FROM_REPLY_ID: u64 = 1;
+
+pub fn bond_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let staking_token = match asset_info {
+        AssetInfo::Token { contract_addr } => contract_addr,
+        AssetInfo::NativeToken { .. } => {
+            return Err(StdError::generic_err(
+                "native staking tokens must be bonded with Bond",
+            ))
+        }
+    };
+    if amount.is_zero() {
+        return Err(StdError::generic_err("Cannot bond zero amount"));
+    }
+    // fail early, the bond itself is validated again in the reply
+    let token_raw = pool_key(deps.storage, deps.api, &staking_token)?;
+    let asset_key =
+        read_staking_token_pool(deps.storage, &token_raw)?.unwrap_or_else(|| token_raw.to_vec());
+    read_pool_info(deps.storage, &asset_key)?;
+
+    store_pending_bond(
+        deps.storage,
+        &PendingBond {
+            funder: info.sender.clone(),
+            staking_token: staking_token.clone(),
+            amount,
+        },
+    )?;
+
+    // a failed pull reverts the whole transaction before anything is bonded
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: staking_token.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            },
+            BOND_FROM_REPLY_ID,
+        ))
+        .add_attributes([
+            attr("action", "bond_from"),
+            attr("funder", info.sender.as_str()),
+            attr("staking_token", staking_token.as_str()),
+            attr("amount", amount.to_string()),
+        ]))
+}
+
+/// credits the bond of a BondFrom once its TransferFrom succeeded
+pub fn bond_from_reply(deps: DepsMut, env: Env) -> StdResult<Response> {
+    let pending_bond =
+        read_pending_bond(deps.storage)?.ok_or_else(|| StdError::generic_err("no pending bond"))?;
+    remove_pending_bond(deps.storage);
+
+    bond(
+        deps,
+        env,
+        pending_bond.funder,
+        None,
+        pending_bond.staking_token,
+        pending_bond.amount,
+        None,
+    )
+}
+
+pub const ZAP_IN_REPLY_ID: u64 = 3;
+
+// the offer is swapped into the staking token by the router and the output bonded in the reply,
+// any failure reverts the transaction so the offer goes back to the sender
+pub fn zap_in(
+    deps: DepsMut,
+    env: Env,
+    staker_addr: Addr,
+    offer_asset: Asset,
+    asset_info: AssetInfo,
+    min_bond_amount: Option<Uint128>,
+) -> StdResult<Response> {
+    if offer_asset.amount.is_zero() {
+        return Err(StdError::generic_err("Cannot zap in zero amount"));
+    }
+    if offer_asset.info == asset_info {
+        return Err(StdError::generic_err(
+            "the offer asset is already the staking token, bond it instead",
+        ));
+    }
+    let router = read_router(deps.storage)?
+        .map(|router| deps.api.addr_humanize(&router))
+        .transpose()?
+        .ok_or_else(|| StdError::generic_err("no router to zap in with"))?;
+    let staking_token = match &asset_info {
+        AssetInfo::Token { contract_addr } => contract_addr.clone(),
+        AssetInfo::NativeToken { denom } => Addr::unchecked(denom),
+    };
+    // fail early, the bond itself is validated again in the reply
+    let token_raw = pool_key(deps.storage, deps.api, &staking_token)?;
+    let asset_key =
+        read_staking_token_pool(deps.storage, &token_raw)?.unwrap_or_else(|| token_raw.to_vec());
+    read_pool_info(deps.storage, &asset_key)?;
+
+    let operations = vec![SwapOperation::OraiSwap {
+        offer_asset_info: offer_asset.info.clone(),
+        ask_asset_info: asset_info.clone(),
+    }];
+    let swap: CosmosMsg = match &offer_asset.info {
+        AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+            contract_addr: router.to_string(),
+            msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: None,
+                to: None,
+            })?,
+            funds: vec![coin(offer_asset.amount.u128(), denom)],
+        }
+        .into(),
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: router.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to: None,
+                })?,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+
+    store_pending_zap(
+        deps.storage,
+        &PendingZap {
+            staker_addr: staker_addr.clone(),
+            staking_token: staking_token.clone(),
+            prev_balance: query_asset_balance(&deps.querier, &env.contract.address, &asset_info)?,
+            ask_asset: asset_info,
+            min_bond_amount: min_bond_amount.unwrap_or_default(),
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(swap, ZAP_IN_REPLY_ID))
+        .add_attributes([
+            attr("action", "zap_in"),
+            attr("staker_addr", staker_addr.as_str()),
+            attr("staking_token", staking_token.as_str()),
+            attr("offer_amount", offer_asset.amount.to_string()),
+        ]))
+}
+
+/// bonds the swap output of a ZapIn for the staker once it reaches min_bond_amount
+pub fn zap_in_reply(deps: DepsMut, env: Env) -> StdResult<Response> {
+    let pending_zap =
+        read_pending_zap(deps.storage)?.ok_or_else(|| StdError::generic_err("no pending zap"))?;
+    remove_pending_zap(deps.storage);
+
+    let balance =
+        query_asset_balance(&deps.querier, &env.contract.address, &pending_zap.ask_asset)?;
+    let amount = balance.checked_sub(pending_zap.prev_balance)?;
+    if amount < pending_zap.min_bond_amount {
+        return Err(StdError::generic_err(
+            "bond amount is below the minimum bond amount",
+        ));
+    }
+
+    Ok(bond(
+        deps,
+        env,
+        pending_zap.staker_addr,
+        None,
+        pending_zap.staking_token,
+        amount,
+        None,
+    )?
+    .add_attribute("zap_in_amount", amount.to_string()))
+}
+
+pub fn bond_native(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    staker: Option<String>,
+) -> StdResult<Response> {
+    let denom = match asset_info {
+        AssetInfo::NativeToken { denom } => denom,
+        AssetInfo::Token { .. } => {
+            return Err(StdError::generic_err(
+                "cw20 staking tokens must be bonded through the Receive hook",
+            ))
+        }
+    };
+    if !is_native_staking_token(deps.storage, denom.as_bytes())? {
+        return Err(StdError::generic_err(
+            "staking token is not a registered native token",
+        ));
+    }
+    let amount = match info.funds.as_slice() {
+        [fund] if fund.denom == denom && !fund.amount.is_zero() => fund.amount,
+        _ => {
+            return Err(StdError::generic_err(format!(
+                "must send exactly one {} coin",
+                denom
+            )))
+        }
+    };
+
+    bond(
+        deps,
+        env,
+        info.sender,
+        staker,
+        Addr::unchecked(denom),
+        amount,
+        None,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn unbond(
+    mut deps: DepsMut,
+    env: Env,
     staker_addr: Addr,
     staking_token: Addr,
-    amount: Uint128,
+    amount: Option<Uint128>,
+    withdraw_rewards: bool,
+    recipient: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let recipient = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?;
+    let config = read_config(deps.storage)?;
+    let mut transfers = RewardTransfers::default();
+    let response = _unbond_pool(
+        &mut deps,
+        &env,
+        &staker_addr,
+        &staker_addr_raw,
+        &config,
+        &mut transfers,
+        staking_token,
+        amount,
+        withdraw_rewards,
+        recipient.as_ref(),
+        limit,
+    )?;
+    transfers.save(deps.storage, &staker_addr_raw, None)?;
+    Ok(response)
+}
+
+pub fn unbond_many(
+    mut deps: DepsMut,
+    env: Env,
+    staker_addr: Addr,
+    unbonds: Vec<UnbondMsg>,
 ) -> StdResult<Response> {
+    if unbonds.is_empty() {
+        return Err(StdError::generic_err("no unbonds provided"));
+    }
+    // shared state is read once for every pool
     let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let config = read_config(deps.storage)?;
+
+    let mut response = Response::new().add_attribute("action", "unbond_many");
+    let mut transfers = RewardTransfers::default();
+    for unbond in unbonds {
+        let pool_response = _unbond_pool(
+            &mut deps,
+            &env,
+            &staker_addr,
+            &staker_addr_raw,
+            &config,
+            &mut transfers,
+            unbond.staking_token,
+            unbond.amount,
+            false,
+            None,
+            None,
+        )?;
+        response = response
+            .add_submessages(pool_response.messages)
+            .add_events(pool_response.events)
+            .add_attributes(pool_response.attributes);
+    }
+    transfers.save(deps.storage, &staker_addr_raw, None)?;
+    Ok(response)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn _unbond_pool(
+    deps: &mut DepsMut,
+    env: &Env,
+    staker_addr: &Addr,
+    staker_addr_raw: &CanonicalAddr,
+    config: &Config,
+    transfers: &mut RewardTransfers,
+    staking_token: Addr,
+    amount: Option<Uint128>,
+    withdraw_rewards: bool,
+    recipient: Option<&Addr>,
+    limit: Option<u32>,
+) -> StdResult<Response> {
     let mut messages = vec![];
     let mut response = Response::new();
-    let asset_key = deps.api.addr_canonicalize(staking_token.as_str())?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    if read_pool_status(deps.storage, &asset_key)?.unbond_paused {
+        return Err(StdError::generic_err("unbonding is paused for this pool"));
+    }
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, env, &asset_key)?;
+    // payouts use the current staking token of the pool, which changes on token migrations
+    let token_addr = pool_staking_token(
+        deps.storage,
+        deps.api,
+        &read_pool_info(deps.storage, &asset_key)?.staking_token,
+    )?;
+    // without an amount the whole bond is unbonded, so no dust is left behind
+    let amount = match amount {
+        Some(amount) => amount,
+        None => REWARDS
+            .may_load(deps.storage, (staker_addr_raw, &asset_key))?
+            .map(|reward_info| reward_info.bond_amount)
+            .unwrap_or_default(),
+    };
 
     // withdraw_avaiable_lock
-    let withdraw_response = _withdraw_lock(deps.storage, &env, &staker_addr, &staking_token)?;
+    let withdraw_response = _withdraw_lock(
+        deps.storage,
+        env,
+        staker_addr,
+        recipient,
+        &staking_token,
+        &token_addr,
+        limit,
+    )?;
 
     messages.extend(
         withdraw_response
             .clone()
             .messages
             .into_iter()
-            .map(|msg| msg.msg)
-            .collect::<Vec<CosmosMsg>>(),
+            .map(|msg| SubMsg::new(msg.msg)),
     );
 
     let withdraw_attrs = withdraw_response.attributes;
+    let mut hook_msgs = vec![];
     if !amount.is_zero() {
+        let old_amount = _bond_amount(deps.storage, staker_addr_raw, &asset_key)?;
         let (_, reward_assets) = _decrease_bond_amount(
             deps.storage,
             deps.api,
-            env.block.height,
-            &staker_addr_raw,
+            &env.block,
+            staker_addr_raw,
             &staking_token,
             amount,
         )?;
+        hook_msgs = stake_changed_hooks(
+            deps.storage,
+            deps.api,
+            staker_addr,
+            &asset_key,
+            old_amount,
+            old_amount.checked_sub(amount)?,
+        )?;
         // withdraw pending_withdraw assets (accumulated when changing reward_per_sec)
+        let payout = payout_reward_assets(
+            deps.as_ref(),
+            transfers,
+            staker_addr,
+            Some(asset_key.as_slice()),
+            reward_assets,
+        )?;
+        messages.extend(payout.messages);
+        response = response
+            .add_events(payout.events)
+            .add_attributes(payout.attributes);
+
+        // the fee is taken upfront, only the remainder is locked or returned
+        let fee_amount = match &config.fee_collector {
+            Some(fee_collector) => {
+                let fee_amount = amount * read_unbond_fee(deps.storage, &asset_key)?;
+                if !fee_amount.is_zero() {
+                    messages.push(SubMsg::new(_transfer_msg(
+                        deps.storage,
+                        &token_addr,
+                        &deps.api.addr_humanize(fee_collector)?,
+                        fee_amount,
+                    )?));
+                    response = response.add_attribute("fee_amount", fee_amount.to_string());
+                }
+                fee_amount
+            }
+            None => Uint128::zero(),
+        };
+        let amount = amount.checked_sub(fee_amount)?;
+
+        let unbond_response = _lock_or_unbond(
+            deps.storage,
+            env,
+            config,
+            &asset_key,
+            staker_addr,
+            &staking_token,
+            &token_addr,
+            amount,
+        )?;
         messages.extend(
-            reward_assets
+            unbond_response
+                .messages
                 .into_iter()
-                .map(|ra| ra.into_msg(None, &deps.querier, staker_addr.clone()))
-                .collect::<StdResult<Vec<CosmosMsg>>>()?,
+                .map(|msg| SubMsg::new(msg.msg)),
         );
-        // checking bonding period
-        if let Ok(period) = read_unbonding_period(deps.storage, &asset_key) {
-            let unlock_time = env.block.time.plus_seconds(period);
-            insert_lock_info(
-                deps.storage,
-                staking_token.as_bytes(),
-                staker_addr.as_bytes(),
-                LockInfo {
-                    amount,
-                    unlock_time,
-                },
-            )?;
+        response = response.add_attributes(unbond_response.attributes);
+    }
 
-            response = response.add_attributes([
-                attr("action", "unbonding"),
-                attr("staker_addr", staker_addr.as_str()),
-                attr("amount", amount.to_string()),
-                attr("staking_token", staking_token.as_str()),
-                attr("unlock_time", unlock_time.seconds().to_string()),
-            ])
-        } else {
-            let unbond_response = _unbond(&staker_addr, &staking_token, amount)?;
-            messages.extend(
-                unbond_response
-                    .messages
-                    .into_iter()
-                    .map(|msg| msg.msg)
-                    .collect::<Vec<CosmosMsg>>(),
-            );
-            response = response.add_attributes(unbond_response.attributes);
+    // rewards are settled by the bond update above, so they include this block
+    // and a full unbond has no bond left for the minimum claim to apply to
+    if withdraw_rewards {
+        can_withdraw_rewards(deps.storage, staker_addr_raw, &asset_key, true)?;
+        let reward_assets = process_reward_assets(
+            deps.storage,
+            staker_addr_raw,
+            &Some(asset_key.to_vec()),
+            Some(env.block.time),
+        )?
+        .into_iter()
+        .filter(|ra| !ra.amount.is_zero())
+        .map(|ra| ra.to_normal(deps.api))
+        .collect::<StdResult<Vec<Asset>>>()?;
+        let payout = payout_reward_assets(
+            deps.as_ref(),
+            transfers,
+            staker_addr,
+            Some(asset_key.as_slice()),
+            reward_assets,
+        )?;
+        messages.extend(payout.messages);
+        response = response
+            .add_events(payout.events)
+            .add_attributes(payout.attributes)
+            .add_attribute("withdraw_rewards", "true");
+    }
+    Ok(response
+        .add_submessages(messages)
+        .add_submessages(hook_msgs)
+        .add_attributes(withdraw_attrs))
+}
+
+// locks the amount for the unbonding period of the pool, or sends it right away without one
+#[allow(clippy::too_many_arguments)]
+fn _lock_or_unbond(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    asset_key: &[u8],
+    staker_addr: &Addr,
+    staking_token: &Addr,
+    token_addr: &Addr,
+    amount: Uint128,
+) -> StdResult<Response> {
+    // checking bonding period
+    let period = match read_unbonding_period(storage, asset_key) {
+        Ok(period) => period,
+        Err(_) => return _unbond(storage, staker_addr, token_addr, amount),
+    };
+    let unlock_time = env.block.time.plus_seconds(period);
+    insert_lock_info(
+        storage,
+        staking_token.as_bytes(),
+        staker_addr.as_bytes(),
+        LockInfo {
+            amount,
+            unlock_time,
+        },
+        config.max_lock_entries(),
+    )?;
+    store_lock_period(
+        storage,
+        staking_token.as_bytes(),
+        staker_addr.as_bytes(),
+        unlock_time,
+        period,
+    )?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "unbonding"),
+        attr("staker_addr", staker_addr.as_str()),
+        attr("amount", amount.to_string()),
+        attr("staking_token", staking_token.as_str()),
+        attr("unlock_time", unlock_time.seconds().to_string()),
+    ]))
+}
+
+pub fn emergency_unbond(
+    deps: DepsMut,
+    env: Env,
+    staker_addr: Addr,
+    staking_token: Addr,
+) -> StdResult<Response> {
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    // only unbonding must be allowed, withdraw pauses are ignored as no reward is sent
+    if read_pool_status(deps.storage, &asset_key)?.unbond_paused {
+        return Err(StdError::generic_err("unbonding is paused for this pool"));
+    }
+    // the other stakers are owed the rewards up to now, unless a broken reward asset fails the accrual
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key).ok();
+    let mut pool_info = read_pool_info(deps.storage, &asset_key)?;
+    let token_addr = pool_staking_token(deps.storage, deps.api, &pool_info.staking_token)?;
+    let reward_info = REWARDS
+        .may_load(deps.storage, (&staker_addr_raw, &asset_key))?
+        .filter(|reward_info| !reward_info.bond_amount.is_zero())
+        .ok_or_else(|| StdError::generic_err("staker has no bond in this pool"))?;
+    let amount = reward_info.bond_amount;
+    if let Some(boost) = read_boost(deps.storage, &asset_key, &staker_addr_raw)? {
+        if boost.expires_at > env.block.time.seconds() {
+            return Err(StdError::generic_err(format!(
+                "bond is committed by a boost until {}",
+                boost.expires_at
+            )));
+        }
+    }
+
+    // the reward accounting may be broken, so nothing here is allowed to fail on it
+    let weight = reward_info.weight();
+    let forfeited_reward = index_amount(weight, pool_info.reward_index)
+        .and_then(|amount| Ok(amount.checked_sub(index_amount(weight, reward_info.index)?)?))
+        .ok()
+        .and_then(|amount| Uint128::try_from(amount).ok())
+        .unwrap_or_default()
+        .saturating_add(reward_info.pending_reward);
+    let forfeited_withdraw = reward_info
+        .pending_withdraw
+        .iter()
+        .map(|pw| {
+            let asset = match &pw.info {
+                AssetInfoRaw::Token { contract_addr } => {
+                    deps.api.addr_humanize(contract_addr)?.to_string()
+                }
+                AssetInfoRaw::NativeToken { denom } => denom.clone(),
+            };
+            Ok(format!("{}{}", pw.amount, asset))
+        })
+        .collect::<StdResult<Vec<String>>>()?
+        .join(",");
+
+    pool_info.total_bond_amount = pool_info.total_bond_amount.checked_sub(amount)?;
+    if let Some(total_weighted_amount) = pool_info.total_weighted_amount {
+        pool_info.total_weighted_amount = Some(total_weighted_amount.saturating_sub(weight));
+    }
+    store_pool_info(deps.storage, &asset_key, &pool_info)?;
+    remove_boost(deps.storage, &asset_key, &staker_addr_raw);
+    STAKED_BALANCES.update(
+        deps.storage,
+        (&asset_key, &staker_addr),
+        env.block.height,
+        |bal| -> StdResult<Uint128> { Ok(bal.unwrap_or_default().checked_sub(amount)?) },
+    )?;
+    STAKED_TOTAL.update(
+        deps.storage,
+        &asset_key,
+        env.block.height,
+        |total| -> StdResult<Uint128> { Ok(total.unwrap_or_default().checked_sub(amount)?) },
+    )?;
+    sub_outstanding_units(deps.storage, &asset_key, forfeited_reward)?;
+    sub_outstanding_assets(deps.storage, &asset_key, &reward_info.pending_withdraw)?;
+    // a zeroed reward info without bond is the same as none
+    REWARDS.remove(deps.storage, (&staker_addr_raw, &asset_key));
+    remove_staker(deps.storage, &asset_key, &staker_addr_raw)?;
+
+    let config = read_config(deps.storage)?;
+    let unbond_response = _lock_or_unbond(
+        deps.storage,
+        &env,
+        &config,
+        &asset_key,
+        &staker_addr,
+        &staking_token,
+        &token_addr,
+        amount,
+    )?;
+    let hook_msgs = stake_changed_hooks(
+        deps.storage,
+        deps.api,
+        &staker_addr,
+        &asset_key,
+        amount,
+        Uint128::zero(),
+    )?;
+
+    Ok(Response::new()
+        .add_submessages(unbond_response.messages)
+        .add_submessages(hook_msgs)
+        .add_attributes([
+            attr("action", "emergency_unbond"),
+            attr("staker_addr", staker_addr.as_str()),
+            attr("staking_token", staking_token.as_str()),
+            attr("amount", amount.to_string()),
+            attr("forfeited_pending_reward", forfeited_reward.to_string()),
+            attr("forfeited_pending_withdraw", forfeited_withdraw),
+        ])
+        .add_attributes(unbond_response.attributes))
+}
+
+pub fn migrate_stake(
+    deps: DepsMut,
+    env: Env,
+    staker_addr: Addr,
+    from_staking_token: Addr,
+    to_staking_token: Addr,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    if is_blacklisted(deps.storage, &staker_addr_raw)? {
+        return Err(StdError::generic_err("staker is blacklisted"));
+    }
+    if amount.is_zero() {
+        return Err(StdError::generic_err("Cannot migrate zero amount"));
+    }
+    let from_asset_key = pool_key(deps.storage, deps.api, &from_staking_token)?;
+    let to_asset_key = pool_key(deps.storage, deps.api, &to_staking_token)?;
+    if !is_stake_migration_enabled(deps.storage, &from_asset_key, &to_asset_key)? {
+        return Err(StdError::generic_err("stake migration is not enabled"));
+    }
+    // a staking token migration of either pool may have split them since the route was enabled
+    let to_pool_info = read_pool_info(deps.storage, &to_asset_key)?;
+    if read_pool_info(deps.storage, &from_asset_key)?.staking_token != to_pool_info.staking_token {
+        return Err(StdError::generic_err(
+            "pools do not share the same staking token",
+        ));
+    }
+    // leaving a deprecated pool is still allowed, like any other exit
+    if read_pool_status(deps.storage, &from_asset_key)?.unbond_paused {
+        return Err(StdError::generic_err("unbonding is paused for this pool"));
+    }
+    if read_pool_status(deps.storage, &to_asset_key)?.bond_paused {
+        return Err(StdError::generic_err("bonding is paused for this pool"));
+    }
+    if to_pool_info.deprecated {
+        return Err(StdError::generic_err("pool is deprecated"));
+    }
+    // the source rewards are settled up to now before the bond leaves it
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &from_asset_key)?;
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &to_asset_key)?;
+
+    let old_from_amount = _bond_amount(deps.storage, &staker_addr_raw, &from_asset_key)?;
+    let old_to_amount = _bond_amount(deps.storage, &staker_addr_raw, &to_asset_key)?;
+    let (_, reward_assets) = _decrease_bond_amount(
+        deps.storage,
+        deps.api,
+        &env.block,
+        &staker_addr_raw,
+        &from_staking_token,
+        amount,
+    )?;
+    _increase_bond_amount(
+        deps.storage,
+        deps.api,
+        &env.block,
+        &staker_addr_raw,
+        to_staking_token.clone(),
+        amount,
+    )?;
+
+    let mut transfers = RewardTransfers::default();
+    let response = payout_reward_assets(
+        deps.as_ref(),
+        &mut transfers,
+        &staker_addr,
+        Some(from_asset_key.as_slice()),
+        reward_assets,
+    )?;
+    transfers.save(deps.storage, &staker_addr_raw, None)?;
+    let mut hook_msgs = stake_changed_hooks(
+        deps.storage,
+        deps.api,
+        &staker_addr,
+        &from_asset_key,
+        old_from_amount,
+        old_from_amount.checked_sub(amount)?,
+    )?;
+    hook_msgs.extend(stake_changed_hooks(
+        deps.storage,
+        deps.api,
+        &staker_addr,
+        &to_asset_key,
+        old_to_amount,
+        old_to_amount + amount,
+    )?);
+
+    Ok(response.add_submessages(hook_msgs).add_attributes([
+        attr("action", "migrate_stake"),
+        attr("staker_addr", staker_addr.as_str()),
+        attr("from_staking_token", from_staking_token.as_str()),
+        attr("to_staking_token", to_staking_token.as_str()),
+        attr("amount", amount.to_string()),
+    ]))
+}
+
+pub fn compound(
+    deps: DepsMut,
+    env: Env,
+    staker_addr: Addr,
+    staking_token: Addr,
+) -> StdResult<Response> {
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    if is_blacklisted(deps.storage, &staker_addr_raw)? {
+        return Err(StdError::generic_err("staker is blacklisted"));
+    }
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    let pool_info = read_pool_info(deps.storage, &asset_key)?;
+    if read_pool_status(deps.storage, &asset_key)?.bond_paused {
+        return Err(StdError::generic_err("bonding is paused for this pool"));
+    }
+    if pool_info.deprecated {
+        return Err(StdError::generic_err("pool is deprecated"));
+    }
+
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key)?;
+    // the rewards that are not compounded are paid out, so withdrawing must be allowed
+    can_withdraw_rewards(deps.storage, &staker_addr_raw, &asset_key, true)?;
+    // settles the reward index first, so the bond increase below earns nothing retroactively
+    let reward_assets = process_reward_assets(
+        deps.storage,
+        &staker_addr_raw,
+        &Some(asset_key.clone()),
+        Some(env.block.time),
+    )?;
+    if reward_assets.iter().all(|ra| ra.amount.is_zero()) {
+        return Err(StdError::generic_err("nothing to compound"));
+    }
+
+    let mut compound_amount = Uint128::zero();
+    let mut paid_assets = vec![];
+    for ra in reward_assets {
+        // native staking tokens are stored as their denom
+        let asset_bytes = match &ra.info {
+            AssetInfoRaw::Token { contract_addr } => contract_addr.as_slice(),
+            AssetInfoRaw::NativeToken { denom } => denom.as_bytes(),
+        };
+        if asset_bytes == pool_info.staking_token.as_slice() {
+            compound_amount += ra.amount;
+        } else if !ra.amount.is_zero() {
+            paid_assets.push(ra.to_normal(deps.api)?);
         }
     }
+
+    let old_amount = _bond_amount(deps.storage, &staker_addr_raw, &asset_key)?;
+    if !compound_amount.is_zero() {
+        _increase_bond_amount(
+            deps.storage,
+            deps.api,
+            &env.block,
+            &staker_addr_raw,
+            staking_token.clone(),
+            compound_amount,
+        )?;
+    }
+
+    // the other reward assets are paid out like a withdrawal
+    let mut transfers = RewardTransfers::default();
+    let response = payout_reward_assets(
+        deps.as_ref(),
+        &mut transfers,
+        &staker_addr,
+        Some(asset_key.as_slice()),
+        paid_assets,
+    )?;
+    transfers.save(deps.storage, &staker_addr_raw, None)?;
+    let hook_msgs = stake_changed_hooks(
+        deps.storage,
+        deps.api,
+        &staker_addr,
+        &asset_key,
+        old_amount,
+        old_amount + compound_amount,
+    )?;
+
+    Ok(response.add_submessages(hook_msgs).add_attributes([
+        attr("action", "compound"),
+        attr("staker_addr", staker_addr.as_str()),
+        attr("staking_token", staking_token.as_str()),
+        attr("compounded_amount", compound_amount.to_string()),
+    ]))
+}
+
+pub fn cancel_unbond(
+    deps: DepsMut,
+    env: Env,
+    staker_addr: Addr,
+    staking_token: Addr,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    if is_blacklisted(deps.storage, &staker_addr_raw)? {
+        return Err(StdError::generic_err("staker is blacklisted"));
+    }
+    if amount.is_zero() {
+        return Err(StdError::generic_err("Cannot cancel zero amount"));
+    }
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    if read_pool_status(deps.storage, &asset_key)?.bond_paused {
+        return Err(StdError::generic_err("bonding is paused for this pool"));
+    }
+    if read_pool_info(deps.storage, &asset_key)?.deprecated {
+        return Err(StdError::generic_err("pool is deprecated"));
+    }
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key)?;
+
+    let unlock_times = remove_lock_amount(
+        deps.storage,
+        staking_token.as_bytes(),
+        staker_addr.as_bytes(),
+        amount,
+    )?;
+    let old_amount = _bond_amount(deps.storage, &staker_addr_raw, &asset_key)?;
+    _increase_bond_amount(
+        deps.storage,
+        deps.api,
+        &env.block,
+        &staker_addr_raw,
+        staking_token.clone(),
+        amount,
+    )?;
+    let hook_msgs = stake_changed_hooks(
+        deps.storage,
+        deps.api,
+        &staker_addr,
+        &asset_key,
+        old_amount,
+        old_amount + amount,
+    )?;
+
+    Ok(Response::new().add_submessages(hook_msgs).add_attributes([
+        attr("action", "cancel_unbond"),
+        attr("staker_addr", staker_addr.as_str()),
+        attr("staking_token", staking_token.as_str()),
+        attr("amount", amount.to_string()),
+        attr(
+            "unlock_times",
+            unlock_times
+                .iter()
+                .map(|unlock_time| unlock_time.seconds().to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+        ),
+    ]))
+}
+
+pub fn restake(
+    deps: DepsMut,
+    env: Env,
+    staker_addr: Addr,
+    staking_token: Addr,
+) -> StdResult<Response> {
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    if is_blacklisted(deps.storage, &staker_addr_raw)? {
+        return Err(StdError::generic_err("staker is blacklisted"));
+    }
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    if read_pool_status(deps.storage, &asset_key)?.bond_paused {
+        return Err(StdError::generic_err("bonding is paused for this pool"));
+    }
+    if read_pool_info(deps.storage, &asset_key)?.deprecated {
+        return Err(StdError::generic_err("pool is deprecated"));
+    }
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key)?;
+
+    // the same locks a withdrawal would release
+    let amount = remove_and_accumulate_lock_info(
+        deps.storage,
+        staking_token.as_bytes(),
+        staker_addr.as_bytes(),
+        env.block.time,
+    )?;
+    if amount.is_zero() {
+        return Err(StdError::generic_err("no matured locks to restake"));
+    }
+
+    let old_amount = _bond_amount(deps.storage, &staker_addr_raw, &asset_key)?;
+    _increase_bond_amount(
+        deps.storage,
+        deps.api,
+        &env.block,
+        &staker_addr_raw,
+        staking_token.clone(),
+        amount,
+    )?;
+    let locked_amount = read_user_lock_amount(
+        deps.storage,
+        staking_token.as_bytes(),
+        staker_addr.as_bytes(),
+    )?;
+
+    let hook_msgs = stake_changed_hooks(
+        deps.storage,
+        deps.api,
+        &staker_addr,
+        &asset_key,
+        old_amount,
+        old_amount + amount,
+    )?;
+
+    Ok(Response::new().add_submessages(hook_msgs).add_attributes([
+        attr("action", "restake"),
+        attr("staker_addr", staker_addr.as_str()),
+        attr("staking_token", staking_token.as_str()),
+        attr("restaked_amount", amount.to_string()),
+        attr("locked_amount", locked_amount.to_string()),
+    ]))
+}
+
+pub fn instant_unbond(
+    deps: DepsMut,
+    env: Env,
+    staker_addr: Addr,
+    staking_token: Addr,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?;
+    if read_pool_status(deps.storage, &asset_key)?.unbond_paused {
+        return Err(StdError::generic_err("unbonding is paused for this pool"));
+    }
+    if amount.is_zero() {
+        return Err(StdError::generic_err("Cannot unbond zero amount"));
+    }
+
+    // a pool without penalty must not allow free instant unbonds
+    let penalty = read_instant_unbond_penalty(deps.storage, &asset_key)?
+        .ok_or_else(|| StdError::generic_err("instant unbond is not enabled for this pool"))?;
+    // the penalty is paid in staking tokens, so it cannot be mixed into the pending reward of the pool
+    let fee_collector = read_config(deps.storage)?
+        .fee_collector
+        .ok_or_else(|| StdError::generic_err("fee collector is not set"))?;
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key)?;
+
+    let old_amount = _bond_amount(deps.storage, &staker_addr_raw, &asset_key)?;
+    let (token_raw, reward_assets) = _decrease_bond_amount(
+        deps.storage,
+        deps.api,
+        &env.block,
+        &staker_addr_raw,
+        &staking_token,
+        amount,
+    )?;
+    let hook_msgs = stake_changed_hooks(
+        deps.storage,
+        deps.api,
+        &staker_addr,
+        &asset_key,
+        old_amount,
+        old_amount.checked_sub(amount)?,
+    )?;
+    let token_addr = pool_staking_token(deps.storage, deps.api, &token_raw)?;
+    let mut transfers = RewardTransfers::default();
+    let response = payout_reward_assets(
+        deps.as_ref(),
+        &mut transfers,
+        &staker_addr,
+        Some(asset_key.as_slice()),
+        reward_assets,
+    )?;
+    transfers.save(deps.storage, &staker_addr_raw, None)?;
+    let mut messages = vec![];
+
+    // rounding down the penalty keeps penalty + payout equal to the unbonded amount
+    let penalty_amount = amount * penalty;
+    let payout_amount = amount.checked_sub(penalty_amount)?;
+    if !penalty_amount.is_zero() {
+        messages.push(_transfer_msg(
+            deps.storage,
+            &token_addr,
+            &deps.api.addr_humanize(&fee_collector)?,
+            penalty_amount,
+        )?);
+    }
+    messages.push(_transfer_msg(
+        deps.storage,
+        &token_addr,
+        &staker_addr,
+        payout_amount,
+    )?);
+
     Ok(response
         .add_messages(messages)
-        .add_attributes(withdraw_attrs))
+        .add_submessages(hook_msgs)
+        .add_attributes([
+            attr("action", "instant_unbond"),
+            attr("staker_addr", staker_addr.as_str()),
+            attr("staking_token", staking_token.as_str()),
+            attr("amount", amount.to_string()),
+            attr("penalty_amount", penalty_amount.to_string()),
+            attr("payout_amount", payout_amount.to_string()),
+        ]))
+}
+
+/// seeds the positions of a redeployment like bonds made at the current block, the stake hooks
+/// are not notified
+pub fn import_stakers(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    entries: Vec<(String, Uint128)>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+    if !read_import_open(deps.storage)? {
+        return Err(StdError::generic_err("import is closed"));
+    }
+
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    // errors when the pool is not registered
+    read_pool_info(deps.storage, &asset_key)?;
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key)?;
+
+    let mut total_amount = Uint128::zero();
+    for (staker_addr, amount) in entries.iter() {
+        if amount.is_zero() {
+            return Err(StdError::generic_err("import amount cannot be zero"));
+        }
+        let staker_addr = deps.api.addr_validate(staker_addr)?;
+        _increase_bond_amount(
+            deps.storage,
+            deps.api,
+            &env.block,
+            &deps.api.addr_canonicalize(staker_addr.as_str())?,
+            staking_token.clone(),
+            *amount,
+        )?;
+        total_amount += *amount;
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "import_stakers"),
+        attr("staking_token", staking_token.as_str()),
+        attr("stakers", entries.len().to_string()),
+        attr("amount", total_amount.to_string()),
+    ]))
+}
+
+pub fn force_unbond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    staker_addr: Addr,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    config.assert_owner(deps.api, &info.sender)?;
+
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key)?;
+    let amount = REWARDS
+        .may_load(deps.storage, (&staker_addr_raw, &asset_key))?
+        .map(|reward_info| reward_info.bond_amount)
+        .unwrap_or_default();
+    // every lock is released, whatever its unlock time
+    let locked_amount = remove_and_accumulate_lock_info(
+        deps.storage,
+        staking_token.as_bytes(),
+        staker_addr.as_bytes(),
+        Timestamp::from_seconds(u64::MAX),
+    )?;
+    if amount.is_zero() && locked_amount.is_zero() {
+        return Err(StdError::generic_err("staker has no bond in this pool"));
+    }
+    // the owner overrides the commitment
+    remove_boost(deps.storage, &asset_key, &staker_addr_raw);
+
+    let (token_raw, mut reward_assets) = if amount.is_zero() {
+        (
+            read_pool_info(deps.storage, &asset_key)?.staking_token,
+            vec![],
+        )
+    } else {
+        _decrease_bond_amount(
+            deps.storage,
+            deps.api,
+            &env.block,
+            &staker_addr_raw,
+            &staking_token,
+            amount,
+        )?
+    };
+    // pending rewards are withdrawn too, so nothing is left behind
+    for ra in process_reward_assets(
+        deps.storage,
+        &staker_addr_raw,
+        &Some(asset_key.clone()),
+        Some(env.block.time),
+    )? {
+        reward_assets.push(ra.to_normal(deps.api)?);
+    }
+
+    let mut transfers = RewardTransfers::default();
+    let response = payout_reward_assets(
+        deps.as_ref(),
+        &mut transfers,
+        &staker_addr,
+        Some(asset_key.as_slice()),
+        reward_assets,
+    )?;
+    transfers.save(deps.storage, &staker_addr_raw, None)?;
+    let unbond_msg = _transfer_msg(
+        deps.storage,
+        &pool_staking_token(deps.storage, deps.api, &token_raw)?,
+        &staker_addr,
+        amount + locked_amount,
+    )?;
+
+    let hook_msgs = stake_changed_hooks(
+        deps.storage,
+        deps.api,
+        &staker_addr,
+        &asset_key,
+        amount,
+        Uint128::zero(),
+    )?;
+
+    Ok(response
+        .add_message(unbond_msg)
+        .add_submessages(hook_msgs)
+        .add_attributes([
+            attr("action", "force_unbond"),
+            attr("staker_addr", staker_addr.as_str()),
+            attr("staking_token", staking_token.as_str()),
+            attr("amount", amount.to_string()),
+            attr("locked_amount", locked_amount.to_string()),
+        ]))
 }
 
 pub fn _withdraw_lock(
     storage: &mut dyn Storage,
     env: &Env,
     staker_addr: &Addr,
+    recipient: Option<&Addr>,
     staking_token: &Addr,
+    token_addr: &Addr,
+    limit: Option<u32>,
 ) -> StdResult<Response> {
-    // execute 10 lock a time
-    let unlock_amount = remove_and_accumulate_lock_info(
+    // the rest of the matured locks stays claimable by the next calls
+    let (unlock_amount, processed, has_more) = remove_and_accumulate_lock_info_limited(
         storage,
         staking_token.as_bytes(),
         staker_addr.as_bytes(),
         env.block.time,
+        limit.unwrap_or(DEFAULT_LOCK_LIMIT) as usize,
     )?;
 
-    if unlock_amount.is_zero() {
+    if processed == 0 {
         return Ok(Response::new());
     }
 
-    let unbond_response = _unbond(staker_addr, staking_token, unlock_amount)?;
-
-    Ok(unbond_response)
+    let response = match recipient {
+        // the matured locks are paid out elsewhere, both sides are kept for auditing
+        Some(recipient) => Ok(Response::new()
+            .add_message(_transfer_msg(
+                storage,
+                token_addr,
+                recipient,
+                unlock_amount,
+            )?)
+            .add_attributes([
+                attr("action", "unbond"),
+                attr("staker_addr", staker_addr.as_str()),
+                attr("amount", unlock_amount.to_string()),
+                attr("staking_token", token_addr.as_str()),
+                attr("claimer", staker_addr.as_str()),
+                attr("recipient", recipient.as_str()),
+            ])),
+        None => _unbond(storage, staker_addr, token_addr, unlock_amount),
+    }?;
+    Ok(response.add_attributes([
+        attr("processed_locks", processed.to_string()),
+        attr("has_more_locks", has_more.to_string()),
+    ]))
 }
 
 fn _increase_bond_amount(
     storage: &mut dyn Storage,
     api: &dyn Api,
-    height: u64,
+    block: &BlockInfo,
     staker_addr: &CanonicalAddr,
     staking_token: Addr,
     amount: Uint128,
 ) -> StdResult<()> {
-    let asset_key = api.addr_canonicalize(staking_token.as_str())?.to_vec();
+    let asset_key = pool_key(storage, api, &staking_token)?.to_vec();
     let mut pool_info = read_pool_info(storage, &asset_key)?;
-    let mut reward_info: RewardInfo = rewards_read(storage, staker_addr)
-        .load(&asset_key)
+    let mut reward_info: RewardInfo = REWARDS
+        .load(storage, (staker_addr, &asset_key))
         .unwrap_or_else(|_| RewardInfo {
             native_token: false,
             index: Decimal::zero(),
             bond_amount: Uint128::zero(),
             pending_reward: Uint128::zero(),
             pending_withdraw: vec![],
+            weighted_amount: None,
         });
 
     // Withdraw reward to pending reward; before changing share
+    split_at_rate_checkpoints(storage, &asset_key, &mut reward_info)?;
     before_share_change(pool_info.reward_index, &mut reward_info)?;
+    let (old_weight, old_total_weight) = (reward_info.weight(), pool_info.total_weight());
 
     // Increase total bond amount
     pool_info.total_bond_amount += amount;
 
     reward_info.bond_amount += amount;
+    if reward_info.bond_amount < read_min_bond_amount(storage, &asset_key)? {
+        return Err(StdError::generic_err("bond amount is below the minimum"));
+    }
+    _reweight(
+        storage,
+        &asset_key,
+        staker_addr,
+        block.time.seconds(),
+        &mut pool_info,
+        &mut reward_info,
+        old_weight,
+        old_total_weight,
+    )?;
+    let height = block.height;
 
     STAKED_BALANCES.update(
         storage,
@@ -178,15 +1521,12 @@ fn _increase_bond_amount(
         Ok(total.unwrap_or_default().checked_add(amount)?)
     })?;
 
-    rewards_store(storage, staker_addr).save(&asset_key, &reward_info)?;
+    REWARDS.save(storage, (staker_addr, &asset_key), &reward_info)?;
 
     store_pool_info(storage, &asset_key, &pool_info)?;
 
     // mark this staker belong to the pool the first time
-    let mut stakers_bucket = stakers_store(storage, &asset_key);
-    if stakers_bucket.may_load(staker_addr)?.is_none() {
-        stakers_bucket.save(staker_addr, &true)?;
-    }
+    add_staker(storage, &asset_key, staker_addr)?;
 
     Ok(())
 }
@@ -194,30 +1534,59 @@ fn _increase_bond_amount(
 fn _decrease_bond_amount(
     storage: &mut dyn Storage,
     api: &dyn Api,
-    height: u64,
+    block: &BlockInfo,
     staker_addr: &CanonicalAddr,
     staking_token: &Addr,
     amount: Uint128,
 ) -> StdResult<(CanonicalAddr, Vec<Asset>)> {
-    let asset_key = api.addr_canonicalize(staking_token.as_str())?.to_vec();
+    let asset_key = pool_key(storage, api, staking_token)?.to_vec();
     let mut pool_info: PoolInfo = read_pool_info(storage, &asset_key)?;
-    let mut reward_info: RewardInfo = rewards_read(storage, staker_addr).load(&asset_key)?;
+    let mut reward_info: RewardInfo = REWARDS.load(storage, (staker_addr, &asset_key))?;
     let mut reward_assets = vec![];
     if reward_info.bond_amount < amount {
         return Err(StdError::generic_err("Cannot unbond more than bond amount"));
     }
+    if let Some(boost) = read_boost(storage, &asset_key, staker_addr)? {
+        if boost.expires_at > block.time.seconds() {
+            return Err(StdError::generic_err(format!(
+                "bond is committed by a boost until {}",
+                boost.expires_at
+            )));
+        }
+    }
 
     // if the lp token was migrated, and the user did not close their position yet, cap the reward at the snapshot
     let (pool_index, staking_token) = (pool_info.reward_index, pool_info.staking_token.clone());
 
     // Distribute reward to pending reward; before changing share
+    split_at_rate_checkpoints(storage, &asset_key, &mut reward_info)?;
     before_share_change(pool_index, &mut reward_info)?;
+    let (old_weight, old_total_weight) = (reward_info.weight(), pool_info.total_weight());
 
     // Update rewards info
     reward_info.bond_amount = reward_info.bond_amount.checked_sub(amount)?;
+    // dust positions are rejected, exit fully instead
+    if !reward_info.bond_amount.is_zero()
+        && reward_info.bond_amount < read_min_bond_amount(storage, &asset_key)?
+    {
+        return Err(StdError::generic_err(
+            "remaining bond amount is below the minimum, unbond the full amount instead",
+        ));
+    }
 
     // Update pool_info
     pool_info.total_bond_amount = pool_info.total_bond_amount.checked_sub(amount)?;
+    _reweight(
+        storage,
+        &asset_key,
+        staker_addr,
+        block.time.seconds(),
+        &mut pool_info,
+        &mut reward_info,
+        old_weight,
+        old_total_weight,
+    )?;
+    let height = block.height;
 
     // update snapshot
     STAKED_BALANCES.update(
@@ -238,9 +1607,12 @@ fn _decrease_bond_amount(
             .iter()
             .map(|ra| ra.to_normal(api))
             .collect::<StdResult<Vec<Asset>>>()?;
+        sub_outstanding_assets(storage, &asset_key, &reward_info.pending_withdraw)?;
         reward_info.pending_withdraw = vec![];
+        // nothing is left for this staker in the pool
+        remove_staker(storage, &asset_key, staker_addr)?;
     }
-    rewards_store(storage, staker_addr).save(&asset_key, &reward_info)?;
+    REWARDS.save(storage, (staker_addr, &asset_key), &reward_info)?;
 
     // Update pool info
     store_pool_info(storage, &asset_key, &pool_info)?;
@@ -248,16 +1620,204 @@ fn _decrease_bond_amount(
     Ok((staking_token, reward_assets))
 }
 
-fn _unbond(staker_addr: &Addr, staking_token_addr: &Addr, amount: Uint128) -> StdResult<Response> {
-    let messages: Vec<CosmosMsg> = vec![WasmMsg::Execute {
-        contract_addr: staking_token_addr.to_string(),
+// keep the weight of the staker and the pool total in line with the bond and its running boost,
+// the expired boosts are dropped
+#[allow(clippy::too_many_arguments)]
+fn _reweight(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    staker_addr: &CanonicalAddr,
+    now: u64,
+    pool_info: &mut PoolInfo,
+    reward_info: &mut RewardInfo,
+    old_weight: Uint128,
+    old_total_weight: Uint128,
+) -> StdResult<()> {
+    let multiplier = match read_boost(storage, asset_key, staker_addr)? {
+        Some(boost) if boost.expires_at > now => Some(boost.multiplier),
+        Some(_) => {
+            remove_boost(storage, asset_key, staker_addr);
+            None
+        }
+        None => None,
+    };
+    reward_info.weighted_amount = multiplier.map(|multiplier| reward_info.bond_amount * multiplier);
+
+    // the total follows the bond until someone gets boosted
+    if pool_info.total_weighted_amount.is_some() || reward_info.weighted_amount.is_some() {
+        pool_info.total_weighted_amount = Some(
+            old_total_weight
+                .checked_sub(old_weight)?
+                .checked_add(reward_info.weight())?,
+        );
+    }
+    Ok(())
+}
+
+/// 1 without a commitment, max_multiplier for a commitment of max_lock_weeks
+fn boost_multiplier(curve: &BoostCurve, lock_weeks: u64) -> Decimal {
+    let extra = curve.max_multiplier - Decimal::one();
+    Decimal::one() + extra * Decimal::from_ratio(lock_weeks, curve.max_lock_weeks)
+}
+
+// commit the whole bond of the pool for lock_weeks, it earns according to the boost curve until then
+// a running commitment can only be extended
+pub fn boost(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    lock_weeks: u64,
+) -> StdResult<Response> {
+    let staker_addr_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if is_blacklisted(deps.storage, &staker_addr_raw)? {
+        return Err(StdError::generic_err("address is blacklisted"));
+    }
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    // the rewards up to now are earned with the former weights
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key)?;
+    let mut pool_info = read_pool_info(deps.storage, &asset_key)?;
+    if pool_info.deprecated {
+        return Err(StdError::generic_err("pool is deprecated"));
+    }
+    let curve = read_boost_curve(deps.storage, &asset_key)?
+        .ok_or_else(|| StdError::generic_err("boosting is not enabled for this pool"))?;
+    if lock_weeks == 0 || lock_weeks > curve.max_lock_weeks {
+        return Err(StdError::generic_err(format!(
+            "lock weeks must be between 1 and {}",
+            curve.max_lock_weeks
+        )));
+    }
+    let mut reward_info = REWARDS
+        .may_load(deps.storage, (&staker_addr_raw, &asset_key))?
+        .filter(|reward_info| !reward_info.bond_amount.is_zero())
+        .ok_or_else(|| StdError::generic_err("staker has no bond in this pool"))?;
+
+    let now = env.block.time.seconds();
+    let expires_at = now + lock_weeks * WEEK;
+    if let Some(current) = read_boost(deps.storage, &asset_key, &staker_addr_raw)? {
+        if current.expires_at > expires_at {
+            return Err(StdError::generic_err(
+                "a boost commitment cannot be shortened",
+            ));
+        }
+    }
+    let multiplier = boost_multiplier(&curve, lock_weeks);
+
+    // rewards so far are earned with the former weight
+    split_at_rate_checkpoints(deps.storage, &asset_key, &mut reward_info)?;
+    before_share_change(pool_info.reward_index, &mut reward_info)?;
+    let (old_weight, old_total_weight) = (reward_info.weight(), pool_info.total_weight());
+    store_boost(
+        deps.storage,
+        &asset_key,
+        &staker_addr_raw,
+        &Boost {
+            multiplier,
+            expires_at,
+        },
+    )?;
+    _reweight(
+        deps.storage,
+        &asset_key,
+        &staker_addr_raw,
+        now,
+        &mut pool_info,
+        &mut reward_info,
+        old_weight,
+        old_total_weight,
+    )?;
+    REWARDS.save(deps.storage, (&staker_addr_raw, &asset_key), &reward_info)?;
+    store_pool_info(deps.storage, &asset_key, &pool_info)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "boost"),
+        attr("staker_addr", info.sender.as_str()),
+        attr("staking_token", staking_token.as_str()),
+        attr("multiplier", multiplier.to_string()),
+        attr("expires_at", expires_at.to_string()),
+    ]))
+}
+
+// anyone can bring the weight of a staker back to the bond once the commitment matured
+pub fn expire_boost(
+    deps: DepsMut,
+    env: Env,
+    staking_token: Addr,
+    staker_addr: Addr,
+) -> StdResult<Response> {
+    let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let asset_key = pool_key(deps.storage, deps.api, &staking_token)?.to_vec();
+    let boost = read_boost(deps.storage, &asset_key, &staker_addr_raw)?
+        .ok_or_else(|| StdError::generic_err("staker has no boost in this pool"))?;
+    let now = env.block.time.seconds();
+    if boost.expires_at > now {
+        return Err(StdError::generic_err("boost has not expired yet"));
+    }
+
+    accrue_pool_rewards(deps.storage, deps.api, &deps.querier, &env, &asset_key)?;
+    let mut pool_info = read_pool_info(deps.storage, &asset_key)?;
+    let mut reward_info: RewardInfo = REWARDS.load(deps.storage, (&staker_addr_raw, &asset_key))?;
+    split_at_rate_checkpoints(deps.storage, &asset_key, &mut reward_info)?;
+    before_share_change(pool_info.reward_index, &mut reward_info)?;
+    let (old_weight, old_total_weight) = (reward_info.weight(), pool_info.total_weight());
+    _reweight(
+        deps.storage,
+        &asset_key,
+        &staker_addr_raw,
+        now,
+        &mut pool_info,
+        &mut reward_info,
+        old_weight,
+        old_total_weight,
+    )?;
+    REWARDS.save(deps.storage, (&staker_addr_raw, &asset_key), &reward_info)?;
+    store_pool_info(deps.storage, &asset_key, &pool_info)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "expire_boost"),
+        attr("staker_addr", staker_addr.as_str()),
+        attr("staking_token", staking_token.as_str()),
+    ]))
+}
+
+// pools of native staking tokens pay out with a bank send
+fn _transfer_msg(
+    storage: &dyn Storage,
+    staking_token: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    if is_native_staking_token(storage, staking_token.as_bytes())? {
+        return Ok(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(amount.u128(), staking_token.as_str())],
+        }
+        .into());
+    }
+    Ok(WasmMsg::Execute {
+        contract_addr: staking_token.to_string(),
         msg: to_binary(&Cw20ExecuteMsg::Transfer {
-            recipient: staker_addr.to_string(),
+            recipient: recipient.to_string(),
             amount,
         })?,
         funds: vec![],
     }
-    .into()];
+    .into())
+}
+
+fn _unbond(
+    storage: &dyn Storage,
+    staker_addr: &Addr,
+    staking_token_addr: &Addr,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let messages: Vec<CosmosMsg> = vec![_transfer_msg(
+        storage,
+        staking_token_addr,
+        staker_addr,
+        amount,
+    )?];
 
     Ok(Response::new().add_messages(messages).add_attributes([
         attr("action", "unbond"),
@@ -1,136 +1,1991 @@
-use crate::msg::LockInfo;
+use std::cmp::Ordering;
+use std::ops::Deref;
+
+use crate::msg::{
+    AccrualMode, BoostCurve, ExecuteMsg, HookKind, LegacyKind, LockInfo, PoolStatus,
+    RewardsPerSecLimits,
+};
 use cosmwasm_schema::cw_serde;
-use cw_storage_plus::{SnapshotMap, Strategy};
-use oraiswap::{asset::AssetRaw, querier::calc_range_start};
+use cosmwasm_schema::serde::{de::DeserializeOwned, Serialize};
+use cw_storage_plus::{Bound, KeyDeserialize, Map, Prefix, PrimaryKey, SnapshotMap, Strategy};
+use oraiswap::{
+    asset::{Asset, AssetInfo, AssetInfoRaw, AssetRaw},
+    querier::calc_range_start,
+};
+
+use cosmwasm_std::{
+    Addr, Api, CanonicalAddr, Decimal, Order, Record, StdError, StdResult, Storage, Timestamp,
+    Uint128,
+};
+use cosmwasm_storage::{singleton, singleton_read, to_length_prefixed, Bucket, ReadonlyBucket};
+
+pub static KEY_CONFIG: &[u8] = b"config_v2";
+pub static KEY_OWNERSHIP_PROPOSAL: &[u8] = b"ownership_proposal";
+pub static KEY_PENDING_REWARDER: &[u8] = b"pending_rewarder";
+pub static KEY_PAUSED: &[u8] = b"paused";
+pub static KEY_IMPORT_OPEN: &[u8] = b"import_open";
+pub static KEY_POOL_COUNT: &[u8] = b"pool_count";
+pub static KEY_PENDING_CHANGE_ID: &[u8] = b"pending_change_id";
+pub static KEY_PENDING_BOND: &[u8] = b"pending_bond";
+pub static KEY_PENDING_SWAP: &[u8] = b"pending_swap";
+pub static KEY_PENDING_ZAP: &[u8] = b"pending_zap";
+pub static KEY_PENDING_PAYOUT: &[u8] = b"pending_payout";
+pub static KEY_ROUTER: &[u8] = b"router";
+pub static KEY_BLOCK_TIME_COUNT: &[u8] = b"block_time_count";
+pub static KEY_MEMBERSHIP: &[u8] = b"membership";
+pub static KEY_CW20_STAKE_POOL: &[u8] = b"cw20_stake_pool";
+pub static PREFIX_PENDING_CHANGES: &[u8] = b"pending_changes";
+pub static PREFIX_REWARDERS: &[u8] = b"rewarders";
+pub static PREFIX_OPERATORS: &[u8] = b"operators";
+pub static PREFIX_HOOKS: &[u8] = b"hooks";
+pub static PREFIX_CLAIM_HOOKS: &[u8] = b"claim_hooks";
+pub static PREFIX_HOOK_FAILURES: &[u8] = b"hook_failures";
+pub static PREFIX_ALLOWED_STAKING_TOKENS: &[u8] = b"allowed_staking_tokens";
+pub static PREFIX_BLACKLIST: &[u8] = b"blacklist";
+pub static PREFIX_CLAIM_DELEGATE: &[u8] = b"claim_delegate";
+pub static PREFIX_REWARD_SPLIT: &[u8] = b"reward_split";
+pub static PREFIX_POOL_STATUS: &[u8] = b"pool_status";
+pub static PREFIX_POOL_ADMIN: &[u8] = b"pool_admin";
+pub static PREFIX_OPEN_DEPOSIT: &[u8] = b"open_deposit";
+pub static PREFIX_BOOST_CURVE: &[u8] = b"boost_curve";
+pub static PREFIX_BOOST: &[u8] = b"boost";
+pub static PREFIX_REWARD_DUST: &[u8] = b"reward_dust";
+pub static PREFIX_ACCRUAL_MODE: &[u8] = b"accrual_mode";
+pub static PREFIX_LAST_DISTRIBUTED: &[u8] = b"last_distributed";
+pub static PREFIX_STAKING_TOKEN_POOL: &[u8] = b"staking_token_pool";
+pub static PREFIX_TOKEN_POOLS: &[u8] = b"token_pools";
+pub static PREFIX_NATIVE_STAKING_TOKENS: &[u8] = b"native_staking_tokens";
+pub static PREFIX_STAKE_MIGRATIONS: &[u8] = b"stake_migrations";
+pub static PREFIX_STAKER_COUNT: &[u8] = b"staker_count";
+pub static PREFIX_REWARDS_PER_SEC_LIMITS: &[u8] = b"rewards_per_sec_limits";
+pub static PREFIX_REWARDS_PER_SEC_UPDATED: &[u8] = b"rewards_per_sec_updated";
+pub static PREFIX_REWARD_SCHEDULES: &[u8] = b"reward_schedules";
+pub static PREFIX_REWARD_SCHEDULES_SYNCED: &[u8] = b"reward_schedules_synced";
+pub static PREFIX_OUTSTANDING_REWARD: &[u8] = b"outstanding_reward";
+// Unbonded
+pub static UNBOND_FEE: &[u8] = b"unbond_fee";
+pub static INSTANT_UNBOND_PENALTY: &[u8] = b"instant_unbond_penalty";
+pub static MIN_BOND_AMOUNT: &[u8] = b"min_bond_amount";
+pub static MIN_CLAIM_AMOUNT: &[u8] = b"min_claim_amount";
+pub static LOCK_PERIOD: &[u8] = b"locking_periods";
+// the locks of a pool across its users, keyed by user then unlock time
+pub static LOCK_INDEX: &[u8] = b"locking_index";
+// sum of the locks of a pool, keyed like the locks
+pub static TOTAL_UNBONDING: &[u8] = b"total_unbonding";
+// ring of the (height, time) of the blocks the contract was executed in
+pub static BLOCK_TIMES: &[u8] = b"block_times";
+// Vesting of the withdrawn rewards
+pub static REWARD_VESTING_PERIOD: &[u8] = b"reward_vesting_period";
+pub static REWARD_VESTING: &[u8] = b"reward_vesting";
+
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+// records of a single ExportState page
+pub const MAX_EXPORT_LIMIT: u32 = 100;
+// stakers of a single RewardInfosBatch query
+pub const MAX_REWARD_INFOS_BATCH: usize = 50;
+// 90 days
+pub const DEFAULT_MAX_UNBONDING_PERIOD: u64 = 90 * 24 * 60 * 60;
+pub const DEFAULT_MAX_LOCK_ENTRIES: u64 = 50;
+// matured locks released by a single unbond when no limit is given
+pub const DEFAULT_LOCK_LIMIT: u32 = 100;
+// 365 days, used to annualize emissions
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+// denom the oracle prices the staking tokens in for PoolValue
+pub const ORACLE_QUOTE_DENOM: &str = "usdt";
+// blocks kept by the block time ring, the oldest are overwritten
+pub const MAX_BLOCK_TIMES: u64 = 10_000;
+// contracts notified of every stake change or claim, each one costs gas on every bond and unbond
+// the limit applies to each list
+pub const MAX_HOOKS: u32 = 10;
+// failed calls after which a hook is skipped, until the owner resets its failures
+pub const MAX_HOOK_FAILURES: u64 = 3;
+// schedules of a pool that have not ended yet
+pub const MAX_REWARD_SCHEDULES: usize = 10;
+// the whole reward, in basis points
+pub const MAX_REWARD_SPLIT_BPS: u16 = 10000;
+// unit of the boost commitments
+pub const WEEK: u64 = 7 * 24 * 60 * 60;
+// the withdrawn rewards unlock in daily tranches over the vesting period
+pub const VESTING_STEP: u64 = 24 * 60 * 60;
+
+#[cw_serde]
+pub struct Config {
+    // None once the ownership has been renounced, stored configs with a plain owner still decode
+    pub owner: Option<CanonicalAddr>,
+    // receives the unbond fees, no fee is charged while unset
+    pub fee_collector: Option<CanonicalAddr>,
+    // seconds sensitive updates wait in the pending queue, zero applies them right away
+    #[serde(default)]
+    pub config_timelock: u64,
+    // unbonding periods cannot be increased above it, DEFAULT_MAX_UNBONDING_PERIOD when unset
+    #[serde(default)]
+    pub max_unbonding_period: Option<u64>,
+    // outstanding unbonding locks per staker and pool, DEFAULT_MAX_LOCK_ENTRIES when unset
+    #[serde(default)]
+    pub max_lock_entries: Option<u64>,
+    // staking token of the pool backing the voting power, kept humanized as the pool may not exist yet
+    #[serde(default)]
+    pub governance_token: Option<Addr>,
+    // oraiswap oracle pricing the staking tokens for PoolValue, values are left out while unset
+    #[serde(default)]
+    pub oracle: Option<CanonicalAddr>,
+}
+
+// config layout before the single rewarder was moved into the rewarders set
+#[cw_serde]
+struct LegacyConfig {
+    pub owner: Option<CanonicalAddr>,
+    pub rewarder: CanonicalAddr,
+}
+
+impl Config {
+    pub fn assert_owner(&self, api: &dyn Api, sender: &Addr) -> StdResult<()> {
+        let sender_raw = api.addr_canonicalize(sender.as_str())?;
+        if self.owner.as_ref() != Some(&sender_raw) {
+            return Err(StdError::generic_err("unauthorized"));
+        }
+        Ok(())
+    }
+
+    /// operators may tune pool parameters but never change the config
+    pub fn assert_owner_or_operator(
+        &self,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        sender: &Addr,
+    ) -> StdResult<()> {
+        let sender_raw = api.addr_canonicalize(sender.as_str())?;
+        if self.owner.as_ref() != Some(&sender_raw) && !is_operator(storage, &sender_raw)? {
+            return Err(StdError::generic_err("unauthorized"));
+        }
+        Ok(())
+    }
+
+    /// pool admins can only manage the pool they were assigned to
+    pub fn assert_owner_or_pool_admin(
+        &self,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        sender: &Addr,
+        asset_key: &[u8],
+    ) -> StdResult<()> {
+        let sender_raw = api.addr_canonicalize(sender.as_str())?;
+        if self.owner.as_ref() != Some(&sender_raw)
+            && read_pool_admin(storage, asset_key)?.as_ref() != Some(&sender_raw)
+        {
+            return Err(StdError::generic_err("unauthorized"));
+        }
+        Ok(())
+    }
+
+    pub fn assert_pool_manager(
+        &self,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        sender: &Addr,
+        asset_key: &[u8],
+    ) -> StdResult<()> {
+        if self.assert_owner_or_operator(storage, api, sender).is_ok() {
+            return Ok(());
+        }
+        self.assert_owner_or_pool_admin(storage, api, sender, asset_key)
+    }
+
+    pub fn max_unbonding_period(&self) -> u64 {
+        self.max_unbonding_period
+            .unwrap_or(DEFAULT_MAX_UNBONDING_PERIOD)
+    }
+
+    pub fn max_lock_entries(&self) -> u64 {
+        self.max_lock_entries.unwrap_or(DEFAULT_MAX_LOCK_ENTRIES)
+    }
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+/// moves the rewarder of a legacy config into the rewarders set, no-op for migrated configs
+pub fn migrate_legacy_config(storage: &mut dyn Storage) -> StdResult<()> {
+    if let Ok(legacy_config) = singleton_read::<LegacyConfig>(storage, KEY_CONFIG).load() {
+        store_rewarder(storage, &legacy_config.rewarder)?;
+        store_config(
+            storage,
+            &Config {
+                owner: legacy_config.owner,
+                fee_collector: None,
+                config_timelock: 0,
+                max_unbonding_period: None,
+                max_lock_entries: None,
+                governance_token: None,
+                oracle: None,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+#[cw_serde]
+pub struct OwnershipProposal {
+    pub owner: CanonicalAddr,
+    pub expires_at: Timestamp,
+}
+
+pub fn store_ownership_proposal(
+    storage: &mut dyn Storage,
+    proposal: &OwnershipProposal,
+) -> StdResult<()> {
+    singleton(storage, KEY_OWNERSHIP_PROPOSAL).save(proposal)
+}
+
+pub fn read_ownership_proposal(storage: &dyn Storage) -> StdResult<Option<OwnershipProposal>> {
+    singleton_read(storage, KEY_OWNERSHIP_PROPOSAL).may_load()
+}
+
+pub fn remove_ownership_proposal(storage: &mut dyn Storage) {
+    singleton::<OwnershipProposal>(storage, KEY_OWNERSHIP_PROPOSAL).remove()
+}
+
+pub fn store_rewarder(storage: &mut dyn Storage, rewarder: &CanonicalAddr) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_REWARDERS).save(rewarder, &true)
+}
+
+pub fn remove_rewarder(storage: &mut dyn Storage, rewarder: &CanonicalAddr) {
+    Bucket::<bool>::new(storage, PREFIX_REWARDERS).remove(rewarder)
+}
+
+pub fn is_rewarder(storage: &dyn Storage, rewarder: &CanonicalAddr) -> StdResult<bool> {
+    Ok(ReadonlyBucket::<bool>::new(storage, PREFIX_REWARDERS)
+        .may_load(rewarder)?
+        .is_some())
+}
+
+pub fn read_rewarders(
+    storage: &dyn Storage,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<CanonicalAddr>> {
+    read_addresses(storage, PREFIX_REWARDERS, start_after, limit)
+}
+
+pub fn store_operator(storage: &mut dyn Storage, operator: &CanonicalAddr) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_OPERATORS).save(operator, &true)
+}
+
+pub fn remove_operator(storage: &mut dyn Storage, operator: &CanonicalAddr) {
+    Bucket::<bool>::new(storage, PREFIX_OPERATORS).remove(operator)
+}
+
+pub fn is_operator(storage: &dyn Storage, operator: &CanonicalAddr) -> StdResult<bool> {
+    Ok(ReadonlyBucket::<bool>::new(storage, PREFIX_OPERATORS)
+        .may_load(operator)?
+        .is_some())
+}
+
+pub fn read_operators(
+    storage: &dyn Storage,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<CanonicalAddr>> {
+    read_addresses(storage, PREFIX_OPERATORS, start_after, limit)
+}
+
+pub fn store_hook(storage: &mut dyn Storage, hook: &CanonicalAddr) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_HOOKS).save(hook, &true)
+}
+
+pub fn remove_hook(storage: &mut dyn Storage, hook: &CanonicalAddr) {
+    Bucket::<bool>::new(storage, PREFIX_HOOKS).remove(hook)
+}
+
+pub fn is_hook(storage: &dyn Storage, hook: &CanonicalAddr) -> StdResult<bool> {
+    Ok(ReadonlyBucket::<bool>::new(storage, PREFIX_HOOKS)
+        .may_load(hook)?
+        .is_some())
+}
+
+/// every registered hook, there are at most MAX_HOOKS
+pub fn read_hooks(storage: &dyn Storage) -> StdResult<Vec<CanonicalAddr>> {
+    read_addresses(storage, PREFIX_HOOKS, None, Some(MAX_HOOKS))
+}
+
+pub fn store_claim_hook(storage: &mut dyn Storage, hook: &CanonicalAddr) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_CLAIM_HOOKS).save(hook, &true)
+}
+
+pub fn remove_claim_hook(storage: &mut dyn Storage, hook: &CanonicalAddr) {
+    Bucket::<bool>::new(storage, PREFIX_CLAIM_HOOKS).remove(hook)
+}
+
+pub fn is_claim_hook(storage: &dyn Storage, hook: &CanonicalAddr) -> StdResult<bool> {
+    Ok(ReadonlyBucket::<bool>::new(storage, PREFIX_CLAIM_HOOKS)
+        .may_load(hook)?
+        .is_some())
+}
+
+pub fn read_claim_hooks(storage: &dyn Storage) -> StdResult<Vec<CanonicalAddr>> {
+    read_addresses(storage, PREFIX_CLAIM_HOOKS, None, Some(MAX_HOOKS))
+}
+
+pub fn read_hooks_of(storage: &dyn Storage, kind: &HookKind) -> StdResult<Vec<CanonicalAddr>> {
+    match kind {
+        HookKind::Stake => read_hooks(storage),
+        HookKind::Claim => read_claim_hooks(storage),
+    }
+}
+
+fn hook_failures_namespace(kind: &HookKind) -> &'static [u8] {
+    match kind {
+        HookKind::Stake => PREFIX_HOOKS,
+        HookKind::Claim => PREFIX_CLAIM_HOOKS,
+    }
+}
+
+/// counts a failed call of the hook, returns its failures so far
+pub fn add_hook_failure(
+    storage: &mut dyn Storage,
+    kind: &HookKind,
+    hook: &CanonicalAddr,
+) -> StdResult<u64> {
+    let failures = read_hook_failures(storage, kind, hook)? + 1;
+    Bucket::multilevel(
+        storage,
+        &[PREFIX_HOOK_FAILURES, hook_failures_namespace(kind)],
+    )
+    .save(hook, &failures)?;
+    Ok(failures)
+}
+
+pub fn read_hook_failures(
+    storage: &dyn Storage,
+    kind: &HookKind,
+    hook: &CanonicalAddr,
+) -> StdResult<u64> {
+    Ok(ReadonlyBucket::<u64>::multilevel(
+        storage,
+        &[PREFIX_HOOK_FAILURES, hook_failures_namespace(kind)],
+    )
+    .may_load(hook)?
+    .unwrap_or_default())
+}
+
+pub fn remove_hook_failures(storage: &mut dyn Storage, kind: &HookKind, hook: &CanonicalAddr) {
+    Bucket::<u64>::multilevel(
+        storage,
+        &[PREFIX_HOOK_FAILURES, hook_failures_namespace(kind)],
+    )
+    .remove(hook)
+}
+
+pub fn store_allowed_staking_token(
+    storage: &mut dyn Storage,
+    staking_token: &CanonicalAddr,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_ALLOWED_STAKING_TOKENS).save(staking_token, &true)
+}
+
+pub fn remove_allowed_staking_token(storage: &mut dyn Storage, staking_token: &CanonicalAddr) {
+    Bucket::<bool>::new(storage, PREFIX_ALLOWED_STAKING_TOKENS).remove(staking_token)
+}
+
+/// every staking token is allowed while the allowlist is empty
+pub fn is_allowed_staking_token(
+    storage: &dyn Storage,
+    staking_token: &CanonicalAddr,
+) -> StdResult<bool> {
+    let bucket = ReadonlyBucket::<bool>::new(storage, PREFIX_ALLOWED_STAKING_TOKENS);
+    if bucket.may_load(staking_token)?.is_some() {
+        return Ok(true);
+    }
+    Ok(bucket.range(None, None, Order::Ascending).next().is_none())
+}
+
+pub fn read_allowed_staking_tokens(
+    storage: &dyn Storage,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<CanonicalAddr>> {
+    read_addresses(storage, PREFIX_ALLOWED_STAKING_TOKENS, start_after, limit)
+}
+
+pub fn store_blacklisted(storage: &mut dyn Storage, address: &CanonicalAddr) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_BLACKLIST).save(address, &true)
+}
+
+pub fn remove_blacklisted(storage: &mut dyn Storage, address: &CanonicalAddr) {
+    Bucket::<bool>::new(storage, PREFIX_BLACKLIST).remove(address)
+}
+
+pub fn is_blacklisted(storage: &dyn Storage, address: &CanonicalAddr) -> StdResult<bool> {
+    Ok(ReadonlyBucket::<bool>::new(storage, PREFIX_BLACKLIST)
+        .may_load(address)?
+        .is_some())
+}
+
+pub fn read_blacklist(
+    storage: &dyn Storage,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<CanonicalAddr>> {
+    read_addresses(storage, PREFIX_BLACKLIST, start_after, limit)
+}
+
+pub fn store_claim_delegate(
+    storage: &mut dyn Storage,
+    staker: &CanonicalAddr,
+    delegate: &CanonicalAddr,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_CLAIM_DELEGATE).save(staker, delegate)
+}
+
+pub fn remove_claim_delegate(storage: &mut dyn Storage, staker: &CanonicalAddr) {
+    Bucket::<CanonicalAddr>::new(storage, PREFIX_CLAIM_DELEGATE).remove(staker)
+}
+
+pub fn read_claim_delegate(
+    storage: &dyn Storage,
+    staker: &CanonicalAddr,
+) -> StdResult<Option<CanonicalAddr>> {
+    ReadonlyBucket::new(storage, PREFIX_CLAIM_DELEGATE).may_load(staker)
+}
+
+// share of the withdrawn rewards of a staker paid to the beneficiary, in basis points
+#[cw_serde]
+pub struct RewardSplit {
+    pub beneficiary: CanonicalAddr,
+    pub bps: u16,
+}
+
+pub fn store_reward_split(
+    storage: &mut dyn Storage,
+    staker: &CanonicalAddr,
+    reward_split: &RewardSplit,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_REWARD_SPLIT).save(staker, reward_split)
+}
+
+pub fn remove_reward_split(storage: &mut dyn Storage, staker: &CanonicalAddr) {
+    Bucket::<RewardSplit>::new(storage, PREFIX_REWARD_SPLIT).remove(staker)
+}
+
+pub fn read_reward_split(
+    storage: &dyn Storage,
+    staker: &CanonicalAddr,
+) -> StdResult<Option<RewardSplit>> {
+    ReadonlyBucket::new(storage, PREFIX_REWARD_SPLIT).may_load(staker)
+}
+
+fn read_addresses(
+    storage: &dyn Storage,
+    prefix: &[u8],
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<CanonicalAddr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after.map(|a| a.to_vec()));
+
+    ReadonlyBucket::<bool>::new(storage, prefix)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, _) = item?;
+            Ok(CanonicalAddr::from(k))
+        })
+        .collect()
+}
+
+#[cw_serde]
+pub struct RewarderProposal {
+    pub rewarder: CanonicalAddr,
+    // the rewarder removed from the set once the proposal is accepted
+    pub replace: Option<CanonicalAddr>,
+}
+
+pub fn store_pending_rewarder(
+    storage: &mut dyn Storage,
+    proposal: &RewarderProposal,
+) -> StdResult<()> {
+    singleton(storage, KEY_PENDING_REWARDER).save(proposal)
+}
+
+pub fn read_pending_rewarder(storage: &dyn Storage) -> StdResult<Option<RewarderProposal>> {
+    singleton_read(storage, KEY_PENDING_REWARDER).may_load()
+}
+
+pub fn remove_pending_rewarder(storage: &mut dyn Storage) {
+    singleton::<RewarderProposal>(storage, KEY_PENDING_REWARDER).remove()
+}
+
+// bond waiting for its TransferFrom to succeed
+#[cw_serde]
+pub struct PendingBond {
+    pub funder: Addr,
+    pub staking_token: Addr,
+    pub amount: Uint128,
+}
+
+pub fn store_pending_bond(storage: &mut dyn Storage, pending_bond: &PendingBond) -> StdResult<()> {
+    singleton(storage, KEY_PENDING_BOND).save(pending_bond)
+}
+
+pub fn read_pending_bond(storage: &dyn Storage) -> StdResult<Option<PendingBond>> {
+    singleton_read(storage, KEY_PENDING_BOND).may_load()
+}
+
+pub fn remove_pending_bond(storage: &mut dyn Storage) {
+    singleton::<PendingBond>(storage, KEY_PENDING_BOND).remove()
+}
+
+// swapped rewards waiting to be checked against min_receive and sent to the staker
+#[cw_serde]
+pub struct PendingSwap {
+    pub staker_addr: Addr,
+    pub to_asset: AssetInfo,
+    // balance of to_asset before the swaps, including the rewards already paid in it
+    pub prev_balance: Uint128,
+    // rewards paid in to_asset, sent along without a swap
+    pub direct_amount: Uint128,
+    pub min_receive: Uint128,
+}
+
+pub fn store_pending_swap(storage: &mut dyn Storage, pending_swap: &PendingSwap) -> StdResult<()> {
+    singleton(storage, KEY_PENDING_SWAP).save(pending_swap)
+}
+
+pub fn read_pending_swap(storage: &dyn Storage) -> StdResult<Option<PendingSwap>> {
+    singleton_read(storage, KEY_PENDING_SWAP).may_load()
+}
+
+pub fn remove_pending_swap(storage: &mut dyn Storage) {
+    singleton::<PendingSwap>(storage, KEY_PENDING_SWAP).remove()
+}
+
+// swap output of a ZapIn waiting to be checked against min_bond_amount and bonded
+#[cw_serde]
+pub struct PendingZap {
+    pub staker_addr: Addr,
+    pub staking_token: Addr,
+    // the staking token as the asset the router swaps into
+    pub ask_asset: AssetInfo,
+    // balance of the staking token before the swap
+    pub prev_balance: Uint128,
+    pub min_bond_amount: Uint128,
+}
+
+pub fn store_pending_zap(storage: &mut dyn Storage, pending_zap: &PendingZap) -> StdResult<()> {
+    singleton(storage, KEY_PENDING_ZAP).save(pending_zap)
+}
+
+pub fn read_pending_zap(storage: &dyn Storage) -> StdResult<Option<PendingZap>> {
+    singleton_read(storage, KEY_PENDING_ZAP).may_load()
+}
+
+pub fn remove_pending_zap(storage: &mut dyn Storage) {
+    singleton::<PendingZap>(storage, KEY_PENDING_ZAP).remove()
+}
+
+// cw20 reward transfers of the last payout, a failing one replies with its position in transfers
+#[cw_serde]
+pub struct PendingPayout {
+    pub staker_addr: CanonicalAddr,
+    // the pool whose pending_withdraw keeps a failing transfer, None reverts the payout
+    pub skip_into: Option<Vec<u8>>,
+    // recipient and asset of each transfer
+    pub transfers: Vec<(Addr, Asset)>,
+}
+
+pub fn store_pending_payout(
+    storage: &mut dyn Storage,
+    pending_payout: &PendingPayout,
+) -> StdResult<()> {
+    singleton(storage, KEY_PENDING_PAYOUT).save(pending_payout)
+}
+
+pub fn read_pending_payout(storage: &dyn Storage) -> StdResult<Option<PendingPayout>> {
+    singleton_read(storage, KEY_PENDING_PAYOUT).may_load()
+}
+
+pub fn store_router(storage: &mut dyn Storage, router: &CanonicalAddr) -> StdResult<()> {
+    singleton(storage, KEY_ROUTER).save(router)
+}
+
+/// rewards cannot be swapped on withdraw without a router
+pub fn read_router(storage: &dyn Storage) -> StdResult<Option<CanonicalAddr>> {
+    singleton_read(storage, KEY_ROUTER).may_load()
+}
+
+pub fn remove_router(storage: &mut dyn Storage) {
+    singleton::<CanonicalAddr>(storage, KEY_ROUTER).remove()
+}
+
+// the pool backing the cw4 membership queries
+#[cw_serde]
+pub struct Membership {
+    pub asset_key: Vec<u8>,
+    // the weight of a member is its staked amount divided by it, so it fits in a u64
+    pub weight_divisor: Uint128,
+}
+
+pub fn store_membership(storage: &mut dyn Storage, membership: &Membership) -> StdResult<()> {
+    singleton(storage, KEY_MEMBERSHIP).save(membership)
+}
+
+pub fn read_membership(storage: &dyn Storage) -> StdResult<Option<Membership>> {
+    singleton_read(storage, KEY_MEMBERSHIP).may_load()
+}
+
+pub fn remove_membership(storage: &mut dyn Storage) {
+    singleton::<Membership>(storage, KEY_MEMBERSHIP).remove()
+}
+
+/// the asset key of the pool served by the cw20-stake queries
+pub fn store_cw20_stake_pool(
+    storage: &mut dyn Storage,
+    asset_key: &CanonicalAddr,
+) -> StdResult<()> {
+    singleton(storage, KEY_CW20_STAKE_POOL).save(asset_key)
+}
+
+pub fn read_cw20_stake_pool(storage: &dyn Storage) -> StdResult<Option<CanonicalAddr>> {
+    singleton_read(storage, KEY_CW20_STAKE_POOL).may_load()
+}
+
+pub fn remove_cw20_stake_pool(storage: &mut dyn Storage) {
+    singleton::<CanonicalAddr>(storage, KEY_CW20_STAKE_POOL).remove()
+}
+
+/// stores the height at which the contract was paused
+pub fn store_paused(storage: &mut dyn Storage, height: u64) -> StdResult<()> {
+    singleton(storage, KEY_PAUSED).save(&height)
+}
+
+pub fn read_paused(storage: &dyn Storage) -> StdResult<Option<u64>> {
+    singleton_read(storage, KEY_PAUSED).may_load()
+}
+
+pub fn remove_paused(storage: &mut dyn Storage) {
+    singleton::<u64>(storage, KEY_PAUSED).remove()
+}
+
+/// only set by instantiate, so the import cannot be reopened once finalized
+pub fn store_import_open(storage: &mut dyn Storage) -> StdResult<()> {
+    singleton(storage, KEY_IMPORT_OPEN).save(&true)
+}
+
+pub fn read_import_open(storage: &dyn Storage) -> StdResult<bool> {
+    Ok(singleton_read(storage, KEY_IMPORT_OPEN)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+pub fn remove_import_open(storage: &mut dyn Storage) {
+    singleton::<bool>(storage, KEY_IMPORT_OPEN).remove()
+}
+
+#[cw_serde]
+pub struct PendingChange {
+    pub msg: ExecuteMsg,
+    pub execute_after: Timestamp,
+}
+
+/// queues a change and returns its id
+pub fn insert_pending_change(
+    storage: &mut dyn Storage,
+    pending_change: &PendingChange,
+) -> StdResult<u64> {
+    let id = singleton_read::<u64>(storage, KEY_PENDING_CHANGE_ID)
+        .may_load()?
+        .unwrap_or_default()
+        + 1;
+    singleton(storage, KEY_PENDING_CHANGE_ID).save(&id)?;
+    Bucket::new(storage, PREFIX_PENDING_CHANGES).save(&id.to_be_bytes(), pending_change)?;
+    Ok(id)
+}
+
+pub fn read_pending_change(storage: &dyn Storage, id: u64) -> StdResult<Option<PendingChange>> {
+    ReadonlyBucket::new(storage, PREFIX_PENDING_CHANGES).may_load(&id.to_be_bytes())
+}
+
+pub fn remove_pending_change(storage: &mut dyn Storage, id: u64) {
+    Bucket::<PendingChange>::new(storage, PREFIX_PENDING_CHANGES).remove(&id.to_be_bytes())
+}
+
+pub fn read_pending_changes(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, PendingChange)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after.map(|id| id.to_be_bytes().to_vec()));
+
+    ReadonlyBucket::<PendingChange>::new(storage, PREFIX_PENDING_CHANGES)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, pending_change) = item?;
+            let id = u64::from_be_bytes(
+                k.try_into()
+                    .map_err(|_| StdError::generic_err("Casting u64 to id fail"))?,
+            );
+            Ok((id, pending_change))
+        })
+        .collect()
+}
+
+#[cw_serde]
+pub struct PoolInfo {
+    pub staking_token: CanonicalAddr,
+    pub pending_reward: Uint128, // not distributed amount due to zero bonding
+    pub total_bond_amount: Uint128,
+    pub reward_index: Decimal,
+    // deprecated pools only accept exits, missing in pools stored before
+    #[serde(default)]
+    pub deprecated: bool,
+    // sum of the boosted bonds, None until a staker of the pool is boosted
+    #[serde(default)]
+    pub total_weighted_amount: Option<Uint128>,
+}
+
+impl PoolInfo {
+    /// the reward index is spread over the boosted bonds
+    pub fn total_weight(&self) -> Uint128 {
+        self.total_weighted_amount.unwrap_or(self.total_bond_amount)
+    }
+}
+
+/// a map with the entries of older deployments left under a legacy namespace. Reads fall back to
+/// the legacy entry, writes go to the current namespace and drop it, so an entry moves over the
+/// next time it is written. Ranges, under a prefix or not, merge both namespaces until
+/// migrate_legacy has moved the rest. Every other method is the one of the current map
+pub struct VersionedMap<'a, K, T> {
+    map: Map<'a, K, T>,
+    legacy: Map<'a, K, T>,
+    namespace: &'a str,
+    legacy_namespace: &'a str,
+}
+
+impl<'a, K, T> VersionedMap<'a, K, T> {
+    pub const fn new(namespace: &'a str, legacy_namespace: &'a str) -> Self {
+        Self {
+            map: Map::new(namespace),
+            legacy: Map::new(legacy_namespace),
+            namespace,
+            legacy_namespace,
+        }
+    }
+
+    /// the entries not moved yet
+    pub fn legacy(&self) -> &Map<'a, K, T> {
+        &self.legacy
+    }
+}
+
+impl<'a, K, T> Deref for VersionedMap<'a, K, T> {
+    type Target = Map<'a, K, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<'a, K, T> VersionedMap<'a, K, T>
+where
+    T: Serialize + DeserializeOwned,
+    K: PrimaryKey<'a>,
+{
+    pub fn may_load(&self, storage: &dyn Storage, key: K) -> StdResult<Option<T>> {
+        match self.map.may_load(storage, key.clone())? {
+            Some(value) => Ok(Some(value)),
+            None => self.legacy.may_load(storage, key),
+        }
+    }
+
+    pub fn load(&self, storage: &dyn Storage, key: K) -> StdResult<T> {
+        match self.may_load(storage, key.clone())? {
+            Some(value) => Ok(value),
+            None => self.map.load(storage, key),
+        }
+    }
+
+    pub fn has(&self, storage: &dyn Storage, key: K) -> bool {
+        self.map.has(storage, key.clone()) || self.legacy.has(storage, key)
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage, key: K, data: &T) -> StdResult<()> {
+        self.legacy.remove(storage, key.clone());
+        self.map.save(storage, key, data)
+    }
+
+    pub fn remove(&self, storage: &mut dyn Storage, key: K) {
+        self.legacy.remove(storage, key.clone());
+        self.map.remove(storage, key)
+    }
+
+    /// moves up to limit legacy entries to the current namespace, where an entry already written
+    /// there is newer and is kept. Returns the number of legacy entries removed and whether none
+    /// is left
+    pub fn migrate_legacy(&self, storage: &mut dyn Storage, limit: usize) -> (usize, bool) {
+        let legacy_prefix = to_length_prefixed(self.legacy_namespace.as_bytes());
+        // the namespaces are ascii, bumping the last byte bounds the keys of the namespace
+        let mut legacy_end = legacy_prefix.clone();
+        if let Some(last) = legacy_end.last_mut() {
+            *last += 1;
+        }
+        // one more entry tells whether the page was the last one
+        let mut entries = storage
+            .range(Some(&legacy_prefix), Some(&legacy_end), Order::Ascending)
+            .take(limit.saturating_add(1))
+            .collect::<Vec<(Vec<u8>, Vec<u8>)>>();
+        let done = entries.len() <= limit;
+        entries.truncate(limit);
+
+        let prefix = to_length_prefixed(self.namespace.as_bytes());
+        for (legacy_key, value) in entries.iter() {
+            let current_key = [prefix.as_slice(), &legacy_key[legacy_prefix.len()..]].concat();
+            if storage.get(&current_key).is_none() {
+                storage.set(&current_key, value);
+            }
+            storage.remove(legacy_key);
+        }
+        (entries.len(), done)
+    }
+}
+
+impl<'a, T> VersionedMap<'a, &'a [u8], T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    pub fn range<'c>(
+        &self,
+        storage: &'c dyn Storage,
+        min: Option<Bound<'a, &'a [u8]>>,
+        max: Option<Bound<'a, &'a [u8]>>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = StdResult<Record<T>>> + 'c>
+    where
+        T: 'c,
+    {
+        merge_ranges(
+            self.map.range_raw(storage, min.clone(), max.clone(), order),
+            self.legacy.range_raw(storage, min, max, order),
+            order,
+        )
+    }
+}
+
+impl<'a, T> VersionedMap<'a, (&'a [u8], &'a [u8]), T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    pub fn prefix(&self, p: &'a [u8]) -> VersionedPrefix<'a, T> {
+        VersionedPrefix {
+            prefix: self.map.prefix(p),
+            legacy: self.legacy.prefix(p),
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn range<'c>(
+        &self,
+        storage: &'c dyn Storage,
+        min: Option<Bound<'a, (&'a [u8], &'a [u8])>>,
+        max: Option<Bound<'a, (&'a [u8], &'a [u8])>>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = StdResult<((Vec<u8>, Vec<u8>), T)>> + 'c>
+    where
+        T: 'c,
+    {
+        let entries = merge_ranges(
+            self.map.range_raw(storage, min.clone(), max.clone(), order),
+            self.legacy.range_raw(storage, min, max, order),
+            order,
+        );
+        Box::new(entries.map(|item| {
+            let (key, value) = item?;
+            Ok((<(&[u8], &[u8]) as KeyDeserialize>::from_vec(key)?, value))
+        }))
+    }
+}
+
+/// the entries of a VersionedMap under a prefix, both namespaces merged
+pub struct VersionedPrefix<'a, T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    prefix: Prefix<&'a [u8], T, &'a [u8]>,
+    legacy: Prefix<&'a [u8], T, &'a [u8]>,
+}
+
+impl<'a, T> VersionedPrefix<'a, T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    pub fn range<'c>(
+        &self,
+        storage: &'c dyn Storage,
+        min: Option<Bound<'a, &'a [u8]>>,
+        max: Option<Bound<'a, &'a [u8]>>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = StdResult<Record<T>>> + 'c>
+    where
+        T: 'c,
+    {
+        merge_ranges(
+            self.prefix
+                .range_raw(storage, min.clone(), max.clone(), order),
+            self.legacy.range_raw(storage, min, max, order),
+            order,
+        )
+    }
+
+    pub fn keys<'c>(
+        &self,
+        storage: &'c dyn Storage,
+        min: Option<Bound<'a, &'a [u8]>>,
+        max: Option<Bound<'a, &'a [u8]>>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = StdResult<Vec<u8>>> + 'c>
+    where
+        T: 'c,
+    {
+        Box::new(
+            self.range(storage, min, max, order)
+                .map(|item| item.map(|(key, _)| key)),
+        )
+    }
+}
+
+/// merges two ranges in the same order, a key in both is taken from current
+fn merge_ranges<'c, T: 'c>(
+    current: Box<dyn Iterator<Item = StdResult<Record<T>>> + 'c>,
+    legacy: Box<dyn Iterator<Item = StdResult<Record<T>>> + 'c>,
+    order: Order,
+) -> Box<dyn Iterator<Item = StdResult<Record<T>>> + 'c> {
+    let mut current = current.peekable();
+    let mut legacy = legacy.peekable();
+    Box::new(std::iter::from_fn(move || {
+        let next = match (current.peek(), legacy.peek()) {
+            (None, None) => return None,
+            (Some(Ok((key, _))), Some(Ok((legacy_key, _)))) => match order {
+                Order::Ascending => key.cmp(legacy_key),
+                Order::Descending => legacy_key.cmp(key),
+            },
+            // errors are passed on as they come
+            (Some(_), _) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+        };
+        match next {
+            Ordering::Less => current.next(),
+            Ordering::Greater => legacy.next(),
+            Ordering::Equal => {
+                legacy.next();
+                current.next()
+            }
+        }
+    }))
+}
+
+/// moves a page of the legacy entries of kind, returns the number moved and whether none is left
+pub fn migrate_legacy_entries(
+    storage: &mut dyn Storage,
+    kind: &LegacyKind,
+    limit: Option<u32>,
+) -> (u32, bool) {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let (migrated, done) = match kind {
+        LegacyKind::PoolInfos => POOL_INFOS.migrate_legacy(storage, limit),
+        LegacyKind::Rewards => REWARDS.migrate_legacy(storage, limit),
+        LegacyKind::Stakers => STAKERS.migrate_legacy(storage, limit),
+        LegacyKind::RewardsPerSec => REWARDS_PER_SEC.migrate_legacy(storage, limit),
+    };
+    (migrated as u32, done)
+}
+
+// the maps below replace the legacy buckets over the same namespaces, a bucket key is the length
+// prefixed namespace followed by the key like a map key, and the length prefixed namespaces of a
+// multilevel bucket are the prefixes of a map with a tuple key. The v3 maps fall back to the v2
+// namespaces of older deployments
+pub const POOL_INFOS: VersionedMap<&[u8], PoolInfo> =
+    VersionedMap::new("pool_info_v3", "pool_info_v2");
+
+pub fn store_pool_info(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    pool_info: &PoolInfo,
+) -> StdResult<()> {
+    POOL_INFOS.save(storage, asset_key, pool_info)
+}
+
+pub fn read_pool_info(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<PoolInfo> {
+    POOL_INFOS.load(storage, asset_key)
+}
+
+/// native staking tokens are not addresses, their pools are keyed by the denom
+pub fn store_native_staking_token(storage: &mut dyn Storage, denom: &str) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_NATIVE_STAKING_TOKENS).save(denom.as_bytes(), &true)
+}
+
+pub fn is_native_staking_token(storage: &dyn Storage, staking_token: &[u8]) -> StdResult<bool> {
+    Ok(
+        ReadonlyBucket::<bool>::new(storage, PREFIX_NATIVE_STAKING_TOKENS)
+            .may_load(staking_token)?
+            .is_some(),
+    )
+}
+
+/// key of the pool of a staking token, either a cw20 address or a native denom
+pub fn pool_key(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    staking_token: &Addr,
+) -> StdResult<CanonicalAddr> {
+    if is_native_staking_token(storage, staking_token.as_bytes())? {
+        return Ok(CanonicalAddr::from(staking_token.as_bytes()));
+    }
+    api.addr_canonicalize(staking_token.as_str())
+}
+
+/// reverse of pool_key, native denoms are returned as is
+pub fn pool_staking_token(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    asset_key: &CanonicalAddr,
+) -> StdResult<Addr> {
+    if is_native_staking_token(storage, asset_key)? {
+        return String::from_utf8(asset_key.to_vec())
+            .map(Addr::unchecked)
+            .map_err(StdError::invalid_utf8);
+    }
+    api.addr_humanize(asset_key)
+}
+
+/// the staking token of a pool as an asset, for messages sent to other contracts
+pub fn pool_asset_info(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    asset_key: &CanonicalAddr,
+) -> StdResult<AssetInfo> {
+    let staking_token = pool_staking_token(storage, api, asset_key)?;
+    Ok(if is_native_staking_token(storage, asset_key)? {
+        AssetInfo::NativeToken {
+            denom: staking_token.to_string(),
+        }
+    } else {
+        AssetInfo::Token {
+            contract_addr: staking_token,
+        }
+    })
+}
+
+/// maps a migrated staking token to the key of its pool
+pub fn store_staking_token_pool(
+    storage: &mut dyn Storage,
+    staking_token: &CanonicalAddr,
+    asset_key: &[u8],
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_STAKING_TOKEN_POOL).save(staking_token, &asset_key.to_vec())
+}
+
+pub fn remove_staking_token_pool(storage: &mut dyn Storage, staking_token: &CanonicalAddr) {
+    Bucket::<Vec<u8>>::new(storage, PREFIX_STAKING_TOKEN_POOL).remove(staking_token)
+}
+
+pub fn read_staking_token_pool(
+    storage: &dyn Storage,
+    staking_token: &CanonicalAddr,
+) -> StdResult<Option<Vec<u8>>> {
+    ReadonlyBucket::new(storage, PREFIX_STAKING_TOKEN_POOL).may_load(staking_token)
+}
+
+/// reverse index from a staking token to the keys of every pool staking it
+pub fn add_token_pool(
+    storage: &mut dyn Storage,
+    staking_token: &[u8],
+    asset_key: &[u8],
+) -> StdResult<()> {
+    Bucket::multilevel(storage, &[PREFIX_TOKEN_POOLS, staking_token]).save(asset_key, &true)
+}
+
+pub fn remove_token_pool(storage: &mut dyn Storage, staking_token: &[u8], asset_key: &[u8]) {
+    Bucket::<bool>::multilevel(storage, &[PREFIX_TOKEN_POOLS, staking_token]).remove(asset_key)
+}
+
+pub fn read_token_pools(storage: &dyn Storage, staking_token: &[u8]) -> StdResult<Vec<Vec<u8>>> {
+    ReadonlyBucket::<bool>::multilevel(storage, &[PREFIX_TOKEN_POOLS, staking_token])
+        .range(None, None, Order::Ascending)
+        .map(|item| item.map(|(asset_key, _)| asset_key))
+        .collect()
+}
+
+pub fn store_stake_migration(
+    storage: &mut dyn Storage,
+    from_asset_key: &[u8],
+    to_asset_key: &[u8],
+) -> StdResult<()> {
+    Bucket::multilevel(storage, &[PREFIX_STAKE_MIGRATIONS, from_asset_key])
+        .save(to_asset_key, &true)
+}
+
+pub fn remove_stake_migration(
+    storage: &mut dyn Storage,
+    from_asset_key: &[u8],
+    to_asset_key: &[u8],
+) {
+    Bucket::<bool>::multilevel(storage, &[PREFIX_STAKE_MIGRATIONS, from_asset_key])
+        .remove(to_asset_key)
+}
+
+pub fn is_stake_migration_enabled(
+    storage: &dyn Storage,
+    from_asset_key: &[u8],
+    to_asset_key: &[u8],
+) -> StdResult<bool> {
+    Ok(
+        ReadonlyBucket::<bool>::multilevel(storage, &[PREFIX_STAKE_MIGRATIONS, from_asset_key])
+            .may_load(to_asset_key)?
+            .is_some(),
+    )
+}
+
+pub fn store_boost_curve(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    curve: &BoostCurve,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_BOOST_CURVE).save(asset_key, curve)
+}
+
+pub fn remove_boost_curve(storage: &mut dyn Storage, asset_key: &[u8]) {
+    Bucket::<BoostCurve>::new(storage, PREFIX_BOOST_CURVE).remove(asset_key)
+}
+
+/// stakers of pools without a curve cannot boost
+pub fn read_boost_curve(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Option<BoostCurve>> {
+    ReadonlyBucket::new(storage, PREFIX_BOOST_CURVE).may_load(asset_key)
+}
+
+#[cw_serde]
+pub struct Boost {
+    pub multiplier: Decimal,
+    // the bond cannot be unbonded before
+    pub expires_at: u64,
+}
+
+pub fn store_boost(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    staker: &CanonicalAddr,
+    boost: &Boost,
+) -> StdResult<()> {
+    Bucket::multilevel(storage, &[PREFIX_BOOST, asset_key]).save(staker, boost)
+}
+
+pub fn remove_boost(storage: &mut dyn Storage, asset_key: &[u8], staker: &CanonicalAddr) {
+    Bucket::<Boost>::multilevel(storage, &[PREFIX_BOOST, asset_key]).remove(staker)
+}
+
+pub fn read_boost(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    staker: &CanonicalAddr,
+) -> StdResult<Option<Boost>> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_BOOST, asset_key]).may_load(staker)
+}
+
+pub fn store_pool_status(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    status: &PoolStatus,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_POOL_STATUS).save(asset_key, status)
+}
+
+/// pools without a stored status are not paused
+pub fn read_pool_status(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<PoolStatus> {
+    Ok(
+        ReadonlyBucket::<PoolStatus>::new(storage, PREFIX_POOL_STATUS)
+            .may_load(asset_key)?
+            .unwrap_or_default(),
+    )
+}
+
+pub fn store_open_deposit(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    open_deposit: bool,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_OPEN_DEPOSIT).save(asset_key, &open_deposit)
+}
+
+/// only rewarders deposit into pools without the flag
+pub fn is_open_deposit(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<bool> {
+    Ok(ReadonlyBucket::<bool>::new(storage, PREFIX_OPEN_DEPOSIT)
+        .may_load(asset_key)?
+        .unwrap_or_default())
+}
+
+pub fn store_reward_dust(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    dust: Decimal,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_REWARD_DUST).save(asset_key, &dust)
+}
+
+/// reward units the index could not represent yet, carried into the next deposit
+pub fn read_reward_dust(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Decimal> {
+    Ok(ReadonlyBucket::new(storage, PREFIX_REWARD_DUST)
+        .may_load(asset_key)?
+        .unwrap_or_default())
+}
+
+pub fn store_accrual_mode(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    mode: &AccrualMode,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_ACCRUAL_MODE).save(asset_key, mode)
+}
+
+/// pools are fed by DepositReward unless set otherwise
+pub fn read_accrual_mode(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<AccrualMode> {
+    Ok(ReadonlyBucket::new(storage, PREFIX_ACCRUAL_MODE)
+        .may_load(asset_key)?
+        .unwrap_or_default())
+}
+
+pub fn store_last_distributed(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    time: u64,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_LAST_DISTRIBUTED).save(asset_key, &time)
+}
+
+pub fn remove_last_distributed(storage: &mut dyn Storage, asset_key: &[u8]) {
+    Bucket::<u64>::new(storage, PREFIX_LAST_DISTRIBUTED).remove(asset_key)
+}
+
+/// time the pull mode accrued the rewards of the pool until
+pub fn read_last_distributed(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Option<u64>> {
+    ReadonlyBucket::new(storage, PREFIX_LAST_DISTRIBUTED).may_load(asset_key)
+}
+
+pub fn store_pool_admin(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    pool_admin: &CanonicalAddr,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_POOL_ADMIN).save(asset_key, pool_admin)
+}
+
+pub fn remove_pool_admin(storage: &mut dyn Storage, asset_key: &[u8]) {
+    Bucket::<CanonicalAddr>::new(storage, PREFIX_POOL_ADMIN).remove(asset_key)
+}
+
+pub fn read_pool_admin(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+) -> StdResult<Option<CanonicalAddr>> {
+    ReadonlyBucket::new(storage, PREFIX_POOL_ADMIN).may_load(asset_key)
+}
+
+pub fn store_pool_count(storage: &mut dyn Storage, count: u64) -> StdResult<()> {
+    singleton(storage, KEY_POOL_COUNT).save(&count)
+}
+
+pub fn read_pool_count(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(singleton_read(storage, KEY_POOL_COUNT)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+pub fn read_all_pool_infos(storage: &dyn Storage) -> StdResult<Vec<(Vec<u8>, PoolInfo)>> {
+    POOL_INFOS
+        .range(storage, None, None, Order::Ascending)
+        .collect()
+}
+
+/// the range bounds of the page after start_after, it is excluded in both orders
+pub fn page_bounds(
+    start_after: Option<&[u8]>,
+    order: Order,
+) -> (Option<Bound<'_, &[u8]>>, Option<Bound<'_, &[u8]>>) {
+    let bound = start_after.map(Bound::exclusive);
+    match order {
+        Order::Ascending => (bound, None),
+        Order::Descending => (None, bound),
+    }
+}
+
+/// a page of the pools in key order, start_after is the key of the last pool of the previous page
+pub fn read_pool_infos(
+    storage: &dyn Storage,
+    start_after: Option<Vec<u8>>,
+    limit: Option<u32>,
+    order: Option<i32>,
+) -> StdResult<Vec<(Vec<u8>, PoolInfo)>> {
+    let order_by = Order::try_from(order.unwrap_or(1))?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let (start, end) = page_bounds(start_after.as_deref(), order_by);
+
+    POOL_INFOS
+        .range(storage, start, end, order_by)
+        .take(limit)
+        .collect()
+}
+
+#[cw_serde]
+pub struct RewardInfo {
+    pub native_token: bool,
+    pub index: Decimal,
+    pub bond_amount: Uint128,
+    pub pending_reward: Uint128,
+    // this is updated by the owner of this contract, when changing the reward_per_sec
+    pub pending_withdraw: Vec<AssetRaw>,
+    // bond_amount times the boost multiplier, None without a running boost
+    #[serde(default)]
+    pub weighted_amount: Option<Uint128>,
+}
+
+impl RewardInfo {
+    /// share of the reward index, the withdrawable principal stays bond_amount
+    pub fn weight(&self) -> Uint128 {
+        self.weighted_amount.unwrap_or(self.bond_amount)
+    }
+}
+
+/// reward infos by (staker, asset_key), the rewards of a staker are its prefix
+pub const REWARDS: VersionedMap<(&[u8], &[u8]), RewardInfo> =
+    VersionedMap::new("reward_v3", "reward_v2");
+
+/// stakers by (asset_key, staker), the stakers of a pool are its prefix
+pub const STAKERS: VersionedMap<(&[u8], &[u8]), bool> = VersionedMap::new("staker_v3", "staker_v2");
+
+/// adds the staker to the pool, the staker count only grows for new stakers
+pub fn add_staker(storage: &mut dyn Storage, asset_key: &[u8], staker: &[u8]) -> StdResult<()> {
+    if STAKERS.has(storage, (asset_key, staker)) {
+        return Ok(());
+    }
+    STAKERS.save(storage, (asset_key, staker), &true)?;
+    let staker_count = read_staker_count(storage, asset_key)? + 1;
+    Bucket::new(storage, PREFIX_STAKER_COUNT).save(asset_key, &staker_count)
+}
+
+/// removes the staker from the pool once nothing is left for it
+pub fn remove_staker(storage: &mut dyn Storage, asset_key: &[u8], staker: &[u8]) -> StdResult<()> {
+    if !STAKERS.has(storage, (asset_key, staker)) {
+        return Ok(());
+    }
+    STAKERS.remove(storage, (asset_key, staker));
+    let staker_count = read_staker_count(storage, asset_key)?.saturating_sub(1);
+    Bucket::new(storage, PREFIX_STAKER_COUNT).save(asset_key, &staker_count)
+}
+
+pub fn read_staker_count(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<u64> {
+    Ok(ReadonlyBucket::<u64>::new(storage, PREFIX_STAKER_COUNT)
+        .may_load(asset_key)?
+        .unwrap_or_default())
+}
+
+/// recounts the stakers of a pool, for pools created before the counter existed, the stakers not
+/// moved from the legacy namespace yet are counted too
+pub fn backfill_staker_count(storage: &mut dyn Storage, asset_key: &[u8]) -> StdResult<()> {
+    let staker_count = STAKERS
+        .prefix(asset_key)
+        .keys(storage, None, None, Order::Ascending)
+        .count() as u64;
+    Bucket::new(storage, PREFIX_STAKER_COUNT).save(asset_key, &staker_count)
+}
+
+pub const REWARDS_PER_SEC: VersionedMap<&[u8], Vec<AssetRaw>> =
+    VersionedMap::new("rewards_per_sec_v3", "rewards_per_sec_v2");
+
+pub fn store_rewards_per_sec(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    assets: Vec<AssetRaw>,
+) -> StdResult<()> {
+    REWARDS_PER_SEC.save(storage, asset_key, &assets)
+}
+
+pub fn read_rewards_per_sec(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Vec<AssetRaw>> {
+    REWARDS_PER_SEC.load(storage, asset_key)
+}
+
+/// a page of the pools with configured rewards per second, in key order
+pub fn read_all_rewards_per_sec(
+    storage: &dyn Storage,
+    start_after: Option<Vec<u8>>,
+    limit: Option<u32>,
+    order: Option<i32>,
+) -> StdResult<Vec<(Vec<u8>, Vec<AssetRaw>)>> {
+    let order_by = Order::try_from(order.unwrap_or(1))?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let (start, end) = page_bounds(start_after.as_deref(), order_by);
+
+    REWARDS_PER_SEC
+        .range(storage, start, end, order_by)
+        .take(limit)
+        .collect()
+}
+
+pub fn store_rewards_per_sec_limits(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    limits: &RewardsPerSecLimits,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_REWARDS_PER_SEC_LIMITS).save(asset_key, limits)
+}
+
+/// pools without stored limits accept any update
+pub fn read_rewards_per_sec_limits(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+) -> StdResult<RewardsPerSecLimits> {
+    Ok(
+        ReadonlyBucket::<RewardsPerSecLimits>::new(storage, PREFIX_REWARDS_PER_SEC_LIMITS)
+            .may_load(asset_key)?
+            .unwrap_or_default(),
+    )
+}
+
+pub fn store_rewards_per_sec_updated(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    updated_at: Timestamp,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_REWARDS_PER_SEC_UPDATED).save(asset_key, &updated_at)
+}
+
+pub fn read_rewards_per_sec_updated(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+) -> StdResult<Option<Timestamp>> {
+    ReadonlyBucket::new(storage, PREFIX_REWARDS_PER_SEC_UPDATED).may_load(asset_key)
+}
+
+#[cw_serde]
+pub struct RewardSchedule {
+    pub id: u64,
+    pub asset_info: AssetInfoRaw,
+    pub amount_per_sec: Uint128,
+    pub start_time: u64,
+    // None for an open-ended schedule
+    pub end_time: Option<u64>,
+}
+
+impl RewardSchedule {
+    pub fn is_ended(&self, now: u64) -> bool {
+        self.end_time.map_or(false, |end_time| end_time <= now)
+    }
+
+    /// seconds of the interval [from, to] covered by the schedule
+    pub fn overlap(&self, from: u64, to: u64) -> u64 {
+        let start = from.max(self.start_time);
+        let end = self.end_time.map_or(to, |end_time| end_time.min(to));
+        end.saturating_sub(start)
+    }
+}
+
+pub fn store_reward_schedules(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    schedules: Vec<RewardSchedule>,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_REWARD_SCHEDULES).save(asset_key, &schedules)
+}
+
+/// schedules are dropped once a deposit covered them entirely
+pub fn read_reward_schedules(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+) -> StdResult<Vec<RewardSchedule>> {
+    Ok(
+        ReadonlyBucket::<Vec<RewardSchedule>>::new(storage, PREFIX_REWARD_SCHEDULES)
+            .may_load(asset_key)?
+            .unwrap_or_default(),
+    )
+}
+
+pub fn store_reward_schedules_synced(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    synced_at: u64,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_REWARD_SCHEDULES_SYNCED).save(asset_key, &synced_at)
+}
 
-use cosmwasm_std::{
-    Addr, CanonicalAddr, Decimal, Order, StdError, StdResult, Storage, Timestamp, Uint128,
-};
-use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+/// seconds of the last deposit accounted against the schedules of the pool
+pub fn read_reward_schedules_synced(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+) -> StdResult<Option<u64>> {
+    ReadonlyBucket::new(storage, PREFIX_REWARD_SCHEDULES_SYNCED).may_load(asset_key)
+}
 
-pub static KEY_CONFIG: &[u8] = b"config_v2";
-pub static PREFIX_POOL_INFO: &[u8] = b"pool_info_v3";
-pub static PREFIX_REWARD: &[u8] = b"reward_v3";
-pub static PREFIX_STAKER: &[u8] = b"staker_v3";
-pub static PREFIX_REWARDS_PER_SEC: &[u8] = b"rewards_per_sec_v3";
-// Unbonded
-pub static UNBONDING_PERIOD: &[u8] = b"unbonding_period";
-pub static LOCK_INFO: &[u8] = b"locking_users";
+/// the rewards per sec of the pool up to the reward index it changed at, keyed by the index atomics
+pub const RATE_CHECKPOINTS: Map<(&[u8], u128), Vec<AssetRaw>> = Map::new("rate_checkpoints");
 
-pub const DEFAULT_LIMIT: u32 = 10;
-pub const MAX_LIMIT: u32 = 30;
+// a second change at the same index ends an empty segment, the first rate is kept
+pub fn store_rate_checkpoint(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    reward_index: Decimal,
+    rewards_per_sec: Vec<AssetRaw>,
+) -> StdResult<()> {
+    let key = (asset_key, reward_index.atomics().u128());
+    if RATE_CHECKPOINTS.has(storage, key) {
+        return Ok(());
+    }
+    RATE_CHECKPOINTS.save(storage, key, &rewards_per_sec)
+}
 
-#[cw_serde]
-pub struct Config {
-    pub owner: CanonicalAddr,
-    pub rewarder: CanonicalAddr,
+/// the checkpoints from reward_index on, in index order
+pub fn read_rate_checkpoints(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    reward_index: Decimal,
+) -> StdResult<Vec<(Decimal, Vec<AssetRaw>)>> {
+    RATE_CHECKPOINTS
+        .prefix(asset_key)
+        .range(
+            storage,
+            Some(Bound::inclusive(reward_index.atomics().u128())),
+            None,
+            Order::Ascending,
+        )
+        .map(|item| {
+            let (atomics, rewards_per_sec) = item?;
+            Ok((Decimal::new(atomics.into()), rewards_per_sec))
+        })
+        .collect()
 }
 
-pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
-    singleton(storage, KEY_CONFIG).save(config)
+pub const UNBONDING_PERIODS: Map<&[u8], u64> = Map::new("unbonding_period");
+
+pub fn store_unbonding_period(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    period: u64,
+) -> StdResult<()> {
+    UNBONDING_PERIODS.save(storage, asset_key, &period)
 }
 
-pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
-    singleton_read(storage, KEY_CONFIG).load()
+pub fn read_unbonding_period(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<u64> {
+    UNBONDING_PERIODS.load(storage, asset_key)
 }
 
-#[cw_serde]
-pub struct PoolInfo {
-    pub staking_token: CanonicalAddr,
-    pub pending_reward: Uint128, // not distributed amount due to zero bonding
-    pub total_bond_amount: Uint128,
-    pub reward_index: Decimal,
+/// a page of the pools with an unbonding period, in key order
+pub fn read_unbonding_periods(
+    storage: &dyn Storage,
+    start_after: Option<Vec<u8>>,
+    limit: Option<u32>,
+    order: Option<i32>,
+) -> StdResult<Vec<(Vec<u8>, u64)>> {
+    let order_by = Order::try_from(order.unwrap_or(1))?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let (start, end) = page_bounds(start_after.as_deref(), order_by);
+
+    UNBONDING_PERIODS
+        .range(storage, start, end, order_by)
+        .take(limit)
+        .collect()
 }
 
-pub fn store_pool_info(
+pub fn remove_unbonding_period(storage: &mut dyn Storage, asset_key: &[u8]) {
+    UNBONDING_PERIODS.remove(storage, asset_key)
+}
+
+pub fn store_unbond_fee(
     storage: &mut dyn Storage,
     asset_key: &[u8],
-    pool_info: &PoolInfo,
+    unbond_fee: Decimal,
 ) -> StdResult<()> {
-    Bucket::new(storage, PREFIX_POOL_INFO).save(asset_key, pool_info)
+    Bucket::new(storage, UNBOND_FEE).save(asset_key, &unbond_fee)
 }
 
-pub fn read_pool_info(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<PoolInfo> {
-    ReadonlyBucket::new(storage, PREFIX_POOL_INFO).load(asset_key)
+/// pools without a stored fee charge nothing on unbond
+pub fn read_unbond_fee(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Decimal> {
+    Ok(ReadonlyBucket::<Decimal>::new(storage, UNBOND_FEE)
+        .may_load(asset_key)?
+        .unwrap_or_default())
 }
 
-pub fn read_all_pool_infos(storage: &dyn Storage) -> StdResult<Vec<(Vec<u8>, PoolInfo)>> {
-    ReadonlyBucket::<PoolInfo>::new(storage, PREFIX_POOL_INFO)
-        .range(None, None, cosmwasm_std::Order::Ascending)
-        .collect()
+pub fn store_instant_unbond_penalty(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    penalty: Decimal,
+) -> StdResult<()> {
+    Bucket::new(storage, INSTANT_UNBOND_PENALTY).save(asset_key, &penalty)
 }
 
-#[cw_serde]
-pub struct RewardInfo {
-    pub native_token: bool,
-    pub index: Decimal,
-    pub bond_amount: Uint128,
-    pub pending_reward: Uint128,
-    // this is updated by the owner of this contract, when changing the reward_per_sec
-    pub pending_withdraw: Vec<AssetRaw>,
+pub fn remove_instant_unbond_penalty(storage: &mut dyn Storage, asset_key: &[u8]) {
+    Bucket::<Decimal>::new(storage, INSTANT_UNBOND_PENALTY).remove(asset_key)
+}
+
+/// instant unbond is disabled for pools without a penalty
+pub fn read_instant_unbond_penalty(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+) -> StdResult<Option<Decimal>> {
+    ReadonlyBucket::new(storage, INSTANT_UNBOND_PENALTY).may_load(asset_key)
 }
 
-/// returns a bucket with all rewards owned by this staker (query it by staker)
-pub fn rewards_store<'a>(storage: &'a mut dyn Storage, staker: &[u8]) -> Bucket<'a, RewardInfo> {
-    Bucket::multilevel(storage, &[PREFIX_REWARD, staker])
+pub fn store_min_bond_amount(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    min_bond_amount: Uint128,
+) -> StdResult<()> {
+    Bucket::new(storage, MIN_BOND_AMOUNT).save(asset_key, &min_bond_amount)
 }
 
-/// returns a bucket with all rewards owned by this staker (query it by staker)
-/// (read-only version for queries)
-pub fn rewards_read<'a>(storage: &'a dyn Storage, staker: &[u8]) -> ReadonlyBucket<'a, RewardInfo> {
-    ReadonlyBucket::multilevel(storage, &[PREFIX_REWARD, staker])
+/// pools without a stored minimum accept any bond amount
+pub fn read_min_bond_amount(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Uint128> {
+    Ok(ReadonlyBucket::<Uint128>::new(storage, MIN_BOND_AMOUNT)
+        .may_load(asset_key)?
+        .unwrap_or_default())
 }
 
-/// returns a bucket with all stakers belong by this staker (query it by staker)
-pub fn stakers_store<'a>(storage: &'a mut dyn Storage, asset_key: &[u8]) -> Bucket<'a, bool> {
-    Bucket::multilevel(storage, &[PREFIX_STAKER, asset_key])
+pub fn store_reward_vesting_period(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    period: u64,
+) -> StdResult<()> {
+    Bucket::new(storage, REWARD_VESTING_PERIOD).save(asset_key, &period)
 }
 
-/// returns a bucket with all rewards owned by this staker (query it by staker)
-/// (read-only version for queries)
-pub fn stakers_read<'a>(storage: &'a dyn Storage, asset_key: &[u8]) -> ReadonlyBucket<'a, bool> {
-    ReadonlyBucket::multilevel(storage, &[PREFIX_STAKER, asset_key])
+pub fn remove_reward_vesting_period(storage: &mut dyn Storage, asset_key: &[u8]) {
+    Bucket::<u64>::new(storage, REWARD_VESTING_PERIOD).remove(asset_key)
 }
 
-pub fn store_rewards_per_sec(
+// what the pool owes its stakers, kept up to date so it is known without going through them
+// units are deposited and not split into the reward assets of a staker yet, assets are split and not paid out
+#[cw_serde]
+#[derive(Default)]
+pub struct OutstandingReward {
+    pub units: Uint128,
+    pub assets: Vec<AssetRaw>,
+}
+
+pub fn store_outstanding_reward(
     storage: &mut dyn Storage,
     asset_key: &[u8],
-    assets: Vec<AssetRaw>,
+    outstanding_reward: &OutstandingReward,
 ) -> StdResult<()> {
-    let mut weight_bucket: Bucket<Vec<AssetRaw>> = Bucket::new(storage, PREFIX_REWARDS_PER_SEC);
-    weight_bucket.save(asset_key, &assets)
+    Bucket::new(storage, PREFIX_OUTSTANDING_REWARD).save(asset_key, outstanding_reward)
 }
 
-pub fn read_rewards_per_sec(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Vec<AssetRaw>> {
-    let weight_bucket: ReadonlyBucket<Vec<AssetRaw>> =
-        ReadonlyBucket::new(storage, PREFIX_REWARDS_PER_SEC);
-    weight_bucket.load(asset_key)
+/// pools are only tracked from their first deposit after the accumulators were added
+pub fn read_outstanding_reward(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+) -> StdResult<OutstandingReward> {
+    Ok(ReadonlyBucket::new(storage, PREFIX_OUTSTANDING_REWARD)
+        .may_load(asset_key)?
+        .unwrap_or_default())
 }
 
-pub fn store_unbonding_period(
+pub fn store_min_claim_amount(
     storage: &mut dyn Storage,
     asset_key: &[u8],
-    period: u64,
+    min_claim_amount: Uint128,
 ) -> StdResult<()> {
-    Bucket::new(storage, UNBONDING_PERIOD).save(asset_key, &period)
+    Bucket::new(storage, MIN_CLAIM_AMOUNT).save(asset_key, &min_claim_amount)
 }
 
-pub fn read_unbonding_period(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<u64> {
-    ReadonlyBucket::new(storage, UNBONDING_PERIOD).load(asset_key)
+/// pools without a stored minimum pay out any claim
+pub fn read_min_claim_amount(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Uint128> {
+    Ok(ReadonlyBucket::<Uint128>::new(storage, MIN_CLAIM_AMOUNT)
+        .may_load(asset_key)?
+        .unwrap_or_default())
+}
+
+/// pools without a vesting period pay the withdrawn rewards right away
+pub fn read_reward_vesting_period(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+) -> StdResult<Option<u64>> {
+    ReadonlyBucket::new(storage, REWARD_VESTING_PERIOD).may_load(asset_key)
+}
+
+/// tranches unlocking at the same second are merged
+pub fn insert_reward_vesting(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    user: &[u8],
+    unlock_time: u64,
+    rewards: Vec<AssetRaw>,
+) -> StdResult<()> {
+    let key = unlock_time.to_be_bytes();
+    let mut bucket =
+        Bucket::<Vec<AssetRaw>>::multilevel(storage, &[REWARD_VESTING, asset_key, user]);
+    let mut tranche = bucket.may_load(&key)?.unwrap_or_default();
+    for rw in rewards {
+        match tranche.iter_mut().find(|ra| ra.info.eq(&rw.info)) {
+            Some(ra) => ra.amount = ra.amount.checked_add(rw.amount)?,
+            None => tranche.push(rw),
+        }
+    }
+    bucket.save(&key, &tranche)
+}
+
+/// vesting tranches of a staker in a pool by unlock time, soonest first
+pub fn read_reward_vestings(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    user: &[u8],
+) -> StdResult<Vec<(u64, Vec<AssetRaw>)>> {
+    ReadonlyBucket::<Vec<AssetRaw>>::multilevel(storage, &[REWARD_VESTING, asset_key, user])
+        .range(None, None, Order::Ascending)
+        .map(|item| {
+            let (time, rewards) = item?;
+            let time = time
+                .try_into()
+                .map_err(|_| StdError::generic_err("Casting u64 to timestamp fail"))?;
+            Ok((u64::from_be_bytes(time), rewards))
+        })
+        .collect()
+}
+
+/// removes the tranches unlocked at timestamp and returns their rewards
+pub fn remove_matured_reward_vestings(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    user: &[u8],
+    timestamp: Timestamp,
+) -> StdResult<Vec<AssetRaw>> {
+    let matured = read_reward_vestings(storage, asset_key, user)?
+        .into_iter()
+        .take_while(|(unlock_time, _)| *unlock_time <= timestamp.seconds())
+        .collect::<Vec<_>>();
+
+    let mut bucket =
+        Bucket::<Vec<AssetRaw>>::multilevel(storage, &[REWARD_VESTING, asset_key, user]);
+    let mut rewards: Vec<AssetRaw> = vec![];
+    for (unlock_time, tranche) in matured {
+        bucket.remove(&unlock_time.to_be_bytes());
+        for rw in tranche {
+            match rewards.iter_mut().find(|ra| ra.info.eq(&rw.info)) {
+                Some(ra) => ra.amount += rw.amount,
+                None => rewards.push(rw),
+            }
+        }
+    }
+    Ok(rewards)
 }
 
+/// locked amounts by (asset_key, user, unlock time in seconds)
+pub const LOCK_INFOS: Map<(&[u8], &[u8], u64), Uint128> = Map::new("locking_users");
+
+/// locks unlocking at the same second are merged, new entries are capped at max_entries
 pub fn insert_lock_info(
     storage: &mut dyn Storage,
     asset_key: &[u8],
     user: &[u8],
     lock_info: LockInfo,
+    max_entries: u64,
 ) -> StdResult<()> {
-    Bucket::multilevel(storage, &[LOCK_INFO, asset_key, user]).save(
-        &lock_info.unlock_time.seconds().to_be_bytes(),
-        &lock_info.amount,
-    )
+    let unlock_time = lock_info.unlock_time.seconds();
+    let key = unlock_time.to_be_bytes();
+    let existing = LOCK_INFOS.may_load(storage, (asset_key, user, unlock_time))?;
+    let amount = match existing {
+        Some(amount) => amount.checked_add(lock_info.amount)?,
+        None => {
+            if read_user_lock_count(storage, asset_key, user)? >= max_entries {
+                return Err(StdError::generic_err(
+                    "too many unbonding locks, withdraw the matured ones first",
+                ));
+            }
+            lock_info.amount
+        }
+    };
+    Bucket::multilevel(storage, &[LOCK_INDEX, asset_key])
+        .save(&lock_index_key(user, &key), &true)?;
+    let total_unbonding =
+        read_total_unbonding(storage, asset_key)?.checked_add(lock_info.amount)?;
+    Bucket::new(storage, TOTAL_UNBONDING).save(asset_key, &total_unbonding)?;
+    LOCK_INFOS.save(storage, (asset_key, user, unlock_time), &amount)
+}
+
+/// the amount of a pool waiting in locks, it is not part of the total bond amount anymore
+pub fn read_total_unbonding(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Uint128> {
+    Ok(ReadonlyBucket::<Uint128>::new(storage, TOTAL_UNBONDING)
+        .may_load(asset_key)?
+        .unwrap_or_default())
+}
+
+fn sub_total_unbonding(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    amount: Uint128,
+) -> StdResult<()> {
+    // saturating in case the locks were written before the counter was backfilled
+    let total_unbonding = read_total_unbonding(storage, asset_key)?.saturating_sub(amount);
+    Bucket::new(storage, TOTAL_UNBONDING).save(asset_key, &total_unbonding)
+}
+
+/// recomputes the total unbonding of a pool from its locks
+pub fn backfill_total_unbonding(storage: &mut dyn Storage, asset_key: &[u8]) -> StdResult<()> {
+    let total_unbonding = read_pool_lock_amount(storage, asset_key)?;
+    Bucket::new(storage, TOTAL_UNBONDING).save(asset_key, &total_unbonding)
+}
+
+fn lock_index_key(user: &[u8], unlock_time: &[u8]) -> Vec<u8> {
+    [user, unlock_time].concat()
+}
+
+fn remove_lock_index(storage: &mut dyn Storage, asset_key: &[u8], user: &[u8], unlock_time: &[u8]) {
+    Bucket::<bool>::multilevel(storage, &[LOCK_INDEX, asset_key])
+        .remove(&lock_index_key(user, unlock_time))
+}
+
+/// indexes the locks of a pool written before the index existed
+pub fn backfill_lock_index(storage: &mut dyn Storage, asset_key: &[u8]) -> StdResult<()> {
+    let keys = LOCK_INFOS
+        .sub_prefix(asset_key)
+        .keys(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (user, unlock_time) = item?;
+            Ok(lock_index_key(&user, &unlock_time.to_be_bytes()))
+        })
+        .collect::<StdResult<Vec<Vec<u8>>>>()?;
+
+    let mut bucket = Bucket::multilevel(storage, &[LOCK_INDEX, asset_key]);
+    for key in keys {
+        bucket.save(&key, &true)?;
+    }
+    Ok(())
+}
+
+/// a page of the locks of a pool by user then unlock time, start_after is the last lock of the previous page
+pub fn read_pool_locks(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    start_after: Option<(Vec<u8>, u64)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(Vec<u8>, LockInfo)>> {
+    let start_after =
+        start_after.map(|(user, unlock_time)| lock_index_key(&user, &unlock_time.to_be_bytes()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    ReadonlyBucket::<bool>::multilevel(storage, &[LOCK_INDEX, asset_key])
+        .range(
+            calc_range_start(start_after).as_deref(),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (key, _) = item?;
+            let (user, time) = key.split_at(key.len() - 8);
+            let unlock_time = u64::from_be_bytes(
+                time.try_into()
+                    .map_err(|_| StdError::generic_err("Casting u64 to timestamp fail"))?,
+            );
+            let amount = LOCK_INFOS.load(storage, (asset_key, user, unlock_time))?;
+            Ok((
+                user.to_vec(),
+                LockInfo {
+                    unlock_time: Timestamp::from_seconds(unlock_time),
+                    amount,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// number of outstanding lock entries of a staker in a pool
+pub fn read_user_lock_count(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    user: &[u8],
+) -> StdResult<u64> {
+    Ok(LOCK_INFOS
+        .prefix((asset_key, user))
+        .keys_raw(storage, None, None, Order::Ascending)
+        .count() as u64)
 }
 
+/// total amount locked in a pool across all stakers
+pub fn read_pool_lock_amount(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Uint128> {
+    LOCK_INFOS
+        .sub_prefix(asset_key)
+        .range_raw(storage, None, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .sum()
+}
+
+/// total amount locked by a staker in a pool
+pub fn read_user_lock_amount(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    user: &[u8],
+) -> StdResult<Uint128> {
+    LOCK_INFOS
+        .prefix((asset_key, user))
+        .range_raw(storage, None, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .sum()
+}
+
+/// amount and number of the locks of a staker in a pool that unlock at or before timestamp,
+/// what remove_and_accumulate_lock_info would release
+pub fn read_user_matured_locks(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    user: &[u8],
+    timestamp: Timestamp,
+) -> StdResult<(Uint128, u64)> {
+    LOCK_INFOS
+        .prefix((asset_key, user))
+        .range_raw(
+            storage,
+            None,
+            Some(Bound::inclusive(timestamp.seconds())),
+            Order::Ascending,
+        )
+        .try_fold(
+            (Uint128::zero(), 0u64),
+            |(amount, count), item| -> StdResult<_> {
+                Ok((amount.checked_add(item?.1)?, count + 1))
+            },
+        )
+}
+
+/// records the unbonding period a lock was created with
+pub fn store_lock_period(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    user: &[u8],
+    unlock_time: Timestamp,
+    period: u64,
+) -> StdResult<()> {
+    Bucket::multilevel(storage, &[LOCK_PERIOD, asset_key, user])
+        .save(&unlock_time.seconds().to_be_bytes(), &period)
+}
+
+/// locks created before the periods were recorded return None
+pub fn read_lock_period(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    user: &[u8],
+    unlock_time: Timestamp,
+) -> StdResult<Option<u64>> {
+    ReadonlyBucket::multilevel(storage, &[LOCK_PERIOD, asset_key, user])
+        .may_load(&unlock_time.seconds().to_be_bytes())
+}
+
+/// start_time is inclusive and end_time exclusive, both bound the unlock time on top of the cursor
+#[allow(clippy::too_many_arguments)]
 pub fn read_user_lock_info(
     storage: &dyn Storage,
     asset_key: &[u8],
@@ -138,28 +1993,48 @@ pub fn read_user_lock_info(
     start_after: Option<u64>,
     limit: Option<u32>,
     order: Option<i32>,
+    start_time: Option<u64>,
+    end_time: Option<u64>,
 ) -> StdResult<Vec<LockInfo>> {
     let order_by = Order::try_from(order.unwrap_or(1))?;
-
-    let start_after = start_after.map(|a| a.to_be_bytes().to_vec());
-
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
+    // start_after is exclusive in both orders: the range start is inclusive so ascending
+    // starts one second later, the range end is already exclusive so descending ends on it.
+    // The tighter of the cursor and the time bound wins, None is unbounded
     let (start, end) = match order_by {
-        Order::Ascending => (calc_range_start(start_after), None),
-        Order::Descending => (None, start_after),
+        Order::Ascending => match start_after {
+            // nothing unlocks after the last second
+            Some(u64::MAX) => return Ok(vec![]),
+            start_after => (start_after.map(|a| a + 1).max(start_time), end_time),
+        },
+        Order::Descending => (
+            start_time,
+            match (start_after, end_time) {
+                (Some(start_after), Some(end_time)) => Some(start_after.min(end_time)),
+                (start_after, end_time) => start_after.or(end_time),
+            },
+        ),
     };
-
-    ReadonlyBucket::multilevel(storage, &[LOCK_INFO, asset_key, user])
-        .range(start.as_deref(), end.as_deref(), order_by)
+    // nothing unlocks in an empty range
+    if let (Some(start), Some(end)) = (start, end) {
+        if start >= end {
+            return Ok(vec![]);
+        }
+    }
+    LOCK_INFOS
+        .prefix((asset_key, user))
+        .range(
+            storage,
+            start.map(Bound::inclusive),
+            end.map(Bound::exclusive),
+            order_by,
+        )
         .take(limit)
         .map(|item| {
             let (time, amount) = item?;
             Ok(LockInfo {
-                unlock_time: Timestamp::from_seconds(u64::from_be_bytes(
-                    time.try_into()
-                        .map_err(|_| StdError::generic_err("Casting u64 to timestamp fail"))?,
-                )),
+                unlock_time: Timestamp::from_seconds(time),
                 amount,
             })
         })
@@ -172,16 +2047,34 @@ pub fn remove_and_accumulate_lock_info(
     user: &[u8],
     timestamp: Timestamp,
 ) -> StdResult<Uint128> {
-    let mut bucket = Bucket::<Uint128>::multilevel(storage, &[LOCK_INFO, asset_key, user]);
+    let (amount, _, _) =
+        remove_and_accumulate_lock_info_limited(storage, asset_key, user, timestamp, usize::MAX)?;
+    Ok(amount)
+}
+
+/// releases at most limit matured locks, also returns how many were released and whether matured ones remain
+pub fn remove_and_accumulate_lock_info_limited(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    user: &[u8],
+    timestamp: Timestamp,
+    limit: usize,
+) -> StdResult<(Uint128, usize, bool)> {
     let mut remove_timestamps = vec![];
     let mut accumulate_amount = Uint128::zero();
+    let mut has_more = false;
 
     // use temporay cursor
     {
-        let mut cursor = bucket.range(None, None, Order::Ascending);
-        let time_in_seconds = timestamp.seconds().to_be_bytes().to_vec();
+        let mut cursor = LOCK_INFOS.prefix((asset_key, user)).range(
+            storage,
+            None,
+            Some(Bound::inclusive(timestamp.seconds())),
+            Order::Ascending,
+        );
         while let Some(Ok((time, amount))) = cursor.next() {
-            if time.cmp(&time_in_seconds) == std::cmp::Ordering::Greater {
+            if remove_timestamps.len() == limit {
+                has_more = true;
                 break;
             }
             remove_timestamps.push(time);
@@ -190,11 +2083,110 @@ pub fn remove_and_accumulate_lock_info(
     }
 
     // remove timestamp
-    for time in remove_timestamps {
-        bucket.remove(&time);
+    for time in &remove_timestamps {
+        LOCK_INFOS.remove(storage, (asset_key, user, *time));
+    }
+    for time in &remove_timestamps {
+        remove_lock_index(storage, asset_key, user, &time.to_be_bytes());
+    }
+    sub_total_unbonding(storage, asset_key, accumulate_amount)?;
+    let mut period_bucket = Bucket::<u64>::multilevel(storage, &[LOCK_PERIOD, asset_key, user]);
+    for time in &remove_timestamps {
+        period_bucket.remove(&time.to_be_bytes());
+    }
+
+    Ok((accumulate_amount, remove_timestamps.len(), has_more))
+}
+
+/// takes amount out of the locks, soonest unlock first, and returns the consumed unlock times
+pub fn remove_lock_amount(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    user: &[u8],
+    amount: Uint128,
+) -> StdResult<Vec<Timestamp>> {
+    let locks = LOCK_INFOS
+        .prefix((asset_key, user))
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(u64, Uint128)>>>()?;
+
+    let mut remaining = amount;
+    let mut unlock_times = vec![];
+    for (time, lock_amount) in locks {
+        if remaining.is_zero() {
+            break;
+        }
+        let taken = lock_amount.min(remaining);
+        remaining -= taken;
+        if taken == lock_amount {
+            LOCK_INFOS.remove(storage, (asset_key, user, time));
+            Bucket::<u64>::multilevel(storage, &[LOCK_PERIOD, asset_key, user])
+                .remove(&time.to_be_bytes());
+            remove_lock_index(storage, asset_key, user, &time.to_be_bytes());
+        } else {
+            // split the lock, the rest keeps its unlock time
+            LOCK_INFOS.save(storage, (asset_key, user, time), &(lock_amount - taken))?;
+        }
+        unlock_times.push(Timestamp::from_seconds(time));
+    }
+
+    if !remaining.is_zero() {
+        return Err(StdError::generic_err(
+            "Cannot cancel more than the locked amount",
+        ));
+    }
+    sub_total_unbonding(storage, asset_key, amount)?;
+    Ok(unlock_times)
+}
+
+#[cw_serde]
+pub struct BlockTime {
+    pub height: u64,
+    pub time: u64,
+}
+
+/// records the block in the ring once per height, heights and times only grow
+pub fn record_block_time(storage: &mut dyn Storage, height: u64, time: u64) -> StdResult<()> {
+    let count = read_block_time_count(storage)?;
+    if count > 0 && read_block_time(storage, count - 1)?.height == height {
+        return Ok(());
     }
+    Bucket::new(storage, BLOCK_TIMES).save(
+        &(count % MAX_BLOCK_TIMES).to_be_bytes(),
+        &BlockTime { height, time },
+    )?;
+    singleton(storage, KEY_BLOCK_TIME_COUNT).save(&(count + 1))
+}
+
+fn read_block_time_count(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(singleton_read(storage, KEY_BLOCK_TIME_COUNT)
+        .may_load()?
+        .unwrap_or_default())
+}
 
-    Ok(accumulate_amount)
+// the nth block ever recorded, it must still be in the ring
+fn read_block_time(storage: &dyn Storage, n: u64) -> StdResult<BlockTime> {
+    ReadonlyBucket::new(storage, BLOCK_TIMES).load(&(n % MAX_BLOCK_TIMES).to_be_bytes())
+}
+
+/// the last recorded block at or before time, None when time predates the ring
+pub fn read_block_at_time(storage: &dyn Storage, time: u64) -> StdResult<Option<BlockTime>> {
+    let count = read_block_time_count(storage)?;
+    // blocks in [low, high) are kept, search for the first one after time
+    let (mut low, mut high) = (count.saturating_sub(MAX_BLOCK_TIMES), count);
+    let oldest = low;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if read_block_time(storage, mid)?.time <= time {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low == oldest {
+        return Ok(None);
+    }
+    read_block_time(storage, low - 1).map(Some)
 }
 
 pub const STAKED_BALANCES: SnapshotMap<(&[u8], &Addr), Uint128> = SnapshotMap::new(
@@ -210,3 +2202,109 @@ pub const STAKED_TOTAL: SnapshotMap<&[u8], Uint128> = SnapshotMap::new(
     "total_staked__changelog",
     Strategy::EveryBlock,
 );
+
+/// the changes to a staked balance as (height, value before the change),
+/// the changelog only holds changes made since the snapshots were deployed
+pub fn read_staked_balance_changelog(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    address: &Addr,
+    start_after_height: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, Option<Uint128>)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    STAKED_BALANCES
+        .changelog()
+        .prefix((asset_key, address))
+        .range(
+            storage,
+            start_after_height.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(height, change)| (height, change.old)))
+        .collect()
+}
+
+/// the changes to the total staked in a pool as (height, value before the change)
+pub fn read_staked_total_changelog(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    start_after_height: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, Option<Uint128>)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    STAKED_TOTAL
+        .changelog()
+        .prefix(asset_key)
+        .range(
+            storage,
+            start_after_height.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(height, change)| (height, change.old)))
+        .collect()
+}
+
+/// writes the bond of a page of the stakers of a pool to the snapshots at height, for the bonds
+/// made before the snapshots were deployed. A balance already matching the bond is left as is and
+/// the total moves by the difference only, so a page can be run again. Returns the number of
+/// balances written and the last staker of the page, None once the stakers are exhausted
+pub fn backfill_staked_snapshots(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    asset_key: &[u8],
+    start_after: Option<Vec<u8>>,
+    limit: Option<u32>,
+    height: u64,
+) -> StdResult<(u32, Option<Vec<u8>>)> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let stakers = STAKERS
+        .prefix(asset_key)
+        .keys(
+            storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<Vec<u8>>>>()?;
+
+    let mut updated = 0u32;
+    for staker in stakers.iter() {
+        let bond_amount = REWARDS
+            .may_load(storage, (staker, asset_key))?
+            .map(|reward_info| reward_info.bond_amount)
+            .unwrap_or_default();
+        let address = api.addr_humanize(&CanonicalAddr::from(staker.as_slice()))?;
+        let balance = STAKED_BALANCES
+            .may_load(storage, (asset_key, &address))?
+            .unwrap_or_default();
+        if balance == bond_amount {
+            continue;
+        }
+        STAKED_BALANCES.save(storage, (asset_key, &address), &bond_amount, height)?;
+        STAKED_TOTAL.update(storage, asset_key, height, |total| -> StdResult<Uint128> {
+            Ok(total.unwrap_or_default().checked_sub(balance)? + bond_amount)
+        })?;
+        updated += 1;
+    }
+
+    let last = if stakers.len() < limit {
+        None
+    } else {
+        stakers.last().cloned()
+    };
+    Ok((updated, last))
+}
+
+// pool reward index, saved on register and on every deposit that moves it
+pub const REWARD_INDEX: SnapshotMap<&[u8], Decimal> = SnapshotMap::new(
+    "reward_index",
+    "reward_index__checkpoints",
+    "reward_index__changelog",
+    Strategy::EveryBlock,
+);
@@ -1,86 +1,1204 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{
+    execute, instantiate, migrate, query, sudo, CONTRACT_NAME, CONTRACT_VERSION,
+};
+use crate::helpers::{TestSuite, OWNER};
 use crate::msg::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg,
-    RewardInfoResponse,
+    AllRewardsPerSecResponseItem, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
+    MigrateMsg, OwnershipProposalResponse, PausedResponse, PendingChangeResponse, PoolInfoResponse,
+    PoolStatus, PoolsResponseItem, QueryMsg, RewardInfoResponse, RewarderProposalResponse,
+    RewardsPerSecResponse, SudoMsg, UnbondingPeriodsResponseItem,
+};
+use crate::state::{
+    is_rewarder, read_config, DEFAULT_MAX_LOCK_ENTRIES, DEFAULT_MAX_UNBONDING_PERIOD, KEY_CONFIG,
 };
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::testing::{
     mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
 };
-use cosmwasm_std::{attr, coin, from_binary, to_binary, Addr, Decimal, Order, StdError, Uint128};
+use cosmwasm_std::{
+    attr, coin, from_binary, to_binary, Addr, Api, CanonicalAddr, Decimal, Event, Order, StdError,
+    Uint128,
+};
+use cosmwasm_storage::singleton;
+use cw2::{get_contract_version, set_contract_version};
 use cw20::Cw20ReceiveMsg;
 use oraiswap::asset::{Asset, AssetInfo, ORAI_DENOM};
 
 #[test]
-fn proper_initialization() {
+fn proper_initialization() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // it worked, let's query the state
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        ConfigResponse {
+            owner: Some(Addr::unchecked("owner")),
+            fee_collector: None,
+            config_timelock: 0,
+            max_unbonding_period: DEFAULT_MAX_UNBONDING_PERIOD,
+            max_lock_entries: DEFAULT_MAX_LOCK_ENTRIES,
+            governance_token: None,
+            oracle: None,
+            contract_name: CONTRACT_NAME.to_string(),
+            contract_version: CONTRACT_VERSION.to_string(),
+            pool_count: 0,
+            import_open: false,
+        },
+        config
+    );
+}
+
+#[test]
+fn update_config() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // update fee collector
+    let info = mock_info("owner", &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        fee_collector: Some(Addr::unchecked("collector")),
+        config_timelock: None,
+        max_unbonding_period: None,
+        max_lock_entries: None,
+        governance_token: None,
+        oracle: None,
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(0, res.messages.len());
+
+    // it worked, let's query the state
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        ConfigResponse {
+            owner: Some(Addr::unchecked("owner")),
+            fee_collector: Some(Addr::unchecked("collector")),
+            config_timelock: 0,
+            max_unbonding_period: DEFAULT_MAX_UNBONDING_PERIOD,
+            max_lock_entries: DEFAULT_MAX_LOCK_ENTRIES,
+            governance_token: None,
+            oracle: None,
+            contract_name: CONTRACT_NAME.to_string(),
+            contract_version: CONTRACT_VERSION.to_string(),
+            pool_count: 0,
+            import_open: false,
+        },
+        config
+    );
+
+    // unauthorized err
+    let info = mock_info("addr", &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        fee_collector: None,
+        config_timelock: None,
+        max_unbonding_period: None,
+        max_lock_entries: None,
+        governance_token: None,
+        oracle: None,
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+}
+
+#[test]
+fn test_ownership_transfer() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only owner can propose
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: Addr::unchecked("owner2"),
+        expires_in: 100,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::OwnershipProposal {}).unwrap();
+    let proposal: Option<OwnershipProposalResponse> = from_binary(&res).unwrap();
+    assert_eq!(
+        proposal,
+        Some(OwnershipProposalResponse {
+            owner: Addr::unchecked("owner2"),
+            expires_at: mock_env().block.time.plus_seconds(100).seconds(),
+        })
+    );
+
+    // only the proposed owner can accept
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    // cannot accept after expiry
+    let mut expired_env = mock_env();
+    expired_env.block.time = expired_env.block.time.plus_seconds(100);
+    let res = execute(
+        deps.as_mut(),
+        expired_env,
+        mock_info("owner2", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "ownership proposal expired"),
+        _ => panic!("Must return expired error"),
+    }
+
+    // owner drops the proposal
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::DropOwnershipProposal {},
+    )
+    .unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::OwnershipProposal {}).unwrap();
+    let proposal: Option<OwnershipProposalResponse> = from_binary(&res).unwrap();
+    assert_eq!(proposal, None);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner2", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+
+    // propose again then accept
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: Addr::unchecked("owner2"),
+        expires_in: 100,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner2", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.owner, Some(Addr::unchecked("owner2")));
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::OwnershipProposal {}).unwrap();
+    let proposal: Option<OwnershipProposalResponse> = from_binary(&res).unwrap();
+    assert_eq!(proposal, None);
+}
+
+#[test]
+fn test_renounce_ownership() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner", &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::RenounceOwnership {},
+    )
+    .unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.owner, None);
+
+    // every owner operation is rejected now
+    let msgs = vec![
+        ExecuteMsg::UpdateConfig {
+            fee_collector: None,
+            config_timelock: None,
+            max_unbonding_period: None,
+            max_lock_entries: None,
+            governance_token: None,
+            oracle: None,
+        },
+        ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked("staking"),
+            unbonding_period: None,
+            native_token: None,
+        },
+        ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: Addr::unchecked("staking"),
+            assets: vec![],
+            force: None,
+        },
+        ExecuteMsg::ProposeNewOwner {
+            owner: Addr::unchecked("owner"),
+            expires_in: 100,
+        },
+    ];
+    for msg in msgs {
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+            _ => panic!("Must return unauthorized error"),
+        }
+    }
+}
+
+#[test]
+fn test_migrate_legacy_config() {
+    #[cw_serde]
+    struct LegacyConfig {
+        owner: CanonicalAddr,
+        rewarder: CanonicalAddr,
+    }
+
+    let mut deps = mock_dependencies();
+    let owner = deps.api.addr_canonicalize("owner").unwrap();
+    let rewarder = deps.api.addr_canonicalize("reward").unwrap();
+    singleton(&mut deps.storage, KEY_CONFIG)
+        .save(&LegacyConfig {
+            owner: owner.clone(),
+            rewarder: rewarder.clone(),
+        })
+        .unwrap();
+
+    // a contract without a stored version takes every transform
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(res.attributes[1], attr("from_version", "none"));
+    assert_eq!(
+        get_contract_version(&deps.storage).unwrap().version,
+        CONTRACT_VERSION
+    );
+
+    let config = read_config(&deps.storage).unwrap();
+    assert_eq!(config.owner, Some(owner));
+    assert!(is_rewarder(&deps.storage, &rewarder).unwrap());
+}
+
+#[test]
+fn test_migrate_version_guard() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    // the same version cannot be migrated again, nor downgraded
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!(
+            "cannot migrate from version {} to {}",
+            CONTRACT_VERSION, CONTRACT_VERSION
+        ))
+    );
+    set_contract_version(&mut deps.storage, CONTRACT_NAME, "99.0.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!(
+            "cannot migrate from version 99.0.0 to {}",
+            CONTRACT_VERSION
+        ))
+    );
+
+    // another contract is rejected whatever its version
+    set_contract_version(&mut deps.storage, "crates.io:cw20-base", "0.1.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("cannot migrate from contract crates.io:cw20-base")
+    );
+    assert_eq!(
+        get_contract_version(&deps.storage).unwrap().contract,
+        "crates.io:cw20-base"
+    );
+
+    set_contract_version(&mut deps.storage, CONTRACT_NAME, "not a version").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("invalid contract version not a version")
+    );
+
+    // an older version of the contract is brought up to date
+    set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate"),
+            attr("from_version", "0.1.0"),
+            attr("to_version", CONTRACT_VERSION),
+        ]
+    );
+    let res: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(res.contract_name, CONTRACT_NAME);
+    assert_eq!(res.contract_version, CONTRACT_VERSION);
+}
+
+#[test]
+fn test_multiple_rewarders() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::AddRewarder {
+        address: Addr::unchecked("reward2"),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Rewarders {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let rewarders: Vec<Addr> = from_binary(&res).unwrap();
+    assert_eq!(rewarders.len(), 2);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Rewarders {
+            start_after: Some(rewarders[0].clone()),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let next_rewarders: Vec<Addr> = from_binary(&res).unwrap();
+    assert_eq!(next_rewarders, vec![rewarders[1].clone()]);
+
+    // both rewarders can deposit
+    let deposit_msg = ExecuteMsg::DepositPrefundedReward { rewards: vec![] };
+    for rewarder in ["reward", "reward2"] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(rewarder, &[]),
+            deposit_msg.clone(),
+        )
+        .unwrap();
+    }
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::RemoveRewarder {
+            address: Addr::unchecked("reward"),
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward", &[]),
+        deposit_msg,
+    )
+    .unwrap_err();
+
+    // removing the last rewarder is rejected
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::RemoveRewarder {
+            address: Addr::unchecked("reward2"),
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "cannot remove the last rewarder")
+        }
+        _ => panic!("Must return last rewarder error"),
+    }
+}
+
+#[test]
+fn test_rewarder_rotation() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::ProposeNewRewarder {
+        rewarder: Addr::unchecked("reward2"),
+        replace: Some(Addr::unchecked("reward")),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "propose_new_rewarder"),
+            attr("new_rewarder", "reward2"),
+            attr("old_rewarder", "reward"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingRewarder {}).unwrap();
+    let pending_rewarder: Option<RewarderProposalResponse> = from_binary(&res).unwrap();
+    assert_eq!(
+        pending_rewarder,
+        Some(RewarderProposalResponse {
+            rewarder: Addr::unchecked("reward2"),
+            replace: Some(Addr::unchecked("reward")),
+        })
+    );
+
+    // old rewarder keeps working while the proposal is pending
+    let deposit_msg = ExecuteMsg::DepositPrefundedReward { rewards: vec![] };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+
+    // only the proposed rewarder can accept
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::AcceptRewarder {},
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward2", &[]),
+        ExecuteMsg::AcceptRewarder {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "accept_rewarder"),
+            attr("new_rewarder", "reward2"),
+            attr("old_rewarder", "reward"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Rewarders {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let rewarders: Vec<Addr> = from_binary(&res).unwrap();
+    assert_eq!(rewarders, vec![Addr::unchecked("reward2")]);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap_err();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward2", &[]),
+        deposit_msg,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_operators() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // only the owner can add operators
+    let msg = ExecuteMsg::AddOperator {
+        address: Addr::unchecked("operator"),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("operator", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Operators {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let operators: Vec<Addr> = from_binary(&res).unwrap();
+    assert_eq!(operators, vec![Addr::unchecked("operator")]);
+
+    let update_rewards_msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("operator", &[]),
+        update_rewards_msg.clone(),
+    )
+    .unwrap();
+
+    // operators cannot touch the config, rewarders or assets
+    let msgs = vec![
+        ExecuteMsg::UpdateConfig {
+            fee_collector: None,
+            config_timelock: None,
+            max_unbonding_period: None,
+            max_lock_entries: None,
+            governance_token: None,
+            oracle: None,
+        },
+        ExecuteMsg::AddRewarder {
+            address: Addr::unchecked("operator"),
+        },
+        ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked("staking2"),
+            unbonding_period: None,
+            native_token: None,
+        },
+        ExecuteMsg::AddOperator {
+            address: Addr::unchecked("operator2"),
+        },
+    ];
+    for msg in msgs {
+        let res = execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), msg);
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+            _ => panic!("Must return unauthorized error"),
+        }
+    }
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::RemoveOperator {
+            address: Addr::unchecked("operator"),
+        },
+    )
+    .unwrap();
+
+    // access is revoked right away
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("operator", &[]),
+        update_rewards_msg,
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::RemoveOperator {
+            address: Addr::unchecked("operator"),
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "address is not an operator"),
+        _ => panic!("Must return not an operator error"),
+    }
+}
+
+#[test]
+fn test_config_timelock() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // without timelock the update applies right away
+    let msg = ExecuteMsg::UpdateConfig {
+        fee_collector: None,
+        config_timelock: Some(100),
+        max_unbonding_period: None,
+        max_lock_entries: None,
+        governance_token: None,
+        oracle: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let update_rewards_msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_rewards_msg.clone(),
+    )
+    .unwrap();
+    let execute_after = mock_env().block.time.plus_seconds(100).seconds();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "queue_change"),
+            attr("id", "1"),
+            attr("execute_after", execute_after.to_string()),
+        ]
+    );
+    query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardsPerSec {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap_err();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PendingChanges {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let pending_changes: Vec<PendingChangeResponse> = from_binary(&res).unwrap();
+    assert_eq!(
+        pending_changes,
+        vec![PendingChangeResponse {
+            id: 1,
+            msg: update_rewards_msg,
+            execute_after,
+        }]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::ExecutePending { id: 1 },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "pending change is still timelocked")
+        }
+        _ => panic!("Must return timelocked error"),
+    }
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        ExecuteMsg::ExecutePending { id: 1 },
+    )
+    .unwrap();
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardsPerSec {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let res: RewardsPerSecResponse = from_binary(&res).unwrap();
+    assert_eq!(res.assets.len(), 1);
+
+    // the owner can cancel a pending change
+    let msg = ExecuteMsg::UpdateConfig {
+        fee_collector: Some(Addr::unchecked("collector")),
+        config_timelock: None,
+        max_unbonding_period: None,
+        max_lock_entries: None,
+        governance_token: None,
+        oracle: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        ExecuteMsg::CancelPending { id: 2 },
+    )
+    .unwrap_err();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecuteMsg::CancelPending { id: 2 },
+    )
+    .unwrap();
+
+    env.block.time = env.block.time.plus_seconds(100);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        ExecuteMsg::ExecutePending { id: 2 },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "no pending change found"),
+        _ => panic!("Must return not found error"),
+    }
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.fee_collector, None);
+
+    // rewarders and the instant unbond penalty are timelocked too
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecuteMsg::RemoveRewarder {
+            address: Addr::unchecked("reward2"),
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "address is not a rewarder"),
+        _ => panic!("Must return not a rewarder error"),
+    }
+    for msg in [
+        ExecuteMsg::AddRewarder {
+            address: Addr::unchecked("reward2"),
+        },
+        ExecuteMsg::UpdateInstantUnbondPenalty {
+            staking_token: Addr::unchecked("staking"),
+            penalty: Decimal::percent(10),
+        },
+    ] {
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+        assert_eq!(res.attributes[0], attr("action", "queue_change"));
+    }
+    let deposit_msg = ExecuteMsg::DepositPrefundedReward { rewards: vec![] };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("reward2", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap_err();
+
+    env.block.time = env.block.time.plus_seconds(100);
+    for id in [3, 4] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr", &[]),
+            ExecuteMsg::ExecutePending { id },
+        )
+        .unwrap();
+    }
+    execute(deps.as_mut(), env, mock_info("reward2", &[]), deposit_msg).unwrap();
+}
+
+#[test]
+fn test_sudo_pause() {
     let mut deps = mock_dependencies();
 
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
     };
-
     let info = mock_info("addr", &[]);
-
-    // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // it worked, let's query the state
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
-    let config: ConfigResponse = from_binary(&res).unwrap();
+    let mut env = mock_env();
+    env.block.height = 100;
+    sudo(deps.as_mut(), env, SudoMsg::Pause {}).unwrap();
+
+    // queries keep working
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Paused {}).unwrap();
+    let paused: PausedResponse = from_binary(&res).unwrap();
     assert_eq!(
-        ConfigResponse {
-            owner: Addr::unchecked("owner"),
-            rewarder: Addr::unchecked("reward"),
+        paused,
+        PausedResponse {
+            paused: true,
+            height: Some(100),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked("staking"),
+            unbonding_period: None,
+            native_token: None,
         },
-        config
     );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "contract is paused"),
+        _ => panic!("Must return paused error"),
+    }
+
+    // owner can still update config
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            fee_collector: None,
+            config_timelock: None,
+            max_unbonding_period: None,
+            max_lock_entries: None,
+            governance_token: None,
+            oracle: None,
+        },
+    )
+    .unwrap();
+
+    sudo(deps.as_mut(), mock_env(), SudoMsg::Unpause {}).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Paused {}).unwrap();
+    let paused: PausedResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        paused,
+        PausedResponse {
+            paused: false,
+            height: None,
+        }
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked("staking"),
+            unbonding_period: None,
+            native_token: None,
+        },
+    )
+    .unwrap();
 }
 
 #[test]
-fn update_config() {
+fn test_sudo_update_owner() {
     let mut deps = mock_dependencies();
 
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
     };
-
     let info = mock_info("addr", &[]);
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // update owner
-    let info = mock_info("owner", &[]);
-    let msg = ExecuteMsg::UpdateConfig {
-        owner: Some(Addr::unchecked("owner2")),
-        rewarder: None,
-    };
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_eq!(0, res.messages.len());
+    // the owner renounces, only the chain governance can bring an owner back
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::RenounceOwnership {},
+    )
+    .unwrap();
 
-    // it worked, let's query the state
+    let res = sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::UpdateOwner {
+            new_owner: "owner2".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new("sudo_update_owner")
+            .add_attribute("old_owner", "")
+            .add_attribute("new_owner", "owner2")]
+    );
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
     let config: ConfigResponse = from_binary(&res).unwrap();
-    assert_eq!(
-        ConfigResponse {
-            owner: Addr::unchecked("owner2"),
-            rewarder: Addr::unchecked("reward"),
+    assert_eq!(config.owner, Some(Addr::unchecked("owner2")));
+
+    // the new owner can use the owner messages
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner2", &[]),
+        ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked("staking"),
+            unbonding_period: None,
+            native_token: None,
         },
-        config
-    );
+    )
+    .unwrap();
 
-    // unauthorized err
-    let info = mock_info("owner", &[]);
-    let msg = ExecuteMsg::UpdateConfig {
-        rewarder: None,
-        owner: None,
+    let err = sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::UpdateOwner {
+            new_owner: "".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, StdError::GenericErr { .. }));
+}
+
+#[test]
+fn test_sudo_update_owner_drops_ownership_proposal() {
+    let mut suite = TestSuite::default();
+
+    // the compromised owner key proposes an owner of its own before governance steps in
+    suite
+        .execute(
+            OWNER,
+            &ExecuteMsg::ProposeNewOwner {
+                owner: Addr::unchecked("attacker"),
+                expires_in: 1000,
+            },
+            &[],
+        )
+        .unwrap();
+    suite
+        .sudo(&SudoMsg::UpdateOwner {
+            new_owner: "governance".to_string(),
+        })
+        .unwrap();
+
+    let err = suite
+        .execute("attacker", &ExecuteMsg::AcceptOwnership {}, &[])
+        .unwrap_err();
+    assert_eq!(err, "Generic error: no ownership proposal found");
+    assert_eq!(suite.config().owner, Some(Addr::unchecked("governance")));
+}
+
+#[test]
+fn test_allowed_staking_tokens() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // an empty allowlist accepts every staking token
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
     };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
 
-    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    let msg = ExecuteMsg::AddAllowedStakingToken {
+        staking_token: Addr::unchecked("staking2"),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    );
     match res {
         Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
         _ => panic!("Must return unauthorized error"),
     }
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "add_allowed_staking_token"),
+            attr("staking_token", "staking2"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllowedStakingTokens {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let allowed: Vec<Addr> = from_binary(&res).unwrap();
+    assert_eq!(allowed, vec![Addr::unchecked("staking2")]);
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking3"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "staking token is not allowed"),
+        _ => panic!("Must return not allowed error"),
+    }
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking2"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // removing the last entry restores the open registration
+    let msg = ExecuteMsg::RemoveAllowedStakingToken {
+        staking_token: Addr::unchecked("staking2"),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "remove_allowed_staking_token"),
+            attr("staking_token", "staking2"),
+        ]
+    );
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking3"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
 }
 
 #[test]
@@ -90,6 +1208,8 @@ fn test_register() {
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -100,6 +1220,7 @@ fn test_register() {
     let msg = ExecuteMsg::RegisterAsset {
         staking_token: Addr::unchecked("staking"),
         unbonding_period: None,
+        native_token: None,
     };
 
     // failed with unauthorized error
@@ -135,11 +1256,26 @@ fn test_register() {
         PoolInfoResponse {
             staking_token: Addr::unchecked("staking"),
             total_bond_amount: Uint128::zero(),
+            total_weighted_amount: Uint128::zero(),
             reward_index: Decimal::zero(),
             pending_reward: Uint128::zero(),
             unbonding_period: None,
+            unbond_fee: Decimal::zero(),
+            instant_unbond_penalty: None,
+            min_bond_amount: Uint128::zero(),
+            min_claim_amount: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            open_deposit: false,
+            deprecated: false,
+            pool_admin: None,
+            status: PoolStatus::default(),
         }
     );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.pool_count, 1);
 }
 
 #[test]
@@ -149,6 +1285,8 @@ fn test_query_staker_pagination() {
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -164,6 +1302,7 @@ fn test_query_staker_pagination() {
             },
             amount: 100u128.into(),
         }],
+        force: None,
     };
     let info = mock_info("owner", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -171,6 +1310,7 @@ fn test_query_staker_pagination() {
     let msg = ExecuteMsg::RegisterAsset {
         staking_token: Addr::unchecked("staking"),
         unbonding_period: None,
+        native_token: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -181,7 +1321,11 @@ fn test_query_staker_pagination() {
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: format!("addr{}", i),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                staker: None,
+                callback: None,
+            })
+            .unwrap(),
         });
         let info = mock_info("staking", &[]);
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -209,3 +1353,267 @@ fn test_query_staker_pagination() {
         println!("{:?}", staker_addrs);
     }
 }
+
+#[test]
+fn test_query_pools() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    for i in 0..35 {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked(format!("staking{}", i)),
+            unbonding_period: if i == 0 { Some(100) } else { None },
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking0"),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let query_pools = |start_after: Option<Addr>, limit: Option<u32>, order: Order| {
+        let res: Vec<PoolsResponseItem> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Pools {
+                    start_after,
+                    limit,
+                    order: Some(order.into()),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res
+    };
+
+    // limits are capped at MAX_LIMIT
+    assert_eq!(query_pools(None, Some(100), Order::Ascending).len(), 30);
+
+    // the last staking token of a page continues with the next one, in both orders
+    let mut pages: Vec<Vec<PoolsResponseItem>> = vec![];
+    for order in [Order::Ascending, Order::Descending] {
+        let mut pools: Vec<PoolsResponseItem> = vec![];
+        loop {
+            let start_after = pools.last().map(|pool| pool.staking_token.clone());
+            let page = query_pools(start_after, Some(4), order);
+            if page.is_empty() {
+                break;
+            }
+            pools.extend(page);
+        }
+        pages.push(pools);
+    }
+    assert_eq!(pages[0].len(), 35);
+    let mut descending = pages[1].clone();
+    descending.reverse();
+    assert_eq!(pages[0], descending);
+
+    let pool = pages[0]
+        .iter()
+        .find(|pool| pool.staking_token == Addr::unchecked("staking0"))
+        .cloned()
+        .unwrap();
+    assert_eq!(
+        pool,
+        PoolsResponseItem {
+            staking_token: Addr::unchecked("staking0"),
+            total_bond_amount: Uint128::zero(),
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            unbonding_period: Some(100),
+            rewards_per_sec: vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            }],
+        }
+    );
+}
+
+#[test]
+fn test_query_all_rewards_per_sec() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    for staking_token in ["staking1", "staking2", "staking3"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked(staking_token),
+            unbonding_period: None,
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+    let assets = vec![
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("reward_token"),
+            },
+            amount: 50u128.into(),
+        },
+    ];
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking2"),
+        assets: assets.clone(),
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    // a deprecated pool keeps an empty list
+    let msg = ExecuteMsg::DeprecatePool {
+        staking_token: Addr::unchecked("staking3"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let mut pools: Vec<AllRewardsPerSecResponseItem> = vec![];
+    loop {
+        let page: Vec<AllRewardsPerSecResponseItem> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllRewardsPerSec {
+                    start_after: pools.last().map(|pool| pool.staking_token.clone()),
+                    limit: Some(2),
+                    order: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        if page.is_empty() {
+            break;
+        }
+        pools.extend(page);
+    }
+    pools.sort_by(|a, b| a.staking_token.cmp(&b.staking_token));
+
+    // pools never configured are left out, the single pool query fails for them
+    assert_eq!(
+        pools,
+        vec![
+            AllRewardsPerSecResponseItem {
+                staking_token: Addr::unchecked("staking2"),
+                assets,
+            },
+            AllRewardsPerSecResponseItem {
+                staking_token: Addr::unchecked("staking3"),
+                assets: vec![],
+            },
+        ]
+    );
+    query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardsPerSec {
+            staking_token: Addr::unchecked("staking1"),
+        },
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn test_query_unbonding_periods() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    for (staking_token, unbonding_period) in [
+        ("staking1", Some(100)),
+        ("staking2", None),
+        ("staking3", Some(0)),
+        ("staking4", Some(50)),
+    ] {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked(staking_token),
+            unbonding_period,
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+
+    let mut periods: Vec<UnbondingPeriodsResponseItem> = vec![];
+    loop {
+        let page: Vec<UnbondingPeriodsResponseItem> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::UnbondingPeriods {
+                    start_after: periods.last().map(|period| period.staking_token.clone()),
+                    limit: Some(1),
+                    order: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        if page.is_empty() {
+            break;
+        }
+        periods.extend(page);
+    }
+    periods.sort_by(|a, b| a.staking_token.cmp(&b.staking_token));
+    assert_eq!(
+        periods,
+        vec![
+            UnbondingPeriodsResponseItem {
+                staking_token: Addr::unchecked("staking1"),
+                unbonding_period: 100,
+            },
+            UnbondingPeriodsResponseItem {
+                staking_token: Addr::unchecked("staking4"),
+                unbonding_period: 50,
+            },
+        ]
+    );
+
+    // the pool info tells a pool without a period apart
+    for (staking_token, unbonding_period) in [("staking1", Some(100)), ("staking3", None)] {
+        let pool_info: PoolInfoResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PoolInfo {
+                    staking_token: Addr::unchecked(staking_token),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(pool_info.unbonding_period, unbonding_period);
+    }
+}
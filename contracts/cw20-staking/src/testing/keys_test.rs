@@ -0,0 +1,387 @@
+use crate::contract::{execute, instantiate};
+use crate::keys::{
+    config_key, lock_info_key, pool_info_key, reward_info_key, staked_balance_key,
+    staked_total_key, staker_key, total_unbonding_key, unbonding_period_key,
+};
+use crate::msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, LegacyKind, LockInfo};
+use crate::state::{
+    read_pool_info, read_rewards_per_sec, read_unbonding_period, read_user_lock_info,
+    remove_and_accumulate_lock_info, store_unbonding_period, Config, PoolInfo, RewardInfo, REWARDS,
+    STAKERS,
+};
+use cosmwasm_schema::serde::de::DeserializeOwned;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{
+    attr, from_slice, to_binary, Addr, Api, Decimal, Order, StdResult, Storage, Timestamp, Uint128,
+};
+use cosmwasm_storage::{Bucket, ReadonlyBucket};
+use cw20::Cw20ReceiveMsg;
+use oraiswap::asset::{AssetInfoRaw, AssetRaw};
+
+fn read_raw<T: DeserializeOwned>(storage: &dyn Storage, key: &[u8]) -> T {
+    from_slice(&storage.get(key).expect("nothing stored at the key")).unwrap()
+}
+
+#[test]
+fn test_raw_keys() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: Some(100),
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(10u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    let staker = deps.api.addr_canonicalize("addr").unwrap();
+    let storage = &deps.storage;
+
+    let config: Config = read_raw(storage, &config_key());
+    assert_eq!(
+        config.owner,
+        Some(deps.api.addr_canonicalize("owner").unwrap())
+    );
+
+    let pool_info: PoolInfo = read_raw(storage, &pool_info_key(&asset_key));
+    assert_eq!(pool_info.total_bond_amount, Uint128::from(90u128));
+
+    let reward_info: RewardInfo = read_raw(storage, &reward_info_key(&staker, &asset_key));
+    assert_eq!(reward_info.bond_amount, Uint128::from(90u128));
+    assert!(read_raw::<bool>(storage, &staker_key(&asset_key, &staker)));
+
+    let unbonding_period: u64 = read_raw(storage, &unbonding_period_key(&asset_key));
+    assert_eq!(unbonding_period, 100);
+
+    let unlock_time = mock_env().block.time.seconds() + 100;
+    let lock_amount: Uint128 = read_raw(storage, &lock_info_key("staking", "addr", unlock_time));
+    assert_eq!(lock_amount, Uint128::from(10u128));
+    let total_unbonding: Uint128 = read_raw(storage, &total_unbonding_key("staking"));
+    assert_eq!(total_unbonding, Uint128::from(10u128));
+
+    let staked_balance: Uint128 = read_raw(
+        storage,
+        &staked_balance_key(&asset_key, &Addr::unchecked("addr")),
+    );
+    assert_eq!(staked_balance, Uint128::from(90u128));
+    let staked_total: Uint128 = read_raw(storage, &staked_total_key(&asset_key));
+    assert_eq!(staked_total, Uint128::from(90u128));
+}
+
+#[test]
+fn test_legacy_buckets_read_by_maps() {
+    let mut deps = mock_dependencies();
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    let staker = deps.api.addr_canonicalize("addr").unwrap();
+    let pool_info = PoolInfo {
+        staking_token: asset_key.clone(),
+        pending_reward: Uint128::zero(),
+        total_bond_amount: Uint128::from(100u128),
+        reward_index: Decimal::zero(),
+        deprecated: false,
+        total_weighted_amount: None,
+    };
+    let reward_info = RewardInfo {
+        native_token: false,
+        index: Decimal::zero(),
+        bond_amount: Uint128::from(100u128),
+        pending_reward: Uint128::from(5u128),
+        pending_withdraw: vec![],
+        weighted_amount: None,
+    };
+    let rewards_per_sec = vec![AssetRaw {
+        info: AssetInfoRaw::NativeToken {
+            denom: "orai".to_string(),
+        },
+        amount: Uint128::from(100u128),
+    }];
+
+    // written like the contract did before the maps
+    let storage = &mut deps.storage;
+    Bucket::new(storage, b"pool_info_v3")
+        .save(&asset_key, &pool_info)
+        .unwrap();
+    Bucket::multilevel(storage, &[b"reward_v3", staker.as_slice()])
+        .save(&asset_key, &reward_info)
+        .unwrap();
+    Bucket::multilevel(storage, &[b"staker_v3", asset_key.as_slice()])
+        .save(&staker, &true)
+        .unwrap();
+    Bucket::new(storage, b"rewards_per_sec_v3")
+        .save(&asset_key, &rewards_per_sec)
+        .unwrap();
+    Bucket::new(storage, b"unbonding_period")
+        .save(&asset_key, &100u64)
+        .unwrap();
+    for (unlock_time, amount) in [(200u64, 10u128), (100, 20)] {
+        Bucket::multilevel(storage, &[b"locking_users", b"staking", b"addr"])
+            .save(&unlock_time.to_be_bytes(), &Uint128::from(amount))
+            .unwrap();
+    }
+
+    assert_eq!(read_pool_info(storage, &asset_key).unwrap(), pool_info);
+    assert_eq!(
+        REWARDS.load(storage, (&staker, &asset_key)).unwrap(),
+        reward_info
+    );
+    assert_eq!(
+        REWARDS
+            .prefix(&staker)
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<Vec<u8>>>>()
+            .unwrap(),
+        vec![asset_key.to_vec()]
+    );
+    assert_eq!(
+        STAKERS
+            .prefix(&asset_key)
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<Vec<u8>>>>()
+            .unwrap(),
+        vec![staker.to_vec()]
+    );
+    assert_eq!(
+        read_rewards_per_sec(storage, &asset_key).unwrap(),
+        rewards_per_sec
+    );
+    assert_eq!(read_unbonding_period(storage, &asset_key).unwrap(), 100);
+    assert_eq!(
+        read_user_lock_info(storage, b"staking", b"addr", None, None, None, None, None).unwrap(),
+        vec![
+            LockInfo {
+                unlock_time: Timestamp::from_seconds(100),
+                amount: Uint128::from(20u128),
+            },
+            LockInfo {
+                unlock_time: Timestamp::from_seconds(200),
+                amount: Uint128::from(10u128),
+            },
+        ]
+    );
+
+    // and the other way around
+    store_unbonding_period(storage, &asset_key, 200).unwrap();
+    let period: u64 = ReadonlyBucket::new(storage, b"unbonding_period")
+        .load(&asset_key)
+        .unwrap();
+    assert_eq!(period, 200);
+    let released =
+        remove_and_accumulate_lock_info(storage, b"staking", b"addr", Timestamp::from_seconds(150))
+            .unwrap();
+    assert_eq!(released, Uint128::from(20u128));
+    assert!(ReadonlyBucket::<Uint128>::multilevel(
+        storage,
+        &[b"locking_users", b"staking", b"addr"]
+    )
+    .may_load(&100u64.to_be_bytes())
+    .unwrap()
+    .is_none());
+}
+
+#[test]
+fn test_lazy_legacy_prefixes() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    let stakers = [
+        deps.api.addr_canonicalize("addr").unwrap(),
+        deps.api.addr_canonicalize("addr2").unwrap(),
+    ];
+    let pool_info = PoolInfo {
+        staking_token: asset_key.clone(),
+        pending_reward: Uint128::zero(),
+        total_bond_amount: Uint128::from(150u128),
+        reward_index: Decimal::zero(),
+        deprecated: false,
+        total_weighted_amount: None,
+    };
+    let reward_info = RewardInfo {
+        native_token: false,
+        index: Decimal::zero(),
+        bond_amount: Uint128::from(75u128),
+        pending_reward: Uint128::zero(),
+        pending_withdraw: vec![],
+        weighted_amount: None,
+    };
+
+    // written by an older deployment under the v2 prefixes
+    let storage = &mut deps.storage;
+    Bucket::new(storage, b"pool_info_v2")
+        .save(&asset_key, &pool_info)
+        .unwrap();
+    for staker in stakers.iter() {
+        Bucket::multilevel(storage, &[b"reward_v2", staker.as_slice()])
+            .save(&asset_key, &reward_info)
+            .unwrap();
+        Bucket::multilevel(storage, &[b"staker_v2", asset_key.as_slice()])
+            .save(staker, &true)
+            .unwrap();
+    }
+
+    // reads and listings fall back to the legacy entries
+    assert_eq!(read_pool_info(storage, &asset_key).unwrap(), pool_info);
+    assert_eq!(
+        REWARDS.load(storage, (&stakers[0], &asset_key)).unwrap(),
+        reward_info
+    );
+    assert!(STAKERS.has(storage, (&asset_key, &stakers[1])));
+    let mut listed = STAKERS
+        .prefix(&asset_key)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Vec<u8>>>>()
+        .unwrap();
+    listed.sort();
+    let mut expected = stakers
+        .iter()
+        .map(|staker| staker.to_vec())
+        .collect::<Vec<Vec<u8>>>();
+    expected.sort();
+    assert_eq!(listed, expected);
+
+    // the next write moves the entry
+    let reward_info = RewardInfo {
+        bond_amount: Uint128::from(80u128),
+        ..reward_info
+    };
+    REWARDS
+        .save(storage, (&stakers[0], &asset_key), &reward_info)
+        .unwrap();
+    let legacy_reward = |storage: &dyn Storage, staker: &[u8]| {
+        ReadonlyBucket::<RewardInfo>::multilevel(storage, &[b"reward_v2", staker])
+            .may_load(&asset_key)
+            .unwrap()
+    };
+    assert!(legacy_reward(storage, &stakers[0]).is_none());
+    let moved: RewardInfo = read_raw(storage, &reward_info_key(&stakers[0], &asset_key));
+    assert_eq!(moved, reward_info);
+
+    // a stale legacy entry never overwrites the moved one
+    Bucket::multilevel(storage, &[b"reward_v2", stakers[0].as_slice()])
+        .save(
+            &asset_key,
+            &RewardInfo {
+                bond_amount: Uint128::zero(),
+                ..reward_info.clone()
+            },
+        )
+        .unwrap();
+
+    // listings take the moved entry only
+    assert_eq!(
+        REWARDS
+            .prefix(&stakers[0])
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(Vec<u8>, RewardInfo)>>>()
+            .unwrap(),
+        vec![(asset_key.to_vec(), reward_info.clone())]
+    );
+
+    // only the owner runs the crank
+    let msg = ExecuteMsg::MigrateLegacy {
+        kind: LegacyKind::Rewards,
+        limit: Some(1),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    let mut done = false;
+    let mut migrated = 0;
+    let mut rounds = 0;
+    while !done {
+        rounds += 1;
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        migrated += res.attributes[2].value.parse::<u32>().unwrap();
+        done = res.attributes[3].value == "true";
+    }
+    // the page moving the last entry reports done
+    assert_eq!((migrated, rounds), (2, 2));
+
+    for kind in [LegacyKind::Stakers, LegacyKind::PoolInfos] {
+        let msg = ExecuteMsg::MigrateLegacy { kind, limit: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        assert_eq!(res.attributes[3], attr("done", "true"));
+    }
+
+    // everything is under the v3 prefixes now
+    let storage = &deps.storage;
+    for staker in stakers.iter() {
+        assert!(legacy_reward(storage, staker).is_none());
+    }
+    assert_eq!(
+        REWARDS.load(storage, (&stakers[0], &asset_key)).unwrap(),
+        reward_info
+    );
+    assert_eq!(
+        REWARDS
+            .load(storage, (&stakers[1], &asset_key))
+            .unwrap()
+            .bond_amount,
+        Uint128::from(75u128)
+    );
+    assert_eq!(
+        STAKERS
+            .prefix(&asset_key)
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<Vec<u8>>>>()
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        STAKERS
+            .legacy()
+            .keys_raw(storage, None, None, Order::Ascending)
+            .count(),
+        0
+    );
+    let moved: PoolInfo = read_raw(storage, &pool_info_key(&asset_key));
+    assert_eq!(moved, pool_info);
+    assert!(ReadonlyBucket::<PoolInfo>::new(storage, b"pool_info_v2")
+        .may_load(&asset_key)
+        .unwrap()
+        .is_none());
+}
@@ -1,14 +1,35 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, query, reply};
+use crate::helpers::TestSuite;
 use crate::msg::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg, RewardInfoResponse,
-    RewardInfoResponseItem, RewardMsg,
+    AccrualMode, AccrualModeResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
+    PendingWithdrawResponse, PoolAprResponse, PoolInfoResponse, PoolWeight, QueryMsg,
+    RewardAprResponse, RewardDustResponse, RewardInfoResponse, RewardInfoResponseItem, RewardMsg,
+    RewardPoolCountResponse, RewardScheduleResponse, RewardScheduleStatus, RewardSplitResponse,
+    RewardVestingResponse, RewardVestingsResponse, RewardsPerSecLimits, RewardsPerSecResponse,
+    DEPOSIT_REWARD_EVENT, WITHDRAW_REWARD_EVENT,
 };
-use crate::state::{read_pool_info, rewards_read, store_pool_info, PoolInfo, RewardInfo};
-use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
-use cosmwasm_std::{coin, from_binary, to_binary, Addr, Api, Decimal, Uint128};
-use cw20::Cw20ReceiveMsg;
+use crate::rewards::{
+    before_share_change, index_amount, REWARD_TRANSFER_REPLY_ID, WITHDRAW_AND_SWAP_REPLY_ID,
+};
+use crate::state::{
+    read_pool_info, read_rate_checkpoints, store_pool_info, PoolInfo, RewardInfo,
+    MAX_REWARD_SCHEDULES, REWARDS, STAKERS,
+};
+use cosmwasm_std::testing::{
+    mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    attr, coin, from_binary, to_binary, Addr, Api, BankMsg, ContractResult, CosmosMsg, Decimal,
+    Event, Order, Reply, StdError, SubMsg, SubMsgResponse, SubMsgResult, SystemResult, Timestamp,
+    Uint128, Uint256, WasmMsg, WasmQuery,
+};
+use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20ReceiveMsg};
 use oraiswap::asset::{Asset, AssetInfo, ORAI_DENOM};
 use oraiswap::create_entry_points_testing;
+use oraiswap::router::{
+    ExecuteMsg as RouterExecuteMsg, QueryMsg as RouterQueryMsg, SimulateSwapOperationsResponse,
+    SwapOperation,
+};
 use oraiswap::testing::{MockApp, ATOM_DENOM};
 
 #[test]
@@ -22,6 +43,8 @@ fn test_deposit_reward() {
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -44,6 +67,7 @@ fn test_deposit_reward() {
                 amount: 200u128.into(),
             },
         ],
+        force: None,
     };
     let info = mock_info("owner", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -51,6 +75,7 @@ fn test_deposit_reward() {
     let msg = ExecuteMsg::RegisterAsset {
         staking_token: staking_token.clone(),
         unbonding_period: None,
+        native_token: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -67,13 +92,17 @@ fn test_deposit_reward() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // factory deposit 100 reward tokens
-    let msg = ExecuteMsg::DepositReward {
+    let msg = ExecuteMsg::DepositPrefundedReward {
         rewards: vec![RewardMsg {
             staking_token: staking_token.clone(),
             total_accumulation_amount: Uint128::from(100u128),
@@ -149,6 +178,8 @@ fn test_deposit_reward_when_no_bonding() {
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -171,6 +202,7 @@ fn test_deposit_reward_when_no_bonding() {
                 amount: 200u128.into(),
             },
         ],
+        force: None,
     };
     let info = mock_info("owner", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -178,6 +210,7 @@ fn test_deposit_reward_when_no_bonding() {
     let msg = ExecuteMsg::RegisterAsset {
         staking_token: Addr::unchecked("staking"),
         unbonding_period: None,
+        native_token: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -188,7 +221,7 @@ fn test_deposit_reward_when_no_bonding() {
     store_pool_info(&mut deps.storage, &asset_key, &pool_info).unwrap();
 
     // factory deposit 100 reward tokens
-    let msg = ExecuteMsg::DepositReward {
+    let msg = ExecuteMsg::DepositPrefundedReward {
         rewards: vec![RewardMsg {
             staking_token: Addr::unchecked("staking"),
             total_accumulation_amount: Uint128::from(100u128),
@@ -264,6 +297,8 @@ fn test_before_share_changes() {
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -286,6 +321,7 @@ fn test_before_share_changes() {
                 amount: 200u128.into(),
             },
         ],
+        force: None,
     };
     let info = mock_info("owner", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -293,6 +329,7 @@ fn test_before_share_changes() {
     let msg = ExecuteMsg::RegisterAsset {
         staking_token: Addr::unchecked("staking"),
         unbonding_period: None,
+        native_token: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -306,12 +343,16 @@ fn test_before_share_changes() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    let msg = ExecuteMsg::DepositReward {
+    let msg = ExecuteMsg::DepositPrefundedReward {
         rewards: vec![RewardMsg {
             staking_token: Addr::unchecked("staking"),
             total_accumulation_amount: Uint128::from(100u128),
@@ -323,8 +364,9 @@ fn test_before_share_changes() {
 
     let asset_key = deps.api.addr_canonicalize("staking").unwrap();
     let addr_raw = deps.api.addr_canonicalize("addr").unwrap();
-    let reward_bucket = rewards_read(&deps.storage, &addr_raw);
-    let reward_info: RewardInfo = reward_bucket.load(asset_key.as_slice()).unwrap();
+    let reward_info: RewardInfo = REWARDS
+        .load(&deps.storage, (&addr_raw, asset_key.as_slice()))
+        .unwrap();
     assert_eq!(
         RewardInfo {
             pending_reward: Uint128::zero(),
@@ -332,6 +374,7 @@ fn test_before_share_changes() {
             index: Decimal::zero(),
             native_token: false,
             pending_withdraw: vec![],
+            weighted_amount: None,
         },
         reward_info
     );
@@ -340,13 +383,18 @@ fn test_before_share_changes() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    let reward_bucket = rewards_read(&deps.storage, &addr_raw);
-    let reward_info: RewardInfo = reward_bucket.load(asset_key.as_slice()).unwrap();
+    let reward_info: RewardInfo = REWARDS
+        .load(&deps.storage, (&addr_raw, asset_key.as_slice()))
+        .unwrap();
     assert_eq!(
         RewardInfo {
             pending_reward: Uint128::from(100u128),
@@ -354,12 +402,13 @@ fn test_before_share_changes() {
             index: Decimal::from_ratio(100u128, 100u128),
             native_token: false,
             pending_withdraw: vec![],
+            weighted_amount: None,
         },
         reward_info
     );
 
     // factory deposit 100 reward tokens; = 0.8 + 0.4 = 1.2 is reward_index
-    let msg = ExecuteMsg::DepositReward {
+    let msg = ExecuteMsg::DepositPrefundedReward {
         rewards: vec![RewardMsg {
             staking_token: Addr::unchecked("staking"),
             total_accumulation_amount: Uint128::from(100u128),
@@ -371,13 +420,17 @@ fn test_before_share_changes() {
     // unbond
     let msg = ExecuteMsg::Unbond {
         staking_token: Addr::unchecked("staking"),
-        amount: Uint128::from(100u128),
+        amount: Some(Uint128::from(100u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
     };
     let info = mock_info("addr", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    let reward_bucket = rewards_read(&deps.storage, &addr_raw);
-    let reward_info: RewardInfo = reward_bucket.load(asset_key.as_slice()).unwrap();
+    let reward_info: RewardInfo = REWARDS
+        .load(&deps.storage, (&addr_raw, asset_key.as_slice()))
+        .unwrap();
     assert_eq!(
         RewardInfo {
             pending_reward: Uint128::from(200u128),
@@ -385,6 +438,7 @@ fn test_before_share_changes() {
             index: Decimal::from_ratio(150u128, 100u128),
             native_token: false,
             pending_withdraw: vec![],
+            weighted_amount: None,
         },
         reward_info
     );
@@ -431,6 +485,8 @@ fn test_withdraw() {
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: reward_addr.clone(),
+        governance_token: None,
+        import_open: None,
     };
 
     let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
@@ -488,6 +544,7 @@ fn test_withdraw() {
                 amount: 200u128.into(),
             },
         ],
+        force: None,
     };
 
     let _res = app
@@ -499,6 +556,7 @@ fn test_withdraw() {
     let msg = ExecuteMsg::RegisterAsset {
         staking_token: lp_addr.clone(),
         unbonding_period: None,
+        native_token: None,
     };
 
     let _res = app
@@ -509,14 +567,18 @@ fn test_withdraw() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
 
     let _res = app
         .execute(lp_addr.clone(), staking_addr.clone(), &msg, &[])
         .unwrap();
 
-    let msg = ExecuteMsg::DepositReward {
+    let msg = ExecuteMsg::DepositPrefundedReward {
         rewards: vec![RewardMsg {
             staking_token: lp_addr.clone(),
             total_accumulation_amount: Uint128::from(100u128),
@@ -542,6 +604,7 @@ fn test_withdraw() {
 
     let msg = ExecuteMsg::Withdraw {
         staking_token: Some(Addr::unchecked("staking")),
+        skip_failing: None,
     };
 
     let res = app
@@ -551,6 +614,86 @@ fn test_withdraw() {
     println!("{:?}", res);
 }
 
+// the same flow on the cw-multi-test harness integrators get with the testing feature
+#[test]
+fn test_withdraw_with_suite() {
+    let mut suite = TestSuite::new(&[]);
+    let lp_addr = suite.create_token("lptoken", &[("addr", 10000000000u128)]);
+    let reward_addr = suite.create_token("reward", &[]);
+
+    // funding the staking contract with the rewards it pays out
+    suite.fund_contract(vec![
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+    ]);
+    suite.fund_contract_token(&reward_addr, 10000000000u128);
+
+    suite.register_pool(&lp_addr, None).unwrap();
+    suite
+        .update_rewards_per_sec(
+            &lp_addr,
+            vec![
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: 100u128.into(),
+                },
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    amount: 200u128.into(),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: reward_addr.clone(),
+                    },
+                    amount: 200u128.into(),
+                },
+            ],
+        )
+        .unwrap();
+
+    // bond 100 tokens
+    suite.bond("addr", &lp_addr, 100u128).unwrap();
+    suite.assert_bond_amount("addr", &lp_addr, 100u128);
+    suite.assert_token_balance(&lp_addr, "addr", 9999999900u128);
+
+    suite.advance_seconds(100);
+    suite.deposit_rewards(&lp_addr, 100u128).unwrap();
+    let res = suite.pending_rewards("addr", Some(&lp_addr));
+    assert_eq!(res.reward_infos[0].pending_reward, Uint128::from(100u128));
+
+    // only the rewarder can deposit
+    let err = suite
+        .execute(
+            "addr",
+            &ExecuteMsg::DepositReward {
+                rewards: vec![RewardMsg {
+                    staking_token: lp_addr.clone(),
+                    total_accumulation_amount: Uint128::from(100u128),
+                }],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, "Generic error: unauthorized");
+
+    // the pending reward is split by the rewards per sec of the pool
+    suite.withdraw("addr", Some(&lp_addr)).unwrap();
+    suite.assert_native_balance("addr", ORAI_DENOM, 20u128);
+    suite.assert_native_balance("addr", ATOM_DENOM, 40u128);
+    suite.assert_token_balance(&reward_addr, "addr", 40u128);
+    let res = suite.pending_rewards("addr", Some(&lp_addr));
+    assert_eq!(res.reward_infos[0].pending_reward, Uint128::zero());
+
+    // unbonding returns the staking token
+    suite.unbond("addr", &lp_addr, None).unwrap();
+    suite.assert_bond_amount("addr", &lp_addr, 0u128);
+    suite.assert_token_balance(&lp_addr, "addr", 10000000000u128);
+}
+
 #[test]
 fn test_cannonical_with_to_vec() {
     let deps = mock_dependencies_with_balance(&[
@@ -576,6 +719,8 @@ fn test_update_rewards_per_sec() {
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
     };
     let staking_token = Addr::unchecked("staking_token");
 
@@ -599,6 +744,7 @@ fn test_update_rewards_per_sec() {
                 amount: 200u128.into(),
             },
         ],
+        force: None,
     };
     let info = mock_info("owner", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -606,6 +752,7 @@ fn test_update_rewards_per_sec() {
     let msg = ExecuteMsg::RegisterAsset {
         staking_token: staking_token.clone(),
         unbonding_period: None,
+        native_token: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -619,13 +766,17 @@ fn test_update_rewards_per_sec() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(300u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
     let info = mock_info(staking_token.as_str(), &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // factory deposit 300 reward tokens
-    let msg = ExecuteMsg::DepositReward {
+    let msg = ExecuteMsg::DepositPrefundedReward {
         rewards: vec![RewardMsg {
             staking_token: staking_token.clone(),
             total_accumulation_amount: Uint128::from(300u128),
@@ -655,12 +806,13 @@ fn test_update_rewards_per_sec() {
                     amount: 67u128.into(),
                 },
             ],
+            force: None,
         },
     )
     .unwrap();
 
     // factory deposit 100 reward tokens
-    let msg = ExecuteMsg::DepositReward {
+    let msg = ExecuteMsg::DepositPrefundedReward {
         rewards: vec![RewardMsg {
             staking_token: staking_token.clone(),
             total_accumulation_amount: Uint128::from(100u128),
@@ -717,6 +869,8 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -739,6 +893,7 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
                 amount: 200u128.into(),
             },
         ],
+        force: None,
     };
     let info = mock_info("owner", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -746,6 +901,7 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
     let msg = ExecuteMsg::RegisterAsset {
         staking_token: Addr::unchecked("staking"),
         unbonding_period: None,
+        native_token: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -759,13 +915,17 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(300u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // factory deposit 300 reward tokens
-    let msg = ExecuteMsg::DepositReward {
+    let msg = ExecuteMsg::DepositPrefundedReward {
         rewards: vec![RewardMsg {
             staking_token: Addr::unchecked("staking"),
             total_accumulation_amount: Uint128::from(300u128),
@@ -795,6 +955,7 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
                     amount: 67u128.into(),
                 },
             ],
+            force: None,
         },
     )
     .unwrap();
@@ -803,7 +964,11 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr1".into(),
         amount: Uint128::from(300u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -832,7 +997,7 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
     );
 
     // factory deposit 100 reward tokens
-    let msg = ExecuteMsg::DepositReward {
+    let msg = ExecuteMsg::DepositPrefundedReward {
         rewards: vec![RewardMsg {
             staking_token: Addr::unchecked("staking"),
             total_accumulation_amount: Uint128::from(100u128),
@@ -902,3 +1067,3743 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
         }
     );
 }
+
+#[test]
+fn test_rewards_per_sec_limits() {
+    let mut deps = mock_dependencies_with_balance(&[]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::AddOperator {
+        address: Addr::unchecked("operator"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::SetRewardsPerSecLimits {
+        staking_token: Addr::unchecked("staking"),
+        max_rewards_per_sec: Some(Uint128::from(1000u128)),
+        cooldown: 100,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let update_msg = |amount: u128, force: Option<bool>| ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: amount.into(),
+        }],
+        force,
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("operator", &[]),
+        update_msg(1001, None),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("rewards per sec exceeds the maximum")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("operator", &[]),
+        update_msg(1000, None),
+    )
+    .unwrap();
+
+    // a second update within the cooldown is rejected unless the owner forces it
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(99);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("operator", &[]),
+        update_msg(500, None),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("rewards per sec was updated too recently")
+    );
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("operator", &[]),
+        update_msg(500, Some(true)),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        update_msg(500, Some(true)),
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardsPerSec {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let res: RewardsPerSecResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        res,
+        RewardsPerSecResponse {
+            assets: vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 500u128.into(),
+            }],
+            limits: RewardsPerSecLimits {
+                max_rewards_per_sec: Some(Uint128::from(1000u128)),
+                cooldown: 100,
+            },
+            last_updated: Some(env.block.time.seconds()),
+        }
+    );
+
+    env.block.time = env.block.time.plus_seconds(100);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("operator", &[]),
+        update_msg(200, None),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_reward_schedules() {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let now = mock_env().block.time.seconds();
+    let schedule_msg =
+        |denom: &str, amount_per_sec: u128, start_time: u64, end_time: Option<u64>| {
+            ExecuteMsg::CreateRewardSchedule {
+                staking_token: Addr::unchecked("staking"),
+                asset_info: AssetInfo::NativeToken {
+                    denom: denom.to_string(),
+                },
+                amount_per_sec: amount_per_sec.into(),
+                start_time,
+                end_time,
+            }
+        };
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        schedule_msg(ORAI_DENOM, 100, now, Some(now + 100)),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        schedule_msg(ORAI_DENOM, 0, now, Some(now + 100)),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("amount per sec cannot be zero"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        schedule_msg(ORAI_DENOM, 100, now + 100, Some(now + 100)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("schedule must end after it starts")
+    );
+
+    // ORAI runs for 100 seconds, ATOM starts halfway and never ends
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        schedule_msg(ORAI_DENOM, 100, now, Some(now + 100)),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        schedule_msg(ATOM_DENOM, 100, now + 50, None),
+    )
+    .unwrap();
+
+    let res: Vec<RewardScheduleResponse> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardSchedules {
+                staking_token: Addr::unchecked("staking"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        vec![
+            RewardScheduleResponse {
+                id: 1,
+                asset_info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount_per_sec: 100u128.into(),
+                start_time: now,
+                end_time: Some(now + 100),
+                status: RewardScheduleStatus::Active,
+                remaining_duration: Some(100),
+                undistributed_budget: Some(10000u128.into()),
+            },
+            RewardScheduleResponse {
+                id: 2,
+                asset_info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount_per_sec: 100u128.into(),
+                start_time: now + 50,
+                end_time: None,
+                status: RewardScheduleStatus::Pending,
+                remaining_duration: None,
+                undistributed_budget: None,
+            },
+        ]
+    );
+
+    // the deposit is split by the 10000 ORAI and 5000 ATOM the schedules ran for
+    let deposit_msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(300u128),
+        }],
+    };
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("rewarder", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: None,
+            skip_failing: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(199u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(99u128, ATOM_DENOM)],
+            })),
+        ]
+    );
+
+    // the ended ORAI schedule is dropped
+    let res: Vec<RewardScheduleResponse> = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RewardSchedules {
+                staking_token: Addr::unchecked("staking"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].id, 2);
+
+    let cancel_msg = |id: u64| ExecuteMsg::CancelRewardSchedule {
+        staking_token: Addr::unchecked("staking"),
+        id,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        cancel_msg(1),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("reward schedule not found"));
+
+    env.block.time = env.block.time.plus_seconds(50);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        cancel_msg(2),
+    )
+    .unwrap();
+
+    // the 50 seconds ATOM ran before the cancellation still accrue
+    env.block.time = env.block.time.plus_seconds(50);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("rewarder", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1], attr("rewards_amount", "300"));
+
+    // nothing runs anymore
+    env.block.time = env.block.time.plus_seconds(100);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("rewarder", &[]),
+        deposit_msg,
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1], attr("rewards_amount", "0"));
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: None,
+            skip_failing: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(300u128, ATOM_DENOM)],
+        }))]
+    );
+}
+
+#[test]
+fn test_reward_schedule_rate_change() {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let bond_msg = |sender: &str| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sender.into(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                staker: None,
+                callback: None,
+            })
+            .unwrap(),
+        })
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg("addr"),
+    )
+    .unwrap();
+
+    // ORAI runs for 100 seconds, then ATOM takes over
+    let now = mock_env().block.time.seconds();
+    for (denom, start_time, end_time) in [
+        (ORAI_DENOM, now, Some(now + 100)),
+        (ATOM_DENOM, now + 100, None),
+    ] {
+        let msg = ExecuteMsg::CreateRewardSchedule {
+            staking_token: Addr::unchecked("staking"),
+            asset_info: AssetInfo::NativeToken {
+                denom: denom.to_string(),
+            },
+            amount_per_sec: 100u128.into(),
+            start_time,
+            end_time,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+
+    let deposit_msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(100u128),
+        }],
+    };
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("rewarder", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+
+    // addr2 joins at the index the rate changes at and earns none of the ORAI
+    env.block.time = env.block.time.plus_seconds(50);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("staking", &[]),
+        bond_msg("addr2"),
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(50);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("rewarder", &[]),
+        deposit_msg,
+    )
+    .unwrap();
+
+    // addr never withdrew, the first deposit is still paid in ORAI
+    let res: Vec<Asset> = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PendingRewards {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: 50u128.into(),
+            },
+        ]
+    );
+
+    // the ORAI was set aside when the rate changed
+    let res: Vec<Asset> =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::OutstandingRewards {}).unwrap())
+            .unwrap();
+    assert_eq!(
+        res,
+        vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            },
+        ]
+    );
+
+    let withdraw_msg = ExecuteMsg::Withdraw {
+        staking_token: None,
+        skip_failing: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        withdraw_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(100u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(50u128, ATOM_DENOM)],
+            })),
+        ]
+    );
+    let res = execute(deps.as_mut(), env, mock_info("addr2", &[]), withdraw_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr2".to_string(),
+            amount: vec![coin(50u128, ATOM_DENOM)],
+        }))]
+    );
+}
+
+#[test]
+fn test_rate_checkpoints_on_split_changes() {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+        coin(1000u128, "uusd"),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let native_rates = |orai: u128, atom: u128| {
+        vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: orai.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: atom.into(),
+            },
+        ]
+    };
+    let deposit_msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(300u128),
+        }],
+    };
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    let checkpoint_count = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        read_rate_checkpoints(&deps.storage, asset_key.as_slice(), Decimal::zero())
+            .unwrap()
+            .len()
+    };
+
+    // the same split at another magnitude leaves nothing behind
+    let mut env = mock_env();
+    for (orai, atom) in [(100u128, 200u128), (50, 100)] {
+        let msg = ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: Addr::unchecked("staking"),
+            assets: native_rates(orai, atom),
+            force: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(10);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("rewarder", &[]),
+            deposit_msg.clone(),
+        )
+        .unwrap();
+    }
+    assert_eq!(checkpoint_count(&deps), 0);
+
+    // an asset paid below one per second on average still changes the split
+    let msg = ExecuteMsg::CreateRewardSchedule {
+        staking_token: Addr::unchecked("staking"),
+        asset_info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount_per_sec: Uint128::one(),
+        start_time: env.block.time.seconds() + 9,
+        end_time: Some(env.block.time.seconds() + 10),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("rewarder", &[]),
+        deposit_msg,
+    )
+    .unwrap();
+    assert_eq!(checkpoint_count(&deps), 1);
+    let res: RewardsPerSecResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::RewardsPerSec {
+                staking_token: Addr::unchecked("staking"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.assets.contains(&Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: Uint128::one(),
+    }));
+}
+
+#[test]
+fn test_concurrent_reward_schedules() {
+    let mut deps = mock_dependencies_with_balance(&[]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // one schedule per partner token, each with its own window
+    let now = mock_env().block.time.seconds();
+    for i in 0..MAX_REWARD_SCHEDULES as u64 {
+        let msg = ExecuteMsg::CreateRewardSchedule {
+            staking_token: Addr::unchecked("staking"),
+            asset_info: AssetInfo::NativeToken {
+                denom: format!("partner{}", i),
+            },
+            amount_per_sec: 100u128.into(),
+            start_time: now + i * 10,
+            end_time: Some(now + 100 + i * 10),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::CreateRewardSchedule {
+        staking_token: Addr::unchecked("staking"),
+        asset_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        amount_per_sec: 100u128.into(),
+        start_time: now,
+        end_time: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("too many reward schedules for this pool")
+    );
+
+    // ended schedules no longer count
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(105);
+    let schedules_at = |deps: &cosmwasm_std::OwnedDeps<_, _, _>,
+                        env: cosmwasm_std::Env,
+                        start_after: Option<u64>| {
+        let res: Vec<RewardScheduleResponse> = from_binary(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::RewardSchedules {
+                    staking_token: Addr::unchecked("staking"),
+                    start_after,
+                    limit: Some(3),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.into_iter()
+            .map(|schedule| (schedule.id, schedule.status))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        schedules_at(&deps, env.clone(), None),
+        vec![
+            (1, RewardScheduleStatus::Ended),
+            (2, RewardScheduleStatus::Active),
+            (3, RewardScheduleStatus::Active),
+        ]
+    );
+    assert_eq!(
+        schedules_at(&deps, mock_env(), Some(8)),
+        vec![
+            (9, RewardScheduleStatus::Pending),
+            (10, RewardScheduleStatus::Pending),
+        ]
+    );
+
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        schedules_at(&deps, env, Some(9)),
+        vec![
+            (10, RewardScheduleStatus::Active),
+            (11, RewardScheduleStatus::Active),
+        ]
+    );
+}
+
+#[test]
+fn test_open_deposit() {
+    let mut deps = mock_dependencies_with_balance(&[]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let update_msg = |assets: Vec<AssetInfo>| ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: assets
+            .into_iter()
+            .map(|info| Asset {
+                info,
+                amount: 100u128.into(),
+            })
+            .collect(),
+        force: None,
+    };
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg(vec![orai.clone()]),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let rewards = vec![RewardMsg {
+        staking_token: Addr::unchecked("staking"),
+        total_accumulation_amount: Uint128::from(100u128),
+    }];
+    let deposit_msg = ExecuteMsg::DepositReward {
+        rewards: rewards.clone(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[coin(100u128, ORAI_DENOM)]),
+        deposit_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    let open_msg = ExecuteMsg::SetOpenDeposit {
+        staking_token: Addr::unchecked("staking"),
+        open_deposit: true,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        open_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), open_msg).unwrap();
+
+    // the funds must match the declared amount exactly
+    for funds in [
+        vec![],
+        vec![coin(99u128, ORAI_DENOM)],
+        vec![coin(100u128, ATOM_DENOM)],
+        vec![coin(100u128, ORAI_DENOM), coin(1u128, ATOM_DENOM)],
+    ] {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &funds),
+            deposit_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            StdError::generic_err("sent funds do not match the declared rewards")
+        );
+    }
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward_token", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "anyone".into(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::DepositReward {
+                rewards: rewards.clone(),
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("sent funds do not match the declared rewards")
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[coin(100u128, ORAI_DENOM)]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_reward"),
+            attr("rewards_amount", "100"),
+            attr("depositor", "anyone"),
+        ]
+    );
+
+    // cw20 rewards are deposited through the hook of the reward token
+    let reward_token = AssetInfo::Token {
+        contract_addr: Addr::unchecked("reward_token"),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg(vec![reward_token.clone()]),
+    )
+    .unwrap();
+    let receive_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "anyone".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward { rewards }).unwrap(),
+    });
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward_token", &[]),
+        receive_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("depositor", "anyone"));
+
+    // a split between several assets cannot be backed by the funds
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg(vec![orai, reward_token]),
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward_token", &[]),
+        receive_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("funded deposits need a pool rewarded in a single asset")
+    );
+}
+
+#[test]
+fn test_deposit_reward_cw20() {
+    let mut deps = mock_dependencies_with_balance(&[]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("reward_token"),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let send_msg = |amount: u128| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "rewarder".into(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::DepositReward {
+                rewards: vec![RewardMsg {
+                    staking_token: Addr::unchecked("staking"),
+                    total_accumulation_amount: Uint128::from(100u128),
+                }],
+            })
+            .unwrap(),
+        })
+    };
+
+    // the sent token must be the reward asset and cover the declared amount
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("other_token", &[]),
+        send_msg(100),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("sent funds do not match the declared rewards")
+    );
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward_token", &[]),
+        send_msg(99),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("sent funds do not match the declared rewards")
+    );
+
+    // the pool does not need open deposits for the rewarder
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("reward_token", &[]),
+        send_msg(150),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_reward"),
+            attr("rewards_amount", "100"),
+            attr("depositor", "rewarder"),
+        ]
+    );
+}
+
+#[test]
+fn test_claim_pending_withdraw() {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let update_msg = |assets: Vec<(&str, u128)>| ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: assets
+            .into_iter()
+            .map(|(denom, amount)| Asset {
+                info: AssetInfo::NativeToken {
+                    denom: denom.to_string(),
+                },
+                amount: amount.into(),
+            })
+            .collect(),
+        force: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg(vec![(ORAI_DENOM, 100), (ATOM_DENOM, 200)]),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let deposit_msg = |amount: u128| ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(amount),
+        }],
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg(300),
+    )
+    .unwrap();
+
+    // changing the reward assets sets the rewards earned so far aside
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg(vec![(ORAI_DENOM, 100)]),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg(100),
+    )
+    .unwrap();
+
+    let pending_withdraw_msg = QueryMsg::PendingWithdraw {
+        staker_addr: Addr::unchecked("addr"),
+        staking_token: None,
+    };
+    let res: PendingWithdrawResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), pending_withdraw_msg.clone()).unwrap())
+            .unwrap();
+    assert_eq!(
+        res,
+        PendingWithdrawResponse {
+            staker_addr: Addr::unchecked("addr"),
+            pending_withdraw: vec![
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: 99u128.into(),
+                },
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    amount: 199u128.into(),
+                },
+            ],
+        }
+    );
+
+    let claim_msg = ExecuteMsg::ClaimPendingWithdraw {
+        staking_token: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        claim_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(99u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(199u128, ATOM_DENOM)],
+            })),
+        ]
+    );
+
+    let res: PendingWithdrawResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), pending_withdraw_msg).unwrap()).unwrap();
+    assert_eq!(res.pending_withdraw, vec![]);
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), claim_msg).unwrap_err();
+    assert_eq!(res, StdError::generic_err("no pending withdraw to claim"));
+
+    // the rewards accrued afterwards are still there
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_infos[0].pending_reward, Uint128::from(100u128));
+}
+
+#[test]
+fn test_withdraw_all() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let staking_tokens = ["staking1", "staking2", "staking3"];
+    for staking_token in staking_tokens {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked(staking_token),
+            unbonding_period: None,
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: Addr::unchecked(staking_token),
+            assets: vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            }],
+            force: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr".into(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                staker: None,
+                callback: None,
+            })
+            .unwrap(),
+        });
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(staking_token, &[]),
+            msg,
+        )
+        .unwrap();
+    }
+
+    let msg = ExecuteMsg::DepositReward {
+        rewards: staking_tokens
+            .iter()
+            .map(|staking_token| RewardMsg {
+                staking_token: Addr::unchecked(*staking_token),
+                total_accumulation_amount: Uint128::from(100u128),
+            })
+            .collect(),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[coin(300u128, ORAI_DENOM)]),
+        msg,
+    )
+    .unwrap();
+
+    let pool_count = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res: RewardPoolCountResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::RewardPoolCount {
+                    staker_addr: Addr::unchecked("addr"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.count
+    };
+    assert_eq!(pool_count(&deps), 3);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::WithdrawAll {
+            start_after: None,
+            limit: Some(2),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(200u128, ORAI_DENOM)],
+        }))]
+    );
+    assert_eq!(res.attributes[2], attr("processed_pools", "2"));
+    assert_eq!(res.attributes[4], attr("has_more_pools", "true"));
+    assert_eq!(pool_count(&deps), 1);
+
+    // continue from the last pool of the previous pass
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::WithdrawAll {
+            start_after: Some(Addr::unchecked(res.attributes[3].value.clone())),
+            limit: Some(2),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(100u128, ORAI_DENOM)],
+        }))]
+    );
+    assert_eq!(res.attributes[2], attr("processed_pools", "1"));
+    assert_eq!(res.attributes[4], attr("has_more_pools", "false"));
+    assert_eq!(pool_count(&deps), 0);
+}
+
+#[test]
+fn test_pending_rewards() {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let update_msg = |assets: Vec<(&str, u128)>| ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: assets
+            .into_iter()
+            .map(|(denom, amount)| Asset {
+                info: AssetInfo::NativeToken {
+                    denom: denom.to_string(),
+                },
+                amount: amount.into(),
+            })
+            .collect(),
+        force: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg(vec![(ORAI_DENOM, 100), (ATOM_DENOM, 200)]),
+    )
+    .unwrap();
+
+    let deposit_msg = |amount: u128| ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(amount),
+        }],
+    };
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let pending_rewards = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res: Vec<Asset> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PendingRewards {
+                    staker_addr: Addr::unchecked("addr"),
+                    staking_token: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.into_iter()
+            .map(|asset| (asset.info, asset.amount.u128()))
+            .collect::<Vec<_>>()
+    };
+
+    // nothing is owed while the pool has no bond, the deposit waits for the first staker
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg(300),
+    )
+    .unwrap();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    assert_eq!(pending_rewards(&deps), vec![]);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg(100),
+    )
+    .unwrap();
+    assert_eq!(
+        pending_rewards(&deps),
+        vec![(native(ORAI_DENOM), 133), (native(ATOM_DENOM), 266)]
+    );
+
+    // rewards earned before the change keep the former split
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg(vec![(ORAI_DENOM, 100)]),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg(100),
+    )
+    .unwrap();
+    assert_eq!(
+        pending_rewards(&deps),
+        vec![(native(ORAI_DENOM), 233), (native(ATOM_DENOM), 266)]
+    );
+
+    // still owed once the whole bond is gone
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: None,
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        pending_rewards(&deps),
+        vec![(native(ORAI_DENOM), 233), (native(ATOM_DENOM), 266)]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: None,
+            skip_failing: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(233u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(266u128, ATOM_DENOM)],
+            })),
+        ]
+    );
+    assert_eq!(pending_rewards(&deps), vec![]);
+}
+
+#[test]
+fn test_pool_apr() {
+    let mut deps = mock_dependencies_with_balance(&[]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let staking = AssetInfo::Token {
+        contract_addr: Addr::unchecked("staking"),
+    };
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![
+            Asset {
+                info: staking.clone(),
+                amount: 100u128.into(),
+            },
+            Asset {
+                info: orai.clone(),
+                amount: 50u128.into(),
+            },
+        ],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let pool_apr = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> PoolAprResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PoolApr {
+                    staking_token: Addr::unchecked("staking"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    // emissions are still reported without any bond
+    assert_eq!(
+        pool_apr(&deps),
+        PoolAprResponse {
+            staking_token: Addr::unchecked("staking"),
+            total_bond_amount: Uint128::zero(),
+            rewards: vec![
+                RewardAprResponse {
+                    asset_info: staking.clone(),
+                    annual_emission: Uint128::from(3153600000u128),
+                    apr: None,
+                },
+                RewardAprResponse {
+                    asset_info: orai.clone(),
+                    annual_emission: Uint128::from(1576800000u128),
+                    apr: None,
+                },
+            ],
+            requires_prices: true,
+        }
+    );
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let res = pool_apr(&deps);
+    assert_eq!(res.total_bond_amount, Uint128::from(1000u128));
+    assert_eq!(
+        res.rewards[0].apr,
+        Some(Decimal::from_ratio(3153600000u128, 1000u128))
+    );
+    assert_eq!(res.rewards[1].apr, None);
+}
+
+#[test]
+fn test_reclaim_pending_reward() {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: 200u128.into(),
+            },
+        ],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let reclaim_msg = ExecuteMsg::ReclaimPendingReward {
+        staking_token: Addr::unchecked("staking"),
+        recipient: "treasury".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        reclaim_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("no pending reward to reclaim"));
+
+    // nobody is bonded, the deposit stays pending in the pool
+    let deposit_msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(300u128),
+        }],
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        reclaim_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        reclaim_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: vec![coin(100u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: vec![coin(200u128, ATOM_DENOM)],
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "reclaim_pending_reward"),
+            attr("staking_token", "staking"),
+            attr("recipient", "treasury"),
+            attr("reclaimed", format!("100{},200{}", ORAI_DENOM, ATOM_DENOM)),
+        ]
+    );
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    let pool_info = read_pool_info(&deps.storage, &asset_key).unwrap();
+    assert_eq!(pool_info.pending_reward, Uint128::zero());
+
+    // a bond landing before the reclaim in the same block keeps the pending reward for the staker
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        reclaim_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("pool has bonded stakers, the pending reward belongs to them")
+    );
+    let pool_info = read_pool_info(&deps.storage, &asset_key).unwrap();
+    assert_eq!(pool_info.pending_reward, Uint128::from(300u128));
+
+    // and the next deposit hands it over
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg,
+    )
+    .unwrap();
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: Some(Addr::unchecked("staking")),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_infos[0].pending_reward, Uint128::from(600u128));
+}
+
+#[test]
+fn test_reward_dust() {
+    let mut deps = mock_dependencies_with_balance(&[]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(3u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let reward_dust = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> Decimal {
+        from_binary::<RewardDustResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::RewardDust {
+                    staking_token: Addr::unchecked("staking"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .dust
+    };
+    assert_eq!(reward_dust(&deps), Decimal::zero());
+
+    // each deposit is smaller than the bond, a third of a unit per bond cannot be held exactly
+    let msg = ExecuteMsg::DepositReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::one(),
+        }],
+    };
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    let mut deposited = Decimal::zero();
+    for dust in [1u128, 2, 0] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("rewarder", &[coin(1u128, ORAI_DENOM)]),
+            msg.clone(),
+        )
+        .unwrap();
+        deposited = deposited + Decimal::one();
+        assert_eq!(reward_dust(&deps), Decimal::raw(dust));
+
+        // deposited == distributed + pending + dust
+        let pool_info = read_pool_info(&deps.storage, &asset_key).unwrap();
+        assert_eq!(
+            pool_info.reward_index * Decimal::from_ratio(pool_info.total_bond_amount, 1u128)
+                + Decimal::from_ratio(pool_info.pending_reward, 1u128)
+                + reward_dust(&deps),
+            deposited
+        );
+    }
+
+    // the carried dust completes the index, nothing is lost to the truncation
+    let pool_info = read_pool_info(&deps.storage, &asset_key).unwrap();
+    assert_eq!(pool_info.reward_index, Decimal::one());
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: Some(Addr::unchecked("staking")),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_infos[0].pending_reward, Uint128::from(3u128));
+}
+
+#[test]
+fn test_reward_index_extreme_magnitudes() {
+    // weights of 18 decimals tokens and long running indexes
+    let weights = [
+        1u128,
+        999_999_999u128,
+        10u128.pow(18),
+        10u128.pow(27),
+        10u128.pow(30),
+    ];
+    let indexes = [
+        Decimal::raw(1),
+        Decimal::from_ratio(1u128, 3u128),
+        Decimal::from_ratio(123_456_789u128, 1000u128),
+        Decimal::from_ratio(10u128.pow(8), 1u128),
+    ];
+    for weight in weights {
+        for index in indexes {
+            for delta in indexes {
+                let pool_index = index + delta;
+                let mut reward_info = RewardInfo {
+                    native_token: false,
+                    index,
+                    bond_amount: Uint128::from(weight),
+                    pending_reward: Uint128::zero(),
+                    pending_withdraw: vec![],
+                    weighted_amount: None,
+                };
+                before_share_change(pool_index, &mut reward_info).unwrap();
+                assert_eq!(reward_info.index, pool_index);
+
+                // floor(w * a) - floor(w * b) is at most one unit above floor(w * (a - b))
+                let exact = index_amount(Uint128::from(weight), delta).unwrap();
+                let pending = Uint256::from(reward_info.pending_reward);
+                assert!(pending >= exact && pending <= exact + Uint256::one());
+            }
+        }
+    }
+
+    // a reward too large for the payout amount is an error, not a panic
+    let mut reward_info = RewardInfo {
+        native_token: false,
+        index: Decimal::zero(),
+        bond_amount: Uint128::MAX,
+        pending_reward: Uint128::zero(),
+        pending_withdraw: vec![],
+        weighted_amount: None,
+    };
+    let err = before_share_change(Decimal::from_ratio(2u128, 1u128), &mut reward_info).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("reward amount overflows 128 bits")
+    );
+
+    // deposits of 18 decimals amounts are scaled in 256 bits
+    for amount in [10u128.pow(21), 10u128.pow(30)] {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg {
+            owner: Some(Addr::unchecked("owner")),
+            rewarder: Addr::unchecked("rewarder"),
+            governance_token: None,
+            import_open: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked("staking"),
+            unbonding_period: None,
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: Addr::unchecked("staking"),
+            assets: vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            }],
+            force: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr".into(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                staker: None,
+                callback: None,
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::DepositReward {
+            rewards: vec![RewardMsg {
+                staking_token: Addr::unchecked("staking"),
+                total_accumulation_amount: Uint128::from(amount),
+            }],
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("rewarder", &[coin(amount, ORAI_DENOM)]),
+            msg,
+        )
+        .unwrap();
+        let res: RewardInfoResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::RewardInfo {
+                    staker_addr: Addr::unchecked("addr"),
+                    staking_token: Some(Addr::unchecked("staking")),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.reward_infos[0].pending_reward, Uint128::from(amount));
+    }
+}
+
+#[test]
+fn test_pull_accrual() {
+    let mut deps = mock_dependencies_with_balance(&[coin(1000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 10u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let crank_msg = ExecuteMsg::UpdatePoolIndex {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        crank_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("pool is not in pull mode"));
+
+    let msg = ExecuteMsg::SetAccrualMode {
+        staking_token: Addr::unchecked("staking"),
+        mode: AccrualMode::Pull,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let accrual_mode = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> AccrualModeResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AccrualMode {
+                    staking_token: Addr::unchecked("staking"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        accrual_mode(&deps),
+        AccrualModeResponse {
+            staking_token: Addr::unchecked("staking"),
+            mode: AccrualMode::Pull,
+            last_distributed: Some(mock_env().block.time.seconds()),
+        }
+    );
+
+    // the rewarder no longer feeds the pool
+    let msg = ExecuteMsg::DepositReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(100u128),
+        }],
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("pool accrues its rewards from rewards per sec")
+    );
+
+    let pending_reward = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> Uint128 {
+        from_binary::<RewardInfoResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::RewardInfo {
+                    staker_addr: Addr::unchecked("addr"),
+                    staking_token: Some(Addr::unchecked("staking")),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .reward_infos[0]
+            .pending_reward
+    };
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(50);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        crank_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_pool_index"),
+            attr("staking_token", "staking"),
+            attr("accrued_amount", "500"),
+            attr("last_distributed", env.block.time.seconds().to_string()),
+        ]
+    );
+    assert_eq!(pending_reward(&deps), Uint128::from(500u128));
+
+    // only the 500 not owed yet can be accrued, so 50 of the 100 seconds
+    env.block.time = env.block.time.plus_seconds(100);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        crank_msg,
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("accrued_amount", "500"));
+    assert_eq!(pending_reward(&deps), Uint128::from(1000u128));
+    assert_eq!(
+        accrual_mode(&deps).last_distributed,
+        Some(env.block.time.seconds())
+    );
+
+    // a withdraw catches up first, nothing is funded anymore
+    env.block.time = env.block.time.plus_seconds(10);
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: Some(Addr::unchecked("staking")),
+            skip_failing: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(1000u128, ORAI_DENOM)],
+        }))]
+    );
+}
+
+#[test]
+fn test_deposit_reward_native_funds() {
+    let mut deps = mock_dependencies_with_balance(&[]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    for staking_token in ["staking", "cw20_rewarded"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked(staking_token),
+            unbonding_period: None,
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("cw20_rewarded"),
+        assets: vec![Asset {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("reward_token"),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let deposit_msg = ExecuteMsg::DepositReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(100u128),
+        }],
+    };
+    // over-sending, under-sending and an unrelated denom along the right amount
+    for funds in [
+        vec![coin(101u128, ORAI_DENOM)],
+        vec![coin(99u128, ORAI_DENOM)],
+        vec![coin(100u128, ORAI_DENOM), coin(1u128, ATOM_DENOM)],
+        vec![coin(100u128, ATOM_DENOM)],
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("rewarder", &funds),
+            deposit_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("sent funds do not match the declared rewards")
+        );
+    }
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[coin(100u128, ORAI_DENOM)]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+    // the rewarder too must send the funds along
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("sent funds do not match the declared rewards")
+    );
+
+    // funding the contract beforehand goes through DepositPrefundedReward
+    let prefunded_msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(100u128),
+        }],
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        prefunded_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[coin(100u128, ORAI_DENOM)]),
+        prefunded_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("prefunded rewards cannot be sent along, use DepositReward")
+    );
+    // the balance must cover both deposits
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![coin(199u128, ORAI_DENOM)]);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        prefunded_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("contract balance does not cover the prefunded rewards")
+    );
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![coin(200u128, ORAI_DENOM)]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        prefunded_msg,
+    )
+    .unwrap();
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    let pool_info = read_pool_info(&deps.storage, &asset_key).unwrap();
+    assert_eq!(pool_info.pending_reward, Uint128::from(200u128));
+
+    // native funds cannot back cw20 rewards
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[coin(100u128, ORAI_DENOM)]),
+        ExecuteMsg::DepositReward {
+            rewards: vec![RewardMsg {
+                staking_token: Addr::unchecked("cw20_rewarded"),
+                total_accumulation_amount: Uint128::from(100u128),
+            }],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("cw20 rewards are deposited through the Receive hook")
+    );
+}
+
+#[test]
+fn test_ibc_denom_rewards() {
+    let ibc_denom = "ibc/A2E2EEC9057A4A1C2C0A6A4C78B0239118DF5F278830F50B4A6BDD7A66506B78";
+    let mut deps =
+        mock_dependencies_with_balance(&[coin(10000u128, ibc_denom), coin(10000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: Addr::unchecked("staking"),
+            assets: vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "ibc/ bad".to_string(),
+                },
+                amount: 100u128.into(),
+            }],
+            force: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("invalid denom: ibc/ bad"));
+
+    let ibc_reward = AssetInfo::NativeToken {
+        denom: ibc_denom.to_string(),
+    };
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: ibc_reward.clone(),
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // the denom round-trips untruncated
+    let res: RewardsPerSecResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardsPerSec {
+                staking_token: Addr::unchecked("staking"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.assets,
+        vec![Asset {
+            info: ibc_reward.clone(),
+            amount: 100u128.into(),
+        }]
+    );
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let deposit_msg = ExecuteMsg::DepositReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(300u128),
+        }],
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[coin(300u128, ibc_denom)]),
+        deposit_msg,
+    )
+    .unwrap();
+
+    // switching assets settles the ibc reward into pending_withdraw
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: Some(Addr::unchecked("staking")),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.reward_infos[0].pending_withdraw,
+        vec![Asset {
+            info: ibc_reward,
+            amount: Uint128::from(300u128),
+        }]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: Some(Addr::unchecked("staking")),
+            skip_failing: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(300u128, ibc_denom)],
+        }))]
+    );
+}
+
+#[test]
+fn test_withdraw_and_swap() {
+    let mut deps =
+        mock_dependencies_with_balance(&[coin(1000u128, ORAI_DENOM), coin(1000u128, ATOM_DENOM)]);
+    let staking_token = Addr::unchecked("staking");
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: staking_token.clone(),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: staking_token.clone(),
+        assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 50u128.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: 30u128.into(),
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked("reward_token"),
+                },
+                amount: 20u128.into(),
+            },
+        ],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    // the cw20 reward was transferred to the contract beforehand too
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { .. } => SystemResult::Ok(ContractResult::Ok(
+            to_binary(&Cw20BalanceResponse {
+                balance: Uint128::from(1000u128),
+            })
+            .unwrap(),
+        )),
+        _ => panic!("unexpected wasm query"),
+    });
+    let msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: staking_token.clone(),
+            total_accumulation_amount: Uint128::from(100u128),
+        }],
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
+
+    let withdraw_msg = ExecuteMsg::WithdrawAndSwap {
+        staking_token: Some(staking_token.clone()),
+        to_asset: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        min_receive: Some(Uint128::from(80u128)),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        withdraw_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("no router to swap the rewards with")
+    );
+
+    let msg = ExecuteMsg::UpdateRouter {
+        router: Some(Addr::unchecked("router")),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let router: Option<Addr> =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Router {}).unwrap()).unwrap();
+    assert_eq!(router, Some(Addr::unchecked("router")));
+
+    // the router only knows a route from atom, each atom swaps for 5/6 orai
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { msg, .. } => match from_binary(msg).unwrap() {
+            RouterQueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations,
+            } => match &operations[0] {
+                SwapOperation::OraiSwap {
+                    offer_asset_info: AssetInfo::NativeToken { denom },
+                    ..
+                } if denom == ATOM_DENOM => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&SimulateSwapOperationsResponse {
+                        amount: offer_amount.multiply_ratio(5u128, 6u128),
+                    })
+                    .unwrap(),
+                )),
+                _ => SystemResult::Ok(ContractResult::Err("no route".to_string())),
+            },
+            _ => panic!("unexpected router query"),
+        },
+        _ => panic!("unexpected wasm query"),
+    });
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        withdraw_msg,
+    )
+    .unwrap();
+    let operations = vec![SwapOperation::OraiSwap {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+    }];
+    assert_eq!(
+        res.messages,
+        vec![
+            // the cw20 reward has no route so it is paid as it is, like any other reward transfer
+            SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: "reward_token".to_string(),
+                    msg: to_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                        recipient: "addr".to_string(),
+                        amount: Uint128::from(20u128),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                },
+                REWARD_TRANSFER_REPLY_ID,
+            ),
+            SubMsg::reply_on_success(
+                WasmMsg::Execute {
+                    contract_addr: "router".to_string(),
+                    msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                        operations,
+                        minimum_receive: None,
+                        to: None,
+                    })
+                    .unwrap(),
+                    funds: vec![coin(30u128, ATOM_DENOM)],
+                },
+                WITHDRAW_AND_SWAP_REPLY_ID,
+            ),
+        ]
+    );
+
+    let swap_reply = Reply {
+        id: WITHDRAW_AND_SWAP_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    // 50 orai withdrawn directly and 25 from the swap, below min_receive of 80
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![coin(1025u128, ORAI_DENOM)]);
+    let err = reply(deps.as_mut(), mock_env(), swap_reply.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("received amount is below the minimum receive")
+    );
+
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![coin(1040u128, ORAI_DENOM)]);
+    let res = reply(deps.as_mut(), mock_env(), swap_reply.clone()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(90u128, ORAI_DENOM)],
+        })]
+    );
+    // the pending swap is settled only once
+    let err = reply(deps.as_mut(), mock_env(), swap_reply).unwrap_err();
+    assert_eq!(err, StdError::generic_err("no pending swap"));
+}
+
+#[test]
+fn test_reward_vesting() {
+    let mut deps = mock_dependencies_with_balance(&[coin(1000u128, ORAI_DENOM)]);
+    let staking_token = Addr::unchecked("staking");
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: staking_token.clone(),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: staking_token.clone(),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // two days vest in two daily tranches
+    let msg = ExecuteMsg::UpdateRewardVestingPeriod {
+        staking_token: staking_token.clone(),
+        reward_vesting_period: Some(2 * 86400),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    let deposit_msg = ExecuteMsg::DepositReward {
+        rewards: vec![RewardMsg {
+            staking_token: staking_token.clone(),
+            total_accumulation_amount: Uint128::from(101u128),
+        }],
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[coin(101u128, ORAI_DENOM)]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+
+    // the withdraw pays nothing right away
+    let withdraw_msg = ExecuteMsg::Withdraw {
+        staking_token: Some(staking_token.clone()),
+        skip_failing: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        withdraw_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+
+    // the tranches start at the next day boundary, a later withdraw merges into them
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(3600);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("rewarder", &[coin(101u128, ORAI_DENOM)]),
+        deposit_msg,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        withdraw_msg,
+    )
+    .unwrap();
+
+    let vestings_query = QueryMsg::RewardVestings {
+        staker_addr: Addr::unchecked("addr"),
+        staking_token: staking_token.clone(),
+    };
+    let res: RewardVestingsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), vestings_query.clone()).unwrap()).unwrap();
+    let orai = |amount: u128| Asset {
+        info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        amount: amount.into(),
+    };
+    assert_eq!(
+        res,
+        RewardVestingsResponse {
+            staker_addr: Addr::unchecked("addr"),
+            staking_token: staking_token.clone(),
+            vesting_period: Some(2 * 86400),
+            vestings: vec![
+                RewardVestingResponse {
+                    rewards: vec![orai(100)],
+                    unlock_time: 1571961600,
+                    matured: false,
+                },
+                RewardVestingResponse {
+                    rewards: vec![orai(102)],
+                    unlock_time: 1572048000,
+                    matured: false,
+                },
+            ],
+        }
+    );
+
+    let claim_msg = ExecuteMsg::ClaimVested {
+        staking_token: staking_token.clone(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        claim_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("no vested rewards to claim"));
+
+    // the vesting rewards are kept out of sweeps
+    let msg = ExecuteMsg::Sweep {
+        asset_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        recipient: Addr::unchecked("owner"),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "owner".to_string(),
+            amount: vec![coin(798u128, ORAI_DENOM)],
+        })]
+    );
+    // and stay outstanding until they are claimed
+    let outstanding_query = QueryMsg::OutstandingRewards {};
+    let res: Vec<Asset> =
+        from_binary(&query(deps.as_ref(), env.clone(), outstanding_query.clone()).unwrap())
+            .unwrap();
+    assert_eq!(res, vec![orai(202)]);
+
+    env.block.time = Timestamp::from_seconds(1571961600);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        claim_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(100u128, ORAI_DENOM)],
+        })]
+    );
+    let res: RewardVestingsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), vestings_query).unwrap()).unwrap();
+    assert_eq!(
+        res.vestings,
+        vec![RewardVestingResponse {
+            rewards: vec![orai(102)],
+            unlock_time: 1572048000,
+            matured: false,
+        }]
+    );
+    let res: Vec<Asset> =
+        from_binary(&query(deps.as_ref(), env, outstanding_query).unwrap()).unwrap();
+    assert_eq!(res, vec![orai(102)]);
+}
+
+#[test]
+fn test_min_claim_amount() {
+    let mut deps = mock_dependencies_with_balance(&[coin(1000u128, ORAI_DENOM)]);
+    let staking_token = Addr::unchecked("staking");
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: staking_token.clone(),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: staking_token.clone(),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateMinClaimAmount {
+        staking_token: staking_token.clone(),
+        min_claim_amount: Uint128::from(50u128),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let res: PoolInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolInfo {
+                staking_token: staking_token.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.min_claim_amount, Uint128::from(50u128));
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    let msg = ExecuteMsg::DepositReward {
+        rewards: vec![RewardMsg {
+            staking_token: staking_token.clone(),
+            total_accumulation_amount: Uint128::from(10u128),
+        }],
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[coin(10u128, ORAI_DENOM)]),
+        msg,
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        staking_token: Some(staking_token.clone()),
+        skip_failing: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            "claim too small: pending rewards 10 are below the minimum claim amount 50"
+        )
+    );
+    // withdrawing from all pools leaves the pool out
+    let msg = ExecuteMsg::Withdraw {
+        staking_token: None,
+        skip_failing: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(res.messages, vec![]);
+    let msg = ExecuteMsg::WithdrawAll {
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(res.messages, vec![]);
+
+    // a partial unbond cannot take the rewards along
+    let msg = ExecuteMsg::Unbond {
+        staking_token: staking_token.clone(),
+        amount: Some(Uint128::from(50u128)),
+        withdraw_rewards: Some(true),
+        recipient: None,
+        limit: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            "claim too small: pending rewards 10 are below the minimum claim amount 50"
+        )
+    );
+
+    // a full exit still takes the rest
+    let msg = ExecuteMsg::Unbond {
+        staking_token: staking_token.clone(),
+        amount: None,
+        withdraw_rewards: Some(true),
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert!(res.messages.contains(&SubMsg::new(BankMsg::Send {
+        to_address: "addr".to_string(),
+        amount: vec![coin(10u128, ORAI_DENOM)],
+    })));
+}
+
+#[test]
+fn test_reward_split() {
+    let mut deps = mock_dependencies_with_balance(&[coin(1000u128, ORAI_DENOM)]);
+    let staking_token = Addr::unchecked("staking");
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: staking_token.clone(),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: staking_token.clone(),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    let deposit_msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: staking_token.clone(),
+            total_accumulation_amount: Uint128::from(100u128),
+        }],
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::SetRewardSplit {
+        beneficiary: Some("grants".to_string()),
+        bps: 10001,
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("reward split bps must be between 1 and 10000")
+    );
+    let msg = ExecuteMsg::SetRewardSplit {
+        beneficiary: Some("grants".to_string()),
+        bps: 3333,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    let split_query = QueryMsg::RewardSplit {
+        staker_addr: Addr::unchecked("addr"),
+    };
+    let res: Option<RewardSplitResponse> =
+        from_binary(&query(deps.as_ref(), mock_env(), split_query.clone()).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        Some(RewardSplitResponse {
+            beneficiary: Addr::unchecked("grants"),
+            bps: 3333,
+        })
+    );
+
+    // the beneficiary share is rounded down, both payouts sum to the pending amount
+    let withdraw_msg = ExecuteMsg::Withdraw {
+        staking_token: Some(staking_token.clone()),
+        skip_failing: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        withdraw_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(67u128, ORAI_DENOM)],
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: "grants".to_string(),
+                amount: vec![coin(33u128, ORAI_DENOM)],
+            }),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("beneficiary", "grants"),
+            attr("staker_amount", "67orai"),
+            attr("beneficiary_amount", "33orai"),
+            attr("action", "withdraw_reward"),
+        ]
+    );
+
+    // rewards withdrawn along an unbond are split too
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+    let msg = ExecuteMsg::Unbond {
+        staking_token: staking_token.clone(),
+        amount: Some(Uint128::from(50u128)),
+        withdraw_rewards: Some(true),
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[1..],
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(67u128, ORAI_DENOM)],
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: "grants".to_string(),
+                amount: vec![coin(33u128, ORAI_DENOM)],
+            }),
+        ]
+    );
+
+    let msg = ExecuteMsg::SetRewardSplit {
+        beneficiary: None,
+        bps: 0,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    let res: Option<RewardSplitResponse> =
+        from_binary(&query(deps.as_ref(), mock_env(), split_query).unwrap()).unwrap();
+    assert_eq!(res, None);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg,
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        withdraw_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(100u128, ORAI_DENOM)],
+        })]
+    );
+}
+
+#[test]
+fn test_deposit_weighted_reward() {
+    let mut deps = mock_dependencies_with_balance(&[]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    for staking_token in ["staking", "staking2"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked(staking_token),
+            unbonding_period: None,
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: Addr::unchecked(staking_token),
+            assets: vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            }],
+            force: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+
+    let deposit_msg = |weights: Vec<(&str, u64)>| ExecuteMsg::DepositWeightedReward {
+        total_amount: Uint128::from(100u128),
+        weights: weights
+            .into_iter()
+            .map(|(staking_token, weight)| PoolWeight {
+                staking_token: Addr::unchecked(staking_token),
+                weight,
+            })
+            .collect(),
+    };
+    let funds = [coin(100u128, ORAI_DENOM)];
+    for (weights, error) in [
+        (vec![], "no pools to deposit the reward to"),
+        (
+            vec![("staking", 1), ("staking2", 0)],
+            "pool weights must be positive",
+        ),
+        (
+            vec![("staking", 1), ("staking", 1)],
+            "duplicate pool staking",
+        ),
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("rewarder", &funds),
+            deposit_msg(weights),
+        )
+        .unwrap_err();
+        assert_eq!(err, StdError::generic_err(error));
+    }
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[coin(99u128, ORAI_DENOM)]),
+        deposit_msg(vec![("staking2", 1), ("staking", 2)]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("sent funds do not match the declared rewards")
+    );
+
+    // 66.6 and 33.3, the remainder goes to the heaviest pool
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &funds),
+        deposit_msg(vec![("staking2", 1), ("staking", 2)]),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes.last(),
+        Some(&attr("pool_amounts", "staking2:33,staking:67"))
+    );
+    for (staking_token, amount) in [("staking", 67u128), ("staking2", 33u128)] {
+        let asset_key = deps.api.addr_canonicalize(staking_token).unwrap();
+        let pool_info = read_pool_info(&deps.storage, &asset_key).unwrap();
+        assert_eq!(pool_info.pending_reward, Uint128::from(amount));
+    }
+}
+
+#[test]
+fn test_outstanding_rewards() {
+    let mut deps =
+        mock_dependencies_with_balance(&[coin(1000u128, ORAI_DENOM), coin(1000u128, ATOM_DENOM)]);
+    let staking_token = Addr::unchecked("staking");
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: staking_token.clone(),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: staking_token.clone(),
+        assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 50u128.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: 50u128.into(),
+            },
+        ],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // what the pool and its stakers owe, going through all of them
+    let assert_outstanding = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, expected: u128| {
+        let asset_key = deps.api.addr_canonicalize(staking_token.as_str()).unwrap();
+        let pool_info = read_pool_info(&deps.storage, &asset_key).unwrap();
+        let mut units = pool_info.pending_reward;
+        let mut split = Uint128::zero();
+        for item in STAKERS
+            .prefix(&asset_key)
+            .keys(&deps.storage, None, None, Order::Ascending)
+        {
+            let staker_addr = item.unwrap();
+            let mut reward_info = REWARDS
+                .load(&deps.storage, (&staker_addr, &asset_key))
+                .unwrap();
+            before_share_change(pool_info.reward_index, &mut reward_info).unwrap();
+            units += reward_info.pending_reward;
+            split += reward_info
+                .pending_withdraw
+                .iter()
+                .map(|pw| pw.amount)
+                .sum::<Uint128>();
+        }
+        let per_asset = units.multiply_ratio(1u128, 2u128).u128() + split.u128() / 2;
+        assert_eq!(per_asset, expected);
+
+        let res: Vec<Asset> = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::OutstandingRewards {}).unwrap(),
+        )
+        .unwrap();
+        let expected = if expected == 0 {
+            vec![]
+        } else {
+            vec![
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: expected.into(),
+                },
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    amount: expected.into(),
+                },
+            ]
+        };
+        assert_eq!(res, expected);
+    };
+    let bond = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, staker: &str, amount: u128| {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.into(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                staker: None,
+                callback: None,
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    };
+    let deposit = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, amount: u128| {
+        let msg = ExecuteMsg::DepositPrefundedReward {
+            rewards: vec![RewardMsg {
+                staking_token: Addr::unchecked("staking"),
+                total_accumulation_amount: Uint128::from(amount),
+            }],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
+    };
+
+    // deposited while nobody is bonded
+    deposit(&mut deps, 100);
+    assert_outstanding(&deps, 50);
+    bond(&mut deps, "addr", 100);
+    deposit(&mut deps, 100);
+    assert_outstanding(&deps, 100);
+    bond(&mut deps, "addr2", 100);
+    deposit(&mut deps, 200);
+    assert_outstanding(&deps, 200);
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: staking_token.clone(),
+        amount: Some(Uint128::from(50u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_outstanding(&deps, 200);
+
+    let msg = ExecuteMsg::Withdraw {
+        staking_token: Some(staking_token.clone()),
+        skip_failing: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr2", &[]), msg).unwrap();
+    assert_outstanding(&deps, 150);
+
+    deposit(&mut deps, 150);
+    assert_outstanding(&deps, 225);
+
+    // full exits take everything along
+    for staker in ["addr", "addr2"] {
+        let msg = ExecuteMsg::Unbond {
+            staking_token: staking_token.clone(),
+            amount: None,
+            withdraw_rewards: Some(true),
+            recipient: None,
+            limit: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(staker, &[]), msg).unwrap();
+    }
+    assert_outstanding(&deps, 0);
+}
+
+#[test]
+fn test_reward_events() {
+    let mut deps =
+        mock_dependencies_with_balance(&[coin(1000u128, ORAI_DENOM), coin(1000u128, ATOM_DENOM)]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let atom = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+    for (staking_token, reward_assets) in [
+        ("staking", vec![orai.clone(), atom.clone()]),
+        ("staking2", vec![orai.clone()]),
+    ] {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked(staking_token),
+            unbonding_period: None,
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: Addr::unchecked(staking_token),
+            assets: reward_assets
+                .into_iter()
+                .map(|info| Asset {
+                    info,
+                    amount: 50u128.into(),
+                })
+                .collect(),
+            force: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![
+            RewardMsg {
+                staking_token: Addr::unchecked("staking"),
+                total_accumulation_amount: Uint128::from(100u128),
+            },
+            RewardMsg {
+                staking_token: Addr::unchecked("staking2"),
+                total_accumulation_amount: Uint128::from(40u128),
+            },
+        ],
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
+    assert_eq!(
+        res.events,
+        vec![
+            Event::new(DEPOSIT_REWARD_EVENT).add_attributes([
+                attr("action", "deposit_reward"),
+                attr("asset_key", "staking"),
+                attr(format!("reward_amount:{}", ORAI_DENOM), "50"),
+                attr(format!("reward_amount:{}", ATOM_DENOM), "50"),
+                attr("reward_index", "1"),
+            ]),
+            // nobody is bonded, the index stays
+            Event::new(DEPOSIT_REWARD_EVENT).add_attributes([
+                attr("action", "deposit_reward"),
+                attr("asset_key", "staking2"),
+                attr(format!("reward_amount:{}", ORAI_DENOM), "40"),
+                attr("reward_index", "0"),
+            ]),
+        ]
+    );
+
+    let msg = ExecuteMsg::Withdraw {
+        staking_token: None,
+        skip_failing: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new(WITHDRAW_REWARD_EVENT).add_attributes([
+            attr("action", "withdraw_reward"),
+            attr("staker_addr", "addr"),
+            attr(format!("payout_amount:{}", ORAI_DENOM), "50"),
+            attr(format!("payout_amount:{}", ATOM_DENOM), "50"),
+        ])]
+    );
+
+    // nothing paid out, nothing to index
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(res.events, vec![]);
+}
+
+#[test]
+fn test_withdraw_failing_cw20_transfer() {
+    let mut deps = mock_dependencies_with_balance(&[coin(1000u128, ORAI_DENOM)]);
+    let staking_token = Addr::unchecked("staking");
+    let reward_token = AssetInfo::Token {
+        contract_addr: Addr::unchecked("reward_token"),
+    };
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: staking_token.clone(),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: staking_token.clone(),
+        assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 80u128.into(),
+            },
+            Asset {
+                info: reward_token.clone(),
+                amount: 20u128.into(),
+            },
+        ],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".into(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    // the rewards were transferred to the contract beforehand
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { .. } => SystemResult::Ok(ContractResult::Ok(
+            to_binary(&Cw20BalanceResponse {
+                balance: Uint128::from(1000u128),
+            })
+            .unwrap(),
+        )),
+        _ => panic!("unexpected wasm query"),
+    });
+    let deposit_msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: staking_token.clone(),
+            total_accumulation_amount: Uint128::from(100u128),
+        }],
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg.clone(),
+    )
+    .unwrap();
+
+    // the cw20 transfer replies on error with its position in the payout
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: Some(staking_token.clone()),
+            skip_failing: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(80u128, ORAI_DENOM)],
+            }),
+            SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: "reward_token".to_string(),
+                    msg: to_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                        recipient: "addr".to_string(),
+                        amount: Uint128::from(20u128),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                },
+                REWARD_TRANSFER_REPLY_ID,
+            ),
+        ]
+    );
+
+    // a token rejecting the transfer is named in the error
+    let failed_reply = Reply {
+        id: REWARD_TRANSFER_REPLY_ID,
+        result: SubMsgResult::Err("token is frozen".to_string()),
+    };
+    let err = reply(deps.as_mut(), mock_env(), failed_reply.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("cannot transfer 20reward_token to addr: token is frozen")
+    );
+
+    // skipping the failing transfer pays the other rewards and keeps it for later
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg,
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: Some(staking_token.clone()),
+            skip_failing: Some(true),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    let res = reply(deps.as_mut(), mock_env(), failed_reply).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "skip_failing_transfer"),
+            attr("recipient", "addr"),
+            attr("asset", "20reward_token"),
+            attr("error", "token is frozen"),
+        ]
+    );
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: Some(staking_token),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.reward_infos[0].pending_withdraw,
+        vec![Asset {
+            info: reward_token,
+            amount: Uint128::from(20u128),
+        }]
+    );
+}
@@ -1,20 +1,44 @@
-use crate::contract::{execute, instantiate, query, query_get_pools_infomation};
+use crate::contract::{
+    execute, instantiate, query, query_get_pools_infomation, reply, CONTRACT_NAME,
+};
 use crate::msg::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, LockInfosResponse, PoolInfoResponse, QueryMsg,
-    RewardInfoResponse, RewardInfoResponseItem, RewardMsg, StakedBalanceAtHeightResponse,
-    TotalStakedAtHeightResponse,
+    BondCallbackExecuteMsg, BondCallbackMsg, BoostCurve, BoostResponse, ClaimHookExecuteMsg,
+    ClaimHookMsg, ClaimableResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, ExportKind,
+    ExportRecord, ExportStateResponse, HookFailuresResponseItem, HookKind, HooksResponse,
+    InfoResponse, InstantiateMsg, IsStakerResponse, LockCountResponse, LockInfo, LockInfoResponse,
+    LockInfosResponse, LockPeriodResponse, Member, MemberListResponse, MemberResponse,
+    MembershipResponse, PoolInfoResponse, PoolLocksResponseItem, PoolStakersResponseItem,
+    PoolStatus, PoolValueResponse, QueryMsg, RewardInfoResponse, RewardInfoResponseItem,
+    RewardInfosBatchResponseItem, RewardMsg, RewardStateAtHeightResponse, RewardsPerSecResponse,
+    SnapshotChangeResponseItem, SnapshotConsistencyResponse, StakeChangedExecuteMsg,
+    StakeChangedHookMsg, StakedBalanceAtHeightResponse, StakedBalanceAtTimeResponse,
+    StakerCountResponse, StakerInfoResponseItem, SummaryPoolItem, SummaryResponse,
+    TotalPowerAtHeightResponse, TotalStakedAtHeightResponse, TotalUnbondingResponse,
+    TotalWeightResponse, UnbondMsg, UnbondingPeriodResponse, UnbondingSummaryResponse,
+    VotingPowerAtHeightResponse,
+};
+use crate::rewards::{CLAIM_HOOK_REPLY_ID, STAKE_HOOK_REPLY_ID};
+use crate::staking::{BOND_CALLBACK_REPLY_ID, ZAP_IN_REPLY_ID};
+use crate::state::{
+    insert_lock_info, read_block_at_time, read_pool_info, record_block_time, store_pool_info,
+    PoolInfo, RewardInfo, DEFAULT_MAX_UNBONDING_PERIOD, MAX_BLOCK_TIMES, MAX_LIMIT, REWARDS,
+    STAKERS,
 };
-use crate::state::{store_pool_info, PoolInfo, MAX_LIMIT};
 use cosmwasm_std::testing::{
     mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockQuerier,
-    MockStorage,
+    MockStorage, MOCK_CONTRACT_ADDR,
 };
 use cosmwasm_std::{
-    attr, coin, from_binary, to_binary, Addr, Api, BankMsg, CosmosMsg, Decimal, OwnedDeps,
-    StdError, SubMsg, Uint128, WasmMsg,
+    attr, coin, from_binary, to_binary, Addr, Api, BankMsg, Binary, ContractResult, CosmosMsg,
+    Decimal, OwnedDeps, Reply, StdError, SubMsg, SubMsgResponse, SubMsgResult, SystemResult,
+    Timestamp, Uint128, WasmMsg, WasmQuery,
 };
-use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg};
 use oraiswap::asset::{Asset, AssetInfo, ORAI_DENOM};
+use oraiswap::oracle::{ExchangeRateItem, ExchangeRateResponse};
+use oraiswap::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
+};
 use oraiswap::testing::ATOM_DENOM;
 
 #[test]
@@ -47,6 +71,8 @@ fn test_query_all_pool_keys() {
             pending_reward: amount.clone(),
             total_bond_amount: amount.clone(),
             reward_index: Decimal::zero(),
+            deprecated: false,
+            total_weighted_amount: None,
         };
         store_pool_info(storage, &asset_key, &pool_info).unwrap();
     }
@@ -70,6 +96,8 @@ fn test_bond_tokens() {
     let msg = InstantiateMsg {
         owner: Some(Addr::unchecked("owner")),
         rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -78,6 +106,7 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::RegisterAsset {
         staking_token: Addr::unchecked("staking"),
         unbonding_period: None,
+        native_token: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -86,7 +115,11 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
 
     let info = mock_info("staking", &[]);
@@ -129,9 +162,20 @@ fn test_bond_tokens() {
         PoolInfoResponse {
             staking_token: Addr::unchecked("staking"),
             total_bond_amount: Uint128::from(100u128),
+            total_weighted_amount: Uint128::from(100u128),
             reward_index: Decimal::zero(),
             pending_reward: Uint128::zero(),
             unbonding_period: None,
+            unbond_fee: Decimal::zero(),
+            instant_unbond_penalty: None,
+            min_bond_amount: Uint128::zero(),
+            min_claim_amount: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            staker_count: 1,
+            open_deposit: false,
+            deprecated: false,
+            pool_admin: None,
+            status: PoolStatus::default(),
         }
     );
 
@@ -139,7 +183,11 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr2".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -158,9 +206,20 @@ fn test_bond_tokens() {
         PoolInfoResponse {
             staking_token: Addr::unchecked("staking"),
             total_bond_amount: Uint128::from(200u128),
+            total_weighted_amount: Uint128::from(200u128),
             reward_index: Decimal::zero(),
             pending_reward: Uint128::zero(),
-            unbonding_period: None
+            unbonding_period: None,
+            unbond_fee: Decimal::zero(),
+            instant_unbond_penalty: None,
+            min_bond_amount: Uint128::zero(),
+            min_claim_amount: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            staker_count: 2,
+            open_deposit: false,
+            deprecated: false,
+            pool_admin: None,
+            status: PoolStatus::default(),
         }
     );
 }
@@ -172,7 +231,10 @@ fn test_unbond() {
     // unbond 150 tokens; failed
     let msg = ExecuteMsg::Unbond {
         staking_token: Addr::unchecked("staking"),
-        amount: Uint128::from(150u128),
+        amount: Some(Uint128::from(150u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -187,7 +249,10 @@ fn test_unbond() {
     // normal unbond
     let msg = ExecuteMsg::Unbond {
         staking_token: Addr::unchecked("staking"),
-        amount: Uint128::from(100u128),
+        amount: Some(Uint128::from(100u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -229,9 +294,20 @@ fn test_unbond() {
         PoolInfoResponse {
             staking_token: Addr::unchecked("staking"),
             total_bond_amount: Uint128::zero(),
+            total_weighted_amount: Uint128::zero(),
             reward_index: Decimal::from_ratio(300u128, 100u128),
             pending_reward: Uint128::zero(),
-            unbonding_period: None
+            unbonding_period: None,
+            unbond_fee: Decimal::zero(),
+            instant_unbond_penalty: None,
+            min_bond_amount: Uint128::zero(),
+            min_claim_amount: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            open_deposit: false,
+            deprecated: false,
+            pool_admin: None,
+            status: PoolStatus::default(),
         }
     );
 
@@ -266,7 +342,10 @@ fn test_unbonding_period_happy_case() {
 
     let msg = ExecuteMsg::Unbond {
         staking_token: Addr::unchecked("staking"),
-        amount: Uint128::from(50u128),
+        amount: Some(Uint128::from(50u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
     };
     let info = mock_info("addr", &[]);
     let mut unbond_env = mock_env();
@@ -302,6 +381,8 @@ fn test_unbonding_period_happy_case() {
             start_after: None,
             limit: None,
             order: None,
+            start_time: None,
+            end_time: None,
         },
     )
     .unwrap();
@@ -326,7 +407,10 @@ fn test_unbonding_period_happy_case() {
     // Unbond and withdraw_lock
     let msg = ExecuteMsg::Unbond {
         staking_token: Addr::unchecked("staking"),
-        amount: Uint128::from(50u128),
+        amount: Some(Uint128::from(50u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
     };
     let _res = execute(deps.as_mut(), unbond_env.clone(), info.clone(), msg).unwrap();
 
@@ -339,6 +423,8 @@ fn test_unbonding_period_happy_case() {
             start_after: None,
             limit: None,
             order: None,
+            start_time: None,
+            end_time: None,
         },
     )
     .unwrap();
@@ -367,6 +453,8 @@ fn test_unbonding_period_happy_case() {
             attr("staker_addr", "addr"),
             attr("amount", Uint128::from(50u128).to_string()),
             attr("staking_token", "staking"),
+            attr("processed_locks", "1"),
+            attr("has_more_locks", "false"),
         ]
     );
     assert_eq!(
@@ -396,7 +484,10 @@ fn test_unbonding_period_happy_case() {
 
     let msg = ExecuteMsg::Unbond {
         staking_token: Addr::unchecked("staking"),
-        amount: Uint128::from(0u128),
+        amount: Some(Uint128::from(0u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
     };
     let _res = execute(deps.as_mut(), unbond_env.clone(), info, msg).unwrap();
 
@@ -409,6 +500,8 @@ fn test_unbonding_period_happy_case() {
             start_after: None,
             limit: None,
             order: None,
+            start_time: None,
+            end_time: None,
         },
     )
     .unwrap();
@@ -423,6 +516,8 @@ fn test_unbonding_period_happy_case() {
             attr("staker_addr", "addr"),
             attr("amount", Uint128::from(50u128).to_string()),
             attr("staking_token", "staking"),
+            attr("processed_locks", "1"),
+            attr("has_more_locks", "false"),
         ]
     );
     assert_eq!(
@@ -449,7 +544,10 @@ pub fn test_multiple_lock() {
     for i in 0..MAX_LIMIT {
         let msg = ExecuteMsg::Unbond {
             staking_token: Addr::unchecked("staking"),
-            amount: Uint128::from(1u128),
+            amount: Some(Uint128::from(1u128)),
+            withdraw_rewards: None,
+            recipient: None,
+            limit: None,
         };
         let mut clone_unbonded = unbond_env.clone();
         clone_unbonded.block.time = clone_unbonded
@@ -467,6 +565,8 @@ pub fn test_multiple_lock() {
             start_after: None,
             limit: Some(30),
             order: None,
+            start_time: None,
+            end_time: None,
         },
     )
     .unwrap();
@@ -481,7 +581,10 @@ pub fn test_multiple_lock() {
 
     let msg = ExecuteMsg::Unbond {
         staking_token: Addr::unchecked("staking"),
-        amount: Uint128::from(0u128),
+        amount: Some(Uint128::from(0u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
     };
 
     let res = execute(deps.as_mut(), unbond_env.clone(), info.clone(), msg).unwrap();
@@ -493,6 +596,8 @@ pub fn test_multiple_lock() {
             attr("staker_addr", "addr"),
             attr("amount", Uint128::from(MAX_LIMIT as u128).to_string()),
             attr("staking_token", "staking"),
+            attr("processed_locks", MAX_LIMIT.to_string()),
+            attr("has_more_locks", "false"),
         ]
     );
 
@@ -519,6 +624,8 @@ pub fn test_multiple_lock() {
             start_after: None,
             limit: None,
             order: None,
+            start_time: None,
+            end_time: None,
         },
     )
     .unwrap();
@@ -563,11 +670,29 @@ fn test_balance_and_bonded_snapshot() {
     assert_eq!(balance.total, Uint128::from(100u128));
     assert_eq!(balance.height, mock_env.block.height + 1);
 
+    // heights before the first checkpoint are zero
+    let res = query(
+        deps.as_ref(),
+        mock_env.clone(),
+        QueryMsg::TotalStakedAtHeight {
+            asset_key: Addr::unchecked("staking"),
+            height: Some(1),
+        },
+    )
+    .unwrap();
+    let balance = from_binary::<TotalStakedAtHeightResponse>(&res).unwrap();
+    assert_eq!(balance.total, Uint128::zero());
+    assert_eq!(balance.height, 1);
+
     // bond 100 tokens
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "sender".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
 
     let mut skip_100_blocks_env = mock_env.clone();
@@ -607,7 +732,10 @@ fn test_balance_and_bonded_snapshot() {
 
     let msg = ExecuteMsg::Unbond {
         staking_token: Addr::unchecked("staking"),
-        amount: Uint128::from(100u128),
+        amount: Some(Uint128::from(100u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
     };
 
     let mut skip_200_blocks_env = mock_env.clone();
@@ -646,100 +774,6005 @@ fn test_balance_and_bonded_snapshot() {
     // because the _setup_staking already bond 100 tokens
     assert_eq!(balance.total, Uint128::from(100u8));
     assert_eq!(balance.height, mock_env.block.height + 201);
+
+    // the changelogs hold the value before each change
+    let height = mock_env.block.height;
+    let changelog = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, msg: QueryMsg| {
+        let changes: Vec<SnapshotChangeResponseItem> =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        changes
+    };
+    assert_eq!(
+        changelog(
+            &deps,
+            QueryMsg::StakedBalanceChangelog {
+                asset_key: Addr::unchecked("staking"),
+                address: "sender".into(),
+                start_after_height: None,
+                limit: None,
+            }
+        ),
+        vec![
+            SnapshotChangeResponseItem {
+                height: height + 100,
+                old_value: None,
+            },
+            SnapshotChangeResponseItem {
+                height: height + 200,
+                old_value: Some(Uint128::from(100u128)),
+            },
+        ]
+    );
+    assert_eq!(
+        changelog(
+            &deps,
+            QueryMsg::StakedBalanceChangelog {
+                asset_key: Addr::unchecked("staking"),
+                address: "sender".into(),
+                start_after_height: Some(height + 100),
+                limit: Some(1),
+            }
+        ),
+        vec![SnapshotChangeResponseItem {
+            height: height + 200,
+            old_value: Some(Uint128::from(100u128)),
+        }]
+    );
+    assert_eq!(
+        changelog(
+            &deps,
+            QueryMsg::StakedTotalChangelog {
+                asset_key: Addr::unchecked("staking"),
+                start_after_height: Some(height),
+                limit: None,
+            }
+        ),
+        vec![
+            SnapshotChangeResponseItem {
+                height: height + 100,
+                old_value: Some(Uint128::from(100u128)),
+            },
+            SnapshotChangeResponseItem {
+                height: height + 200,
+                old_value: Some(Uint128::from(200u128)),
+            },
+        ]
+    );
 }
 
-fn _setup_staking(unbonding_period: Option<u64>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
-    let mut deps = mock_dependencies_with_balance(&[
-        coin(10000000000u128, ORAI_DENOM),
-        coin(20000000000u128, ATOM_DENOM),
-    ]);
-    let msg = InstantiateMsg {
-        owner: Some(Addr::unchecked("owner")),
-        rewarder: Addr::unchecked("rewarder"),
+#[test]
+fn test_reward_state_at_height() {
+    let mut deps = _setup_staking(None);
+    let height = mock_env().block.height;
+    let reward_state = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, height: u64| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardStateAtHeight {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: Addr::unchecked("staking"),
+                height: Some(height),
+            },
+        )
+        .map(|res| from_binary::<RewardStateAtHeightResponse>(&res).unwrap())
     };
 
-    let info = mock_info("addr", &[]);
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // the pool was registered in this block, nothing was checkpointed before it
+    assert_eq!(
+        reward_state(&deps, height).unwrap_err(),
+        StdError::generic_err(format!(
+            "no reward index checkpoint of the pool at height {}",
+            height
+        ))
+    );
+    assert_eq!(
+        reward_state(&deps, height + 1).unwrap(),
+        RewardStateAtHeightResponse {
+            staker_addr: Addr::unchecked("addr"),
+            staking_token: Addr::unchecked("staking"),
+            height: height + 1,
+            bond_amount: Uint128::from(100u128),
+            reward_index: Decimal::from_ratio(3u128, 1u128),
+        }
+    );
 
-    // will also add to the index the pending rewards from before the migration
-    let msg = ExecuteMsg::UpdateRewardsPerSec {
-        staking_token: Addr::unchecked("staking"),
-        assets: vec![
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: 100u128.into(),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ATOM_DENOM.to_string(),
-                },
-                amount: 200u128.into(),
-            },
-        ],
+    let mut env = mock_env();
+    env.block.height += 10;
+    let msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(100u128),
+        }],
     };
+    execute(deps.as_mut(), env.clone(), mock_info("rewarder", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), env, mock_info("staking", &[]), msg).unwrap();
 
-    let info = mock_info("owner", &[]);
-    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // the state of a height is the one at its start
+    let res = reward_state(&deps, height + 10).unwrap();
+    assert_eq!(res.bond_amount, Uint128::from(100u128));
+    assert_eq!(res.reward_index, Decimal::from_ratio(3u128, 1u128));
+    let res = reward_state(&deps, height + 11).unwrap();
+    assert_eq!(res.bond_amount, Uint128::from(150u128));
+    assert_eq!(res.reward_index, Decimal::from_ratio(4u128, 1u128));
+}
 
-    // register asset
-    let msg = ExecuteMsg::RegisterAsset {
+#[test]
+fn test_pool_status() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::SetPoolStatus {
         staking_token: Addr::unchecked("staking"),
-        unbonding_period,
+        bond_paused: true,
+        unbond_paused: true,
+        withdraw_paused: true,
     };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
 
-    let info = mock_info("owner", &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
+    let data = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolInfo {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let pool_info: PoolInfoResponse = from_binary(&data).unwrap();
     assert_eq!(
-        res.attributes,
-        vec![
-            attr("action", "register_asset"),
-            attr("staking_token", "staking"),
-            attr(
-                "unbonding_period",
-                unbonding_period.unwrap_or(0).to_string()
-            )
-        ]
+        pool_info.status,
+        PoolStatus {
+            bond_paused: true,
+            unbond_paused: true,
+            withdraw_paused: true,
+        }
     );
-    // bond 100 tokens
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+
+    let bond_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
     });
-    let info = mock_info("staking", &[]);
-    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("bonding is paused for this pool")
+    );
 
-    let msg = ExecuteMsg::DepositReward {
+    let unbond_msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(50u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        unbond_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("unbonding is paused for this pool")
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: Some(Addr::unchecked("staking")),
+            skip_failing: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("withdrawing is paused for this pool")
+    );
+
+    // withdrawing from all pools skips the paused one
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: None,
+            skip_failing: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    // rewards keep accruing while paused
+    let msg = ExecuteMsg::DepositPrefundedReward {
         rewards: vec![RewardMsg {
             staking_token: Addr::unchecked("staking"),
-            total_accumulation_amount: Uint128::from(300u128),
+            total_accumulation_amount: Uint128::from(100u128),
         }],
     };
-    let info = mock_info("rewarder", &[]);
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
 
-    // will also add to the index the pending rewards from before the migration
-    let msg = ExecuteMsg::UpdateRewardsPerSec {
+    let msg = ExecuteMsg::SetPoolStatus {
         staking_token: Addr::unchecked("staking"),
-        assets: vec![
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: 100u128.into(),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ATOM_DENOM.to_string(),
-                },
-                amount: 100u128.into(),
-            },
-        ],
+        bond_paused: false,
+        unbond_paused: false,
+        withdraw_paused: false,
     };
-    let info = mock_info("owner", &[]);
-    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    deps
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        unbond_msg,
+    )
+    .unwrap();
+
+    let data = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolInfo {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let pool_info: PoolInfoResponse = from_binary(&data).unwrap();
+    assert_eq!(
+        pool_info.reward_index,
+        Decimal::from_ratio(400u128, 100u128)
+    );
+}
+
+#[test]
+fn test_unbond_fee() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::UpdateUnbondFee {
+        staking_token: Addr::unchecked("staking"),
+        unbond_fee: Decimal::one(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unbond fee must be less than 1"));
+
+    let msg = ExecuteMsg::UpdateUnbondFee {
+        staking_token: Addr::unchecked("staking"),
+        unbond_fee: Decimal::percent(5),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // no fee is charged without a fee collector
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(10u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::from(10u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    let msg = ExecuteMsg::UpdateConfig {
+        fee_collector: Some(Addr::unchecked("collector")),
+        config_timelock: None,
+        max_unbonding_period: None,
+        max_lock_entries: None,
+        governance_token: None,
+        oracle: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // 5% of 30 rounds down to 1
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(30u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "collector".to_string(),
+                    amount: Uint128::from(1u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr".to_string(),
+                    amount: Uint128::from(29u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+    assert_eq!(res.attributes[0], attr("fee_amount", "1"));
+
+    let data = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolInfo {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let pool_info: PoolInfoResponse = from_binary(&data).unwrap();
+    assert_eq!(pool_info.unbond_fee, Decimal::percent(5));
+    assert_eq!(pool_info.total_bond_amount, Uint128::from(60u128));
+}
+
+#[test]
+fn test_instant_unbond() {
+    let mut deps = _setup_staking(Some(100));
+
+    let instant_unbond_msg = |amount: u128| ExecuteMsg::InstantUnbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Uint128::from(amount),
+    };
+
+    // rejected while no penalty is configured
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        instant_unbond_msg(10),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("instant unbond is not enabled for this pool")
+    );
+
+    let msg = ExecuteMsg::UpdateInstantUnbondPenalty {
+        staking_token: Addr::unchecked("staking"),
+        penalty: Decimal::percent(3),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        instant_unbond_msg(10),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("fee collector is not set"));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        fee_collector: Some(Addr::unchecked("collector")),
+        config_timelock: None,
+        max_unbonding_period: None,
+        max_lock_entries: None,
+        governance_token: None,
+        oracle: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // penalty plus payout always adds up to the unbonded amount
+    for (amount, penalty_amount) in [(33u128, 0u128), (34, 1), (1, 0), (32, 0)] {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr", &[]),
+            instant_unbond_msg(amount),
+        )
+        .unwrap();
+
+        let mut messages = vec![];
+        if penalty_amount > 0 {
+            messages.push(SubMsg::new(WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "collector".to_string(),
+                    amount: Uint128::from(penalty_amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            }));
+        }
+        messages.push(SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::from(amount - penalty_amount),
+            })
+            .unwrap(),
+            funds: vec![],
+        }));
+        // the last unbond empties the position and also sends the pending rewards
+        if amount != 32 {
+            assert_eq!(res.messages, messages);
+        } else {
+            assert_eq!(res.messages[2..], messages);
+        }
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "instant_unbond"),
+                attr("staker_addr", "addr"),
+                attr("staking_token", "staking"),
+                attr("amount", amount.to_string()),
+                attr("penalty_amount", penalty_amount.to_string()),
+                attr("payout_amount", (amount - penalty_amount).to_string()),
+            ]
+        );
+    }
+
+    // no lock was created
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::LockInfos {
+            staker_addr: Addr::unchecked("addr"),
+            staking_token: Addr::unchecked("staking"),
+            start_after: None,
+            limit: None,
+            order: None,
+            start_time: None,
+            end_time: None,
+        },
+    )
+    .unwrap();
+    let res: LockInfosResponse = from_binary(&res).unwrap();
+    assert_eq!(res.lock_infos, vec![]);
+}
+
+#[test]
+fn test_deprecate_pool() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::DeprecatePool {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let bond_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg,
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("pool is deprecated"));
+
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+        force: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(res, StdError::generic_err("pool is deprecated"));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardsPerSec {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let res: RewardsPerSecResponse = from_binary(&res).unwrap();
+    assert_eq!(res.assets, vec![]);
+
+    // deposits to a deprecated pool are ignored
+    let msg = ExecuteMsg::DepositReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(100u128),
+        }],
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
+
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert!(pools[0].pool_info.deprecated);
+    assert_eq!(
+        pools[0].pool_info.reward_index,
+        Decimal::from_ratio(300u128, 100u128)
+    );
+
+    // rewards earned before the deprecation and the stake can still be withdrawn
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: Some(Addr::unchecked("staking")),
+            skip_failing: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(99u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(199u128, ATOM_DENOM)],
+            })),
+        ]
+    );
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(100u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+}
+
+#[test]
+fn test_migrate_staking_token() {
+    let mut deps = _setup_staking(Some(100));
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } => {
+            let balance = if contract_addr == "staking2" {
+                100u128
+            } else {
+                50u128
+            };
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&Cw20BalanceResponse {
+                    balance: Uint128::from(balance),
+                })
+                .unwrap(),
+            ))
+        }
+        _ => panic!("unexpected query"),
+    });
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(40u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    // the new token must hold every bonded token
+    let msg = ExecuteMsg::MigrateStakingToken {
+        staking_token: Addr::unchecked("staking"),
+        new_staking_token: Addr::unchecked("staking3"),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("new staking token balance is lower than the total bond amount")
+    );
+
+    let msg = ExecuteMsg::MigrateStakingToken {
+        staking_token: Addr::unchecked("staking"),
+        new_staking_token: Addr::unchecked("staking2"),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate_staking_token"),
+            attr("staking_token", "staking"),
+            attr("old_staking_token", "staking"),
+            attr("new_staking_token", "staking2"),
+        ]
+    );
+
+    let data = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolInfo {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let pool_info: PoolInfoResponse = from_binary(&data).unwrap();
+    assert_eq!(pool_info.staking_token, Addr::unchecked("staking2"));
+    assert_eq!(pool_info.total_bond_amount, Uint128::from(60u128));
+
+    // only the new token can bond, into the same pool
+    let bond_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(10u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("staking token was migrated"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking2", &[]),
+        bond_msg,
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("staking_token", "staking"));
+
+    // the lock created before the migration is paid out with the new token
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(101);
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::zero()),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env, mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking2".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::from(40u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // the pool is found by the token it stakes now
+    let pools: Vec<PoolInfoResponse> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolByStakingToken {
+                staking_token: "staking2".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pools.len(), 1);
+    assert_eq!(pools[0].staking_token, Addr::unchecked("staking2"));
+    assert_eq!(pools[0].total_bond_amount, Uint128::from(70u128));
+    for staking_token in ["staking", "staking3"] {
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolByStakingToken {
+                staking_token: staking_token.to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, StdError::not_found("pool"));
+    }
+
+    // the reverse index follows the migration and keeps the pool key
+    for (staking_token, expected) in [
+        ("staking2", vec![Addr::unchecked("staking")]),
+        ("staking", vec![]),
+    ] {
+        let pools: Vec<Addr> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PoolsByStakingToken {
+                    staking_token: staking_token.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(pools, expected);
+    }
+}
+
+#[test]
+fn test_update_unbonding_period() {
+    let mut deps = _setup_staking(Some(100));
+
+    let unbond_msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(10u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        unbond_msg.clone(),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::UpdateUnbondingPeriod {
+        staking_token: Addr::unchecked("staking"),
+        new_period: 200,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_unbonding_period"),
+            attr("staking_token", "staking"),
+            attr("old_unbonding_period", "100"),
+            attr("new_unbonding_period", "200"),
+        ]
+    );
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(1);
+    execute(deps.as_mut(), env, mock_info("addr", &[]), unbond_msg).unwrap();
+
+    // the first lock is grandfathered
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UnbondingPeriod {
+            staking_token: Addr::unchecked("staking"),
+            staker_addr: Some(Addr::unchecked("addr")),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let res: UnbondingPeriodResponse = from_binary(&res).unwrap();
+    let unbond_time = mock_env().block.time;
+    assert_eq!(
+        res,
+        UnbondingPeriodResponse {
+            unbonding_period: Some(200),
+            locks: vec![
+                LockPeriodResponse {
+                    amount: Uint128::from(10u128),
+                    unlock_time: unbond_time.plus_seconds(100).seconds(),
+                    unbonding_period: Some(100),
+                },
+                LockPeriodResponse {
+                    amount: Uint128::from(10u128),
+                    unlock_time: unbond_time.plus_seconds(201).seconds(),
+                    unbonding_period: Some(200),
+                },
+            ],
+        }
+    );
+
+    // increases are capped, the maximum is configurable
+    let msg = ExecuteMsg::UpdateUnbondingPeriod {
+        staking_token: Addr::unchecked("staking"),
+        new_period: DEFAULT_MAX_UNBONDING_PERIOD + 1,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("unbonding period exceeds the maximum")
+    );
+
+    let update_config_msg = ExecuteMsg::UpdateConfig {
+        fee_collector: None,
+        config_timelock: None,
+        max_unbonding_period: Some(DEFAULT_MAX_UNBONDING_PERIOD + 1),
+        max_lock_entries: None,
+        governance_token: None,
+        oracle: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_config_msg,
+    )
+    .unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+}
+
+#[test]
+fn test_blacklist() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::SetBlacklist {
+        address: Addr::unchecked("addr"),
+        blacklisted: true,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::IsBlacklisted {
+            address: Addr::unchecked("addr"),
+        },
+    )
+    .unwrap();
+    assert!(from_binary::<bool>(&res).unwrap());
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Blacklist {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let blacklist: Vec<Addr> = from_binary(&res).unwrap();
+    assert_eq!(blacklist, vec![Addr::unchecked("addr")]);
+
+    // the staker is the cw20 sender, not the staking token calling the hook
+    let bond_msg = |sender: &str| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sender.to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                staker: None,
+                callback: None,
+            })
+            .unwrap(),
+        })
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg("addr"),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("staker is blacklisted"));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg("addr2"),
+    )
+    .unwrap();
+
+    // funds of a blacklisted staker are never trapped
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: Some(Addr::unchecked("staking")),
+            skip_failing: None,
+        },
+    )
+    .unwrap();
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(100u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    let msg = ExecuteMsg::SetBlacklist {
+        address: Addr::unchecked("addr"),
+        blacklisted: false,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg("addr"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_sweep() {
+    let mut deps = _setup_staking(Some(100));
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } => {
+            let balance = if contract_addr == "staking" {
+                150u128
+            } else {
+                30u128
+            };
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&Cw20BalanceResponse {
+                    balance: Uint128::from(balance),
+                })
+                .unwrap(),
+            ))
+        }
+        _ => panic!("unexpected query"),
+    });
+
+    // 60 tokens stay bonded and 40 are locked
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(40u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let sweep_msg = |asset_info: AssetInfo| ExecuteMsg::Sweep {
+        asset_info,
+        recipient: Addr::unchecked("recipient"),
+    };
+    let staking_token = AssetInfo::Token {
+        contract_addr: Addr::unchecked("staking"),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        sweep_msg(staking_token.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        sweep_msg(staking_token),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "sweep"),
+            attr("asset", "staking"),
+            attr("recipient", "recipient"),
+            attr("swept_amount", "50"),
+            attr("protected_amount", "100"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "recipient".to_string(),
+                amount: Uint128::from(50u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // the rewards waiting in pending_withdraw are protected
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        sweep_msg(AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "sweep"),
+            attr("asset", ATOM_DENOM),
+            attr("recipient", "recipient"),
+            attr("swept_amount", "19999999801"),
+            attr("protected_amount", "199"),
+        ]
+    );
+
+    // tokens unrelated to any pool are swept entirely
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        sweep_msg(AssetInfo::Token {
+            contract_addr: Addr::unchecked("random"),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "sweep"),
+            attr("asset", "random"),
+            attr("recipient", "recipient"),
+            attr("swept_amount", "30"),
+            attr("protected_amount", "0"),
+        ]
+    );
+}
+
+#[test]
+fn test_force_unbond() {
+    let mut deps = _setup_staking(Some(100));
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(40u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::ForceUnbond {
+        staking_token: Addr::unchecked("staking"),
+        staker_addr: Addr::unchecked("addr"),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    // the bond, the lock and the rewards all go back to the staker
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "force_unbond"),
+            attr("staker_addr", "addr"),
+            attr("staking_token", "staking"),
+            attr("amount", "60"),
+            attr("locked_amount", "40"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(99u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(199u128, ATOM_DENOM)],
+            })),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr".to_string(),
+                    amount: Uint128::from(100u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::zero());
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TotalStakedAtHeight {
+            asset_key: Addr::unchecked("staking"),
+            height: Some(mock_env().block.height + 1),
+        },
+    )
+    .unwrap();
+    let res: TotalStakedAtHeightResponse = from_binary(&res).unwrap();
+    assert_eq!(res.total, Uint128::zero());
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("staker has no bond in this pool")
+    );
+}
+
+#[test]
+fn test_pool_admin() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking2"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::SetPoolAdmin {
+        staking_token: Addr::unchecked("staking"),
+        pool_admin: Some(Addr::unchecked("partner")),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("partner", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolInfo {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let pool_info: PoolInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(pool_info.pool_admin, Some(Addr::unchecked("partner")));
+
+    let pool_msgs = |staking_token: &str| {
+        vec![
+            ExecuteMsg::UpdateRewardsPerSec {
+                staking_token: Addr::unchecked(staking_token),
+                assets: vec![Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: 100u128.into(),
+                }],
+                force: None,
+            },
+            ExecuteMsg::UpdateUnbondingPeriod {
+                staking_token: Addr::unchecked(staking_token),
+                new_period: 100,
+            },
+            ExecuteMsg::SetPoolStatus {
+                staking_token: Addr::unchecked(staking_token),
+                bond_paused: true,
+                unbond_paused: false,
+                withdraw_paused: false,
+            },
+        ]
+    };
+    for msg in pool_msgs("staking") {
+        execute(deps.as_mut(), mock_env(), mock_info("partner", &[]), msg).unwrap();
+    }
+
+    // other pools, global config and owner only actions stay out of reach
+    let mut msgs = pool_msgs("staking2");
+    msgs.extend([
+        ExecuteMsg::UpdateConfig {
+            fee_collector: None,
+            config_timelock: None,
+            max_unbonding_period: None,
+            max_lock_entries: None,
+            governance_token: None,
+            oracle: None,
+        },
+        ExecuteMsg::SetPoolAdmin {
+            staking_token: Addr::unchecked("staking2"),
+            pool_admin: Some(Addr::unchecked("partner")),
+        },
+        ExecuteMsg::UpdateUnbondFee {
+            staking_token: Addr::unchecked("staking"),
+            unbond_fee: Decimal::percent(1),
+        },
+        ExecuteMsg::DeprecatePool {
+            staking_token: Addr::unchecked("staking"),
+        },
+        ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: Addr::unchecked("staking"),
+            assets: vec![],
+            force: Some(true),
+        },
+    ]);
+    for msg in msgs {
+        let res = execute(deps.as_mut(), mock_env(), mock_info("partner", &[]), msg).unwrap_err();
+        assert_eq!(res, StdError::generic_err("unauthorized"));
+    }
+
+    let msg = ExecuteMsg::SetPoolAdmin {
+        staking_token: Addr::unchecked("staking"),
+        pool_admin: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    for msg in pool_msgs("staking") {
+        let res = execute(deps.as_mut(), mock_env(), mock_info("partner", &[]), msg).unwrap_err();
+        assert_eq!(res, StdError::generic_err("unauthorized"));
+    }
+}
+
+#[test]
+fn test_bond_native_token() {
+    let mut deps = mock_dependencies();
+    let denom = "factory/oraiswap/lp";
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked(denom),
+        unbonding_period: None,
+        native_token: Some(true),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let bond_msg = ExecuteMsg::Bond {
+        asset_info: AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        },
+        staker: None,
+    };
+    let wrong_funds = vec![
+        vec![],
+        vec![coin(100u128, ORAI_DENOM)],
+        vec![coin(100u128, denom), coin(100u128, ORAI_DENOM)],
+    ];
+    for funds in wrong_funds {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr", &funds),
+            bond_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            StdError::generic_err(format!("must send exactly one {} coin", denom))
+        );
+    }
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[coin(100u128, denom)]),
+        ExecuteMsg::Bond {
+            asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("staking"),
+            },
+            staker: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("cw20 staking tokens must be bonded through the Receive hook")
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[coin(100u128, denom)]),
+        bond_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond"),
+            attr("funder", "addr"),
+            attr("staker_addr", "addr"),
+            attr("staking_token", denom),
+            attr("amount", "100"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolInfo {
+            staking_token: Addr::unchecked(denom),
+        },
+    )
+    .unwrap();
+    let pool_info: PoolInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(pool_info.staking_token, Addr::unchecked(denom));
+    assert_eq!(pool_info.total_bond_amount, Uint128::from(100u128));
+
+    // native staking tokens are paid out with a bank send
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked(denom),
+        amount: Some(Uint128::from(40u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(40u128, denom)],
+        })]
+    );
+}
+
+#[test]
+fn test_bond_on_behalf() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "vesting".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: Some("beneficiary".to_string()),
+            callback: None,
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond"),
+            attr("funder", "vesting"),
+            attr("staker_addr", "beneficiary"),
+            attr("staking_token", "staking"),
+            attr("amount", "50"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardInfo {
+            staking_token: None,
+            staker_addr: Addr::unchecked("vesting"),
+        },
+    )
+    .unwrap();
+    let res: RewardInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(res.reward_infos, vec![]);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::StakedBalanceAtHeight {
+            asset_key: Addr::unchecked("staking"),
+            address: "beneficiary".to_string(),
+            height: Some(mock_env().block.height + 1),
+        },
+    )
+    .unwrap();
+    let res: StakedBalanceAtHeightResponse = from_binary(&res).unwrap();
+    assert_eq!(res.balance, Uint128::from(50u128));
+
+    // only the credited staker can exit the position
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(50u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("vesting", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("beneficiary", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "beneficiary".to_string(),
+                amount: Uint128::from(50u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // a blacklisted funder cannot bond for someone else
+    let msg = ExecuteMsg::SetBlacklist {
+        address: Addr::unchecked("vesting"),
+        blacklisted: true,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "vesting".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: Some("beneficiary".to_string()),
+            callback: None,
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap_err();
+    assert_eq!(res, StdError::generic_err("staker is blacklisted"));
+}
+
+#[test]
+fn test_bond_callback() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "vault".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: Some("addr".to_string()),
+            callback: Some(to_binary("deposit 1").unwrap()),
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: "vault".to_string(),
+                msg: to_binary(&BondCallbackExecuteMsg::BondCallback(BondCallbackMsg {
+                    staker: Addr::unchecked("addr"),
+                    asset_info: AssetInfo::Token {
+                        contract_addr: Addr::unchecked("staking"),
+                    },
+                    bonded_amount: Uint128::from(50u128),
+                    total_bond: Uint128::from(150u128),
+                    msg: to_binary("deposit 1").unwrap(),
+                }))
+                .unwrap(),
+                funds: vec![],
+            },
+            BOND_CALLBACK_REPLY_ID,
+        )]
+    );
+
+    // a failing callback keeps the bond
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: BOND_CALLBACK_REPLY_ID,
+            result: SubMsgResult::Err("unknown variant".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond_callback_failed"),
+            attr("error", "unknown variant"),
+        ]
+    );
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staking_token: Some(Addr::unchecked("staking")),
+                staker_addr: Addr::unchecked("addr"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_infos[0].bond_amount, Uint128::from(150u128));
+}
+
+#[test]
+fn test_unbond_full_balance() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(40u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    // the remaining 60 tokens and the pending rewards are sent back
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: None,
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(99u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(199u128, ATOM_DENOM)],
+            })),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr".to_string(),
+                    amount: Uint128::from(60u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::zero());
+
+    // the staker is removed from the pool once nothing is left
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardInfos {
+            staking_token: Addr::unchecked("staking"),
+            start_after: None,
+            limit: None,
+            order: None,
+        },
+    )
+    .unwrap();
+    let res: Vec<RewardInfoResponse> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![]);
+}
+
+#[test]
+fn test_restake() {
+    let mut deps = _setup_staking(Some(100));
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(30u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr", &[]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(50);
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(20u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Restake {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("no matured locks to restake"));
+
+    // only the first lock has matured
+    env.block.time = env.block.time.plus_seconds(70);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "restake"),
+            attr("staker_addr", "addr"),
+            attr("staking_token", "staking"),
+            attr("restaked_amount", "30"),
+            attr("locked_amount", "20"),
+        ]
+    );
+
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::from(80u128));
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RewardInfo {
+            staking_token: Some(Addr::unchecked("staking")),
+            staker_addr: Addr::unchecked("addr"),
+        },
+    )
+    .unwrap();
+    let res: RewardInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(res.reward_infos[0].bond_amount, Uint128::from(80u128));
+
+    let res = execute(deps.as_mut(), env, mock_info("addr", &[]), msg).unwrap_err();
+    assert_eq!(res, StdError::generic_err("no matured locks to restake"));
+}
+
+#[test]
+fn test_cancel_unbond() {
+    let mut deps = _setup_staking(Some(100));
+
+    let mut env = mock_env();
+    let first_unlock = env.block.time.plus_seconds(100).seconds();
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(30u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr", &[]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(10);
+    let second_unlock = env.block.time.plus_seconds(100).seconds();
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(20u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr", &[]), msg).unwrap();
+
+    let cancel_msg = |amount: u128| ExecuteMsg::CancelUnbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Uint128::from(amount),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        cancel_msg(51),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Cannot cancel more than the locked amount")
+    );
+
+    // the first lock is consumed, the second one is split
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        cancel_msg(40),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "cancel_unbond"),
+            attr("staker_addr", "addr"),
+            attr("staking_token", "staking"),
+            attr("amount", "40"),
+            attr(
+                "unlock_times",
+                format!("{},{}", first_unlock, second_unlock)
+            ),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::LockInfos {
+            staker_addr: Addr::unchecked("addr"),
+            staking_token: Addr::unchecked("staking"),
+            start_after: None,
+            limit: None,
+            order: None,
+            start_time: None,
+            end_time: None,
+        },
+    )
+    .unwrap();
+    let res: LockInfosResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        res.lock_infos,
+        vec![LockInfoResponse {
+            amount: Uint128::from(10u128),
+            unlock_time: second_unlock,
+        }]
+    );
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::from(90u128));
+
+    let res = execute(deps.as_mut(), env, mock_info("addr", &[]), cancel_msg(20)).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Cannot cancel more than the locked amount")
+    );
+}
+
+#[test]
+fn test_unbond_with_rewards() {
+    let mut deps = _setup_staking(None);
+
+    let deposit_msg = |amount: u128| ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(amount),
+        }],
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg(200),
+    )
+    .unwrap();
+
+    // the 200 deposited rewards are settled before the payout
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(50u128)),
+        withdraw_rewards: Some(true),
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr".to_string(),
+                    amount: Uint128::from(50u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(199u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(299u128, ATOM_DENOM)],
+            })),
+        ]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Withdraw {
+            staking_token: Some(Addr::unchecked("staking")),
+            skip_failing: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+
+    // a single reward unit rounds down to nothing for both assets
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg(1),
+    )
+    .unwrap();
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(10u128)),
+        withdraw_rewards: Some(true),
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::from(10u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+}
+
+#[test]
+fn test_unbond_many() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking2"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking2", &[]), msg).unwrap();
+
+    let invalid_unbonds = vec![
+        vec![],
+        vec![
+            UnbondMsg {
+                staking_token: Addr::unchecked("staking2"),
+                amount: Some(Uint128::from(51u128)),
+            },
+            UnbondMsg {
+                staking_token: Addr::unchecked("staking"),
+                amount: Some(Uint128::from(10u128)),
+            },
+        ],
+        vec![UnbondMsg {
+            staking_token: Addr::unchecked("staking3"),
+            amount: None,
+        }],
+    ];
+    for unbonds in invalid_unbonds {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr", &[]),
+            ExecuteMsg::UnbondMany { unbonds },
+        )
+        .unwrap_err();
+    }
+
+    let msg = ExecuteMsg::UnbondMany {
+        unbonds: vec![
+            UnbondMsg {
+                staking_token: Addr::unchecked("staking"),
+                amount: Some(Uint128::from(30u128)),
+            },
+            UnbondMsg {
+                staking_token: Addr::unchecked("staking2"),
+                amount: None,
+            },
+        ],
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "unbond_many"),
+            attr("action", "unbond"),
+            attr("staker_addr", "addr"),
+            attr("amount", "30"),
+            attr("staking_token", "staking"),
+            attr("action", "unbond"),
+            attr("staker_addr", "addr"),
+            attr("amount", "50"),
+            attr("staking_token", "staking2"),
+        ]
+    );
+    // pending rewards are paid out with the first pool
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(99u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(199u128, ATOM_DENOM)],
+            })),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr".to_string(),
+                    amount: Uint128::from(30u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "staking2".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr".to_string(),
+                    amount: Uint128::from(50u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+}
+
+#[test]
+fn test_bond_split() {
+    let mut deps = _setup_staking(None);
+
+    for staking_token in ["staking2", "other"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked(staking_token),
+            unbonding_period: None,
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+    // a second reward program on the same collateral
+    let staking2_key = deps.api.addr_canonicalize("staking2").unwrap();
+    let staking_raw = deps.api.addr_canonicalize("staking").unwrap();
+    store_pool_info(
+        deps.as_mut().storage,
+        &staking2_key,
+        &PoolInfo {
+            staking_token: staking_raw,
+            pending_reward: Uint128::zero(),
+            total_bond_amount: Uint128::zero(),
+            reward_index: Decimal::zero(),
+            deprecated: false,
+            total_weighted_amount: None,
+        },
+    )
+    .unwrap();
+
+    let bond_split = |allocations: Vec<(&str, u128)>| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::BondSplit {
+                allocations: allocations
+                    .into_iter()
+                    .map(|(pool, amount)| {
+                        (
+                            AssetInfo::Token {
+                                contract_addr: Addr::unchecked(pool),
+                            },
+                            Uint128::from(amount),
+                        )
+                    })
+                    .collect(),
+            })
+            .unwrap(),
+        })
+    };
+
+    // mismatched sum
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_split(vec![("staking", 50), ("staking2", 40)]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("allocations must sum to the sent amount")
+    );
+
+    // unknown pool
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_split(vec![("staking", 50), ("staking3", 50)]),
+    )
+    .unwrap_err();
+
+    // pool accepting another token
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_split(vec![("other", 100)]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("pool staking token does not match the sent token")
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_split(vec![("staking", 60), ("staking2", 40)]),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond"),
+            attr("funder", "addr"),
+            attr("staker_addr", "addr"),
+            attr("staking_token", "staking"),
+            attr("amount", "60"),
+            attr("action", "bond"),
+            attr("funder", "addr"),
+            attr("staker_addr", "addr"),
+            attr("staking_token", "staking2"),
+            attr("amount", "40"),
+        ]
+    );
+
+    for (staking_token, total_bond_amount) in [("staking", 160u128), ("staking2", 40u128)] {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolInfo {
+                staking_token: Addr::unchecked(staking_token),
+            },
+        )
+        .unwrap();
+        let pool_info: PoolInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            pool_info.total_bond_amount,
+            Uint128::from(total_bond_amount)
+        );
+    }
+}
+
+#[test]
+fn test_migrate_stake() {
+    let mut deps = _setup_staking(None);
+
+    for staking_token in ["staking2", "other"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked(staking_token),
+            unbonding_period: None,
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+    // the new incentive program runs on the same staking token, the balance covers both pools
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { .. } => SystemResult::Ok(ContractResult::Ok(
+            to_binary(&Cw20BalanceResponse {
+                balance: Uint128::from(100u128),
+            })
+            .unwrap(),
+        )),
+        _ => panic!("unexpected query"),
+    });
+    let msg = ExecuteMsg::MigrateStakingToken {
+        staking_token: Addr::unchecked("staking2"),
+        new_staking_token: Addr::unchecked("staking"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let pools: Vec<Addr> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolsByStakingToken {
+                staking_token: "staking".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        pools,
+        vec![Addr::unchecked("staking"), Addr::unchecked("staking2")]
+    );
+
+    let migrate_msg = ExecuteMsg::MigrateStake {
+        from_staking_token: Addr::unchecked("staking"),
+        to_staking_token: Addr::unchecked("staking2"),
+        amount: Uint128::from(40u128),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        migrate_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("stake migration is not enabled"));
+
+    let enable_msg = |to_staking_token: &str| ExecuteMsg::SetStakeMigration {
+        from_staking_token: Addr::unchecked("staking"),
+        to_staking_token: Addr::unchecked(to_staking_token),
+        enabled: true,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        enable_msg("staking2"),
+    )
+    .unwrap_err();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        enable_msg("other"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("pools do not share the same staking token")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        enable_msg("staking2"),
+    )
+    .unwrap();
+
+    // the destination pool must accept bonds
+    let msg = ExecuteMsg::SetPoolStatus {
+        staking_token: Addr::unchecked("staking2"),
+        bond_paused: true,
+        unbond_paused: false,
+        withdraw_paused: false,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        migrate_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("bonding is paused for this pool")
+    );
+    let msg = ExecuteMsg::SetPoolStatus {
+        staking_token: Addr::unchecked("staking2"),
+        bond_paused: false,
+        unbond_paused: false,
+        withdraw_paused: false,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        migrate_msg,
+    )
+    .unwrap();
+    // no token is moved
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate_stake"),
+            attr("staker_addr", "addr"),
+            attr("from_staking_token", "staking"),
+            attr("to_staking_token", "staking2"),
+            attr("amount", "40"),
+        ]
+    );
+
+    for (staking_token, amount) in [("staking", 60u128), ("staking2", 40u128)] {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolInfo {
+                staking_token: Addr::unchecked(staking_token),
+            },
+        )
+        .unwrap();
+        let pool_info: PoolInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(pool_info.total_bond_amount, Uint128::from(amount));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::StakedBalanceAtHeight {
+                asset_key: Addr::unchecked(staking_token),
+                address: "addr".into(),
+                height: Some(mock_env().block.height + 1),
+            },
+        )
+        .unwrap();
+        let balance = from_binary::<StakedBalanceAtHeightResponse>(&res).unwrap();
+        assert_eq!(balance.balance, Uint128::from(amount));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TotalStakedAtHeight {
+                asset_key: Addr::unchecked(staking_token),
+                height: Some(mock_env().block.height + 1),
+            },
+        )
+        .unwrap();
+        let total = from_binary::<TotalStakedAtHeightResponse>(&res).unwrap();
+        assert_eq!(total.total, Uint128::from(amount));
+    }
+}
+
+#[test]
+fn test_unbond_to_recipient() {
+    let unbonding_period = 100u64;
+    let mut deps = _setup_staking(Some(unbonding_period));
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(40u128)),
+        withdraw_rewards: None,
+        recipient: Some("cold".to_string()),
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    // nothing is matured yet, the new lock stays with the staker
+    assert_eq!(res.messages, vec![]);
+
+    let mut unbond_env = mock_env();
+    unbond_env.block.time = unbond_env.block.time.plus_seconds(unbonding_period + 1);
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::zero()),
+        withdraw_rewards: None,
+        recipient: Some(String::new()),
+        limit: None,
+    };
+    execute(
+        deps.as_mut(),
+        unbond_env.clone(),
+        mock_info("addr", &[]),
+        msg,
+    )
+    .unwrap_err();
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::zero()),
+        withdraw_rewards: None,
+        recipient: Some("cold".to_string()),
+        limit: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        unbond_env.clone(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "cold".to_string(),
+                amount: Uint128::from(40u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "unbond"),
+            attr("staker_addr", "addr"),
+            attr("amount", "40"),
+            attr("staking_token", "staking"),
+            attr("claimer", "addr"),
+            attr("recipient", "cold"),
+            attr("processed_locks", "1"),
+            attr("has_more_locks", "false"),
+        ]
+    );
+
+    // the lock was removed
+    let res = execute(deps.as_mut(), unbond_env, mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(res.messages, vec![]);
+}
+
+#[test]
+fn test_min_bond_amount() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::UpdateMinBondAmount {
+        staking_token: Addr::unchecked("staking"),
+        min_bond_amount: Uint128::from(50u128),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolInfo {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let pool_info: PoolInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(pool_info.min_bond_amount, Uint128::from(50u128));
+
+    let bond_msg = |amount: u128| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr2".to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                staker: None,
+                callback: None,
+            })
+            .unwrap(),
+        })
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg(30),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("bond amount is below the minimum")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg(60),
+    )
+    .unwrap();
+    // top-ups of a valid position are fine
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg(1),
+    )
+    .unwrap();
+
+    let unbond_msg = |amount: Option<u128>| ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: amount.map(Uint128::from),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        unbond_msg(Some(60)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            "remaining bond amount is below the minimum, unbond the full amount instead"
+        )
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        unbond_msg(Some(50)),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        unbond_msg(None),
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolInfo {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let pool_info: PoolInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(pool_info.total_bond_amount, Uint128::from(61u128));
+}
+
+#[test]
+fn test_lock_entries_cap() {
+    let unbonding_period = 100u64;
+    let mut deps = _setup_staking(Some(unbonding_period));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        fee_collector: None,
+        config_timelock: None,
+        max_unbonding_period: None,
+        max_lock_entries: Some(2),
+        governance_token: None,
+        oracle: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let unbond_msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(10u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let lock_count = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LockCount {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: Addr::unchecked("staking"),
+            },
+        )
+        .unwrap();
+        from_binary::<LockCountResponse>(&res).unwrap()
+    };
+
+    // unbonds of the same block share one entry
+    for _ in 0..2 {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr", &[]),
+            unbond_msg.clone(),
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        lock_count(&deps),
+        LockCountResponse {
+            count: 1,
+            max_lock_entries: 2,
+        }
+    );
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::LockInfos {
+            staker_addr: Addr::unchecked("addr"),
+            staking_token: Addr::unchecked("staking"),
+            start_after: None,
+            limit: None,
+            order: None,
+            start_time: None,
+            end_time: None,
+        },
+    )
+    .unwrap();
+    let lock_infos: LockInfosResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        lock_infos.lock_infos,
+        vec![LockInfoResponse {
+            amount: Uint128::from(20u128),
+            unlock_time: mock_env()
+                .block
+                .time
+                .plus_seconds(unbonding_period)
+                .seconds(),
+        }]
+    );
+
+    let mut unbond_env = mock_env();
+    unbond_env.block.time = unbond_env.block.time.plus_seconds(1);
+    execute(
+        deps.as_mut(),
+        unbond_env.clone(),
+        mock_info("addr", &[]),
+        unbond_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(lock_count(&deps).count, 2);
+
+    unbond_env.block.time = unbond_env.block.time.plus_seconds(1);
+    let err = execute(
+        deps.as_mut(),
+        unbond_env.clone(),
+        mock_info("addr", &[]),
+        unbond_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("too many unbonding locks, withdraw the matured ones first")
+    );
+
+    // matured locks are withdrawn before the new one is created
+    unbond_env.block.time = unbond_env.block.time.plus_seconds(unbonding_period);
+    execute(
+        deps.as_mut(),
+        unbond_env,
+        mock_info("addr", &[]),
+        unbond_msg,
+    )
+    .unwrap();
+    assert_eq!(lock_count(&deps).count, 1);
+}
+
+#[test]
+fn test_unbond_lock_limit() {
+    let unbonding_period = 100u64;
+    let mut deps = _setup_staking(Some(unbonding_period));
+
+    let mut unbond_env = mock_env();
+    for _ in 0..3 {
+        let msg = ExecuteMsg::Unbond {
+            staking_token: Addr::unchecked("staking"),
+            amount: Some(Uint128::from(10u128)),
+            withdraw_rewards: None,
+            recipient: None,
+            limit: None,
+        };
+        execute(
+            deps.as_mut(),
+            unbond_env.clone(),
+            mock_info("addr", &[]),
+            msg,
+        )
+        .unwrap();
+        unbond_env.block.time = unbond_env.block.time.plus_seconds(1);
+    }
+
+    unbond_env.block.time = unbond_env.block.time.plus_seconds(unbonding_period);
+    let claim_msg = |limit: Option<u32>| ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::zero()),
+        withdraw_rewards: None,
+        recipient: None,
+        limit,
+    };
+    let res = execute(
+        deps.as_mut(),
+        unbond_env.clone(),
+        mock_info("addr", &[]),
+        claim_msg(Some(2)),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "unbond"),
+            attr("staker_addr", "addr"),
+            attr("amount", "20"),
+            attr("staking_token", "staking"),
+            attr("processed_locks", "2"),
+            attr("has_more_locks", "true"),
+        ]
+    );
+
+    // the remaining matured lock is claimed by the next call
+    let res = execute(
+        deps.as_mut(),
+        unbond_env.clone(),
+        mock_info("addr", &[]),
+        claim_msg(None),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "unbond"),
+            attr("staker_addr", "addr"),
+            attr("amount", "10"),
+            attr("staking_token", "staking"),
+            attr("processed_locks", "1"),
+            attr("has_more_locks", "false"),
+        ]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        unbond_env,
+        mock_info("addr", &[]),
+        claim_msg(None),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+}
+
+#[test]
+fn test_bond_from() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::BondFrom {
+        asset_info: AssetInfo::Token {
+            contract_addr: Addr::unchecked("staking"),
+        },
+        amount: Uint128::from(50u128),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr2", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "addr2".to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Uint128::from(50u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            },
+            1,
+        )]
+    );
+    // nothing is bonded before the transfer went through
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::from(100u128));
+
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond"),
+            attr("funder", "addr2"),
+            attr("staker_addr", "addr2"),
+            attr("staking_token", "staking"),
+            attr("amount", "50"),
+        ]
+    );
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::from(150u128));
+
+    // the pending bond is consumed
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap_err();
+
+    // native staking tokens are sent in funds instead
+    let msg = ExecuteMsg::BondFrom {
+        asset_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        amount: Uint128::from(50u128),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr2", &[]), msg).unwrap_err();
+}
+
+#[test]
+fn test_compound() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    // half of the rewards are paid in the staking token itself
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked("staking"),
+                },
+                amount: 100u128.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            },
+        ],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let compound_msg = ExecuteMsg::Compound {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        compound_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("nothing to compound"));
+
+    // the staking token rewards were transferred to the contract beforehand, along the bonds
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { .. } => SystemResult::Ok(ContractResult::Ok(
+            to_binary(&Cw20BalanceResponse {
+                balance: Uint128::from(1000u128),
+            })
+            .unwrap(),
+        )),
+        _ => panic!("unexpected query"),
+    });
+    let deposit_msg = |amount: u128| ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(amount),
+        }],
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg(300),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        compound_msg,
+    )
+    .unwrap();
+    // only the other reward asset leaves the contract
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(150u128, ORAI_DENOM)],
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("staker_addr", "addr"),
+            attr("staking_token", "staking"),
+            attr("compounded_amount", "150"),
+        ]
+    );
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::from(250u128));
+
+    let reward_info = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: Some(Addr::unchecked("staking")),
+            },
+        )
+        .unwrap();
+        from_binary::<RewardInfoResponse>(&res)
+            .unwrap()
+            .reward_infos[0]
+            .clone()
+    };
+    let item = reward_info(&deps);
+    assert_eq!(item.bond_amount, Uint128::from(250u128));
+    assert_eq!(item.pending_reward, Uint128::zero());
+    assert_eq!(item.pending_withdraw, vec![]);
+
+    // the compounded amount only earns from the next deposit on
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("rewarder", &[]),
+        deposit_msg(250),
+    )
+    .unwrap();
+    assert_eq!(reward_info(&deps).pending_reward, Uint128::from(250u128));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::StakedBalanceAtHeight {
+            asset_key: Addr::unchecked("staking"),
+            address: "addr".into(),
+            height: Some(mock_env().block.height + 1),
+        },
+    )
+    .unwrap();
+    let balance = from_binary::<StakedBalanceAtHeightResponse>(&res).unwrap();
+    assert_eq!(balance.balance, Uint128::from(250u128));
+}
+
+#[test]
+fn test_claim_delegate() {
+    let mut deps = _setup_staking(None);
+
+    let withdraw_for = ExecuteMsg::WithdrawFor {
+        staker: Addr::unchecked("addr"),
+        staking_token: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        withdraw_for.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+
+    let msg = ExecuteMsg::SetClaimDelegate {
+        delegate: Some("keeper".to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    let claim_delegate = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ClaimDelegate {
+                staker_addr: Addr::unchecked("addr"),
+            },
+        )
+        .unwrap();
+        from_binary::<Option<Addr>>(&res).unwrap()
+    };
+    assert_eq!(claim_delegate(&deps), Some(Addr::unchecked("keeper")));
+
+    // the delegate acts on its own position, never on the staker's one
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(10u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), msg).unwrap_err();
+    let msg = ExecuteMsg::SetClaimDelegate {
+        delegate: Some("keeper2".to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("keeper", &[]), msg).unwrap();
+    assert_eq!(claim_delegate(&deps), Some(Addr::unchecked("keeper")));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        withdraw_for.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(99u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(199u128, ATOM_DENOM)],
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw_reward_for"),
+            attr("staker_addr", "addr"),
+            attr("delegate", "keeper"),
+        ]
+    );
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::from(100u128));
+
+    // revoking takes effect right away
+    let msg = ExecuteMsg::SetClaimDelegate { delegate: None };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(claim_delegate(&deps), None);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        withdraw_for,
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+}
+
+#[test]
+fn test_emergency_unbond() {
+    let unbonding_period = 100u64;
+    let mut deps = _setup_staking(Some(unbonding_period));
+
+    // a broken reward asset can block withdrawals
+    let msg = ExecuteMsg::SetPoolStatus {
+        staking_token: Addr::unchecked("staking"),
+        bond_paused: false,
+        unbond_paused: false,
+        withdraw_paused: true,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardInfo {
+            staker_addr: Addr::unchecked("addr"),
+            staking_token: Some(Addr::unchecked("staking")),
+        },
+    )
+    .unwrap();
+    let reward_info = from_binary::<RewardInfoResponse>(&res)
+        .unwrap()
+        .reward_infos[0]
+        .clone();
+
+    let msg = ExecuteMsg::EmergencyUnbond {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    // no reward is sent, the bond goes through the unbonding period
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "emergency_unbond"),
+            attr("staker_addr", "addr"),
+            attr("staking_token", "staking"),
+            attr("amount", "100"),
+            attr(
+                "forfeited_pending_reward",
+                reward_info.pending_reward.to_string()
+            ),
+            attr(
+                "forfeited_pending_withdraw",
+                format!("99{},199{}", ORAI_DENOM, ATOM_DENOM)
+            ),
+            attr("action", "unbonding"),
+            attr("staker_addr", "addr"),
+            attr("amount", "100"),
+            attr("staking_token", "staking"),
+            attr(
+                "unlock_time",
+                mock_env()
+                    .block
+                    .time
+                    .plus_seconds(unbonding_period)
+                    .seconds()
+                    .to_string()
+            ),
+        ]
+    );
+
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::zero());
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardInfo {
+            staker_addr: Addr::unchecked("addr"),
+            staking_token: None,
+        },
+    )
+    .unwrap();
+    let res = from_binary::<RewardInfoResponse>(&res).unwrap();
+    assert_eq!(res.reward_infos, vec![]);
+
+    let err = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("staker has no bond in this pool")
+    );
+}
+
+#[test]
+fn test_boost() {
+    let mut deps = _setup_staking(None);
+    let staking_token = Addr::unchecked("staking");
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr2".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let boost_msg = ExecuteMsg::Boost {
+        staking_token: staking_token.clone(),
+        lock_weeks: 26,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        boost_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("boosting is not enabled for this pool")
+    );
+
+    // only the owner sets the curve
+    let msg = ExecuteMsg::SetBoostCurve {
+        staking_token: staking_token.clone(),
+        curve: Some(BoostCurve {
+            max_multiplier: Decimal::from_ratio(3u128, 1u128),
+            max_lock_weeks: 52,
+        }),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Boost {
+            staking_token: staking_token.clone(),
+            lock_weeks: 53,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("lock weeks must be between 1 and 52")
+    );
+
+    // half of the max commitment gets half of the extra weight
+    let expires_at = mock_env().block.time.seconds() + 26 * 7 * 24 * 60 * 60;
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), boost_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "boost"),
+            attr("staker_addr", "addr"),
+            attr("staking_token", "staking"),
+            attr("multiplier", "2"),
+            attr("expires_at", expires_at.to_string()),
+        ]
+    );
+    let query_boost = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        from_binary::<BoostResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Boost {
+                    staker_addr: Addr::unchecked("addr"),
+                    staking_token: Addr::unchecked("staking"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let boost = query_boost(&deps);
+    assert_eq!(boost.multiplier, Decimal::from_ratio(2u128, 1u128));
+    assert_eq!(boost.expires_at, Some(expires_at));
+    assert_eq!(boost.bond_amount, Uint128::from(100u128));
+    assert_eq!(boost.weighted_amount, Uint128::from(200u128));
+
+    // the commitment cannot be shortened
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::Boost {
+            staking_token: staking_token.clone(),
+            lock_weeks: 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("a boost commitment cannot be shortened")
+    );
+
+    let pending_reward = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, staker: &str| {
+        from_binary::<RewardInfoResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::RewardInfo {
+                    staker_addr: Addr::unchecked(staker),
+                    staking_token: Some(Addr::unchecked("staking")),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .reward_infos[0]
+            .pending_reward
+    };
+    let (addr_before, addr2_before) = (
+        pending_reward(&deps, "addr"),
+        pending_reward(&deps, "addr2"),
+    );
+
+    // the boosted staker takes 200 of the 300 weight
+    let msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: staking_token.clone(),
+            total_accumulation_amount: Uint128::from(300u128),
+        }],
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
+    assert_eq!(
+        pending_reward(&deps, "addr") - addr_before,
+        Uint128::from(200u128)
+    );
+    assert_eq!(
+        pending_reward(&deps, "addr2") - addr2_before,
+        Uint128::from(100u128)
+    );
+
+    // the bond is committed until the boost expires
+    let unbond_msg = ExecuteMsg::Unbond {
+        staking_token: staking_token.clone(),
+        amount: None,
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        unbond_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!("bond is committed by a boost until {}", expires_at))
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::EmergencyUnbond {
+            staking_token: staking_token.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!("bond is committed by a boost until {}", expires_at))
+    );
+    let expire_msg = ExecuteMsg::ExpireBoost {
+        staking_token: staking_token.clone(),
+        staker_addr: Addr::unchecked("addr"),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        expire_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("boost has not expired yet"));
+
+    // once matured anyone can bring the weight back to the bond
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(26 * 7 * 24 * 60 * 60);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        expire_msg,
+    )
+    .unwrap();
+    let boost = query_boost(&deps);
+    assert_eq!(boost.multiplier, Decimal::one());
+    assert_eq!(boost.expires_at, None);
+    assert_eq!(boost.weighted_amount, Uint128::from(100u128));
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(
+        pools[0].pool_info.total_weighted_amount,
+        Uint128::from(200u128)
+    );
+
+    execute(deps.as_mut(), env, mock_info("addr", &[]), unbond_msg).unwrap();
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::from(100u128));
+    assert_eq!(
+        pools[0].pool_info.total_weighted_amount,
+        Uint128::from(100u128)
+    );
+}
+
+fn _setup_staking(unbonding_period: Option<u64>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+    ]);
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // will also add to the index the pending rewards from before the migration
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: 200u128.into(),
+            },
+        ],
+        force: None,
+    };
+
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // register asset
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period,
+        native_token: None,
+    };
+
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "register_asset"),
+            attr("staking_token", "staking"),
+            attr(
+                "unbonding_period",
+                unbonding_period.unwrap_or(0).to_string()
+            )
+        ]
+    );
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(300u128),
+        }],
+    };
+    let info = mock_info("rewarder", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+
+    // will also add to the index the pending rewards from before the migration
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            },
+        ],
+        force: None,
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    deps
+}
+
+#[test]
+fn test_query_pool_stakers() {
+    let mut deps = _setup_staking(None);
+
+    for i in 1..5u128 {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: format!("addr{}", i),
+            amount: Uint128::from(10 * i),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                staker: None,
+                callback: None,
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    }
+    let msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(200u128),
+        }],
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
+    // addr keeps its rewards after leaving, so it is still a staker of the pool without a bond
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: None,
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let mut stakers: Vec<PoolStakersResponseItem> = vec![];
+    loop {
+        let page: Vec<PoolStakersResponseItem> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PoolStakers {
+                    staking_token: Addr::unchecked("staking"),
+                    start_after: stakers.last().map(|staker| staker.staker_addr.clone()),
+                    limit: Some(3),
+                    order: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        if page.is_empty() {
+            break;
+        }
+        stakers.extend(page);
+    }
+    stakers.sort_by(|a, b| a.staker_addr.cmp(&b.staker_addr));
+    assert_eq!(
+        stakers,
+        (1..5u128)
+            .map(|i| PoolStakersResponseItem {
+                staker_addr: Addr::unchecked(format!("addr{}", i)),
+                bond_amount: Uint128::from(10 * i),
+            })
+            .collect::<Vec<PoolStakersResponseItem>>()
+    );
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolStakers {
+            staking_token: Addr::unchecked("unknown"),
+            start_after: None,
+            limit: None,
+            order: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, StdError::NotFound { .. }));
+}
+
+#[test]
+fn test_query_pool_locks() {
+    let unbonding_period = 100u64;
+    let mut deps = _setup_staking(Some(unbonding_period));
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr2".to_string(),
+        amount: Uint128::from(20u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    let start = mock_env().block.time.seconds();
+    let mut unbond_env = mock_env();
+    for _ in 0..2 {
+        for (staker, amount) in [("addr", 10u128), ("addr2", 5u128)] {
+            let msg = ExecuteMsg::Unbond {
+                staking_token: Addr::unchecked("staking"),
+                amount: Some(Uint128::from(amount)),
+                withdraw_rewards: None,
+                recipient: None,
+                limit: None,
+            };
+            execute(
+                deps.as_mut(),
+                unbond_env.clone(),
+                mock_info(staker, &[]),
+                msg,
+            )
+            .unwrap();
+        }
+        unbond_env.block.time = unbond_env.block.time.plus_seconds(1);
+    }
+
+    let query_locks = |deps: &cosmwasm_std::OwnedDeps<_, _, _>,
+                       start_after: Option<(Addr, u64)>|
+     -> Vec<PoolLocksResponseItem> {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PoolLocks {
+                    staking_token: Addr::unchecked("staking"),
+                    start_after,
+                    limit: Some(3),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let lock = |staker: &str, offset: u64, amount: u128| PoolLocksResponseItem {
+        staker_addr: Addr::unchecked(staker),
+        unlock_time: start + offset + unbonding_period,
+        amount: Uint128::from(amount),
+    };
+
+    // the first page crosses from the locks of addr to the ones of addr2
+    let page = query_locks(&deps, None);
+    assert_eq!(
+        page,
+        vec![
+            lock("addr", 0, 10),
+            lock("addr", 1, 10),
+            lock("addr2", 0, 5)
+        ]
+    );
+    let page = query_locks(
+        &deps,
+        Some((Addr::unchecked("addr2"), start + unbonding_period)),
+    );
+    assert_eq!(page, vec![lock("addr2", 1, 5)]);
+    let page = query_locks(
+        &deps,
+        Some((Addr::unchecked("addr2"), start + 1 + unbonding_period)),
+    );
+    assert_eq!(page, vec![]);
+
+    // claiming the matured locks of addr drops them from the index
+    unbond_env.block.time = unbond_env.block.time.plus_seconds(unbonding_period);
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::zero()),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), unbond_env, mock_info("addr", &[]), msg).unwrap();
+    let page = query_locks(&deps, None);
+    assert_eq!(page, vec![lock("addr2", 0, 5), lock("addr2", 1, 5)]);
+}
+
+#[test]
+fn test_total_unbonding() {
+    let unbonding_period = 100u64;
+    let mut deps = _setup_staking(Some(unbonding_period));
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr2".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    // the counter must always match the sum of the locks of the pool
+    let assert_total_unbonding = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, expected: u128| {
+        let locks: Vec<PoolLocksResponseItem> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PoolLocks {
+                    staking_token: Addr::unchecked("staking"),
+                    start_after: None,
+                    limit: Some(MAX_LIMIT),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let sum = locks
+            .iter()
+            .fold(Uint128::zero(), |total, lock| total + lock.amount);
+        let res: TotalUnbondingResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TotalUnbonding {
+                    staking_token: Addr::unchecked("staking"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.total_unbonding, sum);
+        assert_eq!(res.total_unbonding, Uint128::from(expected));
+        let pool_info: PoolInfoResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PoolInfo {
+                    staking_token: Addr::unchecked("staking"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(pool_info.total_unbonding, sum);
+    };
+    let unbond_msg = |amount: u128| ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(amount)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+
+    let mut env = mock_env();
+    for (staker, amount) in [("addr", 30u128), ("addr2", 20u128), ("addr", 10u128)] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(staker, &[]),
+            unbond_msg(amount),
+        )
+        .unwrap();
+        env.block.time = env.block.time.plus_seconds(10);
+    }
+    assert_total_unbonding(&deps, 60);
+
+    // cancelling splits the first lock of addr
+    let msg = ExecuteMsg::CancelUnbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Uint128::from(25u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr", &[]), msg).unwrap();
+    assert_total_unbonding(&deps, 35);
+
+    // the remaining 5 of the first lock of addr and the lock of addr2 are matured
+    env.block.time = env.block.time.plus_seconds(unbonding_period - 20);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        unbond_msg(0),
+    )
+    .unwrap();
+    assert_total_unbonding(&deps, 30);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr2", &[]),
+        unbond_msg(0),
+    )
+    .unwrap();
+    assert_total_unbonding(&deps, 10);
+
+    env.block.time = env.block.time.plus_seconds(unbonding_period);
+    execute(deps.as_mut(), env, mock_info("addr", &[]), unbond_msg(0)).unwrap();
+    assert_total_unbonding(&deps, 0);
+}
+
+#[test]
+fn test_unbonding_summary() {
+    let unbonding_period = 100u64;
+    let mut deps = _setup_staking(Some(unbonding_period));
+
+    let query_summary = |deps: &cosmwasm_std::OwnedDeps<_, _, _>,
+                         env: cosmwasm_std::Env|
+     -> UnbondingSummaryResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::UnbondingSummary {
+                    staker_addr: Addr::unchecked("addr"),
+                    staking_token: Addr::unchecked("staking"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let summary = |total_locked: u128,
+                   lock_count: u64,
+                   next_unlock_time: Option<u64>,
+                   matured_amount: u128| UnbondingSummaryResponse {
+        staker_addr: Addr::unchecked("addr"),
+        staking_token: Addr::unchecked("staking"),
+        total_locked: Uint128::from(total_locked),
+        lock_count,
+        next_unlock_time,
+        matured_amount: Uint128::from(matured_amount),
+    };
+
+    // no locks yet
+    assert_eq!(query_summary(&deps, mock_env()), summary(0, 0, None, 0));
+
+    let start = mock_env().block.time.seconds();
+    let mut env = mock_env();
+    for amount in [10u128, 20u128, 30u128] {
+        let msg = ExecuteMsg::Unbond {
+            staking_token: Addr::unchecked("staking"),
+            amount: Some(Uint128::from(amount)),
+            withdraw_rewards: None,
+            recipient: None,
+            limit: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("addr", &[]), msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(10);
+    }
+    let next_unlock_time = Some(start + unbonding_period);
+    assert_eq!(
+        query_summary(&deps, mock_env()),
+        summary(60, 3, next_unlock_time, 0)
+    );
+
+    // the first two locks are matured
+    env.block.time = mock_env().block.time.plus_seconds(unbonding_period + 10);
+    assert_eq!(
+        query_summary(&deps, env.clone()),
+        summary(60, 3, next_unlock_time, 30)
+    );
+
+    // every lock is matured
+    env.block.time = env.block.time.plus_seconds(unbonding_period);
+    assert_eq!(
+        query_summary(&deps, env),
+        summary(60, 3, next_unlock_time, 60)
+    );
+}
+
+#[test]
+fn test_query_all_staker_info() {
+    let unbonding_period = 100u64;
+    let mut deps = _setup_staking(Some(unbonding_period));
+    for staking_token in ["staking2", "staking3"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: Addr::unchecked(staking_token),
+            unbonding_period: Some(unbonding_period),
+            native_token: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+    // addr stays bonded in staking, only has a lock left in staking2 and leaves nothing in staking3
+    for staking_token in ["staking2", "staking3"] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr".to_string(),
+            amount: Uint128::from(50u128),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                staker: None,
+                callback: None,
+            })
+            .unwrap(),
+        });
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(staking_token, &[]),
+            msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::Unbond {
+            staking_token: Addr::unchecked(staking_token),
+            amount: None,
+            withdraw_rewards: None,
+            recipient: None,
+            limit: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    }
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(unbonding_period);
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking3"),
+        amount: None,
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), env, mock_info("addr", &[]), msg).unwrap();
+
+    let mut infos: Vec<StakerInfoResponseItem> = vec![];
+    loop {
+        let page: Vec<StakerInfoResponseItem> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllStakerInfo {
+                    staker_addr: Addr::unchecked("addr"),
+                    start_after: infos.last().map(|info| info.staking_token.clone()),
+                    limit: Some(1),
+                    order: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        if page.is_empty() {
+            break;
+        }
+        infos.extend(page);
+    }
+    infos.sort_by(|a, b| a.staking_token.cmp(&b.staking_token));
+
+    let reward_info: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: Some(Addr::unchecked("staking")),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        infos,
+        vec![
+            StakerInfoResponseItem {
+                staking_token: Addr::unchecked("staking"),
+                bond_amount: Uint128::from(100u128),
+                pending_reward: reward_info.reward_infos[0].pending_reward,
+                pending_withdraw: reward_info.reward_infos[0]
+                    .pending_withdraw
+                    .iter()
+                    .filter(|asset| !asset.amount.is_zero())
+                    .cloned()
+                    .collect(),
+                total_unbonding: Uint128::zero(),
+            },
+            StakerInfoResponseItem {
+                staking_token: Addr::unchecked("staking2"),
+                bond_amount: Uint128::zero(),
+                pending_reward: Uint128::zero(),
+                pending_withdraw: vec![],
+                total_unbonding: Uint128::from(50u128),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_voting_power_at_height() {
+    let mut deps = _setup_staking(None);
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TotalPowerAtHeight { height: None },
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("no governance token configured"));
+    let msg = ExecuteMsg::UpdateConfig {
+        fee_collector: None,
+        config_timelock: None,
+        max_unbonding_period: None,
+        max_lock_entries: None,
+        governance_token: Some(Addr::unchecked("staking")),
+        oracle: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let info: InfoResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Info {}).unwrap()).unwrap();
+    assert_eq!(info.info.contract, CONTRACT_NAME);
+
+    // a proposal module snapshots the power when the proposal is created and votes later
+    let proposal_height = mock_env().block.height + 10;
+    let mut env = mock_env();
+    env.block.height += 20;
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr2".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), mock_info("staking", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(40u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr", &[]), msg).unwrap();
+    env.block.height += 1;
+
+    let voting_power = |deps: &cosmwasm_std::OwnedDeps<_, _, _>,
+                        address: &str,
+                        height: Option<u64>|
+     -> VotingPowerAtHeightResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::VotingPowerAtHeight {
+                    address: address.to_string(),
+                    height,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let total_power = |deps: &cosmwasm_std::OwnedDeps<_, _, _>,
+                       height: Option<u64>|
+     -> TotalPowerAtHeightResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::TotalPowerAtHeight { height },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    // the votes on the proposal count the power at its height
+    assert_eq!(
+        voting_power(&deps, "addr", Some(proposal_height)),
+        VotingPowerAtHeightResponse {
+            power: Uint128::from(100u128),
+            height: proposal_height,
+        }
+    );
+    assert_eq!(
+        voting_power(&deps, "addr2", Some(proposal_height)).power,
+        Uint128::zero()
+    );
+    assert_eq!(
+        total_power(&deps, Some(proposal_height)),
+        TotalPowerAtHeightResponse {
+            power: Uint128::from(100u128),
+            height: proposal_height,
+        }
+    );
+
+    // the current height sees the new balances
+    assert_eq!(
+        voting_power(&deps, "addr", None),
+        VotingPowerAtHeightResponse {
+            power: Uint128::from(60u128),
+            height: env.block.height,
+        }
+    );
+    assert_eq!(
+        voting_power(&deps, "addr2", None).power,
+        Uint128::from(50u128)
+    );
+    assert_eq!(total_power(&deps, None).power, Uint128::from(110u128));
+}
+
+#[test]
+fn test_staker_count() {
+    let mut deps = _setup_staking(None);
+
+    let staker_count = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> u64 {
+        let res: StakerCountResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::StakerCount {
+                    staking_token: Addr::unchecked("staking"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.staker_count
+    };
+    let bond_msg = |staker: &str| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::from(50u128),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                staker: None,
+                callback: None,
+            })
+            .unwrap(),
+        })
+    };
+    let unbond_msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: None,
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    assert_eq!(staker_count(&deps), 1);
+
+    // bonding again does not count the staker twice
+    for _ in 0..2 {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("staking", &[]),
+            bond_msg("addr2"),
+        )
+        .unwrap();
+    }
+    assert_eq!(staker_count(&deps), 2);
+
+    // exit then bond again
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr2", &[]),
+        unbond_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(staker_count(&deps), 1);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg("addr2"),
+    )
+    .unwrap();
+    assert_eq!(staker_count(&deps), 2);
+
+    // the rewards of addr are paid out with the exit
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        unbond_msg,
+    )
+    .unwrap();
+    assert_eq!(staker_count(&deps), 1);
+    let pool_info: PoolInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolInfo {
+                staking_token: Addr::unchecked("staking"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pool_info.staker_count, 1);
+}
+
+#[test]
+fn test_is_staker() {
+    let mut deps = _setup_staking(None);
+
+    let is_staker = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, staker: &str| -> IsStakerResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::IsStaker {
+                    staking_token: Addr::unchecked("staking"),
+                    staker_addr: Addr::unchecked(staker),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        is_staker(&deps, "addr"),
+        IsStakerResponse {
+            is_staker: true,
+            bond_amount: Uint128::from(100u128),
+        }
+    );
+    assert_eq!(
+        is_staker(&deps, "addr2"),
+        IsStakerResponse {
+            is_staker: false,
+            bond_amount: Uint128::zero(),
+        }
+    );
+
+    // a staker only left with rewards in the pool
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    let staker_raw = deps.api.addr_canonicalize("addr3").unwrap();
+    STAKERS
+        .save(&mut deps.storage, (&asset_key, &staker_raw), &true)
+        .unwrap();
+    REWARDS
+        .save(
+            &mut deps.storage,
+            (&staker_raw, &asset_key),
+            &RewardInfo {
+                native_token: false,
+                index: Decimal::zero(),
+                bond_amount: Uint128::zero(),
+                pending_reward: Uint128::from(10u128),
+                pending_withdraw: vec![],
+                weighted_amount: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        is_staker(&deps, "addr3"),
+        IsStakerResponse {
+            is_staker: false,
+            bond_amount: Uint128::zero(),
+        }
+    );
+}
+
+#[test]
+fn test_lock_infos_time_range() {
+    let unbonding_period = 100u64;
+    let mut deps = _setup_staking(Some(unbonding_period));
+
+    let start = mock_env().block.time.seconds() + unbonding_period;
+    let mut env = mock_env();
+    for _ in 0..4 {
+        let msg = ExecuteMsg::Unbond {
+            staking_token: Addr::unchecked("staking"),
+            amount: Some(Uint128::from(10u128)),
+            withdraw_rewards: None,
+            recipient: None,
+            limit: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("addr", &[]), msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(10);
+    }
+
+    let unlock_times = |start_after: Option<u64>,
+                        limit: Option<u32>,
+                        order: Option<i32>,
+                        start_time: Option<u64>,
+                        end_time: Option<u64>|
+     -> Vec<u64> {
+        let res: LockInfosResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::LockInfos {
+                    staker_addr: Addr::unchecked("addr"),
+                    staking_token: Addr::unchecked("staking"),
+                    start_after,
+                    limit,
+                    order,
+                    start_time,
+                    end_time,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.lock_infos
+            .into_iter()
+            .map(|lock| lock.unlock_time - start)
+            .collect()
+    };
+
+    // start_time is inclusive, end_time exclusive
+    assert_eq!(
+        unlock_times(None, None, None, Some(start + 10), Some(start + 30)),
+        vec![10, 20]
+    );
+    // the bounds combine with the cursor and the limit
+    assert_eq!(
+        unlock_times(None, Some(2), None, Some(start + 5), None),
+        vec![10, 20]
+    );
+    assert_eq!(
+        unlock_times(Some(start + 20), Some(2), None, Some(start + 5), None),
+        vec![30]
+    );
+    // descending starts right below end_time
+    assert_eq!(
+        unlock_times(None, None, Some(2), None, Some(start + 21)),
+        vec![20, 10, 0]
+    );
+    assert_eq!(
+        unlock_times(
+            Some(start + 20),
+            None,
+            Some(2),
+            Some(start + 5),
+            Some(start + 30)
+        ),
+        vec![10]
+    );
+    // empty ranges and ranges entirely in the past
+    assert_eq!(
+        unlock_times(None, None, None, Some(start + 10), Some(start + 10)),
+        Vec::<u64>::new()
+    );
+    assert_eq!(
+        unlock_times(None, None, Some(2), Some(start + 30), Some(start + 10)),
+        Vec::<u64>::new()
+    );
+    assert_eq!(
+        unlock_times(None, None, None, Some(0), Some(start - unbonding_period)),
+        Vec::<u64>::new()
+    );
+}
+
+#[test]
+fn test_query_claimable() {
+    let unbonding_period = 100u64;
+    let mut deps = _setup_staking(Some(unbonding_period));
+
+    let start = mock_env().block.time.seconds() + unbonding_period;
+    let mut env = mock_env();
+    for amount in [10u128, 20u128, 30u128] {
+        let msg = ExecuteMsg::Unbond {
+            staking_token: Addr::unchecked("staking"),
+            amount: Some(Uint128::from(amount)),
+            withdraw_rewards: None,
+            recipient: None,
+            limit: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("addr", &[]), msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(10);
+    }
+
+    let claimable = |deps: &cosmwasm_std::OwnedDeps<_, _, _>,
+                     env: cosmwasm_std::Env,
+                     at_time: Option<u64>|
+     -> ClaimableResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::Claimable {
+                    staker_addr: Addr::unchecked("addr"),
+                    staking_token: Addr::unchecked("staking"),
+                    at_time,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    // nothing is matured at the current block time
+    assert_eq!(
+        claimable(&deps, env.clone(), None),
+        ClaimableResponse {
+            amount: Uint128::zero(),
+            lock_count: 0,
+            at_time: env.block.time.seconds(),
+        }
+    );
+    // a lock unlocking exactly at at_time is claimable
+    assert_eq!(
+        claimable(&deps, env.clone(), Some(start + 10)),
+        ClaimableResponse {
+            amount: Uint128::from(30u128),
+            lock_count: 2,
+            at_time: start + 10,
+        }
+    );
+
+    // the claim releases what the query returned
+    env.block.time = Timestamp::from_seconds(start + 10);
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::zero()),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr", &[]), msg).unwrap();
+    assert!(res.attributes.contains(&attr("amount", "30")));
+    assert!(res.attributes.contains(&attr("processed_locks", "2")));
+    assert_eq!(claimable(&deps, env, None).lock_count, 0);
+}
+
+#[test]
+fn test_query_reward_infos_batch() {
+    let deps = _setup_staking(None);
+
+    let res: Vec<RewardInfosBatchResponseItem> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfosBatch {
+                staking_token: Addr::unchecked("staking"),
+                stakers: vec!["addr2".to_string(), "addr".to_string(), "addr3".to_string()],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let reward_info: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staker_addr: Addr::unchecked("addr"),
+                staking_token: Some(Addr::unchecked("staking")),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let zeroed = |staker: &str| RewardInfosBatchResponseItem {
+        staker_addr: Addr::unchecked(staker),
+        bond_amount: Uint128::zero(),
+        pending_reward: Uint128::zero(),
+        pending_withdraw: vec![],
+    };
+    // the response lines up with the requested stakers
+    assert_eq!(
+        res,
+        vec![
+            zeroed("addr2"),
+            RewardInfosBatchResponseItem {
+                staker_addr: Addr::unchecked("addr"),
+                bond_amount: Uint128::from(100u128),
+                pending_reward: reward_info.reward_infos[0].pending_reward,
+                pending_withdraw: reward_info.reward_infos[0].pending_withdraw.clone(),
+            },
+            zeroed("addr3"),
+        ]
+    );
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardInfosBatch {
+            staking_token: Addr::unchecked("staking"),
+            stakers: (0..51).map(|i| format!("addr{}", i)).collect(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("too many stakers, at most 50 per batch")
+    );
+    query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardInfosBatch {
+            staking_token: Addr::unchecked("staking"),
+            stakers: (0..50).map(|i| format!("addr{}", i)).collect(),
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_query_summary() {
+    let mut deps = _setup_staking(Some(100));
+
+    // a second pool nobody staked in yet
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking2"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(10u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let summary = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, start_after: Option<Addr>| {
+        let res: SummaryResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Summary {
+                    start_after,
+                    limit: Some(1),
+                    order: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res
+    };
+
+    let res = summary(&deps, None);
+    assert_eq!(res.version.contract, CONTRACT_NAME);
+    assert_eq!(res.pool_count, 2);
+
+    // pages come in key order, canonical keys do not sort like the addresses
+    let mut pools = res.pools;
+    loop {
+        let res = summary(&deps, pools.last().map(|pool| pool.staking_token.clone()));
+        assert_eq!(res.pool_count, 2);
+        if res.pools.is_empty() {
+            break;
+        }
+        pools.extend(res.pools);
+    }
+    pools.sort_by(|a, b| a.staking_token.cmp(&b.staking_token));
+    assert_eq!(
+        pools,
+        vec![
+            SummaryPoolItem {
+                staking_token: Addr::unchecked("staking"),
+                total_bond_amount: Uint128::from(90u128),
+                total_unbonding: Uint128::from(10u128),
+                staker_count: 1,
+            },
+            SummaryPoolItem {
+                staking_token: Addr::unchecked("staking2"),
+                total_bond_amount: Uint128::zero(),
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_lock_infos_pagination_round_trip() {
+    let mut deps = mock_dependencies();
+    for i in 0..25u64 {
+        insert_lock_info(
+            deps.as_mut().storage,
+            b"staking",
+            b"addr",
+            LockInfo {
+                unlock_time: Timestamp::from_seconds(1000 + i * 10),
+                amount: Uint128::from(i + 1),
+            },
+            25,
+        )
+        .unwrap();
+    }
+
+    for order in [1, 2] {
+        let mut unlock_times: Vec<u64> = vec![];
+        loop {
+            let res: LockInfosResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::LockInfos {
+                        staker_addr: Addr::unchecked("addr"),
+                        staking_token: Addr::unchecked("staking"),
+                        start_after: unlock_times.last().copied(),
+                        limit: Some(7),
+                        order: Some(order),
+                        start_time: None,
+                        end_time: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            if res.lock_infos.is_empty() {
+                break;
+            }
+            unlock_times.extend(res.lock_infos.iter().map(|lock| lock.unlock_time));
+        }
+
+        // every lock shows up exactly once, in the requested order
+        let mut expected: Vec<u64> = (0..25u64).map(|i| 1000 + i * 10).collect();
+        if order == 2 {
+            expected.reverse();
+        }
+        assert_eq!(unlock_times, expected);
+    }
+}
+
+#[test]
+fn test_staked_balance_at_time() {
+    let mut deps = _setup_staking(None);
+    let time = mock_env().block.time.seconds();
+    let balance_at = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, time: u64| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::StakedBalanceAtTime {
+                asset_key: Addr::unchecked("staking"),
+                address: "sender".into(),
+                time,
+            },
+        )
+        .map(|res| from_binary::<StakedBalanceAtTimeResponse>(&res).unwrap())
+    };
+
+    let mut env = mock_env();
+    env.block.height += 100;
+    env.block.time = env.block.time.plus_seconds(100);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "sender".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), mock_info("staking", &[]), msg).unwrap();
+
+    assert_eq!(
+        balance_at(&deps, time - 1).unwrap_err(),
+        StdError::generic_err("time predates the oldest recorded block")
+    );
+    for (at, height, balance) in [
+        (time, mock_env().block.height, 0u128),
+        (time + 99, mock_env().block.height, 0u128),
+        (time + 100, env.block.height, 100u128),
+        (time + 1000, env.block.height, 100u128),
+    ] {
+        assert_eq!(
+            balance_at(&deps, at).unwrap(),
+            StakedBalanceAtTimeResponse {
+                balance: Uint128::from(balance),
+                height,
+                time: at,
+            }
+        );
+    }
+
+    // the ring drops the oldest blocks once full
+    let mut deps = mock_dependencies();
+    for height in 0..MAX_BLOCK_TIMES + 5 {
+        record_block_time(deps.as_mut().storage, height, height * 10).unwrap();
+        // a height is only recorded once
+        record_block_time(deps.as_mut().storage, height, height * 10 + 5).unwrap();
+    }
+    assert_eq!(read_block_at_time(deps.as_ref().storage, 49).unwrap(), None);
+    assert_eq!(
+        read_block_at_time(deps.as_ref().storage, 59)
+            .unwrap()
+            .map(|block| block.height),
+        Some(5)
+    );
+    assert_eq!(
+        read_block_at_time(deps.as_ref().storage, u64::MAX)
+            .unwrap()
+            .map(|block| block.height),
+        Some(MAX_BLOCK_TIMES + 4)
+    );
+}
+
+#[test]
+fn test_stake_changed_hooks() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::AddHook {
+        addr: "hook".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(err, StdError::generic_err("hook is already registered"));
+
+    let res: HooksResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Hooks {}).unwrap()).unwrap();
+    assert_eq!(res.hooks, vec!["hook".to_string()]);
+
+    let hook_msg = |old_amount: u128, new_amount: u128| {
+        SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: "hook".to_string(),
+                msg: to_binary(&StakeChangedExecuteMsg::StakeChangeHook(
+                    StakeChangedHookMsg {
+                        staker: Addr::unchecked("addr2"),
+                        asset_info: AssetInfo::Token {
+                            contract_addr: Addr::unchecked("staking"),
+                        },
+                        old_amount: Uint128::from(old_amount),
+                        new_amount: Uint128::from(new_amount),
+                    },
+                ))
+                .unwrap(),
+                funds: vec![],
+            },
+            STAKE_HOOK_REPLY_ID,
+        )
+    };
+    let bond_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr2".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![hook_msg(0, 50)]);
+
+    // the hook comes after the payout of the unbonded tokens
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(20u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr2", &[]), msg).unwrap();
+    assert_eq!(res.messages.last(), Some(&hook_msg(50, 30)));
+
+    // leaving without the rewards is a bond change too
+    let msg = ExecuteMsg::EmergencyUnbond {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr2", &[]), msg).unwrap();
+    assert_eq!(res.messages.last(), Some(&hook_msg(30, 0)));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![hook_msg(0, 50)]);
+
+    let msg = ExecuteMsg::ForceUnbond {
+        staking_token: Addr::unchecked("staking"),
+        staker_addr: Addr::unchecked("addr2"),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(res.messages.last(), Some(&hook_msg(50, 0)));
+
+    // without hooks nothing is sent
+    let msg = ExecuteMsg::RemoveHook {
+        addr: "hook".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(err, StdError::generic_err("hook is not registered"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        bond_msg,
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+}
+
+#[test]
+fn test_claim_hooks() {
+    let mut deps = _setup_staking(None);
+
+    let msg = ExecuteMsg::AddClaimHook {
+        addr: "loyalty".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let res: HooksResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ClaimHooks {}).unwrap()).unwrap();
+    assert_eq!(res.hooks, vec!["loyalty".to_string()]);
+    // the stake changed hooks are a separate list
+    let res: HooksResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Hooks {}).unwrap()).unwrap();
+    assert!(res.hooks.is_empty());
+
+    let msg = ExecuteMsg::Withdraw {
+        staking_token: Some(Addr::unchecked("staking")),
+        skip_failing: None,
+    };
+    let mut res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    // the payouts come first, then the hook carrying the same amounts
+    let hook_msg = res.messages.pop().unwrap();
+    let rewards = res
+        .messages
+        .iter()
+        .map(|msg| match &msg.msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                assert_eq!(to_address, "addr");
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: amount[0].denom.clone(),
+                    },
+                    amount: amount[0].amount,
+                }
+            }
+            _ => panic!("unexpected message"),
+        })
+        .collect::<Vec<Asset>>();
+    assert_eq!(rewards.len(), 2);
+    assert_eq!(
+        hook_msg,
+        SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: "loyalty".to_string(),
+                msg: to_binary(&ClaimHookExecuteMsg::ClaimHook(ClaimHookMsg {
+                    staker: Addr::unchecked("addr"),
+                    asset_info: Some(AssetInfo::Token {
+                        contract_addr: Addr::unchecked("staking"),
+                    }),
+                    rewards,
+                }))
+                .unwrap(),
+                funds: vec![],
+            },
+            CLAIM_HOOK_REPLY_ID,
+        )
+    );
+
+    // nothing left to claim, so no hook either
+    let msg = ExecuteMsg::Withdraw {
+        staking_token: None,
+        skip_failing: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert!(!res
+        .messages
+        .iter()
+        .any(|msg| matches!(msg.msg, CosmosMsg::Wasm(_))));
+
+    // the rewards withdrawn along an unbond notify the hooks too
+    let msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(300u128),
+        }],
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(50u128)),
+        withdraw_rewards: Some(true),
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert!(matches!(
+        &res.messages.last().unwrap().msg,
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) if contract_addr == "loyalty"
+    ));
+
+    let msg = ExecuteMsg::RemoveClaimHook {
+        addr: "loyalty".to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let res: HooksResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ClaimHooks {}).unwrap()).unwrap();
+    assert!(res.hooks.is_empty());
+}
+
+#[test]
+fn test_hook_failures() {
+    let mut deps = _setup_staking(None);
+    for hook in ["good", "bad"] {
+        let msg = ExecuteMsg::AddHook {
+            addr: hook.to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+
+    let hook_failures = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let mut items: Vec<HookFailuresResponseItem> =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::HookFailures {}).unwrap())
+                .unwrap();
+        items.sort_by(|a, b| a.hook.cmp(&b.hook));
+        items
+            .into_iter()
+            .map(|item| (item.hook, item.failures, item.disabled))
+            .collect::<Vec<(String, u64, bool)>>()
+    };
+    let bond_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr2".to_string(),
+        amount: Uint128::from(10u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    // bonds, then fails the call to the bad hook, the good one succeeds without a reply
+    let bond_and_fail = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("staking", &[]),
+            bond_msg.clone(),
+        )
+        .unwrap();
+        let hooks = res
+            .messages
+            .iter()
+            .map(|msg| match &msg.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+                    (contract_addr.clone(), msg.id)
+                }
+                _ => panic!("unexpected message"),
+            })
+            .collect::<Vec<(String, u64)>>();
+        for (hook, id) in hooks.iter() {
+            if hook == "bad" {
+                reply(
+                    deps.as_mut(),
+                    mock_env(),
+                    Reply {
+                        id: *id,
+                        result: SubMsgResult::Err("bad hook".to_string()),
+                    },
+                )
+                .unwrap();
+            }
+        }
+        hooks
+            .into_iter()
+            .map(|(hook, _)| hook)
+            .collect::<Vec<String>>()
+    };
+
+    for failures in 1..=3u64 {
+        let mut hooks = bond_and_fail(&mut deps);
+        hooks.sort();
+        assert_eq!(hooks, vec!["bad".to_string(), "good".to_string()]);
+        assert_eq!(
+            hook_failures(&deps),
+            vec![
+                ("bad".to_string(), failures, failures == 3),
+                ("good".to_string(), 0, false),
+            ]
+        );
+    }
+    // the bonds landed despite the failing hook
+    let res: IsStakerResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IsStaker {
+                staking_token: Addr::unchecked("staking"),
+                staker_addr: Addr::unchecked("addr2"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.bond_amount, Uint128::from(30u128));
+
+    // the disabled hook is skipped
+    assert_eq!(bond_and_fail(&mut deps), vec!["good".to_string()]);
+
+    let msg = ExecuteMsg::ResetHookFailures {
+        kind: HookKind::Stake,
+        addr: "bad".to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        hook_failures(&deps),
+        vec![
+            ("bad".to_string(), 0, false),
+            ("good".to_string(), 0, false),
+        ]
+    );
+    assert_eq!(bond_and_fail(&mut deps).len(), 2);
+}
+
+#[test]
+fn test_membership_queries() {
+    let mut deps = _setup_staking(None);
+    let member = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, addr: &str, at_height: Option<u64>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Member {
+                addr: addr.into(),
+                at_height,
+            },
+        )
+        .map(|res| from_binary::<MemberResponse>(&res).unwrap().weight)
+    };
+    let total_weight = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, at_height: Option<u64>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TotalWeight { at_height },
+        )
+        .map(|res| from_binary::<TotalWeightResponse>(&res).unwrap().weight)
+    };
+
+    assert_eq!(
+        member(&deps, "addr", None).unwrap_err(),
+        StdError::generic_err("no membership pool configured")
+    );
+
+    let msg = ExecuteMsg::UpdateMembership {
+        staking_token: Some(Addr::unchecked("staking")),
+        weight_divisor: Some(Uint128::from(10u128)),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateMembership {
+            staking_token: Some(Addr::unchecked("staking")),
+            weight_divisor: Some(Uint128::zero()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("weight divisor cannot be zero"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        from_binary::<Option<MembershipResponse>>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Membership {}).unwrap()
+        )
+        .unwrap(),
+        Some(MembershipResponse {
+            staking_token: Addr::unchecked("staking"),
+            weight_divisor: Uint128::from(10u128),
+        })
+    );
+
+    let mut env = mock_env();
+    env.block.height += 10;
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr2".to_string(),
+        amount: Uint128::from(55u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), mock_info("staking", &[]), msg).unwrap();
+
+    assert_eq!(member(&deps, "addr", None).unwrap(), Some(10));
+    assert_eq!(member(&deps, "addr2", None).unwrap(), Some(5));
+    assert_eq!(
+        member(&deps, "addr2", Some(env.block.height)).unwrap(),
+        None
+    );
+    assert_eq!(member(&deps, "addr3", None).unwrap(), None);
+    assert_eq!(total_weight(&deps, None).unwrap(), 15);
+    assert_eq!(total_weight(&deps, Some(env.block.height)).unwrap(), 10);
+
+    // a bond below the weight divisor weighs nothing
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr3".to_string(),
+        amount: Uint128::from(5u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), mock_info("staking", &[]), msg).unwrap();
+    assert_eq!(member(&deps, "addr3", None).unwrap(), None);
+
+    let mut members = vec![];
+    let mut start_after = None;
+    loop {
+        let page = from_binary::<MemberListResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListMembers {
+                    start_after: start_after.clone(),
+                    limit: Some(1),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .members;
+        if page.is_empty() {
+            break;
+        }
+        start_after = page.last().map(|member| member.addr.clone());
+        members.extend(page);
+    }
+    members.sort_by(|a, b| a.addr.cmp(&b.addr));
+    assert_eq!(
+        members,
+        vec![
+            Member {
+                addr: "addr".into(),
+                weight: 10,
+            },
+            Member {
+                addr: "addr2".into(),
+                weight: 5,
+            },
+        ]
+    );
+
+    // without a pool the queries are disabled again
+    let msg = ExecuteMsg::UpdateMembership {
+        staking_token: None,
+        weight_divisor: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        total_weight(&deps, None).unwrap_err(),
+        StdError::generic_err("no membership pool configured")
+    );
+}
+
+#[test]
+fn test_cw20_stake_queries() {
+    let mut deps = _setup_staking(Some(100));
+    let query_raw = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, msg: QueryMsg| {
+        query(deps.as_ref(), mock_env(), msg)
+    };
+
+    assert_eq!(
+        query_raw(&deps, QueryMsg::TotalValue {}).unwrap_err(),
+        StdError::generic_err("no cw20-stake pool configured")
+    );
+    let msg = ExecuteMsg::UpdateCw20StakePool {
+        staking_token: Some(Addr::unchecked("staking")),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr2".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(20u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    // the responses are the upstream json, byte for byte
+    let res = query_raw(
+        &deps,
+        QueryMsg::StakedValue {
+            address: "addr".into(),
+        },
+    )
+    .unwrap();
+    let value = cw20_stake::msg::StakedValueResponse {
+        value: Uint128::from(80u128),
+    };
+    assert_eq!(res, to_binary(&value).unwrap());
+    assert_eq!(
+        from_binary::<cw20_stake::msg::StakedValueResponse>(&res).unwrap(),
+        value
+    );
+
+    let res = query_raw(&deps, QueryMsg::TotalValue {}).unwrap();
+    let total = cw20_stake::msg::TotalValueResponse {
+        total: Uint128::from(130u128),
+    };
+    assert_eq!(res, to_binary(&total).unwrap());
+    assert_eq!(
+        from_binary::<cw20_stake::msg::TotalValueResponse>(&res).unwrap(),
+        total
+    );
+
+    let res = query_raw(&deps, QueryMsg::GetConfig {}).unwrap();
+    let config = cw20_stake::state::Config {
+        token_address: Addr::unchecked("staking"),
+        unstaking_duration: Some(cw_utils::Duration::Time(100)),
+    };
+    assert_eq!(res, to_binary(&config).unwrap());
+    assert_eq!(
+        from_binary::<cw20_stake::state::Config>(&res).unwrap(),
+        config
+    );
+
+    let res = query_raw(
+        &deps,
+        QueryMsg::Claims {
+            address: "addr".into(),
+        },
+    )
+    .unwrap();
+    let claims = cw_controllers::ClaimsResponse {
+        claims: vec![cw_controllers::Claim {
+            amount: Uint128::from(20u128),
+            release_at: cw_utils::Expiration::AtTime(mock_env().block.time.plus_seconds(100)),
+        }],
+    };
+    assert_eq!(res, to_binary(&claims).unwrap());
+    assert_eq!(
+        from_binary::<cw_controllers::ClaimsResponse>(&res).unwrap(),
+        claims
+    );
+
+    let mut stakers = vec![];
+    let mut start_after = None;
+    loop {
+        let page = from_binary::<cw20_stake::msg::ListStakersResponse>(
+            &query_raw(
+                &deps,
+                QueryMsg::ListStakers {
+                    start_after: start_after.clone(),
+                    limit: Some(1),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .stakers;
+        if page.is_empty() {
+            break;
+        }
+        start_after = page.last().map(|staker| staker.address.clone());
+        stakers.extend(page);
+    }
+    stakers.sort_by(|a, b| a.address.cmp(&b.address));
+    assert_eq!(
+        stakers,
+        vec![
+            cw20_stake::msg::StakerBalanceResponse {
+                address: "addr".into(),
+                balance: Uint128::from(80u128),
+            },
+            cw20_stake::msg::StakerBalanceResponse {
+                address: "addr2".into(),
+                balance: Uint128::from(50u128),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_query_pool_value() {
+    let mut deps = _setup_staking(None);
+    let staking_token = AssetInfo::Token {
+        contract_addr: Addr::unchecked("staking"),
+    };
+    let pool_value = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolValue {
+                asset_info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked("staking"),
+                },
+            },
+        )
+        .map(|res| from_binary::<PoolValueResponse>(&res).unwrap())
+    };
+
+    // only the raw amount without an oracle
+    assert_eq!(
+        pool_value(&deps).unwrap(),
+        PoolValueResponse {
+            asset_info: staking_token.clone(),
+            total_bond_amount: Uint128::from(100u128),
+            price: None,
+            value: None,
+        }
+    );
+
+    let msg = ExecuteMsg::UpdateConfig {
+        fee_collector: None,
+        config_timelock: None,
+        max_unbonding_period: None,
+        max_lock_entries: None,
+        governance_token: None,
+        oracle: Some(Addr::unchecked("oracle")),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == "oracle" => {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&ExchangeRateResponse {
+                    base_denom: "staking".to_string(),
+                    item: ExchangeRateItem {
+                        quote_denom: "usdt".to_string(),
+                        exchange_rate: Decimal::from_ratio(3u128, 2u128),
+                    },
+                })
+                .unwrap(),
+            ))
+        }
+        _ => panic!("unexpected query"),
+    });
+    assert_eq!(
+        pool_value(&deps).unwrap(),
+        PoolValueResponse {
+            asset_info: staking_token,
+            total_bond_amount: Uint128::from(100u128),
+            price: Some(Decimal::from_ratio(3u128, 2u128)),
+            value: Some(Uint128::from(150u128)),
+        }
+    );
+
+    // the oracle error is reported with the token it failed to price
+    deps.querier
+        .update_wasm(|_| SystemResult::Ok(ContractResult::Err("no price for staking".to_string())));
+    let err = pool_value(&deps).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("cannot query the oracle price of staking"));
+}
+
+#[test]
+fn test_zap_in() {
+    let mut deps = _setup_staking(None);
+    let staking_token = AssetInfo::Token {
+        contract_addr: Addr::unchecked("staking"),
+    };
+    // the staking token balance of the contract
+    fn set_balance(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, balance: u128) {
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "staking" => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&Cw20BalanceResponse {
+                        balance: Uint128::from(balance),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => panic!("unexpected query"),
+        });
+    }
+    let zap_reply = Reply {
+        id: ZAP_IN_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let msg = ExecuteMsg::ZapIn {
+        asset_info: staking_token.clone(),
+        min_bond_amount: Some(Uint128::from(40u128)),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr2", &[coin(100u128, ORAI_DENOM)]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("no router to zap in with"));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateRouter {
+            router: Some(Addr::unchecked("router")),
+        },
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr2",
+            &[coin(100u128, ORAI_DENOM), coin(100u128, ATOM_DENOM)],
+        ),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("must send exactly one coin to zap in")
+    );
+
+    set_balance(&mut deps, 1000);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr2", &[coin(100u128, ORAI_DENOM)]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: "router".to_string(),
+                msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations: vec![SwapOperation::OraiSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: ORAI_DENOM.to_string(),
+                        },
+                        ask_asset_info: staking_token.clone(),
+                    }],
+                    minimum_receive: None,
+                    to: None,
+                })
+                .unwrap(),
+                funds: vec![coin(100u128, ORAI_DENOM)],
+            },
+            ZAP_IN_REPLY_ID,
+        )]
+    );
+
+    // the swap output is below min_bond_amount, the transaction reverts and refunds the offer
+    set_balance(&mut deps, 1030);
+    let err = reply(deps.as_mut(), mock_env(), zap_reply.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("bond amount is below the minimum bond amount")
+    );
+
+    // a cw20 offer is sent to the router
+    set_balance(&mut deps, 1000);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("token", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr2".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::ZapIn {
+                asset_info: staking_token.clone(),
+                min_bond_amount: Some(Uint128::from(40u128)),
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "router".to_string(),
+                    amount: Uint128::from(100u128),
+                    msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: vec![SwapOperation::OraiSwap {
+                            offer_asset_info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked("token"),
+                            },
+                            ask_asset_info: staking_token.clone(),
+                        }],
+                        minimum_receive: None,
+                        to: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            },
+            ZAP_IN_REPLY_ID,
+        )]
+    );
+
+    // the output is bonded for the sender
+    set_balance(&mut deps, 1060);
+    reply(deps.as_mut(), mock_env(), zap_reply.clone()).unwrap();
+    let pools = query_get_pools_infomation(deps.as_ref()).unwrap();
+    assert_eq!(pools[0].pool_info.total_bond_amount, Uint128::from(160u128));
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staker_addr: Addr::unchecked("addr2"),
+                staking_token: Some(Addr::unchecked("staking")),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_infos[0].bond_amount, Uint128::from(60u128));
+
+    // the pending zap is settled only once
+    let err = reply(deps.as_mut(), mock_env(), zap_reply).unwrap_err();
+    assert_eq!(err, StdError::generic_err("no pending zap"));
+
+    // the staking token itself must be bonded directly
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr2".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::ZapIn {
+                asset_info: staking_token,
+                min_bond_amount: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("the offer asset is already the staking token, bond it instead")
+    );
+}
+
+#[test]
+fn test_backfill_snapshots() {
+    let mut deps = _setup_staking(None);
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+
+    // stakers who bonded before the snapshots were deployed only have a reward info
+    let mut pool_info = read_pool_info(deps.as_ref().storage, &asset_key).unwrap();
+    for (staker, amount) in [("legacy1", 50u128), ("legacy2", 30u128)] {
+        let staker_raw = deps.api.addr_canonicalize(staker).unwrap();
+        REWARDS
+            .save(
+                deps.as_mut().storage,
+                (&staker_raw, &asset_key),
+                &RewardInfo {
+                    native_token: false,
+                    index: pool_info.reward_index,
+                    bond_amount: Uint128::from(amount),
+                    pending_reward: Uint128::zero(),
+                    pending_withdraw: vec![],
+                    weighted_amount: None,
+                },
+            )
+            .unwrap();
+        STAKERS
+            .save(deps.as_mut().storage, (&asset_key, &staker_raw), &true)
+            .unwrap();
+        pool_info.total_bond_amount += Uint128::from(amount);
+    }
+    store_pool_info(deps.as_mut().storage, &asset_key, &pool_info).unwrap();
+
+    let consistency = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+        from_binary::<SnapshotConsistencyResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SnapshotConsistency {
+                    staking_token: Addr::unchecked("staking"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        consistency(&deps),
+        SnapshotConsistencyResponse {
+            staking_token: Addr::unchecked("staking"),
+            snapshot_total: Uint128::from(100u128),
+            total_bond_amount: Uint128::from(180u128),
+            consistent: false,
+        }
+    );
+
+    // only the owner can backfill
+    let msg = ExecuteMsg::BackfillSnapshots {
+        staking_token: Addr::unchecked("staking"),
+        start_after: None,
+        limit: Some(1),
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+
+    // resume page by page from the last staker until every staker is written
+    let mut start_after = None;
+    let mut updated = 0u32;
+    let mut pages = 0;
+    loop {
+        let msg = ExecuteMsg::BackfillSnapshots {
+            staking_token: Addr::unchecked("staking"),
+            start_after: start_after.clone(),
+            limit: Some(1),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        pages += 1;
+        updated += res
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == "updated")
+            .unwrap()
+            .value
+            .parse::<u32>()
+            .unwrap();
+        start_after = res
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == "last_staker")
+            .map(|attribute| Addr::unchecked(&attribute.value));
+        if start_after.is_none() {
+            break;
+        }
+    }
+    // three full pages and an empty one, the staker bonded after the deploy is left as is
+    assert_eq!(pages, 4);
+    assert_eq!(updated, 2);
+    assert!(consistency(&deps).consistent);
+
+    // running it again changes nothing
+    let msg = ExecuteMsg::BackfillSnapshots {
+        staking_token: Addr::unchecked("staking"),
+        start_after: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "backfill_snapshots"),
+            attr("staking_token", "staking"),
+            attr("updated", "0"),
+        ]
+    );
+    assert!(consistency(&deps).consistent);
+
+    // the backfilled balances are readable from the next block on
+    let mut env = mock_env();
+    env.block.height += 1;
+    let res: StakedBalanceAtHeightResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::StakedBalanceAtHeight {
+                asset_key: Addr::unchecked("staking"),
+                address: "legacy1".to_string(),
+                height: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.balance, Uint128::from(50u128));
+    let res: TotalStakedAtHeightResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::TotalStakedAtHeight {
+                asset_key: Addr::unchecked("staking"),
+                height: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.total, Uint128::from(180u128));
+}
+
+#[test]
+fn test_export_state() {
+    let mut deps = _setup_staking(Some(100));
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr2".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker: None,
+            callback: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(10u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let export = |kind: ExportKind, start_after: Option<Binary>, limit: Option<u32>| {
+        from_binary::<ExportStateResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ExportState {
+                    kind,
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    let pool_info = read_pool_info(deps.as_ref().storage, &asset_key).unwrap();
+    let res = export(ExportKind::Pools, None, None);
+    assert_eq!(res.next, None);
+    assert_eq!(
+        res.records,
+        vec![ExportRecord::Pool {
+            staking_token: Addr::unchecked("staking"),
+            current_staking_token: Addr::unchecked("staking"),
+            total_bond_amount: Uint128::from(140u128),
+            total_weighted_amount: pool_info.total_weighted_amount,
+            reward_index: pool_info.reward_index,
+            pending_reward: pool_info.pending_reward,
+            deprecated: false,
+        }]
+    );
+
+    let res = export(ExportKind::Locks, None, None);
+    assert_eq!(
+        res.records,
+        vec![ExportRecord::Lock {
+            staking_token: Addr::unchecked("staking"),
+            staker_addr: Addr::unchecked("addr"),
+            unlock_time: mock_env().block.time.seconds() + 100,
+            amount: Uint128::from(10u128),
+        }]
+    );
+
+    let res = export(ExportKind::RewardsPerSec, None, None);
+    assert_eq!(
+        res.records,
+        vec![ExportRecord::RewardsPerSec {
+            staking_token: Addr::unchecked("staking"),
+            assets: vec![
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: 100u128.into(),
+                },
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    amount: 200u128.into(),
+                },
+            ],
+        }]
+    );
+
+    // the pages put together are the full export, whatever the page size
+    let full = export(ExportKind::Stakers, None, Some(1000));
+    assert_eq!(full.records.len(), 2);
+    assert_eq!(full.next, None);
+    let mut records = vec![];
+    let mut start_after = None;
+    loop {
+        let res = export(ExportKind::Stakers, start_after, Some(1));
+        records.extend(res.records);
+        start_after = res.next;
+        if start_after.is_none() {
+            break;
+        }
+    }
+    assert_eq!(records, full.records);
+    let bonds = records
+        .iter()
+        .map(|record| match record {
+            ExportRecord::Staker {
+                staker_addr,
+                staking_token,
+                bond_amount,
+                ..
+            } => {
+                assert_eq!(staking_token, &Addr::unchecked("staking"));
+                (staker_addr.to_string(), bond_amount.u128())
+            }
+            _ => panic!("not a staker record"),
+        })
+        .collect::<Vec<(String, u128)>>();
+    assert!(bonds.contains(&("addr".to_string(), 90)));
+    assert!(bonds.contains(&("addr2".to_string(), 50)));
+}
+
+#[test]
+fn test_import_stakers() {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+    ]);
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        governance_token: None,
+        import_open: Some(true),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: 200u128.into(),
+            },
+        ],
+        force: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+        unbonding_period: None,
+        native_token: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let import = ExecuteMsg::ImportStakers {
+        staking_token: Addr::unchecked("staking"),
+        entries: vec![
+            ("addr".to_string(), Uint128::from(100u128)),
+            ("addr2".to_string(), Uint128::from(50u128)),
+        ],
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        import.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        import.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "import_stakers"),
+            attr("staking_token", "staking"),
+            attr("stakers", "2"),
+            attr("amount", "150"),
+        ]
+    );
+    assert!(res.messages.is_empty());
+
+    // the imported bonds are accounted like bonds
+    let data = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolInfo {
+            staking_token: Addr::unchecked("staking"),
+        },
+    )
+    .unwrap();
+    let pool_info: PoolInfoResponse = from_binary(&data).unwrap();
+    assert_eq!(pool_info.total_bond_amount, Uint128::from(150u128));
+    assert_eq!(pool_info.staker_count, 2);
+    let mut env = mock_env();
+    env.block.height += 1;
+    let res: StakedBalanceAtHeightResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::StakedBalanceAtHeight {
+                asset_key: Addr::unchecked("staking"),
+                address: "addr2".to_string(),
+                height: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.balance, Uint128::from(50u128));
+    let res: TotalStakedAtHeightResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::TotalStakedAtHeight {
+                asset_key: Addr::unchecked("staking"),
+                height: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.total, Uint128::from(150u128));
+
+    // the import closes for good
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::FinalizeImport {},
+    )
+    .unwrap();
+    assert_eq!(res.attributes, vec![attr("action", "finalize_import")]);
+    for msg in [import, ExecuteMsg::FinalizeImport {}] {
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("import is closed"));
+    }
+    let res: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert!(!res.import_open);
+
+    // imported stakers earn, withdraw and unbond normally
+    let msg = ExecuteMsg::DepositPrefundedReward {
+        rewards: vec![RewardMsg {
+            staking_token: Addr::unchecked("staking"),
+            total_accumulation_amount: Uint128::from(450u128),
+        }],
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        staking_token: Some(Addr::unchecked("staking")),
+        skip_failing: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr2", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr2".to_string(),
+                amount: vec![coin(49u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr2".to_string(),
+                amount: vec![coin(99u128, ATOM_DENOM)],
+            })),
+        ]
+    );
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Some(Uint128::from(100u128)),
+        withdraw_rewards: None,
+        recipient: None,
+        limit: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(99u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr".to_string(),
+                amount: vec![coin(199u128, ATOM_DENOM)],
+            })),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr".to_string(),
+                    amount: Uint128::from(100u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
 }
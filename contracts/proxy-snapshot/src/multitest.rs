@@ -1,5 +1,3 @@
 pub mod contract;
-pub mod cw20_staking_contract;
-pub mod mock_cw20;
 #[cfg(test)]
 mod tests;
@@ -1,99 +1,97 @@
-use cosmwasm_std::{to_binary, Addr, Uint128};
-use cw_multi_test::{App, Executor};
+use cosmwasm_std::{Addr, Uint128};
 use cw_utils::Duration;
-use oraiswap::asset::Asset;
+use oraiswap::asset::{Asset, AssetInfo};
+use oraiswap_staking::helpers::{TestSuite, OWNER};
 
-use super::{
-    contract::ProxySnapshot, cw20_staking_contract::Cw20Staking, mock_cw20::MockCw20Contract,
-};
+use super::contract::ProxySnapshot;
 
 #[test]
 fn test_query_snapshot_balance() {
     // Arrange
-    let mut app = App::default();
-    let owner = Addr::unchecked("owner");
+    let mut suite = TestSuite::default();
+    let owner = Addr::unchecked(OWNER);
 
-    let cw20 = MockCw20Contract::instantiate(&mut app, &owner, &owner).unwrap();
-    let asset_key = cw20.addr().clone();
+    let cw20 = suite.create_token("MCW", &[(OWNER, 10000000u128)]);
+    let asset_key = cw20.clone();
     // contracts instantiation
-    let cw20_staking = Cw20Staking::instantiate(&mut app, &owner, &owner, Some("owner".into()));
+    let staking_addr = suite.staking_addr.clone();
     let snapshot = ProxySnapshot::instantiate(
-        &mut app,
+        &mut suite.app,
         &owner,
         &owner,
         &asset_key,
-        cw20_staking.addr(),
+        &staking_addr,
         Some("owner".into()),
     );
 
     // setup Cw20Staking contract
-    app.execute_contract(
-        owner.clone(),
-        cw20_staking.addr().clone(),
-        &oraiswap_staking::msg::ExecuteMsg::RegisterAsset {
-            staking_token: asset_key.clone(),
-            unbonding_period: Some(100),
-        },
-        &[],
-    )
-    .unwrap();
-
-    app.execute_contract(
-        owner.clone(),
-        cw20_staking.addr().clone(),
-        &oraiswap_staking::msg::ExecuteMsg::UpdateRewardsPerSec {
-            staking_token: asset_key.clone(),
-            assets: vec![Asset {
-                info: oraiswap::asset::AssetInfo::Token {
+    suite.register_pool(&asset_key, Some(100)).unwrap();
+    suite
+        .update_rewards_per_sec(
+            &asset_key,
+            vec![Asset {
+                info: AssetInfo::Token {
                     contract_addr: asset_key.clone(),
                 },
                 amount: 100u128.into(),
             }],
-        },
-        &[],
-    )
-    .unwrap();
+        )
+        .unwrap();
 
     // Action
-    // sSaked
-    app.execute_contract(
-        owner.clone(),
-        cw20.addr().clone(),
-        &cw20_base::msg::ExecuteMsg::Send {
-            contract: cw20_staking.addr().clone().to_string(),
-            amount: Uint128::from(100u128),
-            msg: to_binary(&oraiswap_staking::msg::Cw20HookMsg::Bond {}).unwrap(),
-        },
-        &[],
-    )
-    .unwrap();
-    let mut current_block = app.block_info();
+    // Staked
+    suite.bond(OWNER, &cw20, 100u128).unwrap();
     // Unstaked
-    app.execute_contract(
-        owner.clone(),
-        cw20_staking.addr().clone(),
-        &oraiswap_staking::msg::ExecuteMsg::Unbond {
-            staking_token: asset_key.clone(),
-            amount: Uint128::from(50u128),
-        },
-        &[],
-    )
-    .unwrap();
+    suite.unbond(OWNER, &asset_key, Some(50u128)).unwrap();
 
     // increase block height to confirmed, and update snapshot
-    current_block.height += 1;
-    app.set_block(current_block);
-    let total = snapshot.query_total_staked_at_height(&app, None);
-    let staked_balance = snapshot.query_staked_balace_at_height(&app, &owner, None);
+    suite.advance_seconds(5);
+    let total = snapshot.query_total_staked_at_height(&suite.app, None);
+    let staked_balance = snapshot.query_staked_balace_at_height(&suite.app, &owner, None);
 
     // Assert
-    let config_token_response = snapshot.query_config_token_staking(&app);
+    let config_token_response = snapshot.query_config_token_staking(&suite.app);
 
     assert_eq!(
         config_token_response.unstaking_duration,
         Some(100).map(Duration::Time)
     );
-    assert_eq!(config_token_response.token_address, cw20.addr().clone());
+    assert_eq!(config_token_response.token_address, cw20);
     assert_eq!(total.total.u128(), 50u128);
     assert_eq!(staked_balance.balance.u128(), 50u128);
 }
+
+#[test]
+fn test_bond_from() {
+    let mut suite = TestSuite::default();
+
+    let cw20 = suite.create_token("MCW", &[(OWNER, 10000000u128)]);
+    suite.register_pool(&cw20, None).unwrap();
+
+    let bond_from = oraiswap_staking::msg::ExecuteMsg::BondFrom {
+        asset_info: AssetInfo::Token {
+            contract_addr: cw20.clone(),
+        },
+        amount: Uint128::from(100u128),
+    };
+
+    // without an allowance the pull fails, and nothing is bonded
+    suite.execute(OWNER, &bond_from, &[]).unwrap_err();
+    suite.advance_seconds(5);
+    assert_eq!(suite.total_staked_at_height(&cw20, None), Uint128::zero());
+
+    // an allowance below the amount is not enough either
+    suite.increase_allowance(OWNER, &cw20, 50u128).unwrap();
+    suite.execute(OWNER, &bond_from, &[]).unwrap_err();
+
+    suite.increase_allowance(OWNER, &cw20, 50u128).unwrap();
+    suite.execute(OWNER, &bond_from, &[]).unwrap();
+
+    suite.advance_seconds(5);
+    assert_eq!(
+        suite.staked_balance_at_height(OWNER, &cw20, None),
+        Uint128::from(100u128)
+    );
+    let staking_addr = suite.staking_addr.clone();
+    suite.assert_token_balance(&cw20, staking_addr.as_str(), 100u128);
+}
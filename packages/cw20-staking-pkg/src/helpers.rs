@@ -0,0 +1,196 @@
+use cosmwasm_schema::serde::de::DeserializeOwned;
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, QuerierWrapper, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use oraiswap::asset::AssetInfo;
+
+use crate::msg::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, PoolInfoResponse, QueryMsg, RewardInfoResponse,
+};
+
+/// StakingContract is a wrapper around Addr that provides typed messages and queries of the
+/// staking contract
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StakingContract(pub Addr);
+
+impl StakingContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    pub fn call<T: Into<ExecuteMsg>>(&self, msg: T, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr().into(),
+            msg: to_binary(&msg.into())?,
+            funds,
+        }
+        .into())
+    }
+
+    pub fn query<T: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        msg: &QueryMsg,
+    ) -> StdResult<T> {
+        querier.query_wasm_smart(self.addr(), msg)
+    }
+
+    /// bonds amount of token into the pool of asset_info, a cw20 token is sent to the contract
+    /// with a Bond hook, the native coins are sent along ExecuteMsg::Bond
+    /// the position is credited to staker when set, to the sender otherwise
+    pub fn bond_msg(
+        &self,
+        token: &AssetInfo,
+        amount: Uint128,
+        asset_info: AssetInfo,
+        staker: Option<String>,
+    ) -> StdResult<CosmosMsg> {
+        match token {
+            AssetInfo::Token { contract_addr } => {
+                let hook_msg = if token == &asset_info {
+                    Cw20HookMsg::Bond {
+                        staker,
+                        callback: None,
+                    }
+                } else {
+                    Cw20HookMsg::ZapIn {
+                        asset_info,
+                        min_bond_amount: None,
+                    }
+                };
+                Ok(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Send {
+                        contract: self.addr().into(),
+                        amount,
+                        msg: to_binary(&hook_msg)?,
+                    })?,
+                    funds: vec![],
+                }
+                .into())
+            }
+            AssetInfo::NativeToken { denom } => {
+                let funds = vec![Coin {
+                    denom: denom.clone(),
+                    amount,
+                }];
+                if token == &asset_info {
+                    self.call(ExecuteMsg::Bond { asset_info, staker }, funds)
+                } else {
+                    self.call(
+                        ExecuteMsg::ZapIn {
+                            asset_info,
+                            min_bond_amount: None,
+                        },
+                        funds,
+                    )
+                }
+            }
+        }
+    }
+
+    /// unbonds the whole bond when amount is None
+    pub fn unbond_msg(&self, staking_token: Addr, amount: Option<Uint128>) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::Unbond {
+                staking_token,
+                amount,
+                withdraw_rewards: None,
+                recipient: None,
+                limit: None,
+            },
+            vec![],
+        )
+    }
+
+    /// withdraws the rewards of every pool when staking_token is None
+    pub fn withdraw_msg(&self, staking_token: Option<Addr>) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::Withdraw {
+                staking_token,
+                skip_failing: None,
+            },
+            vec![],
+        )
+    }
+
+    pub fn query_config(&self, querier: &QuerierWrapper) -> StdResult<ConfigResponse> {
+        self.query(querier, &QueryMsg::Config {})
+    }
+
+    pub fn query_pool_info(
+        &self,
+        querier: &QuerierWrapper,
+        staking_token: Addr,
+    ) -> StdResult<PoolInfoResponse> {
+        self.query(querier, &QueryMsg::PoolInfo { staking_token })
+    }
+
+    /// the reward infos of every pool of the staker when staking_token is None
+    pub fn query_reward_info(
+        &self,
+        querier: &QuerierWrapper,
+        staker_addr: Addr,
+        staking_token: Option<Addr>,
+    ) -> StdResult<RewardInfoResponse> {
+        self.query(
+            querier,
+            &QueryMsg::RewardInfo {
+                staker_addr,
+                staking_token,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::coin;
+
+    #[test]
+    fn test_bond_msg() {
+        let staking = StakingContract(Addr::unchecked("staking"));
+        let lp_token = AssetInfo::Token {
+            contract_addr: Addr::unchecked("lptoken"),
+        };
+
+        assert_eq!(
+            staking
+                .bond_msg(&lp_token, Uint128::from(100u128), lp_token.clone(), None)
+                .unwrap(),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "lptoken".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "staking".to_string(),
+                    amount: Uint128::from(100u128),
+                    msg: to_binary(&Cw20HookMsg::Bond {
+                        staker: None,
+                        callback: None
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // a native token which is not the staking token is zapped in
+        let orai = AssetInfo::NativeToken {
+            denom: "orai".to_string(),
+        };
+        assert_eq!(
+            staking
+                .bond_msg(&orai, Uint128::from(100u128), lp_token.clone(), None)
+                .unwrap(),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&ExecuteMsg::ZapIn {
+                    asset_info: lp_token,
+                    min_bond_amount: None,
+                })
+                .unwrap(),
+                funds: vec![coin(100u128, "orai")],
+            })
+        );
+    }
+}
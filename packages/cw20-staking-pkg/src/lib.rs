@@ -0,0 +1,7 @@
+//! Message types of the oraiswap staking contract and a typed wrapper to call it, without the
+//! contract entry points.
+
+pub mod helpers;
+pub mod msg;
+
+pub use helpers::StakingContract;
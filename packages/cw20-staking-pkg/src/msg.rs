@@ -0,0 +1,1453 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+
+use cosmwasm_std::{Addr, Binary, Decimal, Timestamp, Uint128};
+use cw2::ContractVersion;
+use cw20::Cw20ReceiveMsg;
+use cw_controllers::ClaimsResponse;
+use cw_utils::Duration;
+use oraiswap::asset::{Asset, AssetInfo};
+
+// Events indexers can rely on, their keys are kept stable
+// the assets are named by their denom or token contract address
+//
+// deposit_reward, one per pool credited by a deposit:
+//   action = deposit_reward
+//   asset_key = staking token of the pool
+//   reward_amount:<asset> = amount of each reward asset credited to the pool
+//   reward_index = reward index of the pool after the deposit
+// withdraw_reward, one per withdrawal paying out anything:
+//   action = withdraw_reward
+//   staker_addr = staker the rewards were withdrawn for
+//   payout_amount:<asset> = amount of each reward asset paid out, reward split share included
+pub const DEPOSIT_REWARD_EVENT: &str = "deposit_reward";
+pub const WITHDRAW_REWARD_EVENT: &str = "withdraw_reward";
+pub const REWARD_AMOUNT_KEY_PREFIX: &str = "reward_amount:";
+pub const PAYOUT_AMOUNT_KEY_PREFIX: &str = "payout_amount:";
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    // default is sender
+    pub owner: Option<Addr>,
+    pub rewarder: Addr,
+    // pool whose staked balances are the voting power of the dao voting module queries
+    pub governance_token: Option<Addr>,
+    // allow seeding positions with ImportStakers until FinalizeImport, for redeployments
+    pub import_open: Option<bool>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+
+    ////////////////////////
+    /// Owner operations ///
+    ////////////////////////
+    UpdateConfig {
+        fee_collector: Option<Addr>,
+        config_timelock: Option<u64>,
+        max_unbonding_period: Option<u64>,
+        max_lock_entries: Option<u64>,
+        governance_token: Option<Addr>,
+        oracle: Option<Addr>,
+    },
+    // apply a timelocked change once its delay has passed, callable by anyone
+    ExecutePending {
+        id: u64,
+    },
+    CancelPending {
+        id: u64,
+    },
+    AddRewarder {
+        address: Addr,
+    },
+    // the last rewarder cannot be removed
+    RemoveRewarder {
+        address: Addr,
+    },
+    // operators can only update rewards per second and unbonding periods
+    AddOperator {
+        address: Addr,
+    },
+    RemoveOperator {
+        address: Addr,
+    },
+    // contracts receiving a StakeChangedExecuteMsg whenever a staked balance changes
+    AddHook {
+        addr: String,
+    },
+    RemoveHook {
+        addr: String,
+    },
+    // contracts receiving a ClaimHookExecuteMsg whenever rewards are withdrawn
+    AddClaimHook {
+        addr: String,
+    },
+    RemoveClaimHook {
+        addr: String,
+    },
+    // a hook is skipped once it failed MAX_HOOK_FAILURES times, until its failures are reset
+    ResetHookFailures {
+        kind: HookKind,
+        addr: String,
+    },
+    // propose a new owner, who must accept the ownership within expires_in seconds
+    ProposeNewOwner {
+        owner: Addr,
+        expires_in: u64,
+    },
+    DropOwnershipProposal {},
+    // called by the proposed owner to complete the ownership transfer
+    AcceptOwnership {},
+    // give up the ownership permanently, all owner operations are disabled afterwards
+    RenounceOwnership {},
+    // the current rewarders keep working until the proposed one accepts, then `replace` is removed
+    ProposeNewRewarder {
+        rewarder: Addr,
+        replace: Option<Addr>,
+    },
+    AcceptRewarder {},
+    // once the allowlist is not empty, only allowed staking tokens can be registered
+    AddAllowedStakingToken {
+        staking_token: Addr,
+    },
+    RemoveAllowedStakingToken {
+        staking_token: Addr,
+    },
+    // blacklisted addresses cannot bond anymore, but can still unbond and withdraw
+    SetBlacklist {
+        address: Addr,
+        blacklisted: bool,
+    },
+    // a native staking token is registered with its denom as staking_token
+    RegisterAsset {
+        staking_token: Addr,
+        unbonding_period: Option<u64>,
+        native_token: Option<bool>,
+    },
+    // existing locks keep their unlock time, also callable by operators
+    UpdateUnbondingPeriod {
+        staking_token: Addr,
+        new_period: u64,
+    },
+    // share of every future unbond sent to the fee collector
+    UpdateUnbondFee {
+        staking_token: Addr,
+        unbond_fee: Decimal,
+    },
+    // share of an instant unbond sent to the fee collector, zero disables instant unbonds
+    UpdateInstantUnbondPenalty {
+        staking_token: Addr,
+        penalty: Decimal,
+    },
+    // every position of the pool must be at least this amount, unless fully exited
+    UpdateMinBondAmount {
+        staking_token: Addr,
+        min_bond_amount: Uint128,
+    },
+    // withdrawals from the pool must pay out at least this amount summed over the reward assets
+    UpdateMinClaimAmount {
+        staking_token: Addr,
+        min_claim_amount: Uint128,
+    },
+    // withdrawn rewards of the pool vest over this many seconds, None pays them out right away
+    UpdateRewardVestingPeriod {
+        staking_token: Addr,
+        reward_vesting_period: Option<u64>,
+    },
+    // point the pool to the new address of its migrated staking token, stakes are kept as is
+    // the token may already be staked by another pool, the two share it from then on
+    MigrateStakingToken {
+        staking_token: Addr,
+        new_staking_token: Addr,
+    },
+    // write the bonds made before the snapshots were deployed to the snapshots at the current
+    // height, a page of stakers at a time, start_after is the last staker of the previous page
+    BackfillSnapshots {
+        staking_token: Addr,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    // move up to limit entries of kind left by older deployments under the legacy storage
+    // prefixes, until the done attribute is true. Legacy entries are read as is and move on
+    // their next write, so this only finishes the job for the listings
+    MigrateLegacy {
+        kind: LegacyKind,
+        limit: Option<u32>,
+    },
+    // credit the bonds of a snapshot to the stakers without token transfers, the staking tokens
+    // backing them must be sent to the contract separately. Only while the import is open
+    ImportStakers {
+        staking_token: Addr,
+        entries: Vec<(String, Uint128)>,
+    },
+    // permanently close the import
+    FinalizeImport {},
+    // exit the whole position of a staker, locks included, everything is sent to the staker
+    ForceUnbond {
+        staking_token: Addr,
+        staker_addr: Addr,
+    },
+    // send out the balance of an asset above what is owed to stakers
+    Sweep {
+        asset_info: AssetInfo,
+        recipient: Addr,
+    },
+    // allow stakers to move their bond between two pools of the same staking token
+    SetStakeMigration {
+        from_staking_token: Addr,
+        to_staking_token: Addr,
+        enabled: bool,
+    },
+    // permanently stop bonding and rewards of a pool, stakers can still exit
+    DeprecatePool {
+        staking_token: Addr,
+    },
+    // the pool admin can update rewards per second, the unbonding period and the status of its pool
+    SetPoolAdmin {
+        staking_token: Addr,
+        pool_admin: Option<Addr>,
+    },
+    // freeze bond, unbond or withdraw of a single pool, also callable by the pool admin
+    SetPoolStatus {
+        staking_token: Addr,
+        bond_paused: bool,
+        unbond_paused: bool,
+        withdraw_paused: bool,
+    },
+    // update rewards per second for an asset, also callable by operators
+    // only the owner can force an update during the cooldown of the pool
+    UpdateRewardsPerSec {
+        staking_token: Addr,
+        assets: Vec<Asset>,
+        force: Option<bool>,
+    },
+    // guardrails checked by every rewards per second update of the pool
+    SetRewardsPerSecLimits {
+        staking_token: Addr,
+        max_rewards_per_sec: Option<Uint128>,
+        cooldown: u64,
+    },
+    // distribute amount_per_sec of an asset from start_time until end_time, never ends without end_time
+    // also callable by operators, deposits are split by the schedules overlapping the seconds since the previous one
+    // up to MAX_REWARD_SCHEDULES of them can run side by side, each with its own asset
+    CreateRewardSchedule {
+        staking_token: Addr,
+        asset_info: AssetInfo,
+        amount_per_sec: Uint128,
+        start_time: u64,
+        end_time: Option<u64>,
+    },
+    // stop a schedule now, the seconds it already ran are still distributed
+    CancelRewardSchedule {
+        staking_token: Addr,
+        id: u64,
+    },
+    // let anyone deposit rewards into the pool, the deposit must send exactly the declared amount
+    SetOpenDeposit {
+        staking_token: Addr,
+        open_deposit: bool,
+    },
+    // send out the rewards deposited while nobody was bonded, split like the rewards of the pool
+    // only possible while the pool is empty
+    ReclaimPendingReward {
+        staking_token: Addr,
+        recipient: String,
+    },
+    // Pull makes the pool accrue its rewards per sec from the contract balance instead of DepositReward
+    SetAccrualMode {
+        staking_token: Addr,
+        mode: AccrualMode,
+    },
+    // accrue the rewards of a pull mode pool until now, anyone can call it
+    UpdatePoolIndex {
+        staking_token: Addr,
+    },
+    // stakers of the pool can commit their bond for a boosted reward weight, None disables new boosts
+    SetBoostCurve {
+        staking_token: Addr,
+        curve: Option<BoostCurve>,
+    },
+    // reward tokens are in amount proportionaly, and used by minter contract to update amounts after checking the balance, which
+    // will be used as rewards for the specified asset's staking pool.
+    // the native rewards must be sent along, exactly the declared amount per denom
+    DepositReward {
+        rewards: Vec<RewardMsg>,
+    },
+    // rewarders only, for rewards transferred to the contract beforehand, its balance must cover
+    // everything it owes in the credited assets
+    DepositPrefundedReward {
+        rewards: Vec<RewardMsg>,
+    },
+    // split total_amount between the pools by weight and deposit it like DepositReward,
+    // the rounding remainder goes to the heaviest pool
+    DepositWeightedReward {
+        total_amount: Uint128,
+        weights: Vec<PoolWeight>,
+    },
+
+    ////////////////////////
+    /// User operations ///
+    ////////////////////////
+    // bond the native staking token sent in funds, cw20 staking tokens use the Receive hook
+    Bond {
+        asset_info: AssetInfo,
+        staker: Option<String>,
+    },
+    // pull amount of a cw20 staking token with TransferFrom, the sender must have approved an allowance
+    // the bond is only credited once the transfer succeeded
+    BondFrom {
+        asset_info: AssetInfo,
+        amount: Uint128,
+    },
+    // swap the sent coin into the staking token of the pool through the router and bond the output,
+    // the whole transaction reverts when the output is below min_bond_amount
+    ZapIn {
+        asset_info: AssetInfo,
+        min_bond_amount: Option<Uint128>,
+    },
+    // the whole bond amount is unbonded when amount is omitted
+    Unbond {
+        staking_token: Addr,
+        amount: Option<Uint128>,
+        // also withdraw the rewards of the pool in the same transaction
+        withdraw_rewards: Option<bool>,
+        // matured locks released by this unbond are sent here instead of the sender
+        recipient: Option<String>,
+        // matured locks released at most, the rest stays claimable, DEFAULT_LOCK_LIMIT by default
+        limit: Option<u32>,
+    },
+    // unbond from several pools at once, fails if any of them fails
+    UnbondMany {
+        unbonds: Vec<UnbondMsg>,
+    },
+    // bond again an amount that is still locked, taken from the soonest unlocks first
+    CancelUnbond {
+        staking_token: Addr,
+        amount: Uint128,
+    },
+    // move bond to another pool of the same staking token without unbonding, the route must be enabled
+    MigrateStake {
+        from_staking_token: Addr,
+        to_staking_token: Addr,
+        amount: Uint128,
+    },
+    // bond the pending rewards paid in the staking token of the pool, other rewards are withdrawn
+    Compound {
+        staking_token: Addr,
+    },
+    // pay out only the rewards set aside when the reward assets changed, from all pools when omitted
+    ClaimPendingWithdraw {
+        staking_token: Option<Addr>,
+    },
+    // pay out the vesting tranches of the pool that are unlocked
+    ClaimVested {
+        staking_token: Addr,
+    },
+    // unbond everything through the usual locks and forfeit all rewards of the pool, in case they cannot be paid out
+    EmergencyUnbond {
+        staking_token: Addr,
+    },
+    // commit the whole bond of the pool for lock_weeks, it cannot be unbonded until then and earns with the multiplier of the curve
+    Boost {
+        staking_token: Addr,
+        lock_weeks: u64,
+    },
+    // drop the multiplier of a matured commitment, anyone can call it
+    ExpireBoost {
+        staking_token: Addr,
+        staker_addr: Addr,
+    },
+    // bond again the matured locks instead of withdrawing them
+    Restake {
+        staking_token: Addr,
+    },
+    // skip the unbonding period, the penalty of the pool is taken from the amount
+    InstantUnbond {
+        staking_token: Addr,
+        amount: Uint128,
+    },
+    /// Withdraw pending rewards
+    Withdraw {
+        // If the asset token is not given, then all rewards are withdrawn
+        staking_token: Option<Addr>,
+        // a failing cw20 transfer is kept in pending_withdraw instead of reverting the withdraw
+        skip_failing: Option<bool>,
+    },
+    // withdraw the rewards and swap the ones the router has a route for into to_asset,
+    // min_receive applies to the whole to_asset amount paid
+    WithdrawAndSwap {
+        staking_token: Option<Addr>,
+        to_asset: AssetInfo,
+        min_receive: Option<Uint128>,
+    },
+    // the pool whose stakers are the members of the cw4 queries, None disables them
+    // weights are the staked amounts divided by weight_divisor, 1 when unset
+    UpdateMembership {
+        staking_token: Option<Addr>,
+        weight_divisor: Option<Uint128>,
+    },
+    // the pool served by the cw20-stake queries, None disables them
+    UpdateCw20StakePool {
+        staking_token: Option<Addr>,
+    },
+    // the oraiswap router used by WithdrawAndSwap, None disables it
+    UpdateRouter {
+        router: Option<Addr>,
+    },
+    // withdraw from at most limit pools in key order, the attributes tell where to continue with start_after
+    WithdrawAll {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    // the delegate can withdraw the rewards of the sender with WithdrawFor, None revokes it
+    SetClaimDelegate {
+        delegate: Option<String>,
+    },
+    // bps of every reward withdrawn by the sender go to the beneficiary, None removes the split
+    SetRewardSplit {
+        beneficiary: Option<String>,
+        bps: u16,
+    },
+    // called by the claim delegate of staker, the rewards are sent to the staker
+    WithdrawFor {
+        staker: Addr,
+        staking_token: Option<Addr>,
+    },
+    // Withdraw for others in this pool, such as when rewards per second are changed for the pool
+    WithdrawOthers {
+        staking_token: Option<Addr>,
+        staker_addrs: Vec<Addr>,
+    },
+}
+
+/// Messages only callable by the chain governance
+#[cw_serde]
+pub enum SudoMsg {
+    // block every execute message except the owner config update
+    Pause {},
+    Unpause {},
+    // recover the contract when the owner key is lost, also restores a renounced ownership
+    UpdateOwner { new_owner: String },
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    // this call from LP token contract, the position is credited to staker when set
+    Bond {
+        staker: Option<String>,
+        // the sending contract is called back with a BondCallbackExecuteMsg once bonded
+        callback: Option<Binary>,
+    },
+    // splits the sent amount across several pools accepting the same token, amounts must sum up to it
+    BondSplit {
+        allocations: Vec<(AssetInfo, Uint128)>,
+    },
+    // swap the sent token into the staking token of the pool and bond it, see ExecuteMsg::ZapIn
+    ZapIn {
+        asset_info: AssetInfo,
+        min_bond_amount: Option<Uint128>,
+    },
+    // deposit rewards paid in the sent token, which must be the single reward asset of the pools
+    // the sent amount must cover the declared amounts, and match them exactly for open deposits
+    DepositReward {
+        rewards: Vec<RewardMsg>,
+    },
+    // the sent amount split between the pools by weight, see ExecuteMsg::DepositWeightedReward
+    DepositWeightedReward {
+        weights: Vec<PoolWeight>,
+    },
+}
+
+/// We currently take no arguments for migrations
+#[cw_serde]
+pub struct MigrateMsg {}
+
+/// the state kept under versioned storage prefixes
+#[cw_serde]
+pub enum LegacyKind {
+    PoolInfos,
+    Rewards,
+    Stakers,
+    RewardsPerSec,
+}
+
+/// We currently take no arguments for migrations
+#[cw_serde]
+pub struct AmountInfo {
+    pub asset_info: AssetInfo,
+    pub amount: Uint128,
+    // pub new_staking_token: Addr,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    // dao voting module interface, the staked balance snapshots of the governance token pool
+    #[returns(VotingPowerAtHeightResponse)]
+    VotingPowerAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+    #[returns(TotalPowerAtHeightResponse)]
+    TotalPowerAtHeight { height: Option<u64> },
+    #[returns(InfoResponse)]
+    Info {},
+    #[returns(Option<OwnershipProposalResponse>)]
+    OwnershipProposal {},
+    #[returns(Option<RewarderProposalResponse>)]
+    PendingRewarder {},
+    #[returns(PausedResponse)]
+    Paused {},
+    #[returns(Vec<PendingChangeResponse>)]
+    PendingChanges {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(Vec<Addr>)]
+    Rewarders {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    #[returns(HooksResponse)]
+    Hooks {},
+    #[returns(HooksResponse)]
+    ClaimHooks {},
+    // every hook of both kinds with its failed calls
+    #[returns(Vec<HookFailuresResponseItem>)]
+    HookFailures {},
+    #[returns(Vec<Addr>)]
+    Operators {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    #[returns(Vec<Addr>)]
+    AllowedStakingTokens {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    #[returns(Vec<Addr>)]
+    Blacklist {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    #[returns(bool)]
+    IsBlacklisted { address: Addr },
+    #[returns(Option<Addr>)]
+    ClaimDelegate { staker_addr: Addr },
+    #[returns(Option<Addr>)]
+    Router {},
+    // cw4 group interface over the stakers of the membership pool, members without stake have no weight
+    #[returns(MemberResponse)]
+    Member {
+        addr: String,
+        at_height: Option<u64>,
+    },
+    #[returns(MemberListResponse)]
+    ListMembers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(TotalWeightResponse)]
+    TotalWeight { at_height: Option<u64> },
+    #[returns(Option<MembershipResponse>)]
+    Membership {},
+    // cw20-stake interface over the cw20-stake pool, the value of a stake is its bond amount
+    // and the claims are the unbonding locks
+    #[returns(StakedValueResponse)]
+    StakedValue { address: String },
+    #[returns(TotalValueResponse)]
+    TotalValue {},
+    #[returns(Cw20StakeConfigResponse)]
+    GetConfig {},
+    #[returns(ListStakersResponse)]
+    ListStakers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(ClaimsResponse)]
+    Claims { address: String },
+    // rewards owed to the stakers of all pools per reward asset, deposited and not paid out yet, vestings left out
+    #[returns(Vec<Asset>)]
+    OutstandingRewards {},
+    #[returns(Option<RewardSplitResponse>)]
+    RewardSplit { staker_addr: Addr },
+    #[returns(PoolInfoResponse)]
+    PoolInfo { staking_token: Addr },
+    // the bonded amount of the pool priced by the oracle, price and value are None without an oracle
+    #[returns(PoolValueResponse)]
+    PoolValue { asset_info: AssetInfo },
+    #[returns(RewardsPerSecResponse)]
+    RewardsPerSec { staking_token: Addr },
+    #[returns(PoolAprResponse)]
+    PoolApr { staking_token: Addr },
+    #[returns(AccrualModeResponse)]
+    AccrualMode { staking_token: Addr },
+    // reward the index of the pool could not hold yet, deposited == distributed + pending_reward + dust
+    #[returns(RewardDustResponse)]
+    RewardDust { staking_token: Addr },
+    #[returns(Vec<RewardScheduleResponse>)]
+    RewardSchedules {
+        staking_token: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(RewardInfoResponse)]
+    RewardInfo {
+        staker_addr: Addr,
+        staking_token: Option<Addr>,
+    },
+    // commitment of the staker in the pool, the multiplier is 1 without a running one
+    #[returns(BoostResponse)]
+    Boost {
+        staker_addr: Addr,
+        staking_token: Addr,
+    },
+    // pending withdraw of the staker summed per asset, from all pools when staking_token is omitted
+    #[returns(PendingWithdrawResponse)]
+    PendingWithdraw {
+        staker_addr: Addr,
+        staking_token: Option<Addr>,
+    },
+    // vesting tranches of withdrawn rewards, soonest unlock first
+    #[returns(RewardVestingsResponse)]
+    RewardVestings {
+        staker_addr: Addr,
+        staking_token: Addr,
+    },
+    // amounts per reward asset a withdraw would pay out now, from all pools when staking_token is omitted
+    #[returns(Vec<Asset>)]
+    PendingRewards {
+        staker_addr: Addr,
+        staking_token: Option<Addr>,
+    },
+    // pools the staker has rewards to withdraw from, WithdrawAll needs one pass per limit of them
+    #[returns(RewardPoolCountResponse)]
+    RewardPoolCount { staker_addr: Addr },
+    #[returns(Vec<RewardInfoResponse>)]
+    // Query all staker belong to the pool
+    RewardInfos {
+        staking_token: Addr,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        // so can convert or throw error
+        order: Option<i32>,
+    },
+    // bonded stakers of the pool with their bond amounts, start_after is the last staker of the previous page
+    #[returns(Vec<PoolStakersResponseItem>)]
+    PoolStakers {
+        staking_token: Addr,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        order: Option<i32>,
+    },
+    #[returns(Vec<QueryPoolInfoResponse>)]
+    GetPoolsInformation {},
+    // a page of the pools, start_after is the staking token of the last pool of the previous page
+    #[returns(Vec<PoolsResponseItem>)]
+    Pools {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        order: Option<i32>,
+    },
+    // the contract version, the pool count and a page of the pools with their totals
+    #[returns(SummaryResponse)]
+    Summary {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        order: Option<i32>,
+    },
+    // the rewards per second of a page of the pools, pools never configured are left out
+    // as RewardsPerSec fails for them
+    #[returns(Vec<AllRewardsPerSecResponseItem>)]
+    AllRewardsPerSec {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        order: Option<i32>,
+    },
+    // the pools with an unbonding period, zero periods are not stored so pools unbonding
+    // right away are left out
+    #[returns(Vec<UnbondingPeriodsResponseItem>)]
+    UnbondingPeriods {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        order: Option<i32>,
+    },
+    #[returns(UnbondingPeriodResponse)]
+    UnbondingPeriod {
+        staking_token: Addr,
+        // also list the locks of this staker with the period they were created under
+        staker_addr: Option<Addr>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(LockInfosResponse)]
+    LockInfos {
+        staker_addr: Addr,
+        staking_token: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        // so can convert or throw error
+        order: Option<i32>,
+        // bounds of the unlock time, start_time is inclusive and end_time exclusive
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    },
+    // every lock of the pool ordered by staker then unlock time, for indexers
+    // start_after is the (staker, unlock_time) of the last lock of the previous page
+    #[returns(Vec<PoolLocksResponseItem>)]
+    PoolLocks {
+        staking_token: Addr,
+        start_after: Option<(Addr, u64)>,
+        limit: Option<u32>,
+    },
+    // the pools currently staking the token, including a pool migrated to it
+    #[returns(Vec<PoolInfoResponse>)]
+    PoolByStakingToken { staking_token: String },
+    // the keys of every pool staking the token, as passed to the pool messages
+    #[returns(Vec<Addr>)]
+    PoolsByStakingToken { staking_token: String },
+    // a staker has a bond in the pool, entries only left with rewards do not count
+    #[returns(IsStakerResponse)]
+    IsStaker {
+        staking_token: Addr,
+        staker_addr: Addr,
+    },
+    // number of addresses with a bond or rewards left in the pool
+    #[returns(StakerCountResponse)]
+    StakerCount { staking_token: Addr },
+    // the amount of the pool waiting in locks
+    #[returns(TotalUnbondingResponse)]
+    TotalUnbonding { staking_token: Addr },
+    // what unbonding zero would release at at_time, the current block time by default
+    #[returns(ClaimableResponse)]
+    Claimable {
+        staker_addr: Addr,
+        staking_token: Addr,
+        at_time: Option<u64>,
+    },
+    // the locks of a staker in a pool summed up, matured_amount is claimable by unbonding zero
+    #[returns(UnbondingSummaryResponse)]
+    UnbondingSummary {
+        staker_addr: Addr,
+        staking_token: Addr,
+    },
+    // the reward infos of the given stakers in the same order, stakers without one are zeroed
+    #[returns(Vec<RewardInfosBatchResponseItem>)]
+    RewardInfosBatch {
+        staking_token: Addr,
+        stakers: Vec<String>,
+    },
+    // the positions of a staker across the pools, start_after is the staking token of the last pool
+    #[returns(Vec<StakerInfoResponseItem>)]
+    AllStakerInfo {
+        staker_addr: Addr,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        order: Option<i32>,
+    },
+    // compare with max_lock_entries of the config before unbonding again
+    #[returns(LockCountResponse)]
+    LockCount {
+        staker_addr: Addr,
+        staking_token: Addr,
+    },
+    // snapshot
+    #[returns(StakedBalanceAtHeightResponse)]
+    StakedBalanceAtHeight {
+        asset_key: Addr,
+        address: String,
+        height: Option<u64>,
+    },
+    #[returns(TotalStakedAtHeightResponse)]
+    TotalStakedAtHeight {
+        asset_key: Addr,
+        height: Option<u64>,
+    },
+    // staked balance after the last block executed at or before time, in seconds
+    // errors when time predates the oldest recorded block
+    #[returns(StakedBalanceAtTimeResponse)]
+    StakedBalanceAtTime {
+        asset_key: Addr,
+        address: String,
+        time: u64,
+    },
+    // every change to a staked balance with the balance before it, oldest first
+    // only changes made since the snapshots were deployed are recorded
+    #[returns(Vec<SnapshotChangeResponseItem>)]
+    StakedBalanceChangelog {
+        asset_key: Addr,
+        address: String,
+        start_after_height: Option<u64>,
+        limit: Option<u32>,
+    },
+    // every change to the total staked in a pool, like StakedBalanceChangelog
+    #[returns(Vec<SnapshotChangeResponseItem>)]
+    StakedTotalChangelog {
+        asset_key: Addr,
+        start_after_height: Option<u64>,
+        limit: Option<u32>,
+    },
+    // the current snapshot total against the total bond of the pool, equal once backfilled
+    #[returns(SnapshotConsistencyResponse)]
+    SnapshotConsistency { staking_token: Addr },
+    // every stored record of kind in storage order, start_after is the next of the previous page
+    // entries still under the legacy prefixes are not exported, run MigrateLegacy first
+    #[returns(ExportStateResponse)]
+    ExportState {
+        kind: ExportKind,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // bond of the staker and index of the pool at the start of height, errors before the first index checkpoint
+    #[returns(RewardStateAtHeightResponse)]
+    RewardStateAtHeight {
+        staker_addr: Addr,
+        staking_token: Addr,
+        height: Option<u64>,
+    },
+}
+
+// We define a custom struct for each query response
+#[cw_serde]
+pub struct ConfigResponse {
+    /// None once the ownership has been renounced
+    pub owner: Option<Addr>,
+    /// receives the unbond fees and instant unbond penalties
+    pub fee_collector: Option<Addr>,
+    /// seconds sensitive updates stay pending before they can be executed
+    pub config_timelock: u64,
+    /// unbonding periods cannot be increased above it
+    pub max_unbonding_period: u64,
+    /// unbonds are rejected once a staker has this many locks in a pool
+    pub max_lock_entries: u64,
+    /// pool backing the voting power queries
+    pub governance_token: Option<Addr>,
+    /// oracle pricing the staking tokens
+    pub oracle: Option<Addr>,
+    /// cw2 contract name
+    pub contract_name: String,
+    /// cw2 contract version
+    pub contract_version: String,
+    /// number of registered pools
+    pub pool_count: u64,
+    /// ImportStakers is accepted until FinalizeImport
+    pub import_open: bool,
+}
+
+#[cw_serde]
+pub struct VotingPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct TotalPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct HooksResponse {
+    pub hooks: Vec<String>,
+}
+
+#[cw_serde]
+pub enum HookKind {
+    Stake,
+    Claim,
+}
+
+#[cw_serde]
+pub struct HookFailuresResponseItem {
+    pub kind: HookKind,
+    pub hook: String,
+    pub failures: u64,
+    // the hook is not called anymore
+    pub disabled: bool,
+}
+
+// sent to the hooks after the bond of a staker in a pool changed
+#[cw_serde]
+pub struct StakeChangedHookMsg {
+    pub staker: Addr,
+    pub asset_info: AssetInfo,
+    pub old_amount: Uint128,
+    pub new_amount: Uint128,
+}
+
+#[cw_serde]
+pub enum StakeChangedExecuteMsg {
+    StakeChangeHook(StakeChangedHookMsg),
+}
+
+// sent back to the contract which bonded with a callback, its failure does not revert the bond
+#[cw_serde]
+pub struct BondCallbackMsg {
+    pub staker: Addr,
+    pub asset_info: AssetInfo,
+    pub bonded_amount: Uint128,
+    // bond of the staker in the pool after the bond
+    pub total_bond: Uint128,
+    // the callback given to the bond hook
+    pub msg: Binary,
+}
+
+#[cw_serde]
+pub enum BondCallbackExecuteMsg {
+    BondCallback(BondCallbackMsg),
+}
+
+// sent to the claim hooks after a withdrawal paid out rewards
+#[cw_serde]
+pub struct ClaimHookMsg {
+    pub staker: Addr,
+    // None when the rewards of every pool were withdrawn at once
+    pub asset_info: Option<AssetInfo>,
+    // the rewards withdrawn, the reward split share included
+    pub rewards: Vec<Asset>,
+}
+
+#[cw_serde]
+pub enum ClaimHookExecuteMsg {
+    ClaimHook(ClaimHookMsg),
+}
+
+#[cw_serde]
+pub struct MemberResponse {
+    pub weight: Option<u64>,
+}
+
+#[cw_serde]
+pub struct Member {
+    pub addr: String,
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct MemberListResponse {
+    pub members: Vec<Member>,
+}
+
+#[cw_serde]
+pub struct TotalWeightResponse {
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct MembershipResponse {
+    pub staking_token: Addr,
+    pub weight_divisor: Uint128,
+}
+
+#[cw_serde]
+pub struct StakedValueResponse {
+    pub value: Uint128,
+}
+
+#[cw_serde]
+pub struct TotalValueResponse {
+    pub total: Uint128,
+}
+
+#[cw_serde]
+pub struct Cw20StakeConfigResponse {
+    pub token_address: Addr,
+    pub unstaking_duration: Option<Duration>,
+}
+
+#[cw_serde]
+pub struct StakerBalanceResponse {
+    pub address: String,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct ListStakersResponse {
+    pub stakers: Vec<StakerBalanceResponse>,
+}
+
+#[cw_serde]
+pub struct PoolValueResponse {
+    pub asset_info: AssetInfo,
+    pub total_bond_amount: Uint128,
+    // price of one staking token in ORACLE_QUOTE_DENOM
+    pub price: Option<Decimal>,
+    pub value: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct InfoResponse {
+    pub info: ContractVersion,
+}
+
+#[cw_serde]
+pub struct OwnershipProposalResponse {
+    pub owner: Addr,
+    pub expires_at: u64,
+}
+
+#[cw_serde]
+pub struct PendingChangeResponse {
+    pub id: u64,
+    pub msg: ExecuteMsg,
+    pub execute_after: u64,
+}
+
+#[cw_serde]
+pub struct PausedResponse {
+    pub paused: bool,
+    // height at which the contract was paused
+    pub height: Option<u64>,
+}
+
+#[cw_serde]
+pub struct RewarderProposalResponse {
+    pub rewarder: Addr,
+    pub replace: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct RewardsPerSecResponse {
+    pub assets: Vec<Asset>,
+    pub limits: RewardsPerSecLimits,
+    // seconds of the last update
+    pub last_updated: Option<u64>,
+}
+
+#[cw_serde]
+pub struct PoolAprResponse {
+    pub staking_token: Addr,
+    pub total_bond_amount: Uint128,
+    pub rewards: Vec<RewardAprResponse>,
+    // rewards paid in other assets than the staking token need their prices to get an apr
+    pub requires_prices: bool,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum AccrualMode {
+    // the rewarder pushes the rewards with DepositReward or DepositPrefundedReward
+    #[default]
+    Push,
+    // rewards per sec accrue on every bond, unbond and withdraw
+    Pull,
+}
+
+#[cw_serde]
+pub struct AccrualModeResponse {
+    pub staking_token: Addr,
+    pub mode: AccrualMode,
+    // None outside of the pull mode
+    pub last_distributed: Option<u64>,
+}
+
+#[cw_serde]
+pub struct RewardDustResponse {
+    pub staking_token: Addr,
+    // fraction of a reward unit, it goes into the next deposit
+    pub dust: Decimal,
+}
+
+#[cw_serde]
+pub struct RewardAprResponse {
+    pub asset_info: AssetInfo,
+    // rewards per second over a year
+    pub annual_emission: Uint128,
+    // annual emission over the total bond, only for rewards paid in the staking token and a bonded pool
+    pub apr: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct BoostResponse {
+    pub multiplier: Decimal,
+    // unbonding is possible from then on, None without a commitment
+    pub expires_at: Option<u64>,
+    pub bond_amount: Uint128,
+    // bond amount the rewards are computed with
+    pub weighted_amount: Uint128,
+    pub curve: Option<BoostCurve>,
+}
+
+#[cw_serde]
+pub struct BoostCurve {
+    // multiplier of a commitment of max_lock_weeks, shorter ones get a linear share of it
+    pub max_multiplier: Decimal,
+    pub max_lock_weeks: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct RewardsPerSecLimits {
+    // cap applied to every asset amount
+    pub max_rewards_per_sec: Option<Uint128>,
+    // seconds to wait between two updates
+    pub cooldown: u64,
+}
+
+#[cw_serde]
+pub struct RewardScheduleResponse {
+    pub id: u64,
+    pub asset_info: AssetInfo,
+    pub amount_per_sec: Uint128,
+    pub start_time: u64,
+    pub end_time: Option<u64>,
+    pub status: RewardScheduleStatus,
+    // seconds left to run, None for open-ended schedules
+    pub remaining_duration: Option<u64>,
+    // amount not yet covered by a deposit, None for open-ended schedules
+    pub undistributed_budget: Option<Uint128>,
+}
+
+#[cw_serde]
+pub enum RewardScheduleStatus {
+    Pending,
+    Active,
+    Ended,
+}
+
+// We define a custom struct for each query response
+#[cw_serde]
+pub struct PoolInfoResponse {
+    pub staking_token: Addr,
+    pub total_bond_amount: Uint128,
+    // bond amount with the boosts applied, the reward index grows against it
+    pub total_weighted_amount: Uint128,
+    pub reward_index: Decimal,
+    pub pending_reward: Uint128,
+    pub unbonding_period: Option<u64>,
+    pub unbond_fee: Decimal,
+    pub instant_unbond_penalty: Option<Decimal>,
+    pub min_bond_amount: Uint128,
+    // pending rewards of a staker must reach it to be withdrawn, unless the staker has exited
+    pub min_claim_amount: Uint128,
+    // unbonded amount not claimed yet, total_bond_amount + total_unbonding is the tvl of the pool
+    pub total_unbonding: Uint128,
+    pub staker_count: u64,
+    // anyone can deposit rewards
+    pub open_deposit: bool,
+    pub deprecated: bool,
+    pub pool_admin: Option<Addr>,
+    pub status: PoolStatus,
+}
+
+#[cw_serde]
+pub struct StakerInfoResponseItem {
+    pub staking_token: Addr,
+    pub bond_amount: Uint128,
+    pub pending_reward: Uint128,
+    pub pending_withdraw: Vec<Asset>,
+    // locked by unbonding, not part of bond_amount anymore
+    pub total_unbonding: Uint128,
+}
+
+#[cw_serde]
+pub struct PoolStakersResponseItem {
+    pub staker_addr: Addr,
+    pub bond_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct AllRewardsPerSecResponseItem {
+    pub staking_token: Addr,
+    pub assets: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct UnbondingPeriodsResponseItem {
+    pub staking_token: Addr,
+    pub unbonding_period: u64,
+}
+
+#[cw_serde]
+pub struct PoolsResponseItem {
+    pub staking_token: Addr,
+    pub total_bond_amount: Uint128,
+    pub reward_index: Decimal,
+    pub pending_reward: Uint128,
+    pub unbonding_period: Option<u64>,
+    pub rewards_per_sec: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct SummaryResponse {
+    pub version: ContractVersion,
+    pub pool_count: u64,
+    pub pools: Vec<SummaryPoolItem>,
+}
+
+#[cw_serde]
+pub struct SummaryPoolItem {
+    pub staking_token: Addr,
+    pub total_bond_amount: Uint128,
+    pub total_unbonding: Uint128,
+    pub staker_count: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PoolStatus {
+    pub bond_paused: bool,
+    pub unbond_paused: bool,
+    pub withdraw_paused: bool,
+}
+
+// We define a custom struct for each query response
+#[cw_serde]
+pub struct RewardInfoResponse {
+    pub staker_addr: Addr,
+    pub reward_infos: Vec<RewardInfoResponseItem>,
+}
+
+#[cw_serde]
+pub struct RewardInfoResponseItem {
+    pub staking_token: Addr,
+    pub bond_amount: Uint128,
+    pub pending_reward: Uint128,
+    pub pending_withdraw: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct RewardInfosBatchResponseItem {
+    pub staker_addr: Addr,
+    pub bond_amount: Uint128,
+    pub pending_reward: Uint128,
+    pub pending_withdraw: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct PendingWithdrawResponse {
+    pub staker_addr: Addr,
+    pub pending_withdraw: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct RewardSplitResponse {
+    pub beneficiary: Addr,
+    pub bps: u16,
+}
+
+#[cw_serde]
+pub struct RewardVestingResponse {
+    pub rewards: Vec<Asset>,
+    pub unlock_time: u64,
+    pub matured: bool,
+}
+
+#[cw_serde]
+pub struct RewardVestingsResponse {
+    pub staker_addr: Addr,
+    pub staking_token: Addr,
+    pub vesting_period: Option<u64>,
+    pub vestings: Vec<RewardVestingResponse>,
+}
+
+#[cw_serde]
+pub struct RewardPoolCountResponse {
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct RewardMsg {
+    pub staking_token: Addr,
+    pub total_accumulation_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct PoolWeight {
+    pub staking_token: Addr,
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct UnbondMsg {
+    pub staking_token: Addr,
+    // the whole bond amount is unbonded when omitted
+    pub amount: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct QueryPoolInfoResponse {
+    pub asset_key: String,
+    pub pool_info: PoolInfoResponse,
+}
+
+#[cw_serde]
+pub struct LockInfo {
+    pub amount: Uint128,
+    pub unlock_time: Timestamp,
+}
+
+#[cw_serde]
+pub struct LockInfoResponse {
+    pub amount: Uint128,
+    pub unlock_time: u64,
+}
+
+#[cw_serde]
+pub struct PoolLocksResponseItem {
+    pub staker_addr: Addr,
+    pub unlock_time: u64,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct LockInfosResponse {
+    pub staker_addr: Addr,
+    pub staking_token: Addr,
+    pub lock_infos: Vec<LockInfoResponse>,
+}
+
+#[cw_serde]
+pub struct IsStakerResponse {
+    pub is_staker: bool,
+    pub bond_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct StakerCountResponse {
+    pub staking_token: Addr,
+    pub staker_count: u64,
+}
+
+#[cw_serde]
+pub struct TotalUnbondingResponse {
+    pub staking_token: Addr,
+    pub total_unbonding: Uint128,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    pub amount: Uint128,
+    // lock entries released by the claim
+    pub lock_count: u64,
+    pub at_time: u64,
+}
+
+#[cw_serde]
+pub struct UnbondingSummaryResponse {
+    pub staker_addr: Addr,
+    pub staking_token: Addr,
+    pub total_locked: Uint128,
+    pub lock_count: u64,
+    // None when the staker has no locks
+    pub next_unlock_time: Option<u64>,
+    pub matured_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct LockCountResponse {
+    pub count: u64,
+    pub max_lock_entries: u64,
+}
+
+#[cw_serde]
+pub struct UnbondingPeriodResponse {
+    pub unbonding_period: Option<u64>,
+    pub locks: Vec<LockPeriodResponse>,
+}
+
+#[cw_serde]
+pub struct LockPeriodResponse {
+    pub amount: Uint128,
+    pub unlock_time: u64,
+    // None for locks created before the periods were recorded
+    pub unbonding_period: Option<u64>,
+}
+
+#[cw_serde]
+pub struct StakedBalanceAtHeightResponse {
+    pub balance: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct StakedBalanceAtTimeResponse {
+    pub balance: Uint128,
+    // the last block executed at or before time
+    pub height: u64,
+    pub time: u64,
+}
+
+#[cw_serde]
+pub struct TotalStakedAtHeightResponse {
+    pub total: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub enum ExportKind {
+    Pools,
+    // the reward infos of the stakers, by staker then pool
+    Stakers,
+    Locks,
+    RewardsPerSec,
+}
+
+/// a stored record with its addresses humanized, pools are identified by the token they were
+/// registered with
+#[cw_serde]
+pub enum ExportRecord {
+    Pool {
+        staking_token: Addr,
+        // differs from staking_token once the staking token has been migrated
+        current_staking_token: Addr,
+        total_bond_amount: Uint128,
+        total_weighted_amount: Option<Uint128>,
+        reward_index: Decimal,
+        pending_reward: Uint128,
+        deprecated: bool,
+    },
+    Staker {
+        staker_addr: Addr,
+        staking_token: Addr,
+        native_token: bool,
+        index: Decimal,
+        bond_amount: Uint128,
+        weighted_amount: Option<Uint128>,
+        pending_reward: Uint128,
+        pending_withdraw: Vec<Asset>,
+    },
+    Lock {
+        staking_token: Addr,
+        staker_addr: Addr,
+        unlock_time: u64,
+        amount: Uint128,
+    },
+    RewardsPerSec {
+        staking_token: Addr,
+        assets: Vec<Asset>,
+    },
+}
+
+#[cw_serde]
+pub struct ExportStateResponse {
+    pub records: Vec<ExportRecord>,
+    // start_after of the next page, None on the last one
+    pub next: Option<Binary>,
+}
+
+#[cw_serde]
+pub struct SnapshotConsistencyResponse {
+    pub staking_token: Addr,
+    pub snapshot_total: Uint128,
+    pub total_bond_amount: Uint128,
+    pub consistent: bool,
+}
+
+#[cw_serde]
+pub struct SnapshotChangeResponseItem {
+    pub height: u64,
+    // the value before the change at height, None when there was none
+    pub old_value: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct RewardStateAtHeightResponse {
+    pub staker_addr: Addr,
+    pub staking_token: Addr,
+    pub height: u64,
+    pub bond_amount: Uint128,
+    pub reward_index: Decimal,
+}